@@ -0,0 +1,65 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use rust_vss::{Participant, Util, VSS};
+
+// the recursive extended Euclidean algorithm `Util::extend_gcd` used to be
+fn extend_gcd_recursive(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
+    if a.is_zero() {
+        (b.clone(), BigInt::zero(), BigInt::one())
+    } else {
+        let (g, x, y) = extend_gcd_recursive(&(b % a), a);
+
+        (g, y - (b / a) * x.clone(), x)
+    }
+}
+
+fn extend_gcd(c: &mut Criterion) {
+    let q = VSS::new().q;
+    let mut participant = Participant::new();
+
+    participant.initialize();
+
+    // a group-sized operand against the modulus, as in every inverse modulo q
+    let a = participant.publickey;
+    let mut group = c.benchmark_group("extend_gcd");
+
+    group.bench_function("iterative", |b| {
+        b.iter(|| Util::extend_gcd(black_box(&a), black_box(&q)))
+    });
+    group.bench_function("recursive", |b| {
+        b.iter(|| extend_gcd_recursive(black_box(&a), black_box(&q)))
+    });
+    group.finish();
+}
+
+fn mod_inverse(c: &mut Criterion) {
+    let q = VSS::new().q;
+    let q1 = &q - BigInt::one();
+    let mut participant = Participant::new();
+
+    participant.initialize();
+
+    let publickey = participant.publickey;
+    let privatekey = participant.privatekey.expose().clone();
+    // a product of position differences, as in a Lagrange denominator
+    let denominator = BigInt::from(5040);
+    let mut group = c.benchmark_group("mod_inverse");
+
+    // binary, group element modulo the prime q
+    group.bench_function("element", |b| {
+        b.iter(|| Util::mod_inverse(black_box(&publickey), black_box(&q)))
+    });
+    // Euclidean, exponent modulo the even q - 1
+    group.bench_function("exponent", |b| {
+        b.iter(|| Util::mod_inverse(black_box(&privatekey), black_box(&q1)))
+    });
+    // Euclidean, small operand
+    group.bench_function("denominator", |b| {
+        b.iter(|| Util::mod_inverse(black_box(&denominator), black_box(&q1)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, extend_gcd, mod_inverse);
+criterion_main!(benches);
@@ -1,5 +1,6 @@
 use rust_vss::{string_from_secret, string_to_secret, Participant};
 
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 fn main() {
     let secret_message = String::from("Test");
     let mut dealer = Participant::new();
@@ -17,16 +18,18 @@ fn main() {
     participant4.initialize();
 
     // distribute process
-    let distribute_share_boxes = dealer.distribute_secret(
-        &string_to_secret(&secret_message),
-        &vec![
-            participant1.publickey.clone(),
-            participant2.publickey.clone(),
-            participant3.publickey.clone(),
-            participant4.publickey.clone(),
-        ],
-        3,
-    );
+    let distribute_share_boxes = dealer
+        .distribute_secret(
+            &string_to_secret(&secret_message),
+            &vec![
+                participant1.publickey.clone(),
+                participant2.publickey.clone(),
+                participant3.publickey.clone(),
+                participant4.publickey.clone(),
+            ],
+            3,
+        )
+        .unwrap();
 
     assert_eq!(
         participant1.verify_distribution_shares(&distribute_share_boxes),
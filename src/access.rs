@@ -0,0 +1,130 @@
+use num_bigint::BigInt;
+
+use crate::sharebox::DistributionShareBox;
+
+// Minimal authorized subsets
+
+// With a (t, n) threshold every set of exactly t recipients can reconstruct and no smaller set can,
+// so the minimal authorized subsets are the t-combinations of the recipients. Coordinators
+// enumerate them, restricted to the recipients currently reachable, to decide whom to contact.
+
+/// Iterator over the t-combinations of a list of public keys, in lexicographic order of their
+/// indices in that list.
+#[derive(Debug, Clone)]
+pub struct AuthorizedSubsets {
+    publickeys: Vec<BigInt>,
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl AuthorizedSubsets {
+    fn new(publickeys: Vec<BigInt>, threshold: usize) -> Self {
+        AuthorizedSubsets {
+            done: threshold == 0 || threshold > publickeys.len(),
+            indices: (0..threshold).collect(),
+            publickeys,
+        }
+    }
+}
+
+impl Iterator for AuthorizedSubsets {
+    type Item = Vec<BigInt>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let subset = self
+            .indices
+            .iter()
+            .map(|&i| self.publickeys[i].clone())
+            .collect();
+        let n = self.publickeys.len();
+        let t = self.indices.len();
+
+        // advance to the next combination: bump the rightmost index that still has room
+        match (0..t).rev().find(|&i| self.indices[i] < n - t + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+
+                for j in i + 1..t {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+
+        Some(subset)
+    }
+}
+
+impl DistributionShareBox {
+    /// Enumerates the minimal sets of recipients able to reconstruct. Only recipients listed in
+    /// `available` are considered; pass `None` to consider every recipient.
+    pub fn minimal_authorized_subsets(&self, available: Option<&[BigInt]>) -> AuthorizedSubsets {
+        let publickeys = self
+            .publickeys
+            .iter()
+            .filter(|publickey| available.is_none_or(|available| available.contains(publickey)))
+            .cloned()
+            .collect();
+
+        AuthorizedSubsets::new(publickeys, self.threshold())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::sharebox::DistributionShareBox;
+
+    fn distribution(n: i64, t: i64) -> DistributionShareBox {
+        let mut distribution = DistributionShareBox::new();
+
+        distribution.publickeys = (1..=n).map(BigInt::from).collect();
+        distribution.commitments = (0..t).map(BigInt::from).collect();
+
+        distribution
+    }
+
+    #[test]
+    fn test_minimal_authorized_subsets() {
+        let subsets: Vec<Vec<BigInt>> = distribution(4, 2)
+            .minimal_authorized_subsets(None)
+            .collect();
+        let expected: Vec<Vec<BigInt>> = [[1, 2], [1, 3], [1, 4], [2, 3], [2, 4], [3, 4]]
+            .iter()
+            .map(|pair| pair.iter().map(|&key| BigInt::from(key)).collect())
+            .collect();
+
+        assert_eq!(subsets, expected);
+        assert_eq!(
+            distribution(5, 3).minimal_authorized_subsets(None).count(),
+            10
+        );
+        assert_eq!(
+            distribution(3, 3).minimal_authorized_subsets(None).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_offline_recipients() {
+        let available = [BigInt::from(1), BigInt::from(3), BigInt::from(5)];
+        let subsets: Vec<Vec<BigInt>> = distribution(5, 3)
+            .minimal_authorized_subsets(Some(&available))
+            .collect();
+
+        assert_eq!(subsets, vec![available.to_vec()]);
+
+        // too few recipients online to reconstruct
+        assert_eq!(
+            distribution(5, 3)
+                .minimal_authorized_subsets(Some(&available[..2]))
+                .count(),
+            0
+        );
+    }
+}
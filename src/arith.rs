@@ -0,0 +1,110 @@
+use num_bigint::BigInt;
+
+// Arithmetic backend
+
+// Every exponentiation modulo q goes through `modpow`. num-bigint does the work by default, and
+// the fixed-base tables and the shared squarings of `Util::multi_exp` make up for some of its
+// speed. With the `rug` feature GMP does it instead, several times faster for 2048-bit operands,
+// enough that a plain `modpow` beats the tables built from num-bigint multiplications.
+
+pub(crate) trait Arithmetic {
+    /// Whether tables of powers and shared squarings beat separate calls to `modpow`.
+    const TABLES: bool;
+
+    /// base^exponent mod modulus in [0, modulus), for a non-negative exponent.
+    fn modpow(base: &BigInt, exponent: &BigInt, modulus: &BigInt) -> BigInt;
+}
+
+// kept with `rug` for comparison in tests
+#[cfg_attr(feature = "rug", allow(dead_code))]
+pub(crate) struct NumBigint;
+
+impl Arithmetic for NumBigint {
+    const TABLES: bool = true;
+
+    fn modpow(base: &BigInt, exponent: &BigInt, modulus: &BigInt) -> BigInt {
+        base.modpow(exponent, modulus)
+    }
+}
+
+#[cfg(feature = "rug")]
+pub(crate) struct Gmp;
+
+#[cfg(feature = "rug")]
+impl Gmp {
+    fn to_integer(value: &BigInt) -> rug::Integer {
+        let (sign, bytes) = value.to_bytes_le();
+        let integer = rug::Integer::from_digits(&bytes, rug::integer::Order::Lsf);
+
+        match sign {
+            num_bigint::Sign::Minus => -integer,
+            _ => integer,
+        }
+    }
+
+    fn from_integer(value: &rug::Integer) -> BigInt {
+        let bytes = value.to_digits::<u8>(rug::integer::Order::Lsf);
+        let magnitude = BigInt::from_bytes_le(num_bigint::Sign::Plus, &bytes);
+
+        if value.cmp0() == std::cmp::Ordering::Less {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+#[cfg(feature = "rug")]
+impl Arithmetic for Gmp {
+    const TABLES: bool = false;
+
+    fn modpow(base: &BigInt, exponent: &BigInt, modulus: &BigInt) -> BigInt {
+        assert!(
+            exponent.sign() != num_bigint::Sign::Minus,
+            "negative exponent"
+        );
+
+        let result = Gmp::to_integer(base)
+            .pow_mod(&Gmp::to_integer(exponent), &Gmp::to_integer(modulus))
+            .unwrap();
+
+        Gmp::from_integer(&result)
+    }
+}
+
+#[cfg(not(feature = "rug"))]
+pub(crate) type Backend = NumBigint;
+#[cfg(feature = "rug")]
+pub(crate) type Backend = Gmp;
+
+/// base^exponent mod modulus with the selected backend.
+pub(crate) fn modpow(base: &BigInt, exponent: &BigInt, modulus: &BigInt) -> BigInt {
+    Backend::modpow(base, exponent, modulus)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{Arithmetic, NumBigint};
+
+    #[test]
+    fn test_modpow() {
+        let q = BigInt::from(179426549);
+
+        for (base, exponent) in [
+            (1301081, 179426547),
+            (2, 0),
+            (0, 5),
+            (-3, 7),
+            (179426550, 2),
+        ] {
+            let (base, exponent) = (BigInt::from(base), BigInt::from(exponent));
+            let expected = base.modpow(&exponent, &q);
+
+            assert_eq!(NumBigint::modpow(&base, &exponent, &q), expected);
+            #[cfg(feature = "rug")]
+            assert_eq!(super::Gmp::modpow(&base, &exponent, &q), expected);
+        }
+    }
+}
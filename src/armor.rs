@@ -0,0 +1,105 @@
+use std::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256};
+
+use crate::{error::VssError, sharebox::ShareBox};
+
+// Armored shares
+
+// A share box as text that survives email and password managers: the `to_bytes` encoding in
+// base64, wrapped at 64 columns between BEGIN and END lines, and a last line holding `=` and the
+// base64 of the first 4 bytes of its SHA-256, as OpenPGP armor does with its CRC. Whitespace
+// around lines is ignored when parsing, so indented or CRLF text still reads.
+
+const BEGIN: &str = "-----BEGIN VSS SHARE-----";
+const END: &str = "-----END VSS SHARE-----";
+const LINE_WIDTH: usize = 64;
+
+fn checksum(bytes: &[u8]) -> String {
+    STANDARD.encode(&Sha256::digest(bytes)[..4])
+}
+
+impl fmt::Display for ShareBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.to_bytes();
+        let body = STANDARD.encode(&bytes);
+
+        writeln!(f, "{}", BEGIN)?;
+
+        for line in body.as_bytes().chunks(LINE_WIDTH) {
+            // base64 is ASCII, so every chunk is a whole string
+            writeln!(f, "{}", std::str::from_utf8(line).unwrap())?;
+        }
+
+        writeln!(f, "={}", checksum(&bytes))?;
+        write!(f, "{}", END)
+    }
+}
+
+impl FromStr for ShareBox {
+    type Err = VssError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let [begin, body @ .., sum, end] = lines.as_slice() else {
+            return Err(VssError::MalformedEncoding);
+        };
+
+        if *begin != BEGIN || *end != END {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        let sum = sum.strip_prefix('=').ok_or(VssError::MalformedEncoding)?;
+        let bytes = STANDARD
+            .decode(body.concat())
+            .map_err(|_| VssError::MalformedEncoding)?;
+
+        if checksum(&bytes) != sum {
+            return Err(VssError::ChecksumMismatch);
+        }
+
+        ShareBox::from_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::VssError, sharebox::ShareBox, testkit::fixed_ceremony};
+
+    #[test]
+    fn test_armor() {
+        let sharebox = fixed_ceremony(3, 2, 5).unwrap().shares[0].clone();
+        let text = sharebox.to_string();
+
+        assert!(text.starts_with("-----BEGIN VSS SHARE-----\n"));
+        assert!(text.ends_with("\n-----END VSS SHARE-----"));
+        assert!(text.lines().all(|line| line.len() <= 64));
+        assert_eq!(text.parse::<ShareBox>(), Ok(sharebox.clone()));
+        assert_eq!(
+            format!("\n  {}\n", text.replace('\n', "\r\n  ")).parse::<ShareBox>(),
+            Ok(sharebox)
+        );
+
+        let lines: Vec<&str> = text.lines().collect();
+        let mut body = lines[1].to_string().into_bytes();
+
+        body[0] = if body[0] == b'A' { b'B' } else { b'A' };
+
+        let tampered = text.replacen(lines[1], std::str::from_utf8(&body).unwrap(), 1);
+
+        assert_eq!(
+            tampered.parse::<ShareBox>(),
+            Err(VssError::ChecksumMismatch)
+        );
+        assert_eq!(
+            text.replace("VSS SHARE", "PGP MESSAGE").parse::<ShareBox>(),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::BTreeSet;
+
+use num_bigint::BigInt;
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    dkg::{KeyDealing, SECRET_LEN},
+    error::VssError,
+    participant::Participant,
+    secret_to_bytes,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+    wire::Writer,
+};
+
+// Randomness beacon
+
+// SCRAPE-style rounds: every node deals a random polynomial to all nodes, bound to the round, and
+// once the dealings are in the round is closed, which fixes the qualified set before anything is
+// opened. The qualified dealings combine as in key generation, each node opens its share of the
+// combination, and any `threshold` openings reconstruct `SECRET_LEN` bytes derived from G^P(0).
+// Nobody can bias the output: it depends on every qualified dealing, dealings are encrypted until
+// the set is fixed, and with one honest dealer the sum is uniform. A node that withholds its
+// opening cannot stop the round as long as `threshold` others open theirs.
+//
+// `BeaconRound` is the round's public transcript: the dealings, whether the round is closed and
+// the openings, which anyone can check with `verify` and turn into the output with `output`.
+
+/// The public transcript of one beacon round.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BeaconRound {
+    pub round: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub publickeys: Vec<BigInt>,
+    pub threshold: u32,
+    pub dealings: Vec<KeyDealing>,
+    /// set by `close`, after which no dealing is taken and openings are
+    pub closed: bool,
+    pub openings: Vec<ShareBox>,
+}
+
+// dealings are bound to their round and dealer, so none is replayed into another round
+fn context(round: u64, dealer: &BigInt) -> Vec<u8> {
+    let mut writer = Writer::new();
+
+    writer.raw(b"beacon");
+    writer.u64(round);
+    writer.bigint(dealer);
+    writer.into_bytes()
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Deals this node's random contribution to `round` of a beacon run by `publickeys`, which
+    /// must include its own. Collect the dealings with `BeaconRound::add`.
+    pub fn deal_beacon(
+        &mut self,
+        round: u64,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<KeyDealing, VssError> {
+        self.deal_beacon_with_rng(round, publickeys, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `deal_beacon`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn deal_beacon_with_rng(
+        &mut self,
+        round: u64,
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<KeyDealing, VssError> {
+        let distribution =
+            self.deal_random(publickeys, threshold, &context(round, &self.publickey), rng)?;
+
+        Ok(KeyDealing {
+            dealer: self.publickey.clone(),
+            distribution,
+        })
+    }
+
+    /// This node's opening of a closed round: its share of the combined dealings.
+    pub fn open_beacon(&self, round: &BeaconRound) -> Result<ShareBox, VssError> {
+        if !round.closed {
+            return Err(VssError::UnexpectedMessage);
+        }
+
+        self.extract_secret_share(&round.distribution(&self.vss)?, &self.privatekey)
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks that `dealing` is a proven dealing for `round` from one of `publickeys` to all of
+    /// them, at positions `1..=n`, with `threshold`.
+    pub fn verify_beacon_dealing(
+        &self,
+        round: u64,
+        publickeys: &[BigInt],
+        threshold: u32,
+        dealing: &KeyDealing,
+    ) -> bool {
+        publickeys.contains(&dealing.dealer)
+            && self.verify_random_dealing(
+                publickeys,
+                threshold,
+                &dealing.distribution,
+                &context(round, &dealing.dealer),
+            )
+    }
+}
+
+impl BeaconRound {
+    pub fn new(round: u64, publickeys: &[BigInt], threshold: u32) -> Self {
+        BeaconRound {
+            round,
+            publickeys: publickeys.to_vec(),
+            threshold,
+            ..Default::default()
+        }
+    }
+
+    /// Adds `dealing` if the round is still open, it passes `VSS::verify_beacon_dealing` and its
+    /// dealer has not dealt yet.
+    pub fn add<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+        dealing: KeyDealing,
+    ) -> Result<(), VssError> {
+        if self.closed {
+            return Err(VssError::UnexpectedMessage);
+        }
+
+        if self
+            .dealings
+            .iter()
+            .any(|dealt| dealt.dealer == dealing.dealer)
+        {
+            return Err(VssError::DuplicatePublicKey {
+                publickey: dealing.dealer,
+            });
+        }
+
+        if !vss.verify_beacon_dealing(self.round, &self.publickeys, self.threshold, &dealing) {
+            return Err(VssError::InvalidProof);
+        }
+
+        self.dealings.push(dealing);
+
+        Ok(())
+    }
+
+    /// Fixes the qualified set to the dealings added so far. Late dealings are refused from here
+    /// on, and openings taken.
+    pub fn close(&mut self) -> Result<(), VssError> {
+        if self.dealings.is_empty() {
+            return Err(VssError::NoQualifiedDealers);
+        }
+
+        self.closed = true;
+
+        Ok(())
+    }
+
+    /// The combination of the round's dealings, which the nodes open, see
+    /// `VSS::combine_distributions`.
+    pub fn distribution<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+    ) -> Result<DistributionShareBox, VssError> {
+        let distributions: Vec<DistributionShareBox> = self
+            .dealings
+            .iter()
+            .map(|dealing| dealing.distribution.clone())
+            .collect();
+
+        vss.combine_distributions(&distributions)
+    }
+
+    /// Adds an opening of a closed round if it verifies against `distribution` and its node has
+    /// not opened yet.
+    pub fn open<D: Transcript>(&mut self, vss: &VSS<D>, opening: ShareBox) -> Result<(), VssError> {
+        if !self.closed {
+            return Err(VssError::UnexpectedMessage);
+        }
+
+        if self
+            .openings
+            .iter()
+            .any(|opened| opened.publickey == opening.publickey)
+        {
+            return Err(VssError::DuplicatePublicKey {
+                publickey: opening.publickey,
+            });
+        }
+
+        if !vss.verify_share(&opening, &self.distribution(vss)?, &opening.publickey) {
+            return Err(VssError::InvalidShare {
+                publickey: opening.publickey,
+            });
+        }
+
+        self.openings.push(opening);
+
+        Ok(())
+    }
+
+    /// The round's output, `SECRET_LEN` bytes reconstructed from at least `threshold` openings.
+    pub fn output<D: Transcript>(&self, vss: &VSS<D>) -> Result<Vec<u8>, VssError> {
+        if !self.closed {
+            return Err(VssError::UnexpectedMessage);
+        }
+
+        let secret = vss.reconstruct(&self.openings, &self.distribution(vss)?)?;
+
+        secret_to_bytes(&secret, SECRET_LEN)
+    }
+
+    /// Verifies the whole transcript: a closed round with distinct, valid dealings and at least
+    /// `threshold` distinct, valid openings.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let dealers: BTreeSet<&BigInt> = self.dealings.iter().map(|d| &d.dealer).collect();
+        let openers: BTreeSet<&BigInt> = self.openings.iter().map(|o| &o.publickey).collect();
+        let distribution = match self.distribution(vss) {
+            Ok(distribution) => distribution,
+            Err(_) => return false,
+        };
+
+        self.closed
+            && dealers.len() == self.dealings.len()
+            && openers.len() == self.openings.len()
+            && self.openings.len() >= self.threshold as usize
+            && self.dealings.iter().all(|dealing| {
+                vss.verify_beacon_dealing(self.round, &self.publickeys, self.threshold, dealing)
+            })
+            && vss
+                .verify_shares(&self.openings, &distribution)
+                .into_iter()
+                .all(|valid| valid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::BeaconRound;
+
+    #[test]
+    fn test_beacon_round() {
+        let ceremony = fixed_ceremony(3, 2, 37).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let nodes = &ceremony.participants;
+        let publickeys: Vec<BigInt> = nodes.iter().map(|p| p.publickey.clone()).collect();
+        let mut round = BeaconRound::new(7, &publickeys, 2);
+        let dealings: Vec<_> = nodes
+            .iter()
+            .map(|p| p.clone().deal_beacon(7, &publickeys, 2).unwrap())
+            .collect();
+
+        // nothing opens before the qualified set is fixed
+        assert_eq!(
+            nodes[0].open_beacon(&round),
+            Err(VssError::UnexpectedMessage)
+        );
+
+        for dealing in dealings[..2].iter().cloned() {
+            round.add(vss, dealing).unwrap();
+        }
+
+        round.close().unwrap();
+
+        // a late dealing is refused
+        assert_eq!(
+            round.add(vss, dealings[2].clone()),
+            Err(VssError::UnexpectedMessage)
+        );
+
+        let openings: Vec<_> = nodes
+            .iter()
+            .map(|p| p.open_beacon(&round).unwrap())
+            .collect();
+
+        round.open(vss, openings[0].clone()).unwrap();
+
+        assert!(!round.verify(vss));
+        assert!(round.output(vss).is_err());
+
+        round.open(vss, openings[1].clone()).unwrap();
+
+        let output = round.output(vss).unwrap();
+
+        assert_eq!(output.len(), 32);
+        assert!(round.verify(vss));
+
+        // any two openings give the same output
+        let mut other = round.clone();
+
+        other.openings = openings[1..].to_vec();
+
+        assert_eq!(other.output(vss), Ok(output));
+    }
+
+    #[test]
+    fn test_beacon_replay() {
+        let ceremony = fixed_ceremony(3, 2, 41).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let nodes = &ceremony.participants;
+        let publickeys: Vec<BigInt> = nodes.iter().map(|p| p.publickey.clone()).collect();
+        let dealing = nodes[0].clone().deal_beacon(1, &publickeys, 2).unwrap();
+        let mut round = BeaconRound::new(2, &publickeys, 2);
+
+        // a dealing from an earlier round does not count in this one
+        assert_eq!(round.add(vss, dealing), Err(VssError::InvalidProof));
+        assert_eq!(round.close(), Err(VssError::NoQualifiedDealers));
+
+        round
+            .add(
+                vss,
+                nodes[1].clone().deal_beacon(2, &publickeys, 2).unwrap(),
+            )
+            .unwrap();
+        round.close().unwrap();
+
+        let mut opening = nodes[2].open_beacon(&round).unwrap();
+
+        round.open(vss, opening.clone()).unwrap();
+
+        assert_eq!(
+            round.open(vss, opening.clone()),
+            Err(VssError::DuplicatePublicKey {
+                publickey: opening.publickey.clone()
+            })
+        );
+
+        opening.publickey = publickeys[0].clone();
+
+        assert_eq!(
+            round.open(vss, opening),
+            Err(VssError::InvalidShare {
+                publickey: publickeys[0].clone()
+            })
+        );
+    }
+}
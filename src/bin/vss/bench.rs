@@ -0,0 +1,305 @@
+use std::time::{Duration, Instant};
+
+use num_bigint::BigInt;
+use rust_vss::{Participant, ShareBox};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Measurement {
+    pub group: String,
+    pub hash: String,
+    pub participants: usize,
+    pub threshold: u32,
+    pub keygen_ms: f64,
+    pub distribute_ms: f64,
+    pub verify_distribution_ms: f64,
+    pub extract_ms: f64,
+    pub verify_share_ms: f64,
+    pub reconstruct_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub measurements: Vec<Measurement>,
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn timed<T, F: FnOnce() -> T>(f: F) -> (T, f64) {
+    let start = Instant::now();
+    let value = f();
+
+    (value, millis(start.elapsed()))
+}
+
+fn participant<D: Digest + Clone>(group: &str) -> Result<Participant<D>, String> {
+    match group {
+        "2048" => Ok(Participant::new().with_digest()),
+        _ => Err(format!("unsupported group {}", group)),
+    }
+}
+
+// majority threshold, the common choice for committees
+fn threshold(participants: usize) -> u32 {
+    (participants / 2 + 1) as u32
+}
+
+fn measure<D: Digest + Clone>(
+    group: &str,
+    hash: &str,
+    participants: usize,
+) -> Result<Measurement, String> {
+    if participants < 1 {
+        return Err(String::from("a committee needs at least one participant"));
+    }
+
+    let threshold = threshold(participants);
+    let (committee, keygen_ms) = timed(|| {
+        (0..participants)
+            .map(|_| {
+                let mut participant = participant::<D>(group)?;
+
+                participant.initialize();
+
+                Ok(participant)
+            })
+            .collect::<Result<Vec<Participant<D>>, String>>()
+    });
+    let committee = committee?;
+    let mut dealer = participant::<D>(group)?;
+
+    dealer.initialize();
+
+    let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+    let (distribution, distribute_ms) =
+        timed(|| dealer.distribute_secret(&BigInt::from(1234567890), &publickeys, threshold));
+    let distribution = distribution.map_err(|e| e.to_string())?;
+    let (valid, verify_distribution_ms) =
+        timed(|| committee[0].verify_distribution_shares(&distribution));
+
+    if !valid {
+        return Err(String::from("distribution failed to verify"));
+    }
+
+    let (share_boxes, extract_ms) = timed(|| {
+        committee
+            .iter()
+            .take(threshold as usize)
+            .map(|p| p.extract_secret_share(&distribution, &p.privatekey))
+            .collect::<Result<Vec<ShareBox>, _>>()
+    });
+    let share_boxes = share_boxes.map_err(|e| e.to_string())?;
+    let (valid, verify_share_ms) = timed(|| {
+        committee[0].verify_share(&share_boxes[0], &distribution, &committee[0].publickey)
+    });
+
+    if !valid {
+        return Err(String::from("share failed to verify"));
+    }
+
+    let (secret, reconstruct_ms) = timed(|| committee[0].reconstruct(&share_boxes, &distribution));
+
+    if secret.map_err(|e| e.to_string())? != BigInt::from(1234567890) {
+        return Err(String::from("reconstruction failed"));
+    }
+
+    Ok(Measurement {
+        group: group.to_string(),
+        hash: hash.to_string(),
+        participants,
+        threshold,
+        keygen_ms: keygen_ms / participants as f64,
+        distribute_ms,
+        verify_distribution_ms,
+        extract_ms: extract_ms / threshold as f64,
+        verify_share_ms,
+        reconstruct_ms,
+    })
+}
+
+// The threshold BLS workload over the G1 group of BLS12-381, step for step: the dealer draws and
+// shares a signing key, every key share is checked against the commitments, shareholders sign
+// with their shares, one signature share is verified, and the signature aggregated from them is
+// verified under the key. Its hash to the curve is fixed by the ciphersuite.
+#[cfg(feature = "bls12_381")]
+fn measure_bls(hash: &str, participants: usize) -> Result<Measurement, String> {
+    use rust_vss::{Bls12381G1, CyclicGroup};
+
+    if hash != "sha256" {
+        return Err(format!("unsupported hash {} for group bls12_381", hash));
+    }
+
+    if participants < 1 {
+        return Err(String::from("a committee needs at least one participant"));
+    }
+
+    let threshold = threshold(participants);
+    let message = b"rust-vss bench";
+    let (secret, keygen_ms) = timed(|| Bls12381G1.random_scalar());
+    let (dealt, distribute_ms) =
+        timed(|| Bls12381G1::deal_key(&secret, threshold as usize, participants));
+    let (commitments, key_shares) = dealt.map_err(|e| e.to_string())?;
+    let (valid, verify_distribution_ms) = timed(|| {
+        key_shares.iter().all(|share| {
+            Bls12381G1::generator() * share.secret
+                == Bls12381G1::evaluate_commitments(&commitments, share.position)
+        })
+    });
+
+    if !valid {
+        return Err(String::from("distribution failed to verify"));
+    }
+
+    let (signature_shares, extract_ms) = timed(|| {
+        key_shares
+            .iter()
+            .take(threshold as usize)
+            .map(|share| Bls12381G1::sign_share(share, message))
+            .collect::<Vec<_>>()
+    });
+    let (valid, verify_share_ms) =
+        timed(|| Bls12381G1::verify_signature_share(&commitments, message, &signature_shares[0]));
+
+    if !valid {
+        return Err(String::from("share failed to verify"));
+    }
+
+    let (signature, reconstruct_ms) = timed(|| {
+        Bls12381G1::aggregate(threshold as usize, &signature_shares)
+            .map(|signature| Bls12381G1::verify_signature(&commitments[0], message, &signature))
+    });
+
+    if !signature.map_err(|e| e.to_string())? {
+        return Err(String::from("reconstruction failed"));
+    }
+
+    Ok(Measurement {
+        group: String::from("bls12_381"),
+        hash: hash.to_string(),
+        participants,
+        threshold,
+        keygen_ms,
+        distribute_ms,
+        verify_distribution_ms,
+        extract_ms: extract_ms / threshold as f64,
+        verify_share_ms,
+        reconstruct_ms,
+    })
+}
+
+fn measure_hash(group: &str, hash: &str, participants: usize) -> Result<Measurement, String> {
+    #[cfg(feature = "bls12_381")]
+    if group == "bls12_381" {
+        return measure_bls(hash, participants);
+    }
+
+    match hash {
+        "sha256" => measure::<Sha256>(group, hash, participants),
+        #[cfg(feature = "blake3")]
+        "blake3" => measure::<rust_vss::Blake3>(group, hash, participants),
+        _ => Err(format!("unsupported hash {}", hash)),
+    }
+}
+
+pub fn run(participants: &[usize], groups: &[String], hashes: &[String]) -> Result<Report, String> {
+    let mut measurements = Vec::new();
+
+    for group in groups {
+        for hash in hashes {
+            for n in participants {
+                measurements.push(measure_hash(group, hash, *n)?);
+            }
+        }
+    }
+
+    Ok(Report { measurements })
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report is serializable")
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from(
+            "| group | hash | n | t | keygen (ms/key) | distribute (ms) | verify distribution (ms) | extract (ms/share) | verify share (ms) | reconstruct (ms) |\n\
+             |---|---|---|---|---|---|---|---|---|---|\n",
+        );
+
+        for m in &self.measurements {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} | {:.2} |\n",
+                m.group,
+                m.hash,
+                m.participants,
+                m.threshold,
+                m.keygen_ms,
+                m.distribute_ms,
+                m.verify_distribution_ms,
+                m.extract_ms,
+                m.verify_share_ms,
+                m.reconstruct_ms
+            ));
+        }
+
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run, Measurement, Report};
+
+    #[test]
+    fn test_report_formats() {
+        let report = Report {
+            measurements: vec![Measurement {
+                group: String::from("2048"),
+                hash: String::from("sha256"),
+                participants: 10,
+                threshold: 6,
+                keygen_ms: 1.0,
+                distribute_ms: 2.0,
+                verify_distribution_ms: 3.0,
+                extract_ms: 4.0,
+                verify_share_ms: 5.0,
+                reconstruct_ms: 6.0,
+            }],
+        };
+
+        assert!(report
+            .to_markdown()
+            .contains("| 2048 | sha256 | 10 | 6 | 1.00 | 2.00 | 3.00 | 4.00 | 5.00 | 6.00 |"));
+        assert!(report.to_json().contains("\"verify_distribution_ms\": 3.0"));
+    }
+
+    #[test]
+    fn test_unsupported_group() {
+        assert!(run(&[3], &[String::from("1024")], &[String::from("sha256")]).is_err());
+        assert!(run(&[3], &[String::from("2048")], &[String::from("md5")]).is_err());
+    }
+
+    #[cfg(feature = "bls12_381")]
+    #[test]
+    fn test_bls12_381() {
+        let report = run(
+            &[1, 4],
+            &[String::from("bls12_381")],
+            &[String::from("sha256")],
+        )
+        .unwrap();
+
+        assert_eq!(report.measurements.len(), 2);
+        assert_eq!(report.measurements[1].group, "bls12_381");
+        assert_eq!(report.measurements[1].threshold, 3);
+        assert!(run(
+            &[3],
+            &[String::from("bls12_381")],
+            &[String::from("blake3")]
+        )
+        .is_err());
+    }
+}
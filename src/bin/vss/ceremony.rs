@@ -0,0 +1,379 @@
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use num_bigint::BigInt;
+use rust_vss::{string_from_secret, string_to_secret, DistributionShareBox, Participant, ShareBox};
+use serde::{Deserialize, Serialize};
+
+// Interactive ceremony wizard
+
+// Walks a dealer through a ceremony in stages: collecting the participants' public keys, choosing
+// the threshold, dealing, exporting one package per participant and, once participants return
+// their share boxes, reconstructing. The state is saved in the ceremony directory after every
+// step, so the wizard can be quit at any prompt and resumed later by running it again.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum Stage {
+    CollectKeys,
+    ChooseThreshold,
+    Deal,
+    CollectShares,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct State {
+    stage: Stage,
+    /// decimal public keys, in the order they were entered
+    publickeys: Vec<String>,
+    threshold: u32,
+    distribution: Option<DistributionShareBox>,
+    shares: Vec<ShareBox>,
+}
+
+/// What a participant receives after dealing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Package {
+    pub publickey: String,
+    pub distribution: DistributionShareBox,
+}
+
+struct Wizard<'a, R, W> {
+    dir: &'a Path,
+    input: R,
+    output: W,
+    state: State,
+}
+
+fn state_path(dir: &Path) -> PathBuf {
+    dir.join("state.json")
+}
+
+fn load(dir: &Path) -> Result<State, String> {
+    match std::fs::read(state_path(dir)) {
+        Ok(json) => serde_json::from_slice(&json).map_err(|e| e.to_string()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(State {
+            stage: Stage::CollectKeys,
+            publickeys: Vec::new(),
+            threshold: 0,
+            distribution: None,
+            shares: Vec::new(),
+        }),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+impl<R: BufRead, W: Write> Wizard<'_, R, W> {
+    fn save(&self) -> Result<(), String> {
+        let json = serde_json::to_vec_pretty(&self.state).map_err(|e| e.to_string())?;
+        let path = state_path(self.dir);
+        let tmp = path.with_extension("json.tmp");
+
+        std::fs::create_dir_all(self.dir).map_err(|e| e.to_string())?;
+        std::fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        std::fs::rename(tmp, path).map_err(|e| e.to_string())
+    }
+
+    fn say(&mut self, message: &str) -> Result<(), String> {
+        writeln!(self.output, "{}", message).map_err(|e| e.to_string())
+    }
+
+    // `None` at the end of the input, where the wizard stops and can be resumed later
+    fn prompt(&mut self, message: &str) -> Result<Option<String>, String> {
+        write!(self.output, "{} ", message).map_err(|e| e.to_string())?;
+        self.output.flush().map_err(|e| e.to_string())?;
+
+        let mut line = String::new();
+
+        if self.input.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(line.trim().to_string()))
+    }
+
+    fn advance(&mut self, stage: Stage) -> Result<(), String> {
+        self.state.stage = stage;
+        self.save()
+    }
+
+    fn collect_keys(&mut self) -> Result<bool, String> {
+        self.say(
+            "Enter the participants' public keys, one per line, and an empty line when done.",
+        )?;
+
+        loop {
+            let count = self.state.publickeys.len();
+            let line = match self.prompt(&format!("public key #{}:", count + 1))? {
+                Some(line) => line,
+                None => return Ok(false),
+            };
+
+            if line.is_empty() {
+                if count == 0 {
+                    self.say("At least one public key is required.")?;
+                    continue;
+                }
+
+                self.advance(Stage::ChooseThreshold)?;
+                return Ok(true);
+            }
+
+            if line.parse::<BigInt>().is_err() {
+                self.say("Not a decimal public key, try again.")?;
+            } else if self.state.publickeys.contains(&line) {
+                self.say("This key was already entered.")?;
+            } else {
+                self.state.publickeys.push(line);
+                self.save()?;
+            }
+        }
+    }
+
+    fn choose_threshold(&mut self) -> Result<bool, String> {
+        let participants = self.state.publickeys.len();
+        let majority = participants / 2 + 1;
+
+        loop {
+            let line = match self.prompt(&format!(
+                "threshold, between 1 and {} [{}]:",
+                participants, majority
+            ))? {
+                Some(line) => line,
+                None => return Ok(false),
+            };
+            let threshold = if line.is_empty() {
+                majority
+            } else {
+                match line.parse::<usize>() {
+                    Ok(threshold) if (1..=participants).contains(&threshold) => threshold,
+                    _ => {
+                        self.say("Invalid threshold, try again.")?;
+                        continue;
+                    }
+                }
+            };
+
+            self.state.threshold = threshold as u32;
+            self.advance(Stage::Deal)?;
+            return Ok(true);
+        }
+    }
+
+    fn deal(&mut self) -> Result<bool, String> {
+        let secret = match self.prompt("secret to share:")? {
+            Some(secret) => secret,
+            None => return Ok(false),
+        };
+        let publickeys: Vec<BigInt> = self
+            .state
+            .publickeys
+            .iter()
+            .map(|publickey| publickey.parse().unwrap())
+            .collect();
+        let mut dealer = Participant::new();
+
+        dealer.initialize();
+
+        let distribution = dealer
+            .distribute_secret(
+                &string_to_secret(&secret),
+                &publickeys,
+                self.state.threshold,
+            )
+            .map_err(|e| e.to_string())?;
+        let packages = self.dir.join("packages");
+
+        std::fs::create_dir_all(&packages).map_err(|e| e.to_string())?;
+
+        for (i, publickey) in self.state.publickeys.iter().enumerate() {
+            let package = Package {
+                publickey: publickey.clone(),
+                distribution: distribution.clone(),
+            };
+            let json = serde_json::to_vec_pretty(&package).map_err(|e| e.to_string())?;
+
+            std::fs::write(packages.join(format!("participant-{}.json", i + 1)), json)
+                .map_err(|e| e.to_string())?;
+        }
+
+        self.state.distribution = Some(distribution);
+        self.advance(Stage::CollectShares)?;
+        self.say(&format!(
+            "Dealt {}-of-{}. Send each participant its package from {}.",
+            self.state.threshold,
+            publickeys.len(),
+            packages.display()
+        ))?;
+
+        Ok(true)
+    }
+
+    fn collect_shares(&mut self) -> Result<bool, String> {
+        let distribution = self.state.distribution.clone().unwrap();
+        let verifier = Participant::new();
+
+        self.say("Enter the paths of the share boxes returned by the participants.")?;
+
+        while self.state.shares.len() < self.state.threshold as usize {
+            let line = match self.prompt(&format!(
+                "share box {} of {}:",
+                self.state.shares.len() + 1,
+                self.state.threshold
+            ))? {
+                Some(line) => line,
+                None => return Ok(false),
+            };
+            let sharebox: ShareBox = match std::fs::read(&line)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_slice(&json).map_err(|e| e.to_string()))
+            {
+                Ok(sharebox) => sharebox,
+                Err(error) => {
+                    self.say(&format!("Cannot read {}: {}", line, error))?;
+                    continue;
+                }
+            };
+
+            if self
+                .state
+                .shares
+                .iter()
+                .any(|share| share.publickey == sharebox.publickey)
+            {
+                self.say("A share box of this participant was already accepted.")?;
+            } else if !verifier.verify_share(&sharebox, &distribution, &sharebox.publickey) {
+                self.say("The share box does not verify against the distribution.")?;
+            } else {
+                self.state.shares.push(sharebox);
+                self.save()?;
+            }
+        }
+
+        let secret = verifier
+            .reconstruct(&self.state.shares, &distribution)
+            .map_err(|e| e.to_string())?;
+
+        self.advance(Stage::Done)?;
+        self.say(&format!(
+            "Reconstructed secret: {}",
+            string_from_secret(&secret)
+        ))?;
+
+        Ok(true)
+    }
+}
+
+/// Runs the wizard on the ceremony in `dir` until it completes or the input ends.
+pub fn run<R: BufRead, W: Write>(dir: &Path, input: R, output: W) -> Result<(), String> {
+    let state = load(dir)?;
+    let mut wizard = Wizard {
+        dir,
+        input,
+        output,
+        state,
+    };
+
+    loop {
+        let proceed = match wizard.state.stage {
+            Stage::CollectKeys => wizard.collect_keys()?,
+            Stage::ChooseThreshold => wizard.choose_threshold()?,
+            Stage::Deal => wizard.deal()?,
+            Stage::CollectShares => wizard.collect_shares()?,
+            Stage::Done => {
+                wizard.say("The ceremony is complete.")?;
+                return Ok(());
+            }
+        };
+
+        if !proceed {
+            wizard.say("")?;
+            return wizard
+                .say("Progress saved, run the wizard again with the same directory to resume.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use num_bigint::BigInt;
+    use rust_vss::{Participant, ShareBox};
+
+    use super::{run, Package};
+
+    #[test]
+    fn test_resumable_ceremony() {
+        let dir = std::env::temp_dir().join(format!("vss-ceremony-{}", std::process::id()));
+        let committee: Vec<Participant> = (0..3)
+            .map(|_| {
+                let mut participant = Participant::new();
+
+                participant.initialize();
+
+                participant
+            })
+            .collect();
+        let keys: Vec<String> = committee.iter().map(|p| p.publickey.to_string()).collect();
+        let mut output = Vec::new();
+
+        // quit after two keys, then resume
+        run(
+            &dir,
+            Cursor::new(format!("{}\n{}\n", keys[0], keys[1])),
+            &mut output,
+        )
+        .unwrap();
+        run(
+            &dir,
+            Cursor::new(format!("not a key\n{}\n\n5\n2\nhunter2\n", keys[2])),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("Progress saved"));
+        assert!(output.contains("Not a decimal public key"));
+        assert!(output.contains("Invalid threshold"));
+        assert!(output.contains("Dealt 2-of-3"));
+
+        let mut paths = Vec::new();
+
+        for (i, participant) in committee.iter().enumerate().take(2) {
+            let json =
+                std::fs::read(dir.join(format!("packages/participant-{}.json", i + 1))).unwrap();
+            let package: Package = serde_json::from_slice(&json).unwrap();
+            let sharebox: ShareBox = participant
+                .extract_secret_share(&package.distribution, &participant.privatekey)
+                .unwrap();
+            let path = dir.join(format!("share-{}.json", i + 1));
+
+            assert_eq!(
+                package.publickey.parse::<BigInt>().unwrap(),
+                participant.publickey
+            );
+            std::fs::write(&path, serde_json::to_vec(&sharebox).unwrap()).unwrap();
+            paths.push(path.display().to_string());
+        }
+
+        let mut output = Vec::new();
+
+        run(
+            &dir,
+            Cursor::new(format!("{}\n{}\n{}\n", paths[0], paths[0], paths[1])),
+            &mut output,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("already accepted"));
+        assert!(output.contains("Reconstructed secret: hunter2"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,283 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use num_bigint::BigInt;
+use rust_vss::{secret_to_bytes, DistributionShareBox, Participant, ShareBox};
+use serde::{de::DeserializeOwned, Serialize};
+
+// Share file commands
+
+// One command per protocol step, so the scheme can be driven from scripts. Every file is JSON,
+// or CBOR if its name ends in `.cbor`. Key files hold the participant with its parameters and
+// private key; public key files hold the decimal public key alone, to be handed to the dealer.
+
+fn is_cbor(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "cbor")
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    if is_cbor(path) {
+        ciborium::de::from_reader(bytes.as_slice())
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    } else {
+        serde_json::from_slice(&bytes).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+fn encode<T: Serialize>(path: &Path, value: &T) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+
+    if is_cbor(path) {
+        ciborium::ser::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+    } else {
+        bytes = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    }
+
+    Ok(bytes)
+}
+
+fn write<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    std::fs::write(path, encode(path, value)?).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+// a key file is created readable by its owner only, and an existing one is replaced only with
+// `force`, so that a private key is never lost to a mistyped path
+fn write_key<T: Serialize>(path: &Path, value: &T, force: bool) -> Result<(), String> {
+    let bytes = encode(path, value)?;
+    let error = |e: std::io::Error| format!("{}: {}", path.display(), e);
+
+    if force {
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(error(e)),
+            _ => {}
+        }
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path).map_err(error)?;
+
+    file.write_all(&bytes).map_err(error)?;
+    file.sync_all().map_err(error)
+}
+
+fn read_publickey(path: &Path) -> Result<BigInt, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    text.trim()
+        .parse()
+        .map_err(|_| format!("{}: not a decimal public key", path.display()))
+}
+
+/// Writes a fresh key pair to `key` and its public key to `public`. An existing key file is
+/// replaced only with `force`.
+pub fn keygen(key: &Path, public: &Path, force: bool) -> Result<String, String> {
+    let mut participant = Participant::new();
+
+    participant.initialize();
+    write_key(key, &participant, force)?;
+    std::fs::write(public, participant.publickey.to_string())
+        .map_err(|e| format!("{}: {}", public.display(), e))?;
+
+    Ok(format!("Wrote {} and {}.", key.display(), public.display()))
+}
+
+/// Deals the bytes of `secret` to the holders of the public keys in `publickeys`.
+pub fn split(
+    secret: &Path,
+    publickeys: &[PathBuf],
+    threshold: u32,
+    out: &Path,
+) -> Result<String, String> {
+    let secret = std::fs::read(secret).map_err(|e| format!("{}: {}", secret.display(), e))?;
+    let publickeys = publickeys
+        .iter()
+        .map(|path| read_publickey(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut dealer = Participant::new();
+
+    dealer.initialize();
+
+    let distribution = dealer
+        .distribute_secret_bytes(&secret, &publickeys, threshold)
+        .map_err(|e| e.to_string())?;
+
+    write(out, &distribution)?;
+
+    Ok(format!(
+        "Dealt {}-of-{} to {}.",
+        threshold,
+        publickeys.len(),
+        out.display()
+    ))
+}
+
+pub fn verify_distribution(distribution: &Path) -> Result<String, String> {
+    let distribution: DistributionShareBox = read(distribution)?;
+
+    if Participant::new().verify_distribution_shares(&distribution) {
+        let fingerprint: String = distribution
+            .fingerprint()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        Ok(format!(
+            "The distribution is valid, fingerprint {}.",
+            fingerprint
+        ))
+    } else {
+        Err(String::from("the distribution does not verify"))
+    }
+}
+
+/// Decrypts the share of the participant in `key` and writes its share box to `out`.
+pub fn extract(key: &Path, distribution: &Path, out: &Path) -> Result<String, String> {
+    let participant: Participant = read(key)?;
+    let distribution: DistributionShareBox = read(distribution)?;
+
+    if !participant.verify_distribution_shares(&distribution) {
+        return Err(String::from("the distribution does not verify"));
+    }
+
+    let sharebox = participant
+        .extract_secret_share(&distribution, &participant.privatekey)
+        .map_err(|e| e.to_string())?;
+
+    write(out, &sharebox)?;
+
+    Ok(format!("Wrote {}.", out.display()))
+}
+
+pub fn verify_share(distribution: &Path, share: &Path) -> Result<String, String> {
+    let distribution: DistributionShareBox = read(distribution)?;
+    let sharebox: ShareBox = read(share)?;
+
+    if Participant::new().verify_share(&sharebox, &distribution, &sharebox.publickey) {
+        Ok(String::from("The share box is valid."))
+    } else {
+        Err(String::from("the share box does not verify"))
+    }
+}
+
+/// Reconstructs the secret from the share boxes in `shares` and writes its bytes to `out`.
+pub fn reconstruct(distribution: &Path, shares: &[PathBuf], out: &Path) -> Result<String, String> {
+    let distribution: DistributionShareBox = read(distribution)?;
+    let verifier = Participant::new();
+    let mut share_boxes: Vec<ShareBox> = Vec::new();
+
+    for path in shares {
+        let sharebox: ShareBox = read(path)?;
+
+        if !verifier.verify_share(&sharebox, &distribution, &sharebox.publickey) {
+            return Err(format!("{}: the share box does not verify", path.display()));
+        }
+
+        share_boxes.push(sharebox);
+    }
+
+    let secret = verifier
+        .reconstruct(&share_boxes, &distribution)
+        .map_err(|e| e.to_string())?;
+    let len = distribution
+        .secret_len
+        .unwrap_or_else(|| secret.to_bytes_be().1.len());
+    let secret = secret_to_bytes(&secret, len).map_err(|e| e.to_string())?;
+
+    std::fs::write(out, secret).map_err(|e| format!("{}: {}", out.display(), e))?;
+
+    Ok(format!("Wrote {}.", out.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{extract, keygen, reconstruct, split, verify_distribution, verify_share};
+
+    #[test]
+    fn test_share_files() {
+        let dir = std::env::temp_dir().join(format!("vss-commands-{}", std::process::id()));
+        let path = |name: &str| dir.join(name);
+
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for i in 1..=3 {
+            keygen(
+                &path(&format!("key-{}.json", i)),
+                &path(&format!("key-{}.pub", i)),
+                false,
+            )
+            .unwrap();
+        }
+
+        // an existing key is kept unless forced
+        let key = std::fs::read(path("key-1.json")).unwrap();
+
+        assert!(keygen(&path("key-1.json"), &path("key-1.pub"), false).is_err());
+        assert_eq!(std::fs::read(path("key-1.json")).unwrap(), key);
+        keygen(&path("key-3.json"), &path("key-3.pub"), true).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            for i in 1..=3 {
+                let metadata = std::fs::metadata(path(&format!("key-{}.json", i))).unwrap();
+
+                assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+            }
+        }
+
+        let publickeys: Vec<PathBuf> = (1..=3).map(|i| path(&format!("key-{}.pub", i))).collect();
+        let secret = [0, 0, 1, 2, 3, 255];
+
+        std::fs::write(path("secret.bin"), secret).unwrap();
+        split(
+            &path("secret.bin"),
+            &publickeys,
+            2,
+            &path("distribution.json"),
+        )
+        .unwrap();
+        verify_distribution(&path("distribution.json")).unwrap();
+        extract(
+            &path("key-1.json"),
+            &path("distribution.json"),
+            &path("share-1.json"),
+        )
+        .unwrap();
+        extract(
+            &path("key-2.json"),
+            &path("distribution.json"),
+            &path("share-2.cbor"),
+        )
+        .unwrap();
+        verify_share(&path("distribution.json"), &path("share-2.cbor")).unwrap();
+
+        // a share box is bound to the distribution it was extracted from
+        split(&path("secret.bin"), &publickeys, 2, &path("other.json")).unwrap();
+        assert!(verify_share(&path("other.json"), &path("share-1.json")).is_err());
+
+        reconstruct(
+            &path("distribution.json"),
+            &[path("share-1.json"), path("share-2.cbor")],
+            &path("reconstructed.bin"),
+        )
+        .unwrap();
+
+        assert_eq!(std::fs::read(path("reconstructed.bin")).unwrap(), secret);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,164 @@
+use std::{io, path::PathBuf, process::ExitCode};
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+mod bench;
+mod ceremony;
+mod commands;
+
+#[derive(Parser)]
+#[command(name = "vss", version, about = "Publicly verifiable secret sharing")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run standardized workloads and print a performance report
+    Bench {
+        /// Committee sizes to benchmark
+        #[arg(long, value_delimiter = ',', default_value = "10,100")]
+        participants: Vec<usize>,
+        /// Groups to benchmark, `bls12_381` requires the bls12_381 feature
+        #[arg(long, value_delimiter = ',', default_value = "2048")]
+        group: Vec<String>,
+        /// Challenge hashes to benchmark, `blake3` requires the blake3 feature
+        #[arg(long, value_delimiter = ',', default_value = "sha256")]
+        hash: Vec<String>,
+        /// Report format
+        #[arg(long, value_enum, default_value_t = Format::Markdown)]
+        format: Format,
+    },
+    /// Interactively deal a ceremony and later reconstruct it, resumable from its directory
+    Ceremony {
+        /// Directory holding the ceremony state and the participant packages
+        #[arg(long, default_value = "vss-ceremony")]
+        dir: PathBuf,
+    },
+    /// Generate a key pair
+    Keygen {
+        /// Where to write the key file, which holds the private key
+        #[arg(long)]
+        out: PathBuf,
+        /// Where to write the public key, for the dealer
+        #[arg(long)]
+        public_out: PathBuf,
+        /// Replace an existing key file
+        #[arg(long)]
+        force: bool,
+    },
+    /// Deal the bytes of a file to the holders of the given public keys
+    Split {
+        /// File with the secret
+        #[arg(long)]
+        secret: PathBuf,
+        /// Public key files of the participants
+        #[arg(long, value_delimiter = ',', required = true)]
+        publickeys: Vec<PathBuf>,
+        /// Number of share boxes needed to reconstruct
+        #[arg(long)]
+        threshold: u32,
+        /// Where to write the distribution
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Check the proofs of a distribution
+    VerifyDistribution {
+        #[arg(long)]
+        distribution: PathBuf,
+    },
+    /// Decrypt a participant's share of a distribution into a share box
+    Extract {
+        /// Key file of the participant
+        #[arg(long)]
+        key: PathBuf,
+        #[arg(long)]
+        distribution: PathBuf,
+        /// Where to write the share box
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Check the proof of a share box against its distribution
+    VerifyShare {
+        #[arg(long)]
+        distribution: PathBuf,
+        #[arg(long)]
+        share: PathBuf,
+    },
+    /// Reconstruct the secret from share boxes
+    Reconstruct {
+        #[arg(long)]
+        distribution: PathBuf,
+        /// Share box files, at least the threshold
+        #[arg(long, value_delimiter = ',', required = true)]
+        shares: Vec<PathBuf>,
+        /// Where to write the secret
+        #[arg(long)]
+        out: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Json,
+    Markdown,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        Command::Bench {
+            participants,
+            group,
+            hash,
+            format,
+        } => bench::run(&participants, &group, &hash).map(|report| match format {
+            Format::Json => report.to_json(),
+            Format::Markdown => report.to_markdown(),
+        }),
+        Command::Ceremony { dir } => {
+            ceremony::run(&dir, io::stdin().lock(), io::stdout()).map(|()| String::new())
+        }
+        Command::Keygen {
+            out,
+            public_out,
+            force,
+        } => commands::keygen(&out, &public_out, force),
+        Command::Split {
+            secret,
+            publickeys,
+            threshold,
+            out,
+        } => commands::split(&secret, &publickeys, threshold, &out),
+        Command::VerifyDistribution { distribution } => {
+            commands::verify_distribution(&distribution)
+        }
+        Command::Extract {
+            key,
+            distribution,
+            out,
+        } => commands::extract(&key, &distribution, &out),
+        Command::VerifyShare {
+            distribution,
+            share,
+        } => commands::verify_share(&distribution, &share),
+        Command::Reconstruct {
+            distribution,
+            shares,
+            out,
+        } => commands::reconstruct(&distribution, &shares, &out),
+    };
+
+    match result {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use bls12_381::{G1Affine, G1Projective, Scalar};
+use rand::RngCore;
+
+use crate::group::CyclicGroup;
+
+// BLS12-381 backend
+
+// The G1 group of BLS12-381, so that shared secrets can later serve as threshold BLS keys.
+// Elements are encoded in the compressed form of the Zcash serialization used by Ethereum
+// consensus tooling, and commitments to polynomial coefficients are G1 points g^a_j. Plain hashes
+// absorb the generators of DLEQ statements too, as there are no older transcripts to keep.
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Bls12381G1;
+
+impl Bls12381G1 {
+    pub fn generator() -> G1Projective {
+        G1Projective::generator()
+    }
+
+    /// Commitments C_j = g^a_j to the coefficients of a polynomial.
+    pub fn commit(coefficients: &[Scalar]) -> Vec<G1Projective> {
+        coefficients
+            .iter()
+            .map(|coefficient| Bls12381G1::generator() * coefficient)
+            .collect()
+    }
+
+    /// X_i = prod_j C_j^(i^j), the commitment to the value of the polynomial at `position`.
+    pub fn evaluate_commitments(commitments: &[G1Projective], position: u64) -> G1Projective {
+        let position = Scalar::from(position);
+        let mut exponent = Scalar::one();
+        let mut x = G1Projective::identity();
+
+        for commitment in commitments {
+            x += commitment * exponent;
+            exponent *= position;
+        }
+
+        x
+    }
+}
+
+impl CyclicGroup for Bls12381G1 {
+    type Element = G1Projective;
+    type Scalar = Scalar;
+
+    fn exp(&self, base: &G1Projective, exponent: &Scalar) -> G1Projective {
+        base * exponent
+    }
+
+    fn mul(&self, a: &G1Projective, b: &G1Projective) -> G1Projective {
+        a + b
+    }
+
+    fn scalar_sub(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a - b
+    }
+
+    fn scalar_mul(&self, a: &Scalar, b: &Scalar) -> Scalar {
+        a * b
+    }
+
+    fn random_scalar(&self) -> Scalar {
+        let mut bytes = [0u8; 64];
+
+        rand::thread_rng().fill_bytes(&mut bytes);
+
+        Scalar::from_bytes_wide(&bytes)
+    }
+
+    // the hash is read as a little-endian integer and reduced modulo the group order
+    fn scalar_from_hash(&self, hash: &[u8]) -> Scalar {
+        let mut bytes = [0u8; 64];
+        let len = hash.len().min(64);
+
+        bytes[..len].copy_from_slice(&hash[..len]);
+
+        Scalar::from_bytes_wide(&bytes)
+    }
+
+    fn element_to_bytes(&self, element: &G1Projective) -> Vec<u8> {
+        G1Affine::from(element).to_compressed().to_vec()
+    }
+
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<G1Projective> {
+        let bytes: [u8; 48] = bytes.try_into().ok()?;
+
+        Option::<G1Affine>::from(G1Affine::from_compressed(&bytes)).map(G1Projective::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{G1Projective, Scalar};
+    use sha2::{Digest, Sha256};
+
+    use crate::{dleq::DLEQ, group::CyclicGroup};
+
+    use super::Bls12381G1;
+
+    #[test]
+    fn test_commitments() {
+        let coefficients = [Scalar::from(7), Scalar::from(3), Scalar::from(5)];
+        let commitments = Bls12381G1::commit(&coefficients);
+
+        // f(2) = 7 + 3 * 2 + 5 * 4 = 33
+        assert_eq!(
+            Bls12381G1::evaluate_commitments(&commitments, 2),
+            Bls12381G1::generator() * Scalar::from(33)
+        );
+    }
+
+    #[test]
+    fn test_encoding() {
+        let group = Bls12381G1;
+        let element = Bls12381G1::generator() * group.random_scalar();
+        let bytes = group.element_to_bytes(&element);
+
+        assert_eq!(bytes.len(), 48);
+        assert_eq!(group.element_from_bytes(&bytes), Some(element));
+        assert_eq!(group.element_from_bytes(&bytes[1..]), None);
+        assert_eq!(group.element_from_bytes(&[0xff; 48]), None::<G1Projective>);
+    }
+
+    #[test]
+    fn test_dleq() {
+        let group = Bls12381G1;
+        let alpha = group.random_scalar();
+        let g1 = Bls12381G1::generator();
+        let g2 = g1 * Scalar::from(1337);
+        let mut prover = DLEQ::with_group(
+            group,
+            g1,
+            group.exp(&g1, &alpha),
+            g2,
+            group.exp(&g2, &alpha),
+            alpha,
+            group.random_scalar(),
+        );
+        let challenge = Sha256::new()
+            .chain(group.element_to_bytes(&prover.g1))
+            .chain(group.element_to_bytes(&prover.h1))
+            .chain(group.element_to_bytes(&prover.g2))
+            .chain(group.element_to_bytes(&prover.h2))
+            .chain(group.element_to_bytes(&prover.get_a1()))
+            .chain(group.element_to_bytes(&prover.get_a2()))
+            .finalize();
+
+        prover.c = Some(group.scalar_from_hash(&challenge[..]));
+        prover.r = prover.get_r();
+
+        let mut hasher = Sha256::new();
+
+        prover.update_hash(&mut hasher);
+        assert!(prover.check(&hasher));
+
+        prover.h2 = group.exp(&g2, &group.random_scalar());
+
+        let mut hasher = Sha256::new();
+
+        prover.update_hash(&mut hasher);
+        assert!(!prover.check(&hasher));
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::BTreeMap;
+
+use bls12_381::{
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    pairing, G1Affine, G1Projective, G2Affine, G2Projective, Scalar,
+};
+use num_bigint::BigInt;
+
+use crate::{bls::Bls12381G1, error::VssError, group::CyclicGroup};
+
+// Threshold BLS signatures
+
+// A shared BLS12-381 scalar x signs like any BLS key, with public key g1^x in G1 and signatures
+// H(m)^x in G2, the minimal-pubkey-size variant. Shareholder i holds p(i) and signs with it alone;
+// its signature share is checked against the commitment X_i = g1^p(i) by pairing, and any
+// `threshold` shares interpolate in the exponent to H(m)^p(0). BLS signatures are unique, so
+// every set of shares gives the same signature; aggregation takes those at the lowest `threshold`
+// positions and ignores the rest, so that extra shares cost nothing.
+//
+// A constrained shareholder, such as a microcontroller, checks its own key share against the
+// commitments with `BoundedKeyCommitments`, which decodes them into a fixed array and evaluates
+// them in place, so memory is bounded by the largest threshold it accepts and nothing allocates.
+
+/// The hash-to-curve domain separation tag of the basic scheme of the IETF BLS draft.
+pub const BLS_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// A shareholder's share p(i) of a BLS key, at its position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlsKeyShare {
+    pub position: u64,
+    pub secret: Scalar,
+}
+
+/// A signature share H(m)^p(i), at the signer's position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlsSignatureShare {
+    pub position: u64,
+    pub signature: G2Projective,
+}
+
+impl Bls12381G1 {
+    /// Shares the BLS key `secret` among positions `1..=n` with `threshold`, returning the
+    /// commitments, the first of which is the public key, and the key shares.
+    pub fn deal_key(
+        secret: &Scalar,
+        threshold: usize,
+        n: usize,
+    ) -> Result<(Vec<G1Projective>, Vec<BlsKeyShare>), VssError> {
+        if threshold == 0 || threshold > n {
+            return Err(VssError::InvalidThreshold {
+                threshold: threshold as u32,
+                participants: n,
+            });
+        }
+
+        let coefficients: Vec<Scalar> = std::iter::once(*secret)
+            .chain((1..threshold).map(|_| Bls12381G1.random_scalar()))
+            .collect();
+        let shares = (1..=n as u64)
+            .map(|position| {
+                let x = Scalar::from(position);
+                let secret = coefficients
+                    .iter()
+                    .rev()
+                    .fold(Scalar::zero(), |value, coefficient| value * x + coefficient);
+
+                BlsKeyShare { position, secret }
+            })
+            .collect();
+
+        Ok((Bls12381G1::commit(&coefficients), shares))
+    }
+
+    /// H(m) in G2 under `BLS_DST`.
+    pub fn hash_to_g2(message: &[u8]) -> G2Projective {
+        <G2Projective as HashToCurve<ExpandMsgXmd<sha2::Sha256>>>::hash_to_curve(message, BLS_DST)
+    }
+
+    /// Signs `message` with the whole key, as a single signer would.
+    pub fn sign(secret: &Scalar, message: &[u8]) -> G2Projective {
+        Bls12381G1::hash_to_g2(message) * secret
+    }
+
+    /// Signs `message` with a key share.
+    pub fn sign_share(share: &BlsKeyShare, message: &[u8]) -> BlsSignatureShare {
+        BlsSignatureShare {
+            position: share.position,
+            signature: Bls12381G1::sign(&share.secret, message),
+        }
+    }
+
+    /// Checks `signature` on `message` under `public_key`: e(g1, signature) = e(public_key, H(m)).
+    pub fn verify_signature(
+        public_key: &G1Projective,
+        message: &[u8],
+        signature: &G2Projective,
+    ) -> bool {
+        pairing(&G1Affine::generator(), &G2Affine::from(signature))
+            == pairing(
+                &G1Affine::from(public_key),
+                &G2Affine::from(Bls12381G1::hash_to_g2(message)),
+            )
+    }
+
+    /// Checks a signature share against the signer's commitment X_i, evaluated from the
+    /// commitments of the key.
+    pub fn verify_signature_share(
+        commitments: &[G1Projective],
+        message: &[u8],
+        share: &BlsSignatureShare,
+    ) -> bool {
+        share.position != 0
+            && Bls12381G1::verify_signature(
+                &Bls12381G1::evaluate_commitments(commitments, share.position),
+                message,
+                &share.signature,
+            )
+    }
+
+    /// Combines the `threshold` signature shares at the lowest positions into the signature of
+    /// the key. The shares are not checked here; verify them with `verify_signature_share` first,
+    /// or the result with `verify_signature`.
+    pub fn aggregate(
+        threshold: usize,
+        shares: &[BlsSignatureShare],
+    ) -> Result<G2Projective, VssError> {
+        let weighted = lowest_positions(
+            threshold,
+            shares.iter().map(|share| (share.position, share.signature)),
+        )?;
+
+        Ok(weighted
+            .iter()
+            .fold(G2Projective::identity(), |signature, (lambda, share)| {
+                signature + share * lambda
+            }))
+    }
+}
+
+/// The commitments of a dealt BLS key in a fixed array of capacity `N`, so that a dealing with a
+/// threshold above `N` is refused rather than allocated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundedKeyCommitments<const N: usize> {
+    commitments: [G1Projective; N],
+    len: usize,
+}
+
+impl<const N: usize> BoundedKeyCommitments<N> {
+    /// Copies `commitments`, or `None` if there are more than `N` of them.
+    pub fn new(commitments: &[G1Projective]) -> Option<Self> {
+        if commitments.len() > N {
+            return None;
+        }
+
+        let mut bounded = [G1Projective::identity(); N];
+
+        bounded[..commitments.len()].copy_from_slice(commitments);
+
+        Some(BoundedKeyCommitments {
+            commitments: bounded,
+            len: commitments.len(),
+        })
+    }
+
+    /// Decodes commitments concatenated in the 48 byte compressed encoding of
+    /// `Bls12381G1::element_to_bytes`, or `None` if there are more than `N` of them or one is not a
+    /// point of G1.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if !bytes.len().is_multiple_of(48) || bytes.len() / 48 > N {
+            return None;
+        }
+
+        let mut commitments = [G1Projective::identity(); N];
+
+        for (commitment, encoded) in commitments.iter_mut().zip(bytes.chunks_exact(48)) {
+            *commitment = Bls12381G1.element_from_bytes(encoded)?;
+        }
+
+        Some(BoundedKeyCommitments {
+            commitments,
+            len: bytes.len() / 48,
+        })
+    }
+
+    pub fn commitments(&self) -> &[G1Projective] {
+        &self.commitments[..self.len]
+    }
+
+    /// The public key of the dealt key, the first commitment.
+    pub fn public_key(&self) -> Option<&G1Projective> {
+        self.commitments().first()
+    }
+
+    /// Checks `share` against the commitments, g1^p(i) = X_i.
+    pub fn verify_share(&self, share: &BlsKeyShare) -> bool {
+        share.position != 0
+            && self.len > 0
+            && Bls12381G1::generator() * share.secret
+                == Bls12381G1::evaluate_commitments(self.commitments(), share.position)
+    }
+}
+
+// the values at the `threshold` lowest of `values`' positions, which must be distinct and nonzero,
+// each with its Lagrange coefficient at 0 among them
+pub(crate) fn lowest_positions<T>(
+    threshold: usize,
+    values: impl IntoIterator<Item = (u64, T)>,
+) -> Result<Vec<(Scalar, T)>, VssError> {
+    let mut by_position = BTreeMap::new();
+
+    for (position, value) in values {
+        if position == 0 {
+            return Err(VssError::InvalidPosition {
+                position: BigInt::from(position),
+            });
+        }
+
+        if by_position.insert(position, value).is_some() {
+            return Err(VssError::DuplicatePosition {
+                position: BigInt::from(position),
+            });
+        }
+    }
+
+    if threshold == 0 || by_position.len() < threshold {
+        return Err(VssError::ThresholdNotMet {
+            required: threshold,
+            provided: by_position.len(),
+        });
+    }
+
+    let positions: Vec<Scalar> = by_position
+        .keys()
+        .take(threshold)
+        .map(|&position| Scalar::from(position))
+        .collect();
+
+    Ok(by_position
+        .into_values()
+        .zip(&positions)
+        .map(|(value, i)| {
+            // lambda_i = prod_{j != i} j / (j - i), invertible as positions are distinct
+            let (numerator, denominator) = positions.iter().filter(|j| *j != i).fold(
+                (Scalar::one(), Scalar::one()),
+                |(numerator, denominator), j| (numerator * j, denominator * (j - i)),
+            );
+
+            (numerator * denominator.invert().unwrap(), value)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::{G2Projective, Scalar};
+
+    use crate::{bls::Bls12381G1, error::VssError, group::CyclicGroup};
+
+    use super::{BlsSignatureShare, BoundedKeyCommitments};
+
+    #[test]
+    fn test_bounded_key_commitments() {
+        let (commitments, shares) = Bls12381G1::deal_key(&Scalar::from(0x5ec2e7), 3, 5).unwrap();
+        let bytes: Vec<u8> = commitments
+            .iter()
+            .flat_map(|commitment| Bls12381G1.element_to_bytes(commitment))
+            .collect();
+        let bounded = BoundedKeyCommitments::<4>::from_bytes(&bytes).unwrap();
+
+        assert_eq!(BoundedKeyCommitments::<4>::new(&commitments), Some(bounded));
+        assert_eq!(bounded.commitments(), &commitments[..]);
+        assert_eq!(bounded.public_key(), Some(&commitments[0]));
+        assert!(shares.iter().all(|share| bounded.verify_share(share)));
+
+        let mut share = shares[0];
+
+        share.secret += Scalar::one();
+        assert!(!bounded.verify_share(&share));
+
+        share = shares[0];
+        share.position = 0;
+        assert!(!bounded.verify_share(&share));
+
+        // a threshold above the capacity, a truncated commitment, or one off the curve
+        assert_eq!(BoundedKeyCommitments::<2>::new(&commitments), None);
+        assert_eq!(BoundedKeyCommitments::<2>::from_bytes(&bytes), None);
+        assert_eq!(BoundedKeyCommitments::<4>::from_bytes(&bytes[1..]), None);
+        assert_eq!(BoundedKeyCommitments::<4>::from_bytes(&[0xff; 48]), None);
+        assert!(!BoundedKeyCommitments::<4>::new(&[])
+            .unwrap()
+            .verify_share(&shares[0]));
+    }
+
+    #[test]
+    fn test_threshold_signature() {
+        let secret = Scalar::from(0x5ec2e7);
+        let (commitments, shares) = Bls12381G1::deal_key(&secret, 3, 5).unwrap();
+        let public_key = commitments[0];
+        let message = b"block 1024";
+        let signature_shares: Vec<BlsSignatureShare> = shares
+            .iter()
+            .map(|share| Bls12381G1::sign_share(share, message))
+            .collect();
+
+        assert!(signature_shares
+            .iter()
+            .all(|share| Bls12381G1::verify_signature_share(&commitments, message, share)));
+
+        // any three shares give the signature of the whole key
+        let signature = Bls12381G1::aggregate(3, &signature_shares[2..]).unwrap();
+
+        assert_eq!(signature, Bls12381G1::sign(&secret, message));
+        assert_eq!(
+            Bls12381G1::aggregate(
+                3,
+                &[
+                    signature_shares[4],
+                    signature_shares[0],
+                    signature_shares[3]
+                ]
+            ),
+            Ok(signature)
+        );
+        assert!(Bls12381G1::verify_signature(
+            &public_key,
+            message,
+            &signature
+        ));
+        assert!(!Bls12381G1::verify_signature(
+            &public_key,
+            b"block 1025",
+            &signature
+        ));
+        assert_eq!(
+            Bls12381G1::aggregate(3, &signature_shares[..2]),
+            Err(VssError::ThresholdNotMet {
+                required: 3,
+                provided: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_signature_share() {
+        let (commitments, shares) = Bls12381G1::deal_key(&Scalar::from(7), 2, 3).unwrap();
+        let message = b"epoch 9";
+        let mut share = Bls12381G1::sign_share(&shares[0], message);
+
+        assert!(!Bls12381G1::verify_signature_share(
+            &commitments,
+            b"epoch 10",
+            &share
+        ));
+
+        share.position = 2;
+
+        assert!(!Bls12381G1::verify_signature_share(
+            &commitments,
+            message,
+            &share
+        ));
+        assert_eq!(
+            Bls12381G1::aggregate(2, &[share, share]),
+            Err(VssError::DuplicatePosition { position: 2.into() })
+        );
+
+        share.signature = G2Projective::identity();
+        share.position = 0;
+
+        assert!(!Bls12381G1::verify_signature_share(
+            &commitments,
+            message,
+            &share
+        ));
+        assert!(Bls12381G1::deal_key(&Scalar::from(7), 4, 3).is_err());
+    }
+}
@@ -0,0 +1,391 @@
+use num_bigint::BigInt;
+use num_traits::{One, Signed};
+
+use crate::{
+    error::VssError,
+    participant::Participant,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Committees
+
+// Distributions, share boxes and errors all name shareholders by public key, and their positions
+// live in a map keyed by it, so applications end up keeping the name of every member next to its
+// key and position in parallel lists. A `Committee` keeps the three together, in the order members
+// joined, checks that none of them is taken twice, and translates between them: dealing to the
+// committee gives every member its position, and reconstruction takes the share boxes of members
+// only and checks the distribution against the committee first.
+
+/// A shareholder of a committee: an application-chosen id, its public key and its position.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Member {
+    pub id: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::position"))]
+    pub position: BigInt,
+}
+
+/// Members with distinct ids, public keys and positions, in the order they were added. It
+/// serializes as the list of its members, which are added again one by one when read back, so
+/// that a committee from elsewhere passes the same checks.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "Vec<Member>", into = "Vec<Member>")
+)]
+pub struct Committee {
+    members: Vec<Member>,
+}
+
+impl TryFrom<Vec<Member>> for Committee {
+    type Error = VssError;
+
+    fn try_from(members: Vec<Member>) -> Result<Self, VssError> {
+        let mut committee = Committee::new();
+
+        for member in members {
+            committee.add_at(&member.id, &member.publickey, member.position)?;
+        }
+
+        Ok(committee)
+    }
+}
+
+impl From<Committee> for Vec<Member> {
+    fn from(committee: Committee) -> Self {
+        committee.members
+    }
+}
+
+impl Committee {
+    pub fn new() -> Self {
+        Committee {
+            members: Vec::new(),
+        }
+    }
+
+    /// Adds a member at the position after the highest one taken, 1 for the first.
+    pub fn add(&mut self, id: &str, publickey: &BigInt) -> Result<&Member, VssError> {
+        let position = self
+            .members
+            .iter()
+            .map(|member| &member.position)
+            .max()
+            .map_or_else(BigInt::one, |position| position + 1);
+
+        self.add_at(id, publickey, position)
+    }
+
+    /// Adds a member at `position`, which must be positive and not taken.
+    pub fn add_at(
+        &mut self,
+        id: &str,
+        publickey: &BigInt,
+        position: impl Into<BigInt>,
+    ) -> Result<&Member, VssError> {
+        let position = position.into();
+
+        if self.get(id).is_some() {
+            return Err(VssError::DuplicateMember { id: id.to_string() });
+        }
+
+        if self.by_publickey(publickey).is_some() {
+            return Err(VssError::DuplicatePublicKey {
+                publickey: publickey.clone(),
+            });
+        }
+
+        if !position.is_positive() {
+            return Err(VssError::InvalidPosition { position });
+        }
+
+        if self.by_position(&position).is_some() {
+            return Err(VssError::DuplicatePosition { position });
+        }
+
+        self.members.push(Member {
+            id: id.to_string(),
+            publickey: publickey.clone(),
+            position,
+        });
+
+        Ok(self.members.last().unwrap())
+    }
+
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Member> {
+        self.members.iter().find(|member| member.id == id)
+    }
+
+    pub fn by_publickey(&self, publickey: &BigInt) -> Option<&Member> {
+        self.members
+            .iter()
+            .find(|member| &member.publickey == publickey)
+    }
+
+    pub fn by_position(&self, position: &BigInt) -> Option<&Member> {
+        self.members
+            .iter()
+            .find(|member| &member.position == position)
+    }
+
+    /// The public keys of the members, in order.
+    pub fn publickeys(&self) -> Vec<BigInt> {
+        self.members
+            .iter()
+            .map(|member| member.publickey.clone())
+            .collect()
+    }
+
+    /// Every member's public key with its position, in order, as taken by
+    /// `distribute_secret_with_positions`.
+    pub fn recipients(&self) -> Vec<(BigInt, BigInt)> {
+        self.members
+            .iter()
+            .map(|member| (member.publickey.clone(), member.position.clone()))
+            .collect()
+    }
+
+    /// The share box of the member `id` among `share_boxes`.
+    pub fn share_of<'a>(&self, id: &str, share_boxes: &'a [ShareBox]) -> Option<&'a ShareBox> {
+        let member = self.get(id)?;
+
+        share_boxes
+            .iter()
+            .find(|share_box| share_box.publickey == member.publickey)
+    }
+
+    /// The ids of the members without a share box among `share_boxes`, in order.
+    pub fn missing(&self, share_boxes: &[ShareBox]) -> Vec<&str> {
+        self.members
+            .iter()
+            .filter(|member| {
+                !share_boxes
+                    .iter()
+                    .any(|share_box| share_box.publickey == member.publickey)
+            })
+            .map(|member| member.id.as_str())
+            .collect()
+    }
+
+    /// Checks that `distribution_sharebox` was dealt to exactly this committee, every member at
+    /// its own position.
+    pub fn check_distribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), VssError> {
+        if let Some(publickey) = distribution_sharebox
+            .publickeys
+            .iter()
+            .find(|publickey| self.by_publickey(publickey).is_none())
+        {
+            return Err(VssError::UnknownShareholder {
+                publickey: publickey.clone(),
+            });
+        }
+
+        match self.members.iter().find(|member| {
+            distribution_sharebox.positions.get(&member.publickey) != Some(&member.position)
+        }) {
+            Some(member) => Err(VssError::CommitteeMismatch {
+                id: member.id.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Reconstructs the secret of a distribution to this committee from share boxes of its
+    /// members; a share box of anybody else fails.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<BigInt, VssError> {
+        self.check_distribution(distribution_sharebox)?;
+
+        if let Some(share_box) = share_boxes
+            .iter()
+            .find(|share_box| self.by_publickey(&share_box.publickey).is_none())
+        {
+            return Err(VssError::UnknownShareholder {
+                publickey: share_box.publickey.clone(),
+            });
+        }
+
+        vss.reconstruct(share_boxes, distribution_sharebox)
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Like `distribute_secret`, to every member of `committee` at its position.
+    pub fn distribute_to_committee(
+        &mut self,
+        secret: &BigInt,
+        committee: &Committee,
+        threshold: u32,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_secret_with_positions(secret, &committee.recipients(), threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::{Committee, Member};
+
+    #[test]
+    fn test_committee() {
+        let ceremony = fixed_ceremony(3, 2, 127).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let mut committee = Committee::new();
+
+        committee.add("alice", &participants[0].publickey).unwrap();
+        committee
+            .add_at("bob", &participants[1].publickey, 5)
+            .unwrap();
+        committee.add("carol", &participants[2].publickey).unwrap();
+
+        assert_eq!(committee.len(), 3);
+        assert_eq!(committee.get("carol").unwrap().position, BigInt::from(6));
+        assert_eq!(
+            committee.by_position(&BigInt::from(5)).unwrap().id,
+            "bob".to_string()
+        );
+        assert_eq!(
+            committee.add("alice", &BigInt::from(2)),
+            Err(VssError::DuplicateMember {
+                id: "alice".to_string()
+            })
+        );
+        assert_eq!(
+            committee.add("dave", &participants[1].publickey),
+            Err(VssError::DuplicatePublicKey {
+                publickey: participants[1].publickey.clone()
+            })
+        );
+        assert_eq!(
+            committee.add_at("dave", &BigInt::from(2), 1),
+            Err(VssError::DuplicatePosition {
+                position: BigInt::from(1)
+            })
+        );
+        assert_eq!(committee.len(), 3);
+
+        let secret = BigInt::from(1234567);
+        let distribution = ceremony
+            .dealer
+            .clone()
+            .distribute_to_committee(&secret, &committee, 2)
+            .unwrap();
+
+        assert_eq!(committee.check_distribution(&distribution), Ok(()));
+
+        let shares: Vec<_> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            committee.share_of("bob", &shares).unwrap().publickey,
+            participants[1].publickey
+        );
+        assert_eq!(committee.missing(&shares[1..]), vec!["alice"]);
+        // positions 1 and 6
+        assert_eq!(
+            committee.reconstruct(vss, &[shares[0].clone(), shares[2].clone()], &distribution),
+            Ok(secret)
+        );
+
+        // the ceremony's own distribution puts bob at 2
+        assert_eq!(
+            committee.check_distribution(&ceremony.distribution),
+            Err(VssError::CommitteeMismatch {
+                id: "bob".to_string()
+            })
+        );
+
+        let mut smaller = Committee::new();
+
+        smaller.add("alice", &participants[0].publickey).unwrap();
+
+        assert_eq!(
+            smaller.check_distribution(&distribution),
+            Err(VssError::UnknownShareholder {
+                publickey: participants[1].publickey.clone()
+            })
+        );
+    }
+
+    #[test]
+    fn test_from_members() {
+        let member = |id: &str, publickey: i64, position: i64| Member {
+            id: id.to_string(),
+            publickey: BigInt::from(publickey),
+            position: BigInt::from(position),
+        };
+        let committee =
+            Committee::try_from(vec![member("alice", 2, 1), member("bob", 3, 4)]).unwrap();
+
+        assert_eq!(committee.get("bob").unwrap().position, BigInt::from(4));
+        assert_eq!(
+            Vec::from(committee.clone()),
+            vec![member("alice", 2, 1), member("bob", 3, 4)]
+        );
+        assert_eq!(
+            Committee::try_from(vec![member("alice", 2, 1), member("alice", 3, 2)]),
+            Err(VssError::DuplicateMember {
+                id: "alice".to_string()
+            })
+        );
+        assert_eq!(
+            Committee::try_from(vec![member("alice", 2, 1), member("bob", 2, 2)]),
+            Err(VssError::DuplicatePublicKey {
+                publickey: BigInt::from(2)
+            })
+        );
+        assert_eq!(
+            Committee::try_from(vec![member("alice", 2, 0)]),
+            Err(VssError::InvalidPosition {
+                position: BigInt::from(0)
+            })
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&committee).unwrap();
+
+            assert_eq!(serde_json::from_str::<Committee>(&json).unwrap(), committee);
+
+            // a list with a position taken twice is refused rather than taken as it is
+            let json =
+                serde_json::to_string(&vec![member("alice", 2, 1), member("bob", 3, 1)]).unwrap();
+            let error = serde_json::from_str::<Committee>(&json).unwrap_err();
+
+            assert!(error.to_string().contains("position"));
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::BTreeSet;
+
+use num_bigint::{BigInt, ToBigInt};
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::VssError,
+    multi::MaskedSecret,
+    participant::{sequential_positions, Participant},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Compartmented access structures
+
+// "2 of ops AND 1 of security": every compartment must reach its own threshold. Each compartment
+// is dealt a random polynomial p_c of its own, as an ordinary distribution to its members, and
+// the secret is masked under the product of the shared values, G^(sum_c p_c(0)). A compartment
+// below its threshold leaves its term, and so the mask, uniform whatever the others pool, and no
+// compartment can stand in for another. Every compartment is proven and verified on its own, and
+// a key belongs to one compartment only, so that share boxes go back to theirs unambiguously.
+
+/// The members of one compartment and how many of them must take part.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Compartment {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub publickeys: Vec<BigInt>,
+    pub threshold: u32,
+}
+
+/// A secret that takes the threshold of every compartment to reconstruct.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompartmentedDistribution {
+    /// one distribution per compartment, in the dealer's order
+    pub compartments: Vec<DistributionShareBox>,
+    /// masked under the product of the compartments' shared values
+    pub secret: MaskedSecret,
+}
+
+impl Compartment {
+    pub fn new(publickeys: &[BigInt], threshold: u32) -> Self {
+        Compartment {
+            publickeys: publickeys.to_vec(),
+            threshold,
+        }
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Distributes `secret` so that it takes `threshold` members of every one of `compartments`.
+    pub fn distribute_compartmented(
+        &mut self,
+        secret: &BigInt,
+        compartments: &[Compartment],
+    ) -> Result<CompartmentedDistribution, VssError> {
+        self.distribute_compartmented_with_rng(secret, compartments, &mut rand::thread_rng())
+    }
+
+    /// Like `distribute_compartmented`, drawing the polynomials and the proof nonces from `rng`.
+    pub fn distribute_compartmented_with_rng(
+        &mut self,
+        secret: &BigInt,
+        compartments: &[Compartment],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<CompartmentedDistribution, VssError> {
+        if compartments.is_empty() {
+            return Err(VssError::NoRecipients);
+        }
+
+        let secret = secret.to_biguint().ok_or(VssError::NegativeSecret)?;
+        let mut seen = BTreeSet::new();
+
+        for publickey in compartments.iter().flat_map(|c| &c.publickeys) {
+            if !seen.insert(publickey) {
+                return Err(VssError::DuplicatePublicKey {
+                    publickey: publickey.clone(),
+                });
+            }
+        }
+
+        let order = &self.vss.q - BigInt::one();
+        let mut shared_value = BigInt::one();
+        let mut distributions = Vec::with_capacity(compartments.len());
+
+        for compartment in compartments {
+            let (distribution, polynomial) = self.deal_polynomial(
+                &BigInt::zero(),
+                &compartment.publickeys,
+                &sequential_positions(compartment.publickeys.len()),
+                compartment.threshold,
+                &[],
+                Some(0),
+                rng,
+            )?;
+            let value = self.vss.exp_G(
+                &self
+                    .vss
+                    .blind(&polynomial.get_value_mod(&BigInt::zero(), &order)),
+            );
+
+            shared_value = (shared_value * value) % &self.vss.q;
+            distributions.push(distribution);
+        }
+
+        let secret_len = secret.to_bytes_be().len();
+
+        Ok(CompartmentedDistribution {
+            compartments: distributions,
+            secret: MaskedSecret {
+                u: (secret ^ self.vss.secret_mask(&shared_value, Some(secret_len)))
+                    .to_bigint()
+                    .unwrap(),
+                secret_len,
+            },
+        })
+    }
+
+    /// This participant's share box, from the compartment that lists it.
+    pub fn extract_compartment_share(
+        &self,
+        compartmented: &CompartmentedDistribution,
+    ) -> Result<ShareBox, VssError> {
+        let distribution = compartmented
+            .compartment_of(&self.publickey)
+            .ok_or_else(|| VssError::MissingShare {
+                publickey: self.publickey.clone(),
+            })?;
+
+        self.extract_secret_share(distribution, &self.privatekey)
+    }
+}
+
+impl CompartmentedDistribution {
+    /// The distribution of the compartment `publickey` belongs to.
+    pub fn compartment_of(&self, publickey: &BigInt) -> Option<&DistributionShareBox> {
+        self.compartments
+            .iter()
+            .find(|distribution| distribution.shares.contains_key(publickey))
+    }
+
+    /// Verifies every compartment, and that no key sits in two of them.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let keys: BTreeSet<&BigInt> = self
+            .compartments
+            .iter()
+            .flat_map(|distribution| &distribution.publickeys)
+            .collect();
+        let listed: usize = self
+            .compartments
+            .iter()
+            .map(|distribution| distribution.publickeys.len())
+            .sum();
+
+        !self.compartments.is_empty()
+            && keys.len() == listed
+            && self
+                .compartments
+                .iter()
+                .all(|distribution| vss.verify_distribution_shares(distribution))
+    }
+
+    /// Reconstructs the secret from share boxes that meet the threshold of every compartment.
+    /// Each share box counts towards its own compartment; one that belongs to none fails.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        if let Some(share_box) = share_boxes
+            .iter()
+            .find(|share_box| self.compartment_of(&share_box.publickey).is_none())
+        {
+            return Err(VssError::UnknownShareholder {
+                publickey: share_box.publickey.clone(),
+            });
+        }
+
+        let mut shared_value = BigInt::one();
+
+        for distribution in &self.compartments {
+            let shares: Vec<ShareBox> = share_boxes
+                .iter()
+                .filter(|share_box| distribution.shares.contains_key(&share_box.publickey))
+                .cloned()
+                .collect();
+
+            shared_value = (shared_value * vss.interpolate(&shares, distribution)?) % &vss.q;
+        }
+
+        Ok(
+            (vss.secret_mask(&shared_value, Some(self.secret.secret_len))
+                ^ self.secret.u.to_biguint().unwrap())
+            .to_bigint()
+            .unwrap(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::Compartment;
+
+    #[test]
+    fn test_compartmented_distribution() {
+        let ceremony = fixed_ceremony(5, 2, 71).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        // 2 of ops AND 1 of security
+        let compartments = [
+            Compartment::new(&publickeys[..3], 2),
+            Compartment::new(&publickeys[3..], 1),
+        ];
+        let secret = BigInt::from(8675309);
+        let compartmented = ceremony
+            .dealer
+            .clone()
+            .distribute_compartmented(&secret, &compartments)
+            .unwrap();
+
+        assert!(compartmented.verify(vss));
+
+        let shares: Vec<_> = participants
+            .iter()
+            .map(|p| p.extract_compartment_share(&compartmented).unwrap())
+            .collect();
+        let quorum = [shares[0].clone(), shares[1].clone(), shares[4].clone()];
+
+        assert_eq!(compartmented.reconstruct(vss, &quorum), Ok(secret));
+
+        // all of ops without security, and security with one of ops
+        assert_eq!(
+            compartmented.reconstruct(vss, &shares[..3]),
+            Err(VssError::ThresholdNotMet {
+                required: 1,
+                provided: 0
+            })
+        );
+        assert_eq!(
+            compartmented.reconstruct(vss, &shares[2..]),
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_compartments() {
+        let ceremony = fixed_ceremony(3, 2, 73).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let publickeys: Vec<BigInt> = ceremony
+            .participants
+            .iter()
+            .map(|p| p.publickey.clone())
+            .collect();
+
+        assert_eq!(
+            ceremony
+                .dealer
+                .clone()
+                .distribute_compartmented(&BigInt::from(1), &[]),
+            Err(VssError::NoRecipients)
+        );
+        assert_eq!(
+            ceremony.dealer.clone().distribute_compartmented(
+                &BigInt::from(1),
+                &[
+                    Compartment::new(&publickeys[..2], 1),
+                    Compartment::new(&publickeys[1..], 1)
+                ]
+            ),
+            Err(VssError::DuplicatePublicKey {
+                publickey: publickeys[1].clone()
+            })
+        );
+        assert_eq!(
+            ceremony
+                .dealer
+                .clone()
+                .distribute_compartmented(&BigInt::from(1), &[Compartment::new(&publickeys, 4)]),
+            Err(VssError::InvalidThreshold {
+                threshold: 4,
+                participants: 3
+            })
+        );
+
+        let mut compartmented = ceremony
+            .dealer
+            .clone()
+            .distribute_compartmented(
+                &BigInt::from(1),
+                &[
+                    Compartment::new(&publickeys[..1], 1),
+                    Compartment::new(&publickeys[1..], 2),
+                ],
+            )
+            .unwrap();
+
+        assert!(compartmented.verify(vss));
+
+        // a share moved to another compartment
+        let moved = compartmented.compartments[0].shares.clone();
+
+        compartmented.compartments[1].shares.extend(moved);
+
+        assert!(!compartmented.verify(vss));
+    }
+}
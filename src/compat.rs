@@ -0,0 +1,128 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use num_bigint::BigInt;
+
+use crate::{
+    sharebox::{DistributionShareBox, ShareBox},
+    vss::VSS,
+};
+
+// Cross-version compatibility corpus
+
+// Every release records complete transcripts (parameters, distribution, extracted shares and the
+// secret) as JSON below a fixtures directory. Later versions load all of them and check that they
+// still verify and reconstruct, so serialization and transcript changes can't silently break
+// shares written by older releases.
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CorpusEntry {
+    /// crate version that produced the transcript
+    pub crate_version: String,
+    pub params: VSS,
+    pub distribution: DistributionShareBox,
+    pub shares: Vec<ShareBox>,
+    #[serde(with = "crate::serde_utils::bigint")]
+    pub secret: BigInt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompatFailure {
+    Distribution,
+    Share(usize),
+    Reconstruction,
+}
+
+impl CorpusEntry {
+    pub fn verify(&self) -> Result<(), CompatFailure> {
+        if !self.params.verify_distribution_shares(&self.distribution) {
+            return Err(CompatFailure::Distribution);
+        }
+
+        for (i, share) in self.shares.iter().enumerate() {
+            if !self
+                .params
+                .verify_share(share, &self.distribution, &share.publickey)
+            {
+                return Err(CompatFailure::Share(i));
+            }
+        }
+
+        match self.params.reconstruct(&self.shares, &self.distribution) {
+            Ok(secret) if secret == self.secret => Ok(()),
+            _ => Err(CompatFailure::Reconstruction),
+        }
+    }
+}
+
+pub fn write_entry<P: AsRef<Path>>(path: P, entry: &CorpusEntry) -> io::Result<()> {
+    let json = serde_json::to_vec_pretty(entry)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    fs::write(path, json)
+}
+
+/// Loads every `*.json` transcript below `dir`, sorted by path.
+pub fn load_corpus<P: AsRef<Path>>(dir: P) -> io::Result<Vec<(PathBuf, CorpusEntry)>> {
+    let mut entries = Vec::new();
+    let mut pending = vec![dir.as_ref().to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        for item in fs::read_dir(dir)? {
+            let path = item?.path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                let entry = serde_json::from_slice(&fs::read(&path)?).map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{}: {}", path.display(), e),
+                    )
+                })?;
+
+                entries.push((path, entry));
+            }
+        }
+    }
+
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_traits::One;
+
+    use super::{CompatFailure, CorpusEntry};
+
+    fn entry() -> CorpusEntry {
+        serde_json::from_str(include_str!(
+            "../tests/fixtures/v0.1.0/modp_small_3_of_4.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_verify_entry() {
+        assert_eq!(entry().verify(), Ok(()));
+
+        let mut tampered = entry();
+        tampered.distribution.challenge += 1;
+        assert_eq!(tampered.verify(), Err(CompatFailure::Distribution));
+
+        let mut tampered = entry();
+        tampered.shares[1].response += 1;
+        assert_eq!(tampered.verify(), Err(CompatFailure::Share(1)));
+
+        let mut tampered = entry();
+        tampered.secret += num_bigint::BigInt::one();
+        assert_eq!(tampered.verify(), Err(CompatFailure::Reconstruction));
+    }
+}
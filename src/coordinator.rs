@@ -0,0 +1,380 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+use num_bigint::BigInt;
+
+use crate::{
+    error::VssError,
+    participant::Participant,
+    sharebox::{DistributionShareBox, ShareBox},
+};
+
+// Ceremony coordination
+
+// Runs a whole ceremony between one dealer and its shareholders, each shareholder on its own thread
+// and reachable only through a channel. The coordinator enforces the round schedule: the dealer's
+// distribution is broadcast, every shareholder answers with an ack or a complaint within the round
+// timeout, and the shareholders that acked are asked to reveal their share boxes until the
+// threshold is met. Messages arriving outside their round are dropped.
+
+const DEFAULT_ROUND_TIMEOUT: Duration = Duration::from_secs(10);
+
+enum Message {
+    Distribution(Box<DistributionShareBox>),
+    Reveal,
+    Done,
+}
+
+enum Reply {
+    Ack,
+    Complaint(VssError),
+    Share(ShareBox),
+}
+
+pub struct Dealer {
+    participant: Participant,
+}
+
+impl Dealer {
+    pub fn new(participant: Participant) -> Self {
+        Dealer { participant }
+    }
+}
+
+pub struct Shareholder {
+    participant: Participant,
+}
+
+impl Shareholder {
+    pub fn new(participant: Participant) -> Self {
+        Shareholder { participant }
+    }
+
+    pub fn publickey(&self) -> &BigInt {
+        &self.participant.publickey
+    }
+
+    // answers the coordinator's messages until the ceremony is over or the channel is closed
+    fn serve(&self, inbox: Receiver<Message>, outbox: Sender<(BigInt, Reply)>) {
+        let publickey = self.participant.publickey.clone();
+        let mut sharebox = None;
+
+        while let Ok(message) = inbox.recv() {
+            let reply = match message {
+                Message::Distribution(distribution) => {
+                    if !self.participant.verify_distribution_shares(&distribution) {
+                        Reply::Complaint(VssError::InvalidProof)
+                    } else {
+                        match self
+                            .participant
+                            .extract_secret_share(&distribution, &self.participant.privatekey)
+                        {
+                            Ok(extracted) => {
+                                sharebox = Some(extracted);
+                                Reply::Ack
+                            }
+                            Err(error) => Reply::Complaint(error),
+                        }
+                    }
+                }
+                Message::Reveal => match &sharebox {
+                    Some(sharebox) => Reply::Share(sharebox.clone()),
+                    None => continue,
+                },
+                Message::Done => return,
+            };
+
+            if outbox.send((publickey.clone(), reply)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Complaint {
+    pub publickey: BigInt,
+    pub reason: VssError,
+    /// whether the coordinator found the dealer at fault
+    pub upheld: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CeremonyOutcome {
+    pub distribution: DistributionShareBox,
+    /// shareholders that verified the distribution and extracted their share
+    pub acks: Vec<BigInt>,
+    pub complaints: Vec<Complaint>,
+    /// shareholders that did not answer a round in time
+    pub unresponsive: Vec<BigInt>,
+    /// the reconstructed secret, or why it could not be reconstructed
+    pub secret: Result<BigInt, VssError>,
+}
+
+pub struct Coordinator {
+    dealer: Dealer,
+    shareholders: Vec<Shareholder>,
+    threshold: u32,
+    round_timeout: Duration,
+}
+
+impl Coordinator {
+    pub fn new(dealer: Dealer, shareholders: Vec<Shareholder>, threshold: u32) -> Self {
+        Coordinator {
+            dealer,
+            shareholders,
+            threshold,
+            round_timeout: DEFAULT_ROUND_TIMEOUT,
+        }
+    }
+
+    /// How long shareholders have to answer each round, 10 seconds by default.
+    pub fn set_round_timeout(&mut self, timeout: Duration) {
+        self.round_timeout = timeout;
+    }
+
+    // A complaint is upheld when the dealer is at fault: the distribution fails public
+    // verification or holds no share for the complainant. Anything else is the shareholder's own
+    // problem, and it is only left out of the reveal.
+    fn judge(&self, distribution: &DistributionShareBox, complaint: &mut Complaint) {
+        complaint.upheld = !self
+            .dealer
+            .participant
+            .verify_distribution_shares(distribution)
+            || !distribution.shares.contains_key(&complaint.publickey);
+    }
+
+    /// Deals `secret` to the shareholders and reconstructs it from their revealed share boxes.
+    /// Fails only if the dealer cannot deal; everything after is reported in the outcome.
+    pub fn run(&mut self, secret: &BigInt) -> Result<CeremonyOutcome, VssError> {
+        let publickeys: Vec<BigInt> = self
+            .shareholders
+            .iter()
+            .map(|shareholder| shareholder.publickey().clone())
+            .collect();
+        let distribution =
+            self.dealer
+                .participant
+                .distribute_secret(secret, &publickeys, self.threshold)?;
+        let coordinator = &*self;
+
+        let outcome = thread::scope(|scope| {
+            let (outbox, replies) = mpsc::channel();
+            let inboxes: Vec<Sender<Message>> = coordinator
+                .shareholders
+                .iter()
+                .map(|shareholder| {
+                    let (inbox, messages) = mpsc::channel();
+                    let outbox = outbox.clone();
+
+                    scope.spawn(move || shareholder.serve(messages, outbox));
+
+                    inbox
+                })
+                .collect();
+
+            drop(outbox);
+
+            let outcome = coordinator.rounds(&distribution, &publickeys, &inboxes, &replies);
+
+            for inbox in &inboxes {
+                let _ = inbox.send(Message::Done);
+            }
+
+            outcome
+        });
+
+        Ok(outcome)
+    }
+
+    fn rounds(
+        &self,
+        distribution: &DistributionShareBox,
+        publickeys: &[BigInt],
+        inboxes: &[Sender<Message>],
+        replies: &Receiver<(BigInt, Reply)>,
+    ) -> CeremonyOutcome {
+        let mut acks = Vec::new();
+        let mut complaints = Vec::new();
+
+        // round 1: broadcast the distribution, collect acks and complaints
+        for inbox in inboxes {
+            let _ = inbox.send(Message::Distribution(Box::new(distribution.clone())));
+        }
+
+        let deadline = Instant::now() + self.round_timeout;
+
+        while acks.len() + complaints.len() < inboxes.len() {
+            let (publickey, reply) = match receive(replies, deadline) {
+                Some(reply) => reply,
+                None => break,
+            };
+            let answered = acks.contains(&publickey)
+                || complaints
+                    .iter()
+                    .any(|complaint: &Complaint| complaint.publickey == publickey);
+
+            match reply {
+                Reply::Ack if !answered => acks.push(publickey),
+                Reply::Complaint(reason) if !answered => {
+                    let mut complaint = Complaint {
+                        publickey,
+                        reason,
+                        upheld: false,
+                    };
+
+                    self.judge(distribution, &mut complaint);
+                    complaints.push(complaint);
+                }
+                _ => {}
+            }
+        }
+
+        let mut unresponsive: Vec<BigInt> = publickeys
+            .iter()
+            .filter(|publickey| {
+                !acks.contains(publickey)
+                    && !complaints
+                        .iter()
+                        .any(|complaint| &complaint.publickey == *publickey)
+            })
+            .cloned()
+            .collect();
+        let outcome = |acks, complaints, unresponsive, secret| CeremonyOutcome {
+            distribution: distribution.clone(),
+            acks,
+            complaints,
+            unresponsive,
+            secret,
+        };
+
+        if complaints.iter().any(|complaint| complaint.upheld) {
+            return outcome(acks, complaints, unresponsive, Err(VssError::InvalidProof));
+        }
+
+        let required = distribution.threshold();
+
+        if acks.len() < required {
+            let provided = acks.len();
+
+            return outcome(
+                acks,
+                complaints,
+                unresponsive,
+                Err(VssError::ThresholdNotMet { required, provided }),
+            );
+        }
+
+        // round 2: ask the shareholders that acked for their share boxes
+        for (inbox, publickey) in inboxes.iter().zip(publickeys) {
+            if acks.contains(publickey) {
+                let _ = inbox.send(Message::Reveal);
+            }
+        }
+
+        let deadline = Instant::now() + self.round_timeout;
+        let mut shares: Vec<ShareBox> = Vec::new();
+        let mut revealed: Vec<BigInt> = Vec::new();
+
+        while shares.len() < required && revealed.len() < acks.len() {
+            let (publickey, reply) = match receive(replies, deadline) {
+                Some(reply) => reply,
+                None => break,
+            };
+
+            if let Reply::Share(sharebox) = reply {
+                if !acks.contains(&publickey) || revealed.contains(&publickey) {
+                    continue;
+                }
+
+                revealed.push(publickey.clone());
+
+                if sharebox.publickey == publickey
+                    && self
+                        .dealer
+                        .participant
+                        .verify_share(&sharebox, distribution, &publickey)
+                {
+                    shares.push(sharebox);
+                }
+            }
+        }
+
+        if shares.len() < required {
+            unresponsive.extend(
+                acks.iter()
+                    .filter(|publickey| !revealed.contains(publickey))
+                    .cloned(),
+            );
+        }
+
+        let secret = self.dealer.participant.reconstruct(&shares, distribution);
+
+        outcome(acks, complaints, unresponsive, secret)
+    }
+}
+
+fn receive(replies: &Receiver<(BigInt, Reply)>, deadline: Instant) -> Option<(BigInt, Reply)> {
+    replies
+        .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, secret_key::SecretKey, testkit::test_participant};
+
+    use super::{Coordinator, Dealer, Shareholder};
+
+    fn coordinator(threshold: u32) -> Coordinator {
+        let mut shareholders: Vec<Shareholder> = [7901, 4801, 1453, 2179]
+            .map(|privatekey| Shareholder::new(test_participant(privatekey)))
+            .into_iter()
+            .collect();
+
+        // a shareholder that lost its private key cannot extract its share
+        shareholders[3].participant.privatekey = SecretKey::new(BigInt::from(1));
+
+        Coordinator::new(
+            Dealer::new(test_participant(105929)),
+            shareholders,
+            threshold,
+        )
+    }
+
+    #[test]
+    fn test_run() {
+        let secret = BigInt::from(1234567890);
+        let outcome = coordinator(3).run(&secret).unwrap();
+
+        assert_eq!(outcome.acks.len(), 3);
+        assert_eq!(outcome.complaints.len(), 1);
+        assert_eq!(
+            outcome.complaints[0].reason,
+            VssError::MissingShare {
+                publickey: BigInt::from(15486487)
+            }
+        );
+        assert!(!outcome.complaints[0].upheld);
+        assert!(outcome.unresponsive.is_empty());
+        assert_eq!(outcome.secret.unwrap(), secret);
+    }
+
+    #[test]
+    fn test_threshold_not_met() {
+        let outcome = coordinator(4).run(&BigInt::from(1234567890)).unwrap();
+
+        assert_eq!(
+            outcome.secret.unwrap_err(),
+            VssError::ThresholdNotMet {
+                required: 4,
+                provided: 3
+            }
+        );
+    }
+}
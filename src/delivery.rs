@@ -0,0 +1,141 @@
+use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_integer::Integer;
+use num_traits::One;
+
+use crate::{
+    dleq::DLEQ,
+    error::VssError,
+    polynomial::Polynomial,
+    session::{session_context, SessionId},
+    sharebox::DistributionShareBox,
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Share delivery
+
+// A recipient needs the commitments and its own entry, not every encrypted share and response of
+// the distribution. The joint challenge of the distribution can only be checked with all of them,
+// so the dealer proves each delivered share on its own: a DLEQ that log_g X_i = log_y Y_i with a
+// challenge over that entry alone. The delivery names the distribution it was cut from by its
+// fingerprint, to be matched against the published one before reconstructing, and carries its
+// session, which the proof and the share boxes extracted from the delivery are bound to.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShareDelivery {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub commitments: Vec<BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::position"))]
+    pub position: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub encrypted_share: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response: BigInt,
+    /// `DistributionShareBox::params_fingerprint` of the distribution
+    pub params_fingerprint: Option<[u8; 8]>,
+    /// `DistributionShareBox::fingerprint` of the distribution
+    pub distribution: [u8; 32],
+    /// `DistributionShareBox::session_id` of the distribution
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub session_id: Option<SessionId>,
+}
+
+impl ShareDelivery {
+    // cut by the dealer, who still holds the polynomial, with the proof nonce `w`
+    pub(crate) fn new<D: Transcript>(
+        vss: &VSS<D>,
+        distribution_sharebox: &DistributionShareBox,
+        polynomial: &Polynomial,
+        publickey: &BigInt,
+        w: &BigInt,
+    ) -> Result<Self, VssError> {
+        let missing = || VssError::MissingShare {
+            publickey: publickey.clone(),
+        };
+        let position = distribution_sharebox
+            .positions
+            .get(publickey)
+            .ok_or_else(missing)?;
+        let encrypted_share = distribution_sharebox
+            .shares
+            .get(publickey)
+            .ok_or_else(missing)?;
+        let secret_share = polynomial.get_value_mod(position, &(&vss.q - BigInt::one()));
+        let mut dleq = DLEQ::new();
+
+        dleq.init2(
+            vss.g.clone(),
+            vss.evaluate_commitments(&distribution_sharebox.commitments, position),
+            publickey.clone(),
+            encrypted_share.clone(),
+            vss.group(),
+            secret_share,
+            w.clone(),
+        );
+
+        let mut challenge_hasher = vss.context_hasher(&distribution_sharebox.proof_context(&[]));
+
+        dleq.append_recomputed(
+            &[vss.exp_g(&vss.blind(w)), dleq.get_a2()],
+            &mut challenge_hasher,
+        );
+
+        let challenge = BigUint::from_bytes_be(&challenge_hasher.challenge()[..])
+            .mod_floor(&(vss.q.to_biguint().unwrap() - BigUint::one()))
+            .to_bigint()
+            .unwrap();
+
+        dleq.c = Some(challenge.clone());
+
+        Ok(ShareDelivery {
+            commitments: distribution_sharebox.commitments.clone(),
+            publickey: publickey.clone(),
+            position: position.clone(),
+            encrypted_share: encrypted_share.clone(),
+            challenge,
+            response: dleq.get_r().unwrap(),
+            params_fingerprint: distribution_sharebox.params_fingerprint,
+            distribution: distribution_sharebox.fingerprint(),
+            session_id: distribution_sharebox.session_id,
+        })
+    }
+
+    /// Whether the encrypted share is the one committed to for this position.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        if matches!(self.params_fingerprint, Some(fingerprint) if fingerprint != vss.params_fingerprint())
+        {
+            return false;
+        }
+
+        let mut dleq = DLEQ::new();
+        let mut challenge_hasher = vss.context_hasher(&self.proof_context(&[]));
+
+        dleq.g1 = vss.g.clone();
+        dleq.h1 = vss.evaluate_commitments(&self.commitments, &self.position);
+        dleq.g2 = self.publickey.clone();
+        dleq.h2 = self.encrypted_share.clone();
+        dleq.r = Some(self.response.clone());
+        dleq.c = Some(self.challenge.clone());
+        dleq.group = vss.group();
+        dleq.update_hash(&mut challenge_hasher);
+        dleq.check(&challenge_hasher)
+    }
+
+    /// The number of share boxes needed to reconstruct.
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+
+    // `DistributionShareBox::proof_context` of the distribution
+    pub(crate) fn proof_context(&self, context: &[u8]) -> Vec<u8> {
+        session_context(self.session_id.as_ref(), context)
+    }
+}
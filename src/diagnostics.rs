@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+
+use crate::{
+    dleq::DLEQ, error::VssError, light::EntryDigest, sharebox::DistributionShareBox,
+    transcript::Transcript, vss::VSS,
+};
+
+// Distribution diagnostics
+
+// `verify_distribution_shares` folds every entry into one challenge hash, so a failure says
+// nothing about where it came from. The diagnosis runs the same steps one at a time and keeps
+// going after a failure: structural checks per commitment and per entry, the range of the
+// challenge, and the recomputed a1, a2 of every entry. The hash alone cannot tell which entry
+// changed; for that the recomputed digests are compared against reference digests, e.g. the
+// `digests` of a `LightDistribution` the dealer built from its own copy.
+
+/// One reason a distribution fails to verify, see `VSS::diagnose_distribution`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DistributionFault {
+    /// the parameters, threshold or recorded sizes are wrong, so nothing further was checked
+    Malformed(VssError),
+    /// the commitment at this index is not a group element
+    InvalidCommitment { index: usize },
+    /// the entry of this public key lacks a position, response or encrypted share, or holds a
+    /// value outside the group
+    InvalidEntry { publickey: BigInt },
+    /// the challenge is not in `0..q - 1`
+    MalformedChallenge,
+    /// the recomputed digests of these entries differ from the reference digests
+    EntryMismatch { publickeys: Vec<BigInt> },
+    /// the challenge is not the hash of the recomputed entries
+    ChallengeMismatch,
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks `distribution_sharebox` like `verify_distribution_shares_with_context`, returning
+    /// every fault found instead of a bool; the distribution verifies if and only if none is.
+    /// With `reference` digests, the entries whose recomputed digest differs are named.
+    pub fn diagnose_distribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        context: &[u8],
+        reference: Option<&[EntryDigest]>,
+    ) -> Vec<DistributionFault> {
+        if let Err(error) = self
+            .check_params(distribution_sharebox)
+            .and_then(|_| distribution_sharebox.validate())
+        {
+            return vec![DistributionFault::Malformed(error)];
+        }
+
+        let mut faults = Vec::new();
+
+        for (index, commitment) in distribution_sharebox.commitments.iter().enumerate() {
+            if !self.in_group(commitment) {
+                faults.push(DistributionFault::InvalidCommitment { index });
+            }
+        }
+
+        let mut dleqs = Vec::with_capacity(distribution_sharebox.publickeys.len());
+
+        for publickey in &distribution_sharebox.publickeys {
+            match (
+                distribution_sharebox.positions.get(publickey),
+                distribution_sharebox.responses.get(publickey),
+                distribution_sharebox.shares.get(publickey),
+            ) {
+                (Some(position), Some(response), Some(encrypted_share))
+                    if self.in_group(publickey)
+                        && self.in_group(encrypted_share)
+                        && self.in_exponent_range(response) =>
+                {
+                    let mut dleq = DLEQ::new();
+
+                    dleq.g1 = self.g.clone();
+                    dleq.g2 = publickey.clone();
+                    dleq.h2 = encrypted_share.clone();
+                    dleq.r = Some(response.clone());
+                    dleq.c = Some(distribution_sharebox.challenge.clone());
+                    dleq.group = self.group();
+                    dleqs.push((publickey, position, dleq));
+                }
+                _ => faults.push(DistributionFault::InvalidEntry {
+                    publickey: publickey.clone(),
+                }),
+            }
+        }
+
+        let challenge = &distribution_sharebox.challenge;
+
+        if !self.in_exponent_range(challenge) {
+            faults.push(DistributionFault::MalformedChallenge);
+        }
+
+        if !faults.is_empty() {
+            return faults;
+        }
+
+        let reference: Option<BTreeMap<&BigInt, &EntryDigest>> = reference.map(|digests| {
+            digests
+                .iter()
+                .map(|digest| (&digest.position, digest))
+                .collect()
+        });
+        let mut mismatched = Vec::new();
+        let mut challenge_hasher =
+            self.context_hasher(&distribution_sharebox.proof_context(context));
+
+        for (publickey, position, dleq) in dleqs.iter_mut() {
+            dleq.h1 = self.evaluate_commitments(&distribution_sharebox.commitments, position);
+
+            let commitments = dleq.recompute();
+
+            if let Some(reference) = &reference {
+                let [a1, a2] = &commitments;
+                let digest = EntryDigest {
+                    position: (*position).clone(),
+                    x: dleq.h1.clone(),
+                    encrypted_share: dleq.h2.clone(),
+                    a1: a1.clone(),
+                    a2: a2.clone(),
+                    publickey: (*publickey).clone(),
+                };
+
+                if reference.get(position) != Some(&&digest) {
+                    mismatched.push((*publickey).clone());
+                }
+            }
+
+            dleq.append_recomputed(&commitments, &mut challenge_hasher);
+        }
+
+        if !mismatched.is_empty() {
+            faults.push(DistributionFault::EntryMismatch {
+                publickeys: mismatched,
+            });
+        }
+
+        match dleqs.last() {
+            Some((_, _, dleq)) if dleq.check(&challenge_hasher) => {}
+            _ => faults.push(DistributionFault::ChallengeMismatch),
+        }
+
+        faults
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{light::LightDistribution, testkit::fixed_ceremony};
+
+    use super::DistributionFault;
+
+    #[test]
+    fn test_diagnose_distribution() {
+        let ceremony = fixed_ceremony(4, 2, 3).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let distribution = &ceremony.distribution;
+        let reference =
+            LightDistribution::new(vss, distribution, &distribution.publickeys[0]).unwrap();
+
+        assert!(vss
+            .diagnose_distribution(distribution, &[], Some(&reference.digests))
+            .is_empty());
+
+        let publickey = distribution.publickeys[2].clone();
+        let mut tampered = distribution.clone();
+
+        *tampered.responses.get_mut(&publickey).unwrap() += 1;
+
+        assert_eq!(
+            vss.diagnose_distribution(&tampered, &[], None),
+            vec![DistributionFault::ChallengeMismatch]
+        );
+        assert_eq!(
+            vss.diagnose_distribution(&tampered, &[], Some(&reference.digests)),
+            vec![
+                DistributionFault::EntryMismatch {
+                    publickeys: vec![publickey.clone()]
+                },
+                DistributionFault::ChallengeMismatch
+            ]
+        );
+
+        let mut tampered = distribution.clone();
+
+        tampered.shares.insert(publickey.clone(), BigInt::from(0));
+        tampered.commitments[1] = vss.q.clone();
+        tampered.challenge = -BigInt::from(1);
+
+        assert_eq!(
+            vss.diagnose_distribution(&tampered, &[], None),
+            vec![
+                DistributionFault::InvalidCommitment { index: 1 },
+                DistributionFault::InvalidEntry { publickey },
+                DistributionFault::MalformedChallenge
+            ]
+        );
+    }
+}
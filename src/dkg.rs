@@ -0,0 +1,811 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::{BigInt, RandBigInt};
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    polynomial::Polynomial,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+    wire::Writer,
+};
+
+// Distributed key generation
+
+// Joint-Feldman without a trusted dealer: every participant deals a random polynomial p_j to all
+// participants, as an ordinary distribution proven with the dealer's key as context, and the
+// dealings that verify are combined. Multiplying their commitments and encrypted shares gives a
+// distribution of P = sum p_j, so each participant's share of it is the product of the shares
+// dealt to it, G^P(i), and the key is x = P(0), which nobody learns as long as one dealer was
+// honest. Its public key is g^x, the product of the dealers' first commitments; the holders of
+// `threshold` shares compute G^x together.
+//
+// The same dealings establish a secret nobody chose: the combined distribution masks nothing
+// under `u`, so reconstructing it yields the mask itself, `SECRET_LEN` bytes derived from G^x.
+// Distributions and decrypted shares combine on their own as well, by multiplying them entry by
+// entry, see `VSS::combine_distributions` and `VSS::combine_shares`.
+//
+// With the dealings sent to each participant rather than broadcast, a participant may get none
+// from some dealer or one damaged on the way. `KeyGeneration` runs Gennaro's complaint round over
+// that: after the dealing phase every participant complains about each dealer it holds no valid
+// dealing from, attaching what it got, so anyone can check the complaint. A dealer complained about
+// opens its dealing to everyone in the response phase, and is disqualified if it does not or the
+// opening fails to verify. A complaint about a dealing that verifies is dismissed. Equivocation is
+// out of reach: a dealing's proof binds the dealer's key but is no signature, so every
+// participant is expected to be handed the same dealing for each dealer.
+
+/// One participant's dealing in a key generation, see `Participant::deal_key_share`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyDealing {
+    /// the dealer's public key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub dealer: BigInt,
+    pub distribution: DistributionShareBox,
+}
+
+/// A key generation among `publickeys`, at positions `1..=n`, collecting the verified dealings.
+/// Anyone can check it with `verify`; the participants extract their key shares from
+/// `distribution`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JointDistribution {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub publickeys: Vec<BigInt>,
+    pub threshold: u32,
+    pub dealings: Vec<KeyDealing>,
+}
+
+// the length in bytes of a secret established by a key generation
+pub(crate) const SECRET_LEN: usize = 32;
+
+// dealings are bound to their dealer, so nobody can pass off another's dealing as its own
+fn context(dealer: &BigInt) -> Vec<u8> {
+    let mut writer = Writer::new();
+
+    writer.raw(b"dkg");
+    writer.bigint(dealer);
+    writer.into_bytes()
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Deals this participant's random contribution to a key shared by `publickeys`, which must
+    /// include its own, with `threshold`. Collect the dealings with `JointDistribution::add`.
+    pub fn deal_key_share(
+        &mut self,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<KeyDealing, VssError> {
+        self.deal_key_share_with_rng(publickeys, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `deal_key_share`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn deal_key_share_with_rng(
+        &mut self,
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<KeyDealing, VssError> {
+        let distribution =
+            self.deal_random(publickeys, threshold, &context(&self.publickey), rng)?;
+
+        Ok(KeyDealing {
+            dealer: self.publickey.clone(),
+            distribution,
+        })
+    }
+
+    // a random polynomial dealt to `publickeys` at positions `1..=n`, proven under `context`
+    pub(crate) fn deal_random(
+        &mut self,
+        publickeys: &[BigInt],
+        threshold: u32,
+        context: &[u8],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        if !publickeys.contains(&self.publickey) {
+            return Err(VssError::UnknownShareholder {
+                publickey: self.publickey.clone(),
+            });
+        }
+
+        let positions = sequential_positions(publickeys.len());
+
+        self.validate_inputs(&BigInt::zero(), publickeys, &positions, threshold, None)?;
+
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_with_rng((threshold - 1) as i32, &self.vss.q, rng);
+
+        let w = rng.gen_bigint_range(&BigInt::zero(), &self.vss.q);
+
+        self.distribute(
+            &BigInt::zero(),
+            publickeys,
+            &positions,
+            threshold,
+            &polynomial,
+            &w,
+            context,
+            None,
+        )
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks that `dealing` is a proven distribution from one of `publickeys` to all of them, at
+    /// positions `1..=n`, with `threshold`.
+    pub fn verify_key_dealing(
+        &self,
+        publickeys: &[BigInt],
+        threshold: u32,
+        dealing: &KeyDealing,
+    ) -> bool {
+        publickeys.contains(&dealing.dealer)
+            && self.verify_random_dealing(
+                publickeys,
+                threshold,
+                &dealing.distribution,
+                &context(&dealing.dealer),
+            )
+    }
+
+    // whether `distribution` is a proven dealing to `publickeys` at positions `1..=n`, with
+    // `threshold`, under `context`
+    pub(crate) fn verify_random_dealing(
+        &self,
+        publickeys: &[BigInt],
+        threshold: u32,
+        distribution: &DistributionShareBox,
+        context: &[u8],
+    ) -> bool {
+        distribution.publickeys == publickeys
+            && distribution.positions.len() == publickeys.len()
+            && publickeys
+                .iter()
+                .zip(sequential_positions(publickeys.len()))
+                .all(|(publickey, position)| {
+                    distribution.positions.get(publickey) == Some(&position)
+                })
+            && distribution.commitments.len() == threshold as usize
+            && self.verify_distribution_shares_with_context(distribution, context)
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Combines distributions to the same recipients at the same positions with the same
+    /// threshold into the distribution of the sum of their polynomials, by multiplying their
+    /// commitments and encrypted shares. Its secret is `SECRET_LEN` bytes derived from the shared
+    /// value, as there is nothing under `u`. The challenge and responses are those of the first
+    /// distribution and prove nothing about the result.
+    pub fn combine_distributions(
+        &self,
+        distributions: &[DistributionShareBox],
+    ) -> Result<DistributionShareBox, VssError> {
+        let first = distributions
+            .first()
+            .ok_or(VssError::InconsistentDistribution)?;
+
+        for distribution in distributions {
+            self.check_params(distribution)?;
+            distribution.validate()?;
+
+            if distribution.publickeys != first.publickeys
+                || distribution.positions != first.positions
+                || distribution.shares.keys().ne(first.shares.keys())
+                || distribution.commitments.len() != first.commitments.len()
+            {
+                return Err(VssError::InconsistentDistribution);
+            }
+        }
+
+        let mut combined = first.clone();
+
+        for distribution in &distributions[1..] {
+            for (commitment, dealt) in combined
+                .commitments
+                .iter_mut()
+                .zip(&distribution.commitments)
+            {
+                *commitment = (&*commitment * dealt) % &self.q;
+            }
+
+            for (publickey, share) in combined.shares.iter_mut() {
+                *share = (&*share * &distribution.shares[publickey]) % &self.q;
+            }
+        }
+
+        combined.u = BigInt::zero();
+        combined.secret_len = Some(SECRET_LEN);
+
+        Ok(combined)
+    }
+
+    /// Combines one shareholder's decrypted shares of several distributions into its share of
+    /// their combination, see `combine_distributions`. The result carries no proof; extract it
+    /// from the combined distribution instead where one is needed.
+    pub fn combine_shares(&self, share_boxes: &[ShareBox]) -> Result<ShareBox, VssError> {
+        let first = share_boxes.first().ok_or(VssError::ThresholdNotMet {
+            required: 1,
+            provided: 0,
+        })?;
+        let mut combined = ShareBox::new();
+
+        combined.publickey = first.publickey.clone();
+        combined.share = BigInt::one();
+
+        for share_box in share_boxes {
+            if share_box.publickey != first.publickey {
+                return Err(VssError::ForeignShare {
+                    publickey: share_box.publickey.clone(),
+                });
+            }
+
+            combined.share = (combined.share * &share_box.share) % &self.q;
+        }
+
+        Ok(combined)
+    }
+}
+
+impl JointDistribution {
+    pub fn new(publickeys: &[BigInt], threshold: u32) -> Self {
+        JointDistribution {
+            publickeys: publickeys.to_vec(),
+            threshold,
+            dealings: Vec::new(),
+        }
+    }
+
+    /// Adds `dealing` if it passes `VSS::verify_key_dealing` and its dealer has not dealt yet.
+    pub fn add<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+        dealing: KeyDealing,
+    ) -> Result<(), VssError> {
+        if self
+            .dealings
+            .iter()
+            .any(|dealt| dealt.dealer == dealing.dealer)
+        {
+            return Err(VssError::DuplicatePublicKey {
+                publickey: dealing.dealer,
+            });
+        }
+
+        if !vss.verify_key_dealing(&self.publickeys, self.threshold, &dealing) {
+            return Err(VssError::InvalidProof);
+        }
+
+        self.dealings.push(dealing);
+
+        Ok(())
+    }
+
+    /// The dealers whose dealings were accepted, in the order they were added.
+    pub fn qualified(&self) -> Vec<BigInt> {
+        self.dealings
+            .iter()
+            .map(|dealing| dealing.dealer.clone())
+            .collect()
+    }
+
+    /// Verifies every dealing, at most one per dealer, and that there is at least one.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let dealers: BTreeSet<&BigInt> = self
+            .dealings
+            .iter()
+            .map(|dealing| &dealing.dealer)
+            .collect();
+
+        !self.dealings.is_empty()
+            && dealers.len() == self.dealings.len()
+            && self
+                .dealings
+                .iter()
+                .all(|dealing| vss.verify_key_dealing(&self.publickeys, self.threshold, dealing))
+    }
+
+    /// g^x, the product of the dealers' first commitments.
+    pub fn public_key<D: Transcript>(&self, vss: &VSS<D>) -> BigInt {
+        self.dealings
+            .iter()
+            .filter_map(|dealing| dealing.distribution.commitments.first())
+            .fold(BigInt::one(), |product, commitment| {
+                (product * commitment) % &vss.q
+            })
+    }
+
+    /// The distribution of the key, for `extract_secret_share` and `verify_share` as usual, see
+    /// `VSS::combine_distributions`. It keeps the challenge and responses of the first dealing,
+    /// which do not prove it; check `verify` instead.
+    pub fn distribution<D: Transcript>(&self, vss: &VSS<D>) -> DistributionShareBox {
+        let distributions: Vec<DistributionShareBox> = self
+            .dealings
+            .iter()
+            .map(|dealing| dealing.distribution.clone())
+            .collect();
+
+        vss.combine_distributions(&distributions)
+            .unwrap_or_default()
+    }
+
+    /// G^x, interpolated from at least `threshold` key shares extracted from `distribution`.
+    pub fn shared_key<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        vss.interpolate(share_boxes, &self.distribution(vss))
+    }
+
+    /// The secret the dealings establish, reconstructed from at least `threshold` key shares:
+    /// `SECRET_LEN` bytes derived from G^x, which no dealer could choose.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        vss.reconstruct(share_boxes, &self.distribution(vss))
+    }
+}
+
+/// The phases of a `KeyGeneration`, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyGenerationPhase {
+    /// dealings are sent
+    Dealing,
+    /// complaints about missing or invalid dealings are broadcast
+    Complaints,
+    /// dealers complained about broadcast their dealings
+    Responses,
+    /// the key is fixed, see `KeyGeneration::finish`
+    Finished,
+}
+
+/// A complaint that `complainer` holds no valid dealing from `dealer`, with the dealing it got if
+/// any. Anyone can check it with `VSS::verify_key_dealing`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyComplaint {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub complainer: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub dealer: BigInt,
+    pub received: Option<KeyDealing>,
+}
+
+/// What the participants of a `KeyGeneration` send each other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyGenerationMessage {
+    /// a dealer's dealing, sent in the dealing phase
+    Dealing(KeyDealing),
+    /// broadcast in the complaint phase
+    Complaint(KeyComplaint),
+    /// a dealing opened to everyone in the response phase
+    Opening(KeyDealing),
+}
+
+/// One participant's view of a key generation with a complaint round. Feed it every message
+/// received with `handle`, move it on with `advance` once a phase's messages are in, and send
+/// what `advance` returns to everyone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyGeneration {
+    /// this participant's public key
+    pub publickey: BigInt,
+    pub publickeys: Vec<BigInt>,
+    pub threshold: u32,
+    pub phase: KeyGenerationPhase,
+    own: Option<KeyDealing>,
+    received: BTreeMap<BigInt, KeyDealing>,
+    complaints: Vec<KeyComplaint>,
+    openings: BTreeMap<BigInt, KeyDealing>,
+}
+
+impl KeyGeneration {
+    pub fn new(publickey: &BigInt, publickeys: &[BigInt], threshold: u32) -> Self {
+        KeyGeneration {
+            publickey: publickey.clone(),
+            publickeys: publickeys.to_vec(),
+            threshold,
+            phase: KeyGenerationPhase::Dealing,
+            own: None,
+            received: BTreeMap::new(),
+            complaints: Vec::new(),
+            openings: BTreeMap::new(),
+        }
+    }
+
+    /// Records this participant's own dealing, see `Participant::deal_key_share`, and returns the
+    /// message that sends it.
+    pub fn deal(&mut self, dealing: KeyDealing) -> Result<KeyGenerationMessage, VssError> {
+        if self.phase != KeyGenerationPhase::Dealing || self.own.is_some() {
+            return Err(VssError::UnexpectedMessage);
+        }
+
+        if dealing.dealer != self.publickey {
+            return Err(VssError::ForeignShare {
+                publickey: dealing.dealer,
+            });
+        }
+
+        self.own = Some(dealing.clone());
+        self.received
+            .insert(dealing.dealer.clone(), dealing.clone());
+
+        Ok(KeyGenerationMessage::Dealing(dealing))
+    }
+
+    /// Takes a message from another participant. Messages from unknown keys or for another phase
+    /// are refused; of repeated ones the first counts.
+    pub fn handle(&mut self, message: KeyGenerationMessage) -> Result<(), VssError> {
+        let known = |publickey: &BigInt| {
+            if self.publickeys.contains(publickey) {
+                Ok(())
+            } else {
+                Err(VssError::UnknownShareholder {
+                    publickey: publickey.clone(),
+                })
+            }
+        };
+
+        match (self.phase, message) {
+            (KeyGenerationPhase::Dealing, KeyGenerationMessage::Dealing(dealing)) => {
+                known(&dealing.dealer)?;
+                self.received
+                    .entry(dealing.dealer.clone())
+                    .or_insert(dealing);
+            }
+            (KeyGenerationPhase::Complaints, KeyGenerationMessage::Complaint(complaint)) => {
+                known(&complaint.complainer)?;
+                known(&complaint.dealer)?;
+
+                if !self.complaints.iter().any(|filed| {
+                    filed.complainer == complaint.complainer && filed.dealer == complaint.dealer
+                }) {
+                    self.complaints.push(complaint);
+                }
+            }
+            (KeyGenerationPhase::Responses, KeyGenerationMessage::Opening(dealing)) => {
+                known(&dealing.dealer)?;
+                self.openings
+                    .entry(dealing.dealer.clone())
+                    .or_insert(dealing);
+            }
+            _ => return Err(VssError::UnexpectedMessage),
+        }
+
+        Ok(())
+    }
+
+    /// Moves on to the next phase, returning this participant's messages for it: its complaints
+    /// when entering the complaint phase, the opening of its own dealing when entering the
+    /// response phase with an upheld complaint against it.
+    pub fn advance<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+    ) -> Result<Vec<KeyGenerationMessage>, VssError> {
+        let mut messages = Vec::new();
+
+        match self.phase {
+            KeyGenerationPhase::Dealing => {
+                for dealer in &self.publickeys {
+                    let received = self.received.get(dealer);
+
+                    if !received.is_some_and(|dealing| {
+                        vss.verify_key_dealing(&self.publickeys, self.threshold, dealing)
+                    }) {
+                        let complaint = KeyComplaint {
+                            complainer: self.publickey.clone(),
+                            dealer: dealer.clone(),
+                            received: received.cloned(),
+                        };
+
+                        self.complaints.push(complaint.clone());
+                        messages.push(KeyGenerationMessage::Complaint(complaint));
+                    }
+                }
+
+                self.phase = KeyGenerationPhase::Complaints;
+            }
+            KeyGenerationPhase::Complaints => {
+                if let Some(own) = &self.own {
+                    if self.upheld(vss, &self.publickey) {
+                        self.openings.insert(own.dealer.clone(), own.clone());
+                        messages.push(KeyGenerationMessage::Opening(own.clone()));
+                    }
+                }
+
+                self.phase = KeyGenerationPhase::Responses;
+            }
+            KeyGenerationPhase::Responses => self.phase = KeyGenerationPhase::Finished,
+            KeyGenerationPhase::Finished => return Err(VssError::UnexpectedMessage),
+        }
+
+        Ok(messages)
+    }
+
+    // whether a complaint against `dealer` holds: it got nothing, or something that fails
+    fn upheld<D: Transcript>(&self, vss: &VSS<D>, dealer: &BigInt) -> bool {
+        self.complaints.iter().any(|complaint| {
+            &complaint.dealer == dealer
+                && !complaint.received.as_ref().is_some_and(|dealing| {
+                    &dealing.dealer == dealer
+                        && vss.verify_key_dealing(&self.publickeys, self.threshold, dealing)
+                })
+        })
+    }
+
+    // the dealing that counts for `dealer`: its opening if a complaint against it holds, else
+    // the dealing received
+    fn settled<D: Transcript>(&self, vss: &VSS<D>, dealer: &BigInt) -> Option<&KeyDealing> {
+        let dealing = if self.upheld(vss, dealer) {
+            self.openings.get(dealer)
+        } else {
+            self.received.get(dealer)
+        };
+
+        dealing.filter(|dealing| {
+            &dealing.dealer == dealer
+                && vss.verify_key_dealing(&self.publickeys, self.threshold, dealing)
+        })
+    }
+
+    /// The dealers without a valid dealing once complaints are settled, in the order of
+    /// `publickeys`.
+    pub fn disqualified<D: Transcript>(&self, vss: &VSS<D>) -> Vec<BigInt> {
+        self.publickeys
+            .iter()
+            .filter(|dealer| self.settled(vss, dealer).is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// The key generated from the dealings of the qualified dealers, once finished.
+    pub fn finish<D: Transcript>(&self, vss: &VSS<D>) -> Result<JointDistribution, VssError> {
+        if self.phase != KeyGenerationPhase::Finished {
+            return Err(VssError::UnexpectedMessage);
+        }
+
+        let mut joint = JointDistribution::new(&self.publickeys, self.threshold);
+
+        for dealer in &self.publickeys {
+            if let Some(dealing) = self.settled(vss, dealer) {
+                joint.add(vss, dealing.clone())?;
+            }
+        }
+
+        if joint.dealings.is_empty() {
+            return Err(VssError::NoQualifiedDealers);
+        }
+
+        Ok(joint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, sharebox::ShareBox, testkit::fixed_ceremony};
+
+    use super::{
+        JointDistribution, KeyComplaint, KeyGeneration, KeyGenerationMessage, KeyGenerationPhase,
+    };
+
+    #[test]
+    fn test_joint_distribution() {
+        let ceremony = fixed_ceremony(3, 2, 17).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let mut joint = JointDistribution::new(&publickeys, 2);
+        let dealings: Vec<_> = participants
+            .iter()
+            .map(|p| p.clone().deal_key_share(&publickeys, 2).unwrap())
+            .collect();
+
+        // a dealing passed off as another participant's does not verify
+        let mut stolen = dealings[0].clone();
+
+        stolen.dealer = publickeys[1].clone();
+
+        assert_eq!(joint.add(vss, stolen), Err(VssError::InvalidProof));
+
+        for dealing in dealings.iter().cloned() {
+            joint.add(vss, dealing).unwrap();
+        }
+
+        assert_eq!(
+            joint.add(vss, dealings[2].clone()),
+            Err(VssError::DuplicatePublicKey {
+                publickey: publickeys[2].clone()
+            })
+        );
+        assert!(joint.verify(vss));
+        assert_eq!(joint.qualified(), publickeys);
+
+        let distribution = joint.distribution(vss);
+        let shareboxes: Vec<ShareBox> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(shareboxes.iter().all(|sharebox| vss.verify_share(
+            sharebox,
+            &distribution,
+            &sharebox.publickey
+        )));
+        assert_eq!(distribution.commitments[0], joint.public_key(vss));
+
+        // any two key shares give the same G^x
+        let shared_key = joint.shared_key(vss, &shareboxes[..2]).unwrap();
+
+        assert_eq!(joint.shared_key(vss, &shareboxes[1..]), Ok(shared_key));
+        assert!(joint.shared_key(vss, &shareboxes[..1]).is_err());
+    }
+
+    #[test]
+    fn test_established_secret() {
+        let ceremony = fixed_ceremony(3, 2, 23).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let mut joint = JointDistribution::new(&publickeys, 2);
+
+        for participant in participants {
+            let dealing = participant.clone().deal_key_share(&publickeys, 2).unwrap();
+
+            joint.add(vss, dealing).unwrap();
+        }
+
+        let distribution = joint.distribution(vss);
+
+        // each participant's share of the sum is the product of its shares of the dealings
+        let shareboxes: Vec<ShareBox> = participants
+            .iter()
+            .map(|p| {
+                let dealt: Vec<ShareBox> = joint
+                    .dealings
+                    .iter()
+                    .map(|dealing| {
+                        p.extract_secret_share(&dealing.distribution, &p.privatekey)
+                            .unwrap()
+                    })
+                    .collect();
+                let combined = vss.combine_shares(&dealt).unwrap();
+                let extracted = p
+                    .extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap();
+
+                assert_eq!(combined.share, extracted.share);
+
+                combined
+            })
+            .collect();
+
+        let secret = joint.reconstruct(vss, &shareboxes[..2]).unwrap();
+
+        assert_eq!(joint.reconstruct(vss, &shareboxes[1..]), Ok(secret.clone()));
+        assert!(secret.bits() <= 256);
+        assert_eq!(
+            vss.combine_shares(&shareboxes),
+            Err(VssError::ForeignShare {
+                publickey: publickeys[1].clone()
+            })
+        );
+
+        let mut other = joint.dealings[0].distribution.clone();
+
+        other.commitments.pop();
+
+        assert_eq!(
+            vss.combine_distributions(&[joint.dealings[1].distribution.clone(), other]),
+            Err(VssError::InconsistentDistribution)
+        );
+    }
+
+    #[test]
+    fn test_key_generation() {
+        let ceremony = fixed_ceremony(3, 2, 19).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let mut machines: Vec<KeyGeneration> = publickeys
+            .iter()
+            .map(|publickey| KeyGeneration::new(publickey, &publickeys, 2))
+            .collect();
+
+        // dealer 0's dealing never reaches participant 2, dealer 1's reaches participant 0
+        // damaged, and dealer 1's answer to the complaint goes missing
+        for (dealer, participant) in participants.iter().enumerate() {
+            let dealing = participant.clone().deal_key_share(&publickeys, 2).unwrap();
+            let message = machines[dealer].deal(dealing.clone()).unwrap();
+
+            for (recipient, machine) in machines.iter_mut().enumerate() {
+                match (dealer, recipient) {
+                    (_, recipient) if recipient == dealer => {}
+                    (0, 2) => {}
+                    (1, 0) => {
+                        let mut damaged = dealing.clone();
+
+                        damaged.distribution.challenge += 1;
+                        machine
+                            .handle(KeyGenerationMessage::Dealing(damaged))
+                            .unwrap();
+                    }
+                    _ => machine.handle(message.clone()).unwrap(),
+                }
+            }
+        }
+
+        let mut complaints: Vec<KeyGenerationMessage> = machines
+            .iter_mut()
+            .flat_map(|machine| machine.advance(vss).unwrap())
+            .collect();
+
+        assert_eq!(complaints.len(), 2);
+
+        // a complaint about a dealing that verifies is dismissed
+        let received = machines[0].received[&publickeys[2]].clone();
+
+        complaints.push(KeyGenerationMessage::Complaint(KeyComplaint {
+            complainer: publickeys[0].clone(),
+            dealer: publickeys[2].clone(),
+            received: Some(received),
+        }));
+
+        for (index, machine) in machines.iter_mut().enumerate() {
+            for complaint in &complaints {
+                match complaint {
+                    KeyGenerationMessage::Complaint(filed)
+                        if filed.complainer == publickeys[index] => {}
+                    _ => machine.handle(complaint.clone()).unwrap(),
+                }
+            }
+        }
+
+        let openings: Vec<Vec<KeyGenerationMessage>> = machines
+            .iter_mut()
+            .map(|machine| machine.advance(vss).unwrap())
+            .collect();
+
+        assert_eq!(openings[0].len(), 1);
+        assert_eq!(openings[1].len(), 1);
+        assert!(openings[2].is_empty());
+
+        // participants 0 and 2 never see an opening from dealer 1 and disqualify it
+        machines[2].handle(openings[0][0].clone()).unwrap();
+
+        for machine in machines.iter_mut() {
+            assert!(machine.advance(vss).unwrap().is_empty());
+            assert_eq!(machine.phase, KeyGenerationPhase::Finished);
+        }
+
+        let joints: Vec<JointDistribution> = [&machines[0], &machines[2]]
+            .iter()
+            .map(|machine| machine.finish(vss).unwrap())
+            .collect();
+
+        assert_eq!(machines[0].disqualified(vss), vec![publickeys[1].clone()]);
+        assert_eq!(joints[0], joints[1]);
+        assert_eq!(
+            joints[0].qualified(),
+            vec![publickeys[0].clone(), publickeys[2].clone()]
+        );
+        assert!(joints[0].verify(vss));
+        assert_eq!(
+            machines[0].handle(openings[0][0].clone()),
+            Err(VssError::UnexpectedMessage)
+        );
+    }
+}
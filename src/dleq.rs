@@ -1,10 +1,17 @@
-use num_bigint::{BigInt, BigUint, ToBigInt};
-use num_integer::Integer;
-use num_primes::Generator;
-use num_traits::{One, Zero};
-use sha2::{Digest, Sha256};
+use num_bigint::BigInt;
+use num_traits::Zero;
+use rand::{CryptoRng, RngCore};
 use std::option::Option;
 
+use crate::{
+    error::VssError,
+    group::{CyclicGroup, ModpGroup},
+    sigma::{AndProof, DleqStatement},
+    transcript::Transcript,
+    vss::VSS,
+    wire::{Reader, Writer},
+};
+
 // Chaum-Pedersen Protocol
 
 // To prove that log_g1(h1)= log_g2(h2), for generators g1,h1,g2,h2 ∈ Gq,   Gq is group of order q and q is prime
@@ -20,71 +27,87 @@ use std::option::Option;
 struct Prover {}
 
 impl Prover {
-    fn send(g: &BigInt, w: &BigInt, q: &BigInt) -> BigInt {
-        g.modpow(w, q)
-    }
-
-    fn response(w: &BigInt, alpha: &BigInt, c: &Option<BigInt>, q: &BigInt) -> Option<BigInt> {
-        match c {
-            None => None,
-            Some(c) => {
-                let r: BigInt = w.to_bigint().unwrap() - (alpha * c).to_bigint().unwrap();
-                let result = r.mod_floor(&(q.clone().to_bigint().unwrap() - BigInt::one()));
-                Some(result)
-            }
-        }
+    fn send<G: CyclicGroup>(group: &G, g: &G::Element, w: &G::Scalar) -> G::Element {
+        group.exp_secret(g, w)
+    }
+
+    fn response<G: CyclicGroup>(
+        group: &G,
+        w: &G::Scalar,
+        alpha: &G::Scalar,
+        c: &Option<G::Scalar>,
+    ) -> Option<G::Scalar> {
+        c.as_ref()
+            .map(|c| group.scalar_sub(w, &group.scalar_mul(alpha, c)))
     }
 }
 struct Verifier {}
 
 impl Verifier {
-    fn send() -> BigInt {
-        BigInt::zero()
+    fn check<G: CyclicGroup, T: Transcript>(
+        group: &G,
+        c: &G::Scalar,
+        challenge_hasher: &T,
+    ) -> bool {
+        let challenge_hash = challenge_hasher.clone().challenge();
+
+        group.scalar_from_hash(&challenge_hash[..]) == *c
     }
 
-    fn check(c: &BigInt, q: &BigInt, challenge_hasher: &Sha256) -> bool {
-        let challenge_hash = challenge_hasher.clone().finalize();
-        let challenge_big_uint = BigUint::from_bytes_be(&challenge_hash[..])
-            .mod_floor(&(q.clone().to_biguint().unwrap() - BigUint::one()));
-
-        challenge_big_uint == (*c).to_biguint().unwrap()
+    fn recompute<G: CyclicGroup>(
+        group: &G,
+        g: &G::Element,
+        h: &G::Element,
+        response: &G::Scalar,
+        c: &G::Scalar,
+    ) -> G::Element {
+        group.mul(&group.exp(g, response), &group.exp(h, c))
     }
+}
 
-    #[allow(clippy::too_many_arguments)]
-    fn update(
-        g1: &BigInt,
-        h1: &BigInt,
-        g2: &BigInt,
-        h2: &BigInt,
-        response: &BigInt,
-        c: &BigInt,
-        q: &BigInt,
-        challenge_hasher: &mut Sha256,
-    ) {
-        let a1 = (g1.modpow(response, q) * h1.modpow(c, q)) % q;
-        let a2 = (g2.modpow(response, q) * h2.modpow(c, q)) % q;
+// g1, h1, g2, h2, a1, a2 in the order of the transcript spec, the generators as context
+fn append_statement<G: CyclicGroup, T: Transcript>(
+    group: &G,
+    elements: [&G::Element; 6],
+    challenge_hasher: &mut T,
+) {
+    let [g1, h1, g2, h2, a1, a2] = elements;
+
+    challenge_hasher.append_bound_context(
+        group.binds_context(),
+        b"g1",
+        &group.element_to_bytes(g1),
+    );
+    challenge_hasher.append(b"h1", &group.element_to_bytes(h1));
+    challenge_hasher.append_bound_context(
+        group.binds_context(),
+        b"g2",
+        &group.element_to_bytes(g2),
+    );
+    challenge_hasher.append(b"h2", &group.element_to_bytes(h2));
+    challenge_hasher.append(b"a1", &group.element_to_bytes(a1));
+    challenge_hasher.append(b"a2", &group.element_to_bytes(a2));
+}
 
-        challenge_hasher.update(h1.to_biguint().unwrap().to_str_radix(10).as_bytes());
-        challenge_hasher.update(h2.to_biguint().unwrap().to_str_radix(10).as_bytes());
-        challenge_hasher.update(a1.to_biguint().unwrap().to_str_radix(10).as_bytes());
-        challenge_hasher.update(a2.to_biguint().unwrap().to_str_radix(10).as_bytes());
-    }
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone)]
+pub struct DLEQ<G: CyclicGroup = ModpGroup> {
+    pub g1: G::Element,
+    pub h1: G::Element,
+    pub g2: G::Element,
+    pub h2: G::Element,
+
+    pub w: G::Scalar,
+    pub group: G,
+    pub alpha: G::Scalar,
+    pub c: Option<G::Scalar>,
+    pub r: Option<G::Scalar>,
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct DLEQ {
-    pub g1: BigInt,
-    pub h1: BigInt,
-    pub g2: BigInt,
-    pub h2: BigInt,
-
-    pub w: BigInt,
-    pub q: BigInt,
-    pub alpha: BigInt,
-    pub c: Option<BigInt>,
-    pub a1: BigInt,
-    pub a2: BigInt,
-    pub r: Option<BigInt>,
+impl Default for DLEQ {
+    fn default() -> Self {
+        DLEQ::new()
+    }
 }
 
 impl DLEQ {
@@ -96,82 +119,202 @@ impl DLEQ {
             h2: BigInt::zero(),
 
             w: BigInt::zero(),
-            q: BigInt::zero(),
+            group: ModpGroup::default(),
             alpha: BigInt::zero(),
             c: None,
-            a1: BigInt::zero(),
-            a2: BigInt::zero(),
             r: None,
         }
     }
+}
 
-    #[allow(dead_code)]
-    pub fn init(
-        &mut self,
-        g1: BigInt,
-        h1: BigInt,
-        g2: BigInt,
-        h2: BigInt,
-        length: u32,
-        q: BigInt,
-        alpha: BigInt,
-    ) {
-        let w: BigUint = Generator::new_prime(length as usize).mod_floor(&q.to_biguint().unwrap());
-
-        self.init2(g1, h1, g2, h2, q, alpha, w.to_bigint().unwrap());
+impl<G: CyclicGroup> DLEQ<G> {
+    /// A prover for `alpha` = log_g1(h1) = log_g2(h2) with nonce `w`.
+    #[cfg(any(test, feature = "bls12_381"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_group(
+        group: G,
+        g1: G::Element,
+        h1: G::Element,
+        g2: G::Element,
+        h2: G::Element,
+        alpha: G::Scalar,
+        w: G::Scalar,
+    ) -> Self {
+        DLEQ {
+            g1,
+            h1,
+            g2,
+            h2,
+            w,
+            group,
+            alpha,
+            c: None,
+            r: None,
+        }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn init2(
         &mut self,
-        g1: BigInt,
-        h1: BigInt,
-        g2: BigInt,
-        h2: BigInt,
-        q: BigInt,
-        alpha: BigInt,
-        w: BigInt,
+        g1: G::Element,
+        h1: G::Element,
+        g2: G::Element,
+        h2: G::Element,
+        group: G,
+        alpha: G::Scalar,
+        w: G::Scalar,
     ) {
         self.g1 = g1;
         self.h1 = h1;
         self.g2 = g2;
         self.h2 = h2;
-        self.q = q;
+        self.group = group;
         self.alpha = alpha;
         self.w = w;
     }
 
-    pub fn get_a1(&self) -> BigInt {
-        Prover::send(&self.g1, &self.w, &self.q)
+    #[cfg(any(test, feature = "bls12_381"))]
+    pub fn get_a1(&self) -> G::Element {
+        Prover::send(&self.group, &self.g1, &self.w)
     }
 
-    pub fn get_a2(&self) -> BigInt {
-        Prover::send(&self.g2, &self.w, &self.q)
+    pub fn get_a2(&self) -> G::Element {
+        Prover::send(&self.group, &self.g2, &self.w)
     }
 
-    pub fn get_r(&self) -> Option<BigInt> {
-        Prover::response(&self.w, &self.alpha, &self.c, &self.q)
+    pub fn get_r(&self) -> Option<G::Scalar> {
+        Prover::response(&self.group, &self.w, &self.alpha, &self.c)
     }
 
-    #[allow(dead_code)]
-    pub fn get_c(&self) -> BigInt {
-        Verifier::send()
+    /// Appends the statement and the prover's commitments a1, a2, from which the challenge is
+    /// derived.
+    #[cfg(feature = "bls12_381")]
+    pub fn commit_hash<T: Transcript>(&self, challenge_hasher: &mut T) {
+        append_statement(
+            &self.group,
+            [
+                &self.g1,
+                &self.h1,
+                &self.g2,
+                &self.h2,
+                &self.get_a1(),
+                &self.get_a2(),
+            ],
+            challenge_hasher,
+        );
     }
 
-    pub fn update_hash(&self, challenge_hasher: &mut Sha256) {
-        Verifier::update(
-            &self.g1,
-            &self.h1,
-            &self.g2,
-            &self.h2,
-            &self.r.clone().unwrap(),
-            &self.c.clone().unwrap(),
-            &self.q,
+    /// Appends the statement and the commitments recomputed from the response and the challenge.
+    pub fn update_hash<T: Transcript>(&self, challenge_hasher: &mut T) {
+        self.append_recomputed(&self.recompute(), challenge_hasher)
+    }
+
+    /// The commitments a1, a2 recomputed from the response and the challenge, the four
+    /// exponentiations of `update_hash`, which can run apart from the hashing.
+    pub fn recompute(&self) -> [G::Element; 2] {
+        let r = self.r.as_ref().unwrap();
+        let c = self.c.as_ref().unwrap();
+
+        [
+            Verifier::recompute(&self.group, &self.g1, &self.h1, r, c),
+            Verifier::recompute(&self.group, &self.g2, &self.h2, r, c),
+        ]
+    }
+
+    /// Appends the statement and the commitments a1, a2, as `update_hash` does with those from
+    /// `recompute` and `commit_hash` with the prover's.
+    pub fn append_recomputed<T: Transcript>(
+        &self,
+        commitments: &[G::Element; 2],
+        challenge_hasher: &mut T,
+    ) {
+        let [a1, a2] = commitments;
+
+        append_statement(
+            &self.group,
+            [&self.g1, &self.h1, &self.g2, &self.h2, a1, a2],
             challenge_hasher,
-        )
+        );
     }
 
-    pub fn check(&self, challenge_hasher: &Sha256) -> bool {
-        Verifier::check(&self.c.clone().unwrap(), &self.q, challenge_hasher)
+    pub fn check<T: Transcript>(&self, challenge_hasher: &T) -> bool {
+        Verifier::check(&self.group, self.c.as_ref().unwrap(), challenge_hasher)
+    }
+}
+
+/// A non-interactive proof that log_g1(h1) = log_g2(h2) in the group of a `VSS`, for statements
+/// of the application's own: the `AndProof` of a single `DleqStatement`, see `sigma`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DleqProof {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub c: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub r: BigInt,
+}
+
+impl DleqProof {
+    /// Proves that `alpha` = log_g1(h1) = log_g2(h2), the nonce drawn from `rng`.
+    pub fn prove<D: Transcript>(
+        vss: &VSS<D>,
+        g1: &BigInt,
+        h1: &BigInt,
+        g2: &BigInt,
+        h2: &BigInt,
+        alpha: &BigInt,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Self {
+        let mut proof = AndProof::prove(
+            vss,
+            &[(DleqStatement::new(g1, h1, g2, h2), alpha.clone())],
+            rng,
+        );
+
+        DleqProof {
+            c: proof.challenge,
+            r: proof.responses.remove(0),
+        }
+    }
+
+    /// Checks the proof for the statement log_g1(h1) = log_g2(h2). Elements outside the group
+    /// fail.
+    pub fn verify<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        g1: &BigInt,
+        h1: &BigInt,
+        g2: &BigInt,
+        h2: &BigInt,
+    ) -> bool {
+        AndProof {
+            challenge: self.c.clone(),
+            responses: vec![self.r.clone()],
+        }
+        .verify(vss, &[DleqStatement::new(g1, h1, g2, h2)])
+    }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.bigint(&self.c);
+        writer.bigint(&self.r);
+
+        writer.into_bytes()
+    }
+
+    /// Decodes `to_bytes`: the challenge, then the response, each as a length-prefixed
+    /// big-endian magnitude. Only the canonical encoding is accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let mut reader = Reader::new(bytes);
+        let proof = DleqProof {
+            c: reader.bigint()?,
+            r: reader.bigint()?,
+        };
+
+        reader.finish()?;
+
+        Ok(proof)
     }
 }
 
@@ -179,7 +322,17 @@ impl DLEQ {
 mod tests {
     use num_bigint::BigInt;
 
-    use super::DLEQ;
+    use sha2::{Digest, Sha256};
+
+    use rand::{prng::ChaChaRng, SeedableRng};
+
+    use crate::{
+        error::VssError,
+        group::{CyclicGroup, HashEncoding, ModpGroup},
+        testkit::fixed_ceremony,
+    };
+
+    use super::{DleqProof, DLEQ};
 
     #[test]
     fn test_dleq() {
@@ -193,7 +346,7 @@ mod tests {
 
         let mut dleq = DLEQ::new();
 
-        dleq.init2(g1, h1, g2, h2, q.clone(), alpha, w);
+        dleq.init2(g1, h1, g2, h2, ModpGroup::new(q.clone()), alpha, w);
 
         let a1 = BigInt::from(14735247);
         let a2 = BigInt::from(5290058);
@@ -208,16 +361,154 @@ mod tests {
 
         assert_eq!(dleq.get_r().unwrap(), r);
         assert_eq!(
-            (dleq.g1.modpow(&dleq.get_r().unwrap(), &dleq.q)
-                * dleq.h1.modpow(&dleq.c.clone().unwrap(), &dleq.q))
+            (dleq.g1.modpow(&dleq.get_r().unwrap(), &q)
+                * dleq.h1.modpow(&dleq.c.clone().unwrap(), &q))
                 % q.clone(),
             a1
         );
         assert_eq!(
-            (dleq.g2.modpow(&dleq.get_r().unwrap(), &dleq.q)
-                * dleq.h2.modpow(&dleq.c.clone().unwrap(), &dleq.q))
+            (dleq.g2.modpow(&dleq.get_r().unwrap(), &q)
+                * dleq.h2.modpow(&dleq.c.clone().unwrap(), &q))
                 % q.clone(),
             a2
         );
     }
+
+    #[test]
+    fn test_dleq_with_group() {
+        let group = ModpGroup::new(BigInt::from(15487469));
+        let alpha = BigInt::from(163027);
+        let g1 = BigInt::from(8443);
+        let g2 = BigInt::from(1299721);
+        let mut prover = DLEQ::with_group(
+            group.clone(),
+            g1.clone(),
+            group.exp(&g1, &alpha),
+            g2.clone(),
+            group.exp(&g2, &alpha),
+            alpha,
+            group.random_scalar(),
+        );
+        let challenge = Sha256::new()
+            .chain(group.element_to_bytes(&prover.h1))
+            .chain(group.element_to_bytes(&prover.h2))
+            .chain(group.element_to_bytes(&prover.get_a1()))
+            .chain(group.element_to_bytes(&prover.get_a2()))
+            .finalize();
+
+        prover.c = Some(group.scalar_from_hash(&challenge[..]));
+        prover.r = prover.get_r();
+
+        let mut hasher = Sha256::new();
+
+        prover.update_hash(&mut hasher);
+        assert!(prover.check(&hasher));
+
+        prover.r = prover.r.map(|r| r + 1);
+
+        let mut hasher = Sha256::new();
+
+        prover.update_hash(&mut hasher);
+        assert!(!prover.check(&hasher));
+    }
+
+    #[test]
+    fn test_dleq_binds_generators() {
+        let group = ModpGroup {
+            encoding: HashEncoding::FixedWidth,
+            ..ModpGroup::new(BigInt::from(15487469))
+        };
+        let alpha = BigInt::from(163027);
+        let g1 = BigInt::from(8443);
+        let g2 = BigInt::from(1299721);
+        let mut prover = DLEQ::with_group(
+            group.clone(),
+            g1.clone(),
+            group.exp(&g1, &alpha),
+            g2.clone(),
+            group.exp(&g2, &alpha),
+            alpha,
+            group.random_scalar(),
+        );
+        let challenge = Sha256::new()
+            .chain(group.element_to_bytes(&prover.g1))
+            .chain(group.element_to_bytes(&prover.h1))
+            .chain(group.element_to_bytes(&prover.g2))
+            .chain(group.element_to_bytes(&prover.h2))
+            .chain(group.element_to_bytes(&prover.get_a1()))
+            .chain(group.element_to_bytes(&prover.get_a2()))
+            .finalize();
+
+        prover.c = Some(group.scalar_from_hash(&challenge[..]));
+        prover.r = prover.get_r();
+
+        let mut hasher = Sha256::new();
+
+        prover.update_hash(&mut hasher);
+        assert!(prover.check(&hasher));
+
+        // the same proof without the generators in the challenge, as decimal transcripts have it
+        let mut hasher = Sha256::new();
+
+        hasher.update(group.element_to_bytes(&prover.h1));
+        hasher.update(group.element_to_bytes(&prover.h2));
+        hasher.update(group.element_to_bytes(&prover.get_a1()));
+        hasher.update(group.element_to_bytes(&prover.get_a2()));
+        assert!(!prover.check(&hasher));
+    }
+
+    #[test]
+    fn test_dleq_proof() {
+        let ceremony = fixed_ceremony(3, 2, 97).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let alpha = BigInt::from(424243);
+        let (h1, h2) = (vss.exp_g(&alpha), vss.exp_G(&alpha));
+        let mut rng = ChaChaRng::from_seed([7; 32]);
+        let proof = DleqProof::prove(vss, &vss.g, &h1, &vss.G, &h2, &alpha, &mut rng);
+
+        assert!(proof.verify(vss, &vss.g, &h1, &vss.G, &h2));
+        // another statement, or the generators swapped
+        assert!(!proof.verify(vss, &vss.g, &h1, &vss.G, &vss.exp_G(&(alpha + 1))));
+        assert!(!proof.verify(vss, &vss.G, &h1, &vss.g, &h2));
+        assert!(!proof.verify(vss, &vss.g, &h1, &vss.G, &BigInt::from(0)));
+
+        let mut forged = proof.clone();
+
+        forged.r += 1;
+
+        assert!(!forged.verify(vss, &vss.g, &h1, &vss.G, &h2));
+    }
+
+    #[test]
+    fn test_dleq_proof_encoding() {
+        let ceremony = fixed_ceremony(3, 2, 97).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let alpha = BigInt::from(271);
+        let (h1, h2) = (vss.exp_g(&alpha), vss.exp_G(&alpha));
+        let mut rng = ChaChaRng::from_seed([9; 32]);
+        let proof = DleqProof::prove(vss, &vss.g, &h1, &vss.G, &h2, &alpha, &mut rng);
+        let mut bytes = proof.to_bytes();
+        let decoded = DleqProof::from_bytes(&bytes).unwrap();
+
+        assert_eq!(decoded, proof);
+        assert!(decoded.verify(vss, &vss.g, &h1, &vss.G, &h2));
+
+        bytes.push(0);
+
+        assert_eq!(
+            DleqProof::from_bytes(&bytes),
+            Err(VssError::MalformedEncoding)
+        );
+        assert_eq!(
+            DleqProof::from_bytes(&bytes[..4]),
+            Err(VssError::MalformedEncoding)
+        );
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&proof).unwrap();
+
+            assert_eq!(serde_json::from_str::<DleqProof>(&json).unwrap(), proof);
+        }
+    }
 }
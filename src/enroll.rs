@@ -0,0 +1,337 @@
+use std::collections::BTreeSet;
+
+use num_bigint::BigInt;
+use num_traits::One;
+
+use crate::{
+    error::VssError,
+    repair::{RepairContribution, RepairRequest},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Enrollment
+
+// Adding a shareholder would otherwise take a new dealing, and with it someone who knows the
+// secret. A share at a position nobody holds yet is interpolated exactly like a lost one, so an
+// enrollment is a repair whose request names the new key at a fresh position, with the new key as
+// recipient: the helpers' blinded contributions give the new shareholder G^p(k) and nothing else.
+// The base distribution cannot take another entry, since its proof covers the entries it was dealt
+// with and nobody knows p(k) to prove one more. The enrollments are kept next to it instead, each
+// with its blindings and contributions as evidence, and place the enrolled keys for reconstruction.
+
+/// The record of one enrollment, see `EnrolledDistribution::request`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Enrollment {
+    pub request: RepairRequest,
+    pub blindings: Vec<DistributionShareBox>,
+    pub contributions: Vec<RepairContribution>,
+}
+
+/// A distribution together with the shareholders enrolled into it since it was dealt. Anyone can
+/// check it with `verify`; all shareholders reconstruct with `reconstruct`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EnrolledDistribution {
+    pub base: DistributionShareBox,
+    pub enrollments: Vec<Enrollment>,
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks that `enrollment` gives a new key a share of `distribution_sharebox`: a request for
+    /// a key and position the distribution does not hold, encrypted to that key, the blindings of
+    /// all helpers and a valid contribution from each.
+    pub fn verify_enrollment(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        enrollment: &Enrollment,
+    ) -> bool {
+        let request = &enrollment.request;
+
+        if request.recipient != request.publickey
+            || distribution_sharebox
+                .positions
+                .contains_key(&request.publickey)
+            || enrollment.contributions.len() != request.helpers.len()
+        {
+            return false;
+        }
+
+        let positions =
+            match self.check_blindings(distribution_sharebox, request, &enrollment.blindings) {
+                Ok(positions) => positions,
+                Err(_) => return false,
+            };
+        let contributors: BTreeSet<&BigInt> = enrollment
+            .contributions
+            .iter()
+            .map(|contribution| &contribution.publickey)
+            .collect();
+
+        contributors.len() == request.helpers.len()
+            && enrollment.contributions.iter().all(|contribution| {
+                self.verify_contribution_proof(
+                    distribution_sharebox,
+                    request,
+                    &positions,
+                    &enrollment.blindings,
+                    contribution,
+                )
+            })
+    }
+}
+
+impl EnrolledDistribution {
+    pub fn new(base: DistributionShareBox) -> Self {
+        EnrolledDistribution {
+            base,
+            enrollments: Vec::new(),
+        }
+    }
+
+    /// The position of `publickey`, dealt or enrolled.
+    pub fn position(&self, publickey: &BigInt) -> Option<&BigInt> {
+        self.base.positions.get(publickey).or_else(|| {
+            self.enrollments
+                .iter()
+                .map(|enrollment| &enrollment.request)
+                .find(|request| &request.publickey == publickey)
+                .map(|request| &request.position)
+        })
+    }
+
+    /// Number of shareholders, dealt and enrolled.
+    pub fn n(&self) -> usize {
+        self.base.n() + self.enrollments.len()
+    }
+
+    /// A request to enroll `publickey` at the next free position, with the help of `helpers`,
+    /// which must be shareholders of the base. The helpers run it like a repair, see
+    /// `Participant::repair_blinding` and `Participant::repair_contribution`, the new shareholder
+    /// decrypts its share with `Participant::repair_share`, and the result is recorded with `add`.
+    pub fn request(
+        &self,
+        publickey: &BigInt,
+        helpers: &[BigInt],
+    ) -> Result<RepairRequest, VssError> {
+        if self.position(publickey).is_some() {
+            return Err(VssError::DuplicatePublicKey {
+                publickey: publickey.clone(),
+            });
+        }
+
+        let position = self
+            .base
+            .positions
+            .values()
+            .chain(
+                self.enrollments
+                    .iter()
+                    .map(|enrollment| &enrollment.request.position),
+            )
+            .max()
+            .cloned()
+            .unwrap_or_default()
+            + BigInt::one();
+        let request = RepairRequest {
+            publickey: publickey.clone(),
+            position,
+            recipient: publickey.clone(),
+            helpers: helpers.to_vec(),
+        };
+
+        request.helper_positions(&self.base)?;
+
+        Ok(request)
+    }
+
+    /// Records `enrollment` if it passes `VSS::verify_enrollment` against the base and enrolls a
+    /// key and position not enrolled before.
+    pub fn add<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+        enrollment: Enrollment,
+    ) -> Result<(), VssError> {
+        let request = &enrollment.request;
+
+        for enrolled in self.enrollments.iter().map(|enrolled| &enrolled.request) {
+            if enrolled.publickey == request.publickey {
+                return Err(VssError::DuplicatePublicKey {
+                    publickey: request.publickey.clone(),
+                });
+            }
+
+            if enrolled.position == request.position {
+                return Err(VssError::DuplicatePosition {
+                    position: request.position.clone(),
+                });
+            }
+        }
+
+        if !vss.verify_enrollment(&self.base, &enrollment) {
+            return Err(VssError::InvalidProof);
+        }
+
+        self.enrollments.push(enrollment);
+
+        Ok(())
+    }
+
+    /// Verifies the base distribution, dealt without a context, and every enrollment against it.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let publickeys: BTreeSet<&BigInt> = self
+            .enrollments
+            .iter()
+            .map(|enrollment| &enrollment.request.publickey)
+            .collect();
+        let positions: BTreeSet<&BigInt> = self
+            .enrollments
+            .iter()
+            .map(|enrollment| &enrollment.request.position)
+            .collect();
+
+        publickeys.len() == self.enrollments.len()
+            && positions.len() == self.enrollments.len()
+            && vss.verify_distribution_shares(&self.base)
+            && self
+                .enrollments
+                .iter()
+                .all(|enrollment| vss.verify_enrollment(&self.base, enrollment))
+    }
+
+    /// Reconstructs the secret from share boxes of dealt and enrolled shareholders alike.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        let shared_value = vss.interpolate_with(share_boxes, &self.base, |publickey| {
+            self.position(publickey)
+        })?;
+
+        vss.unmask(&shared_value, &self.base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{
+        error::VssError, participant::Participant, secret_key::SecretKey, testkit::fixed_ceremony,
+    };
+
+    use super::{EnrolledDistribution, Enrollment};
+
+    #[test]
+    fn test_enroll() {
+        let ceremony = fixed_ceremony(3, 2, 9).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let base = &ceremony.distribution;
+        let helpers = &ceremony.participants[..2];
+        let helper_keys: Vec<BigInt> = helpers.iter().map(|p| p.publickey.clone()).collect();
+        let mut enrolled = EnrolledDistribution::new(base.clone());
+        let mut shares = Vec::new();
+
+        for key in [7901, 4801] {
+            let newcomer =
+                Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(key)));
+            let request = enrolled.request(&newcomer.publickey, &helper_keys).unwrap();
+            let blindings: Vec<_> = helpers
+                .iter()
+                .map(|p| p.clone().repair_blinding(base, &request).unwrap())
+                .collect();
+            let contributions: Vec<_> = helpers
+                .iter()
+                .map(|p| p.repair_contribution(base, &request, &blindings).unwrap())
+                .collect();
+
+            shares.push(
+                newcomer
+                    .repair_share(base, &request, &blindings, &contributions)
+                    .unwrap(),
+            );
+            enrolled
+                .add(
+                    vss,
+                    Enrollment {
+                        request,
+                        blindings,
+                        contributions,
+                    },
+                )
+                .unwrap();
+        }
+
+        assert_eq!(enrolled.n(), 5);
+        assert_eq!(
+            enrolled.position(&shares[1].publickey),
+            Some(&BigInt::from(5))
+        );
+        assert!(enrolled.verify(vss));
+
+        // enrolled shares combine with dealt ones and with each other
+        assert_eq!(
+            enrolled.reconstruct(vss, &[ceremony.shares[0].clone(), shares[0].clone()]),
+            Ok(ceremony.secret.clone())
+        );
+        assert_eq!(
+            enrolled.reconstruct(vss, &shares),
+            Ok(ceremony.secret.clone())
+        );
+    }
+
+    #[test]
+    fn test_invalid_enrollment() {
+        let ceremony = fixed_ceremony(3, 2, 9).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let base = &ceremony.distribution;
+        let helpers = &ceremony.participants[..2];
+        let helper_keys: Vec<BigInt> = helpers.iter().map(|p| p.publickey.clone()).collect();
+        let mut enrolled = EnrolledDistribution::new(base.clone());
+
+        assert_eq!(
+            enrolled.request(&base.publickeys[2], &helper_keys),
+            Err(VssError::DuplicatePublicKey {
+                publickey: base.publickeys[2].clone()
+            })
+        );
+
+        let newcomer =
+            Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(7901)));
+        let request = enrolled.request(&newcomer.publickey, &helper_keys).unwrap();
+        let blindings: Vec<_> = helpers
+            .iter()
+            .map(|p| p.clone().repair_blinding(base, &request).unwrap())
+            .collect();
+        let contributions: Vec<_> = helpers
+            .iter()
+            .map(|p| p.repair_contribution(base, &request, &blindings).unwrap())
+            .collect();
+
+        // every helper must contribute
+        let partial = Enrollment {
+            request: request.clone(),
+            blindings: blindings.clone(),
+            contributions: contributions[..1].to_vec(),
+        };
+
+        assert_eq!(enrolled.add(vss, partial), Err(VssError::InvalidProof));
+
+        // an enrollment encrypted to someone else is not one
+        let mut misdirected = request.clone();
+
+        misdirected.recipient = helper_keys[0].clone();
+
+        let enrollment = Enrollment {
+            request: misdirected,
+            blindings,
+            contributions,
+        };
+
+        assert!(!vss.verify_enrollment(base, &enrollment));
+        assert!(enrolled.enrollments.is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use std::fmt;
+
+use num_bigint::BigInt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VssError {
+    /// the threshold is zero or exceeds the number of recipients
+    InvalidThreshold { threshold: u32, participants: usize },
+    /// a secret was dealt to an empty list of recipients
+    NoRecipients,
+    /// secrets are non-negative integers
+    NegativeSecret,
+    /// the distribution holds no encrypted share for this public key
+    MissingShare { publickey: BigInt },
+    /// a recipient public key is out of range or outside the subgroup generated by `G`
+    InvalidPublicKey { publickey: BigInt },
+    /// a public key appears more than once among the recipients
+    DuplicatePublicKey { publickey: BigInt },
+    /// a share box was submitted by a key that is not a recipient of the distribution
+    UnknownShareholder { publickey: BigInt },
+    /// no recipient of the distribution holds this position
+    UnknownPosition { position: BigInt },
+    /// a recipient position is zero or negative, where the secret or nothing would be dealt
+    InvalidPosition { position: BigInt },
+    /// two recipients were given the same position
+    DuplicatePosition { position: BigInt },
+    /// fewer share boxes than the threshold, or than the threshold of a level of an access
+    /// structure, were provided
+    ThresholdNotMet { required: usize, provided: usize },
+    /// these shareholders submitted share boxes whose proofs failed, leaving too few to reconstruct
+    InvalidShares { publickeys: Vec<BigInt> },
+    /// a proof produced or received failed to verify
+    InvalidProof,
+    /// a value that must be inverted modulo the group order has no inverse
+    NonInvertible,
+    /// the Lagrange coefficient of the share at this position has a denominator with no inverse
+    /// modulo the group order, so the share cannot be interpolated with the others provided
+    NonInvertibleCoefficient { position: BigInt },
+    /// the decrypted share of this shareholder is not an element of the group
+    InvalidShare { publickey: BigInt },
+    /// the share box was extracted under another public key than the participant's own
+    ForeignShare { publickey: BigInt },
+    /// the record has no field with this label
+    UnknownField { label: String },
+    /// group parameters are missing or insecure in this field
+    InvalidParameters { field: &'static str },
+    /// the distribution was dealt over group parameters with this fingerprint
+    ParamsMismatch { fingerprint: [u8; 8] },
+    /// a ceremony manifest disagrees with the party's own parameters
+    ManifestMismatch { field: &'static str },
+    /// the deadline, in seconds since the unix epoch, has passed
+    DeadlinePassed { deadline: u64 },
+    /// the secret does not fit in the requested number of bytes
+    SecretTooLarge { length: usize },
+    /// a ciphertext failed to authenticate under the reconstructed key, or its proof failed
+    InvalidCiphertext,
+    /// bytes are not the canonical binary encoding of the value being decoded
+    MalformedEncoding,
+    /// the recorded threshold or number of recipients disagrees with the distribution's contents
+    InconsistentDistribution,
+    /// the checksum of armored text does not match its contents
+    ChecksumMismatch,
+    /// the encoding was written by a newer format version than this release reads
+    UnsupportedVersion { version: u16 },
+    /// the encoding was written for different group parameters
+    GroupMismatch,
+    /// a protocol message arrived in a phase that does not take it
+    UnexpectedMessage,
+    /// every dealer of a key generation was disqualified
+    NoQualifiedDealers,
+    /// a plaintext is not a nonzero element below the modulus
+    InvalidPlaintext,
+    /// a recipient was given no weight
+    InvalidWeight { publickey: BigInt },
+    /// the share box carries no nonce and issue time
+    MissingFreshness { publickey: BigInt },
+    /// the share box was issued, in seconds since the unix epoch, outside the accepted window
+    StaleShare { issued_at: u64 },
+    /// the nonce of the share box was already admitted
+    ReplayedShare { publickey: BigInt },
+    /// an integer that must not be negative is
+    NegativeValue,
+    /// a committee already has a member with this id
+    DuplicateMember { id: String },
+    /// the distribution does not hold this member of the committee at its position
+    CommitteeMismatch { id: String },
+}
+
+impl fmt::Display for VssError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VssError::InvalidThreshold {
+                threshold,
+                participants,
+            } => write!(
+                f,
+                "threshold {} is not between 1 and the number of participants {}",
+                threshold, participants
+            ),
+            VssError::NoRecipients => write!(f, "no recipients to deal the secret to"),
+            VssError::NegativeSecret => write!(f, "the secret is negative"),
+            VssError::MissingShare { publickey } => {
+                write!(
+                    f,
+                    "no share for public key {} in the distribution",
+                    publickey
+                )
+            }
+            VssError::InvalidPublicKey { publickey } => {
+                write!(f, "public key {} is not a valid group element", publickey)
+            }
+            VssError::DuplicatePublicKey { publickey } => {
+                write!(f, "public key {} is listed more than once", publickey)
+            }
+            VssError::UnknownShareholder { publickey } => {
+                write!(
+                    f,
+                    "public key {} is not a recipient of the distribution",
+                    publickey
+                )
+            }
+            VssError::UnknownPosition { position } => {
+                write!(
+                    f,
+                    "no recipient of the distribution at position {}",
+                    position
+                )
+            }
+            VssError::InvalidPosition { position } => {
+                write!(f, "position {} is not positive", position)
+            }
+            VssError::DuplicatePosition { position } => {
+                write!(f, "position {} is assigned more than once", position)
+            }
+            VssError::ThresholdNotMet { required, provided } => write!(
+                f,
+                "{} share boxes are required to reconstruct, {} provided",
+                required, provided
+            ),
+            VssError::InvalidShares { publickeys } => {
+                let publickeys: Vec<String> =
+                    publickeys.iter().map(|key| key.to_string()).collect();
+
+                write!(
+                    f,
+                    "invalid share boxes from public keys {}",
+                    publickeys.join(", ")
+                )
+            }
+            VssError::InvalidProof => write!(f, "proof failed to verify"),
+            VssError::NonInvertible => write!(f, "value has no inverse modulo the group order"),
+            VssError::NonInvertibleCoefficient { position } => write!(
+                f,
+                "the Lagrange coefficient of the share at position {} has no inverse",
+                position
+            ),
+            VssError::InvalidShare { publickey } => {
+                write!(
+                    f,
+                    "the share of public key {} is not a group element",
+                    publickey
+                )
+            }
+            VssError::ForeignShare { publickey } => {
+                write!(f, "the share box belongs to public key {}", publickey)
+            }
+            VssError::UnknownField { label } => write!(f, "the record has no field {}", label),
+            VssError::InvalidParameters { field } => {
+                write!(f, "invalid or insecure group parameter {}", field)
+            }
+            VssError::ParamsMismatch { fingerprint } => {
+                let fingerprint: String = fingerprint
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+
+                write!(
+                    f,
+                    "the distribution was dealt over group parameters {}",
+                    fingerprint
+                )
+            }
+            VssError::ManifestMismatch { field } => {
+                write!(
+                    f,
+                    "the ceremony manifest does not match the local {}",
+                    field
+                )
+            }
+            VssError::DeadlinePassed { deadline } => write!(f, "deadline {} has passed", deadline),
+            VssError::InvalidCiphertext => write!(f, "ciphertext failed to authenticate"),
+            VssError::MalformedEncoding => write!(f, "malformed binary encoding"),
+            VssError::InconsistentDistribution => write!(
+                f,
+                "the recorded threshold or number of recipients does not match the distribution"
+            ),
+            VssError::ChecksumMismatch => write!(f, "checksum does not match"),
+            VssError::UnsupportedVersion { version } => {
+                write!(f, "format version {} is not supported", version)
+            }
+            VssError::GroupMismatch => write!(f, "encoded for different group parameters"),
+            VssError::UnexpectedMessage => write!(f, "message not expected in this phase"),
+            VssError::NoQualifiedDealers => write!(f, "every dealer was disqualified"),
+            VssError::InvalidPlaintext => write!(f, "plaintext is not an element of the group"),
+            VssError::InvalidWeight { publickey } => {
+                write!(f, "shareholder {} has no weight", publickey)
+            }
+            VssError::MissingFreshness { publickey } => {
+                write!(f, "share box of {} carries no nonce", publickey)
+            }
+            VssError::StaleShare { issued_at } => {
+                write!(f, "share box issued at {} is not fresh", issued_at)
+            }
+            VssError::ReplayedShare { publickey } => {
+                write!(f, "share box of {} was already submitted", publickey)
+            }
+            VssError::NegativeValue => write!(f, "value is negative"),
+            VssError::DuplicateMember { id } => {
+                write!(f, "member {} is listed more than once", id)
+            }
+            VssError::CommitteeMismatch { id } => {
+                write!(
+                    f,
+                    "member {} is not a recipient of the distribution at its position",
+                    id
+                )
+            }
+            VssError::SecretTooLarge { length } => {
+                write!(f, "the secret does not fit in {} bytes", length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VssError {}
@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    polynomial::Polynomial,
+    transcript::Transcript,
+    util::Util,
+    vss::VSS,
+};
+
+// Plain Feldman VSS
+
+// For shares that travel over channels already private and authenticated, e.g. between processes
+// of one operator, the encryption and the proofs of PVSS buy nothing. Feldman's scheme keeps the
+// commitments C_j = g^a_j of the same polynomial and hands out p(i) in the clear: each holder
+// checks g^p(i) against the commitments evaluated at its position, as a PVSS verifier checks X_i,
+// and nobody else can check anything. The secret is p(0) itself, an exponent below q - 1, and is
+// reconstructed by Lagrange interpolation modulo q - 1, with the same coefficients as PVSS
+// reconstruction. Being an exponent rather than an element of the subgroup of G, it cannot be
+// taken modulo the prime order of that subgroup, so denominators sharing a factor with q - 1 fail
+// with `NonInvertibleCoefficient`.
+
+/// A Feldman share, p(position) in the clear.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeldmanShare {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub position: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub value: BigInt,
+}
+
+/// The commitments of a Feldman dealing, C_0 = g^secret first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeldmanDistribution {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub commitments: Vec<BigInt>,
+}
+
+impl FeldmanDistribution {
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Deals `secret`, below q - 1, to `n` holders at positions `1..=n` with `threshold`, as
+    /// clear shares to be sent over private channels. Share i goes to the holder at position i + 1.
+    pub fn distribute_feldman(
+        &self,
+        secret: &BigInt,
+        n: usize,
+        threshold: u32,
+    ) -> Result<(FeldmanDistribution, Vec<FeldmanShare>), VssError> {
+        self.distribute_feldman_with_rng(secret, n, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `distribute_feldman`, drawing the polynomial from `rng`.
+    pub fn distribute_feldman_with_rng(
+        &self,
+        secret: &BigInt,
+        n: usize,
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<(FeldmanDistribution, Vec<FeldmanShare>), VssError> {
+        let order = &self.vss.q - BigInt::one();
+
+        self.vss.validate_clear_dealing(secret, n, threshold)?;
+
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_with_rng((threshold - 1) as i32, &order, rng);
+        polynomial.coefficients[0] = secret.clone();
+
+        let commitments = polynomial
+            .coefficients
+            .iter()
+            .map(|coefficient| self.vss.exp_g(coefficient))
+            .collect();
+        let shares = sequential_positions(n)
+            .into_iter()
+            .map(|position| FeldmanShare {
+                value: polynomial.get_value_mod(&position, &order),
+                position,
+            })
+            .collect();
+
+        Ok((FeldmanDistribution { commitments }, shares))
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    // the checks of a dealing of clear shares, Feldman's or Pedersen's
+    pub(crate) fn validate_clear_dealing(
+        &self,
+        secret: &BigInt,
+        n: usize,
+        threshold: u32,
+    ) -> Result<(), VssError> {
+        let order = &self.q - BigInt::one();
+
+        if n == 0 {
+            return Err(VssError::NoRecipients);
+        }
+
+        if threshold == 0 || threshold as usize > n {
+            return Err(VssError::InvalidThreshold {
+                threshold,
+                participants: n,
+            });
+        }
+
+        if secret < &BigInt::zero() {
+            return Err(VssError::NegativeSecret);
+        }
+
+        // only secrets below the group order survive the exponent
+        if secret >= &order {
+            return Err(VssError::SecretTooLarge {
+                length: (order.bits() - 1) / 8,
+            });
+        }
+
+        Ok(())
+    }
+
+    // p(0) mod q - 1 from at least `threshold` clear values by position, each position counted
+    // once
+    pub(crate) fn interpolate_clear<'a>(
+        &self,
+        values: impl IntoIterator<Item = (&'a BigInt, &'a BigInt)>,
+        threshold: usize,
+    ) -> Result<BigInt, VssError> {
+        let order = &self.q - BigInt::one();
+        let values: BTreeMap<&BigInt, &BigInt> = values.into_iter().collect();
+
+        if values.len() < threshold {
+            return Err(VssError::ThresholdNotMet {
+                required: threshold,
+                provided: values.len(),
+            });
+        }
+
+        let positions: Vec<BigInt> = values.keys().map(|&position| position.clone()).collect();
+        let mut secret = BigInt::zero();
+
+        for (position, value) in values {
+            let (numerator, denominator) =
+                Util::lagrange_coefficient_at(position, &positions, &BigInt::zero());
+            let inverse =
+                Util::mod_inverse(&denominator.mod_floor(&order), &order).ok_or_else(|| {
+                    VssError::NonInvertibleCoefficient {
+                        position: position.clone(),
+                    }
+                })?;
+
+            secret = (secret + numerator * inverse * value).mod_floor(&order);
+        }
+
+        Ok(secret)
+    }
+
+    /// Checks a Feldman share against the commitments: g^value = prod_j C_j^(position^j).
+    pub fn verify_feldman_share(
+        &self,
+        distribution: &FeldmanDistribution,
+        share: &FeldmanShare,
+    ) -> bool {
+        share.position > BigInt::zero()
+            && !distribution.commitments.is_empty()
+            && self.exp_g(&share.value.mod_floor(&(&self.q - BigInt::one())))
+                == self.evaluate_commitments(&distribution.commitments, &share.position)
+    }
+
+    /// Interpolates the secret from at least `threshold` Feldman shares, which are taken as
+    /// they are: check them with `verify_feldman_share` first.
+    pub fn reconstruct_feldman(
+        &self,
+        distribution: &FeldmanDistribution,
+        shares: &[FeldmanShare],
+    ) -> Result<BigInt, VssError> {
+        self.interpolate_clear(
+            shares.iter().map(|share| (&share.position, &share.value)),
+            distribution.threshold(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    #[test]
+    fn test_feldman() {
+        let ceremony = fixed_ceremony(3, 2, 79).unwrap();
+        let dealer = &ceremony.dealer;
+        let vss = &dealer.vss;
+        let secret = BigInt::from(31337);
+        let (distribution, shares) = dealer.distribute_feldman(&secret, 5, 2).unwrap();
+
+        assert_eq!(distribution.threshold(), 2);
+        assert_eq!(distribution.commitments[0], vss.exp_g(&secret));
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_feldman_share(&distribution, share)));
+        assert_eq!(
+            vss.reconstruct_feldman(&distribution, &shares[..2]),
+            Ok(secret.clone())
+        );
+        assert_eq!(
+            vss.reconstruct_feldman(&distribution, &shares[3..]),
+            Ok(secret)
+        );
+        assert_eq!(
+            vss.reconstruct_feldman(&distribution, &[shares[0].clone(), shares[0].clone()]),
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 1
+            })
+        );
+
+        // a share altered on the way, or claimed at another position
+        let mut share = shares[1].clone();
+
+        share.value += 1;
+
+        assert!(!vss.verify_feldman_share(&distribution, &share));
+
+        share = shares[1].clone();
+        share.position = BigInt::from(3);
+
+        assert!(!vss.verify_feldman_share(&distribution, &share));
+    }
+
+    #[test]
+    fn test_invalid_feldman() {
+        let ceremony = fixed_ceremony(3, 2, 83).unwrap();
+        let dealer = &ceremony.dealer;
+
+        assert_eq!(
+            dealer.distribute_feldman(&BigInt::from(1), 0, 1),
+            Err(VssError::NoRecipients)
+        );
+        assert_eq!(
+            dealer.distribute_feldman(&BigInt::from(1), 2, 3),
+            Err(VssError::InvalidThreshold {
+                threshold: 3,
+                participants: 2
+            })
+        );
+        assert_eq!(
+            dealer.distribute_feldman(&BigInt::from(-1), 2, 2),
+            Err(VssError::NegativeSecret)
+        );
+        assert_eq!(
+            dealer.distribute_feldman(&dealer.vss.q, 2, 2),
+            Err(VssError::SecretTooLarge { length: 3 })
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::BTreeSet;
+
+use num_bigint::{BigInt, RandBigInt, ToBigInt};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::VssError,
+    participant::Participant,
+    secret_key::SecretKey,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Share box freshness
+
+// A share box proves the decryption of one encrypted share and nothing about when it was made,
+// so one recorded during a reconstruction verifies just as well in any later one, and whoever
+// sits between a shareholder and the reconstructor can keep submitting it. A fresh share box
+// carries a random nonce and the time it was issued, both absorbed into its challenge after the
+// statement, so neither can be changed without the proof failing. A `FreshnessPolicy` on the
+// reconstructor's side then admits a share box once, and only within its maximum age. Share
+// boxes without freshness still verify as they always did; the policy rejects them.
+
+/// The nonce and issue time, in seconds since the unix epoch, of a fresh share box.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Freshness {
+    pub nonce: [u8; 16],
+    pub issued_at: u64,
+}
+
+impl Freshness {
+    /// A random nonce from `rng`, issued at `issued_at`.
+    pub fn new(issued_at: u64, rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        let mut nonce = [0; 16];
+
+        rng.fill_bytes(&mut nonce);
+
+        Freshness { nonce, issued_at }
+    }
+
+    // the nonce, then the issue time as 8 bytes big-endian
+    pub(crate) fn to_bytes(self) -> [u8; 24] {
+        let mut bytes = [0; 24];
+
+        bytes[..16].copy_from_slice(&self.nonce);
+        bytes[16..].copy_from_slice(&self.issued_at.to_be_bytes());
+
+        bytes
+    }
+
+    pub(crate) fn append_to<D: Transcript>(&self, challenge_hasher: &mut D) {
+        challenge_hasher.append(b"freshness", &self.to_bytes());
+    }
+}
+
+/// Which share boxes a reconstructor admits: fresh ones, issued at most `max_age` seconds ago
+/// and at most `max_skew` seconds ahead of its clock, each nonce once. Nonces are only kept
+/// until their share boxes grow too old to be admitted anyway, so the policy stays as small as
+/// the traffic of one `max_age`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FreshnessPolicy {
+    pub max_age: u64,
+    pub max_skew: u64,
+    // the issue times and nonces admitted, ordered by issue time for pruning
+    seen: BTreeSet<(u64, [u8; 16])>,
+    // share boxes issued before this are stale, whatever the clock says later, as their nonces
+    // may have been pruned
+    pruned_before: u64,
+}
+
+impl FreshnessPolicy {
+    pub fn new(max_age: u64) -> Self {
+        FreshnessPolicy {
+            max_age,
+            max_skew: 0,
+            seen: BTreeSet::new(),
+            pruned_before: 0,
+        }
+    }
+
+    /// Tolerates issue times up to `max_skew` seconds ahead of the reconstructor's clock.
+    pub fn with_max_skew(mut self, max_skew: u64) -> Self {
+        self.max_skew = max_skew;
+        self
+    }
+
+    /// Whether the nonce of a share box has been admitted, and not yet dropped as too old.
+    pub fn has_seen(&self, nonce: &[u8; 16]) -> bool {
+        self.seen.iter().any(|(_, seen)| seen == nonce)
+    }
+
+    // forgets the nonces of share boxes issued more than `max_age` seconds before `now`
+    fn prune(&mut self, now: u64) {
+        let cutoff = now.saturating_sub(self.max_age);
+
+        if cutoff > self.pruned_before {
+            self.seen = self.seen.split_off(&(cutoff, [0; 16]));
+            self.pruned_before = cutoff;
+        }
+    }
+
+    /// Checks the freshness of `sharebox` at time `now` and its proof against
+    /// `distribution_sharebox`, then records its nonce and drops those too old to matter at
+    /// `now`. A share box that fails any check leaves the policy as it was, so that forged share
+    /// boxes cannot use nonces up.
+    pub fn admit<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+        sharebox: &ShareBox,
+        distribution_sharebox: &DistributionShareBox,
+        now: u64,
+    ) -> Result<(), VssError> {
+        let freshness = sharebox
+            .freshness
+            .ok_or_else(|| VssError::MissingFreshness {
+                publickey: sharebox.publickey.clone(),
+            })?;
+
+        if freshness.issued_at.saturating_add(self.max_age) < now
+            || freshness.issued_at < self.pruned_before
+            || freshness.issued_at > now.saturating_add(self.max_skew)
+        {
+            return Err(VssError::StaleShare {
+                issued_at: freshness.issued_at,
+            });
+        }
+
+        if self.seen.contains(&(freshness.issued_at, freshness.nonce)) {
+            return Err(VssError::ReplayedShare {
+                publickey: sharebox.publickey.clone(),
+            });
+        }
+
+        if !vss.verify_share(sharebox, distribution_sharebox, &sharebox.publickey) {
+            return Err(VssError::InvalidProof);
+        }
+
+        self.seen.insert((freshness.issued_at, freshness.nonce));
+        self.prune(now);
+
+        Ok(())
+    }
+
+    /// `admit` for every share box, returning those admitted, in input order, and the public
+    /// key and reason of every other.
+    pub fn admit_all<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+        shareboxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+        now: u64,
+    ) -> (Vec<ShareBox>, Vec<(BigInt, VssError)>) {
+        let mut admitted = Vec::new();
+        let mut rejected = Vec::new();
+
+        for sharebox in shareboxes {
+            match self.admit(vss, sharebox, distribution_sharebox, now) {
+                Ok(()) => admitted.push(sharebox.clone()),
+                Err(error) => rejected.push((sharebox.publickey.clone(), error)),
+            }
+        }
+
+        (admitted, rejected)
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Like `extract_secret_share`, for a share box issued at `now`, in seconds since the unix
+    /// epoch, under a fresh nonce, see `FreshnessPolicy`.
+    pub fn extract_fresh_share(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        private_key: &SecretKey,
+        now: u64,
+    ) -> Result<ShareBox, VssError> {
+        self.extract_fresh_share_with_rng(
+            distribution_sharebox,
+            private_key,
+            now,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Like `extract_fresh_share`, drawing the nonce and the proof nonce from `rng`.
+    pub fn extract_fresh_share_with_rng(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        private_key: &SecretKey,
+        now: u64,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<ShareBox, VssError> {
+        let freshness = Freshness::new(now, rng);
+        let w = rng.gen_biguint_below(&self.vss.q.to_biguint().unwrap());
+        let sharebox = self.extract_share_with_freshness(
+            distribution_sharebox,
+            private_key,
+            &w.to_bigint().unwrap(),
+            &[],
+            Some(freshness),
+        )?;
+
+        if self.verify_after_sign
+            && !self
+                .vss
+                .verify_share(&sharebox, distribution_sharebox, &sharebox.publickey)
+        {
+            return Err(VssError::InvalidProof);
+        }
+
+        Ok(sharebox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{prng::ChaChaRng, SeedableRng};
+
+    use crate::{error::VssError, sharebox::ShareBox, testkit::fixed_ceremony};
+
+    use super::FreshnessPolicy;
+
+    #[test]
+    fn test_fresh_share() {
+        let ceremony = fixed_ceremony(3, 2, 109).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let distribution = &ceremony.distribution;
+        let participants = &ceremony.participants;
+        let mut rng = ChaChaRng::from_seed([13; 32]);
+        let shares: Vec<ShareBox> = participants
+            .iter()
+            .map(|p| {
+                p.extract_fresh_share_with_rng(distribution, &p.privatekey, 1000, &mut rng)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(vss.verify_shares(&shares, distribution), vec![true; 3]);
+        assert_eq!(
+            ShareBox::from_bytes(&shares[0].to_bytes()),
+            Ok(shares[0].clone())
+        );
+
+        let mut policy = FreshnessPolicy::new(60).with_max_skew(5);
+
+        assert_eq!(policy.admit(vss, &shares[0], distribution, 1030), Ok(()));
+        assert!(policy.has_seen(&shares[0].freshness.unwrap().nonce));
+        // resubmitted, too old, or from too far ahead
+        assert_eq!(
+            policy.admit(vss, &shares[0], distribution, 1030),
+            Err(VssError::ReplayedShare {
+                publickey: shares[0].publickey.clone()
+            })
+        );
+        assert_eq!(
+            policy.admit(vss, &shares[1], distribution, 1061),
+            Err(VssError::StaleShare { issued_at: 1000 })
+        );
+        assert_eq!(
+            policy.admit(vss, &shares[1], distribution, 994),
+            Err(VssError::StaleShare { issued_at: 1000 })
+        );
+
+        // a timestamp moved forward breaks the proof, and leaves the nonce unused
+        let mut moved = shares[1].clone();
+
+        moved.freshness.as_mut().unwrap().issued_at = 1050;
+
+        assert!(!vss.verify_share(&moved, distribution, &moved.publickey));
+        assert_eq!(
+            policy.admit(vss, &moved, distribution, 1060),
+            Err(VssError::InvalidProof)
+        );
+
+        let (admitted, rejected) = policy.admit_all(vss, &shares, distribution, 1040);
+
+        assert_eq!(admitted, shares[1..].to_vec());
+        assert_eq!(rejected.len(), 1);
+
+        // a share box without freshness still verifies, but is not admitted
+        let plain = participants[0]
+            .extract_secret_share(distribution, &participants[0].privatekey)
+            .unwrap();
+
+        assert!(vss.verify_share(&plain, distribution, &plain.publickey));
+        assert_eq!(
+            policy.admit(vss, &plain, distribution, 1040),
+            Err(VssError::MissingFreshness {
+                publickey: plain.publickey.clone()
+            })
+        );
+    }
+
+    #[test]
+    fn test_prune() {
+        let ceremony = fixed_ceremony(3, 2, 109).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let distribution = &ceremony.distribution;
+        let participants = &ceremony.participants;
+        let mut rng = ChaChaRng::from_seed([17; 32]);
+        let shares: Vec<ShareBox> = [1000, 1020, 1040]
+            .into_iter()
+            .zip(participants)
+            .map(|(issued_at, p)| {
+                p.extract_fresh_share_with_rng(distribution, &p.privatekey, issued_at, &mut rng)
+                    .unwrap()
+            })
+            .collect();
+        let mut policy = FreshnessPolicy::new(60);
+
+        assert_eq!(policy.admit_all(vss, &shares, distribution, 1040).0, shares);
+        assert_eq!(policy.seen.len(), 3);
+
+        // past its maximum age, the first nonce is dropped; its share box is still stale
+        let late = participants[0]
+            .extract_fresh_share_with_rng(distribution, &participants[0].privatekey, 1070, &mut rng)
+            .unwrap();
+
+        assert_eq!(policy.admit(vss, &late, distribution, 1075), Ok(()));
+        assert_eq!(policy.seen.len(), 3);
+        assert!(!policy.has_seen(&shares[0].freshness.unwrap().nonce));
+        assert!(policy.has_seen(&shares[1].freshness.unwrap().nonce));
+
+        // nor does a clock set back bring it back
+        assert_eq!(
+            policy.admit(vss, &shares[0], distribution, 1040),
+            Err(VssError::StaleShare { issued_at: 1000 })
+        );
+        assert_eq!(
+            policy.admit(vss, &shares[1], distribution, 1040),
+            Err(VssError::ReplayedShare {
+                publickey: shares[1].publickey.clone()
+            })
+        );
+    }
+}
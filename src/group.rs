@@ -0,0 +1,256 @@
+use std::fmt::Debug;
+
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::arith;
+
+// Group abstraction
+
+// The proofs only need a handful of operations on the group and its exponents. Backends implement
+// them for their own element and scalar types; `ModpGroup` is the multiplicative group modulo the
+// safe prime `q` used by `VSS`, with exponents taken modulo q - 1.
+
+// Only `DLEQ` and the threshold BLS and VRF schemes are generic over the group. `VSS`,
+// `Participant` and the share boxes stay on `ModpGroup`: their encodings, stores and bindings all
+// carry `BigInt` values modulo q, and a distribution over another group needs its own share boxes.
+
+// Exponentiations with a secret exponent may leak it through their timing. Short of a
+// constant-time backend, `blind_exponent` adds a fresh random multiple of the group order to the
+// exponent, which leaves the result unchanged but varies the exponent the arithmetic sees.
+
+// Integers are hashed as their decimal digits, as they always have been, so existing transcripts
+// keep their challenges. Parameters built with `HashEncoding::FixedWidth` hash big-endian bytes
+// zero padded to the byte length of the modulus instead, whose concatenations are unambiguous.
+
+pub trait CyclicGroup: Clone + Debug {
+    type Element: Clone + Debug + PartialEq;
+    type Scalar: Clone + Debug + PartialEq;
+
+    /// base^exponent
+    fn exp(&self, base: &Self::Element, exponent: &Self::Scalar) -> Self::Element;
+
+    /// base^exponent for a secret exponent, blinded where the backend supports it
+    fn exp_secret(&self, base: &Self::Element, exponent: &Self::Scalar) -> Self::Element {
+        self.exp(base, exponent)
+    }
+
+    /// the group operation
+    fn mul(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+
+    fn scalar_sub(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+
+    fn scalar_mul(&self, a: &Self::Scalar, b: &Self::Scalar) -> Self::Scalar;
+
+    /// uniformly random exponent
+    fn random_scalar(&self) -> Self::Scalar;
+
+    /// reduces a hash output to an exponent, used for Fiat-Shamir challenges
+    fn scalar_from_hash(&self, hash: &[u8]) -> Self::Scalar;
+
+    /// encoding of an element fed into transcript hashes
+    fn element_to_bytes(&self, element: &Self::Element) -> Vec<u8>;
+
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<Self::Element>;
+
+    /// whether plain hashes absorb the context of a statement, see `Transcript::append_bound_context`
+    fn binds_context(&self) -> bool {
+        true
+    }
+}
+
+/// How integers are fed into hashes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HashEncoding {
+    /// decimal digits, the encoding every transcript has used unless told otherwise
+    #[default]
+    Decimal,
+    /// big-endian bytes, zero padded to the byte length of the modulus, so that no two sequences
+    /// of elements hash alike; opt in with `VssParamsBuilder::encoding`
+    FixedWidth,
+}
+
+impl HashEncoding {
+    // serialized parameters without an encoding predate fixed-width hashing
+    #[cfg(feature = "serde")]
+    pub(crate) fn legacy() -> Self {
+        HashEncoding::Decimal
+    }
+
+    pub fn to_bytes(&self, value: &BigInt, width: usize) -> Vec<u8> {
+        match self {
+            HashEncoding::Decimal => value.to_biguint().unwrap().to_str_radix(10).into_bytes(),
+            HashEncoding::FixedWidth => {
+                let bytes = value.to_biguint().unwrap().to_bytes_be();
+                let mut padded = vec![0; width.saturating_sub(bytes.len())];
+
+                padded.extend_from_slice(&bytes);
+
+                padded
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModpGroup {
+    pub q: BigInt,
+    pub encoding: HashEncoding,
+    /// blind secret exponents, see `VSS::exponent_blinding`
+    pub exponent_blinding: bool,
+}
+
+impl ModpGroup {
+    pub fn new(q: BigInt) -> Self {
+        ModpGroup {
+            q,
+            encoding: HashEncoding::default(),
+            exponent_blinding: false,
+        }
+    }
+
+    /// byte length of the modulus, the width of fixed-width encoded elements
+    pub fn byte_width(&self) -> usize {
+        self.q.bits().div_ceil(8)
+    }
+
+    fn order(&self) -> BigInt {
+        &self.q - BigInt::one()
+    }
+}
+
+impl CyclicGroup for ModpGroup {
+    type Element = BigInt;
+    type Scalar = BigInt;
+
+    fn exp(&self, base: &BigInt, exponent: &BigInt) -> BigInt {
+        arith::modpow(base, exponent, &self.q)
+    }
+
+    fn exp_secret(&self, base: &BigInt, exponent: &BigInt) -> BigInt {
+        if self.exponent_blinding {
+            self.exp(base, &blind_exponent(exponent, &self.order()))
+        } else {
+            self.exp(base, exponent)
+        }
+    }
+
+    fn mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a * b) % &self.q
+    }
+
+    fn scalar_sub(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a - b).mod_floor(&self.order())
+    }
+
+    fn scalar_mul(&self, a: &BigInt, b: &BigInt) -> BigInt {
+        (a * b).mod_floor(&self.order())
+    }
+
+    fn random_scalar(&self) -> BigInt {
+        rand::thread_rng()
+            .gen_biguint_below(&self.order().to_biguint().unwrap())
+            .to_bigint()
+            .unwrap()
+    }
+
+    fn scalar_from_hash(&self, hash: &[u8]) -> BigInt {
+        BigUint::from_bytes_be(hash)
+            .mod_floor(&self.order().to_biguint().unwrap())
+            .to_bigint()
+            .unwrap()
+    }
+
+    fn element_to_bytes(&self, element: &BigInt) -> Vec<u8> {
+        self.encoding.to_bytes(element, self.byte_width())
+    }
+
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<BigInt> {
+        let element = match self.encoding {
+            HashEncoding::Decimal => BigInt::parse_bytes(bytes, 10)?,
+            HashEncoding::FixedWidth if bytes.len() == self.byte_width() => {
+                BigInt::from_bytes_be(num_bigint::Sign::Plus, bytes)
+            }
+            HashEncoding::FixedWidth => return None,
+        };
+
+        if element <= BigInt::zero() || element >= self.q {
+            return None;
+        }
+
+        Some(element)
+    }
+
+    fn binds_context(&self) -> bool {
+        self.encoding == HashEncoding::FixedWidth
+    }
+}
+
+/// `exponent` plus a random 64-bit multiple of `order`, the same exponent for elements whose
+/// order divides `order`.
+pub(crate) fn blind_exponent(exponent: &BigInt, order: &BigInt) -> BigInt {
+    exponent + order * rand::thread_rng().gen_biguint(64).to_bigint().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{CyclicGroup, HashEncoding, ModpGroup};
+
+    #[test]
+    fn test_modp_group() {
+        let group = ModpGroup::new(BigInt::from(23));
+
+        assert_eq!(
+            group.exp(&BigInt::from(5), &BigInt::from(3)),
+            BigInt::from(10)
+        );
+        assert_eq!(
+            group.mul(&BigInt::from(10), &BigInt::from(7)),
+            BigInt::from(1)
+        );
+        assert_eq!(
+            group.scalar_sub(&BigInt::from(3), &BigInt::from(5)),
+            BigInt::from(20)
+        );
+        assert_eq!(
+            group.scalar_mul(&BigInt::from(7), &BigInt::from(5)),
+            BigInt::from(13)
+        );
+        assert_eq!(group.scalar_from_hash(&[1, 0]), BigInt::from(14));
+        assert!(group.random_scalar() < BigInt::from(22));
+
+        let bytes = group.element_to_bytes(&BigInt::from(17));
+
+        assert_eq!(bytes, b"17".to_vec());
+        assert_eq!(group.element_from_bytes(&bytes), Some(BigInt::from(17)));
+        assert_eq!(group.element_from_bytes(b"23"), None);
+
+        let group = ModpGroup {
+            encoding: HashEncoding::FixedWidth,
+            ..group
+        };
+        let bytes = group.element_to_bytes(&BigInt::from(17));
+
+        assert_eq!(bytes, vec![17]);
+        assert_eq!(group.element_from_bytes(&bytes), Some(BigInt::from(17)));
+        assert_eq!(group.element_from_bytes(&[23]), None);
+        assert_eq!(group.element_from_bytes(&[0]), None);
+        assert_eq!(group.element_from_bytes(&[0, 17]), None);
+    }
+
+    #[test]
+    fn test_fixed_width_bytes() {
+        let value = BigInt::from(0x0102);
+        let mut padded = vec![0; 98];
+
+        padded.extend_from_slice(&[1, 2]);
+
+        assert_eq!(HashEncoding::FixedWidth.to_bytes(&value, 100), padded);
+        assert_eq!(HashEncoding::FixedWidth.to_bytes(&value, 1), vec![1, 2]);
+        assert_eq!(HashEncoding::Decimal.to_bytes(&value, 100), b"258".to_vec());
+    }
+}
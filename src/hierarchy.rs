@@ -0,0 +1,522 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::{BigInt, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::{
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    polynomial::{derivative_row, Polynomial},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Hierarchical access structures
+
+// Tassa's hierarchical threshold sharing. Recipients sit in levels, the most senior first, with
+// cumulative thresholds t_0 < t_1 < ... < t_L: a set is authorized when, for every level l, at least
+// t_l of its members sit at level l or above. "A director and any three managers" is directors at
+// threshold 1 and managers at threshold 4, where a second director stands in for a manager.
+//
+// One polynomial p of degree t_L - 1 is dealt. A member of level l receives the derivative
+// p^(t_(l-1))(i) instead of p(i), which says nothing about the t_(l-1) lowest coefficients, so
+// juniors only reach p(0) together with enough seniors. A derivative is as linear in the
+// coefficients as a value, so its commitment X_i is the commitments raised to the factors of the
+// derivative and the distribution is proven, decrypted and verified as usual, given the orders.
+// Reconstruction is Birkhoff rather than Lagrange interpolation: the coefficients of the shares
+// come from solving a linear system, which with positions assigned in level order has a solution
+// for every authorized set. As with Lagrange coefficients, a denominator without an inverse
+// modulo the group order fails with `NonInvertibleCoefficient`.
+
+/// One level of an `AccessStructure`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessLevel {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub publickeys: Vec<BigInt>,
+    /// members needed from this level and the levels above it together
+    pub threshold: u32,
+}
+
+/// Recipients by level, the most senior first, see `AccessStructure::builder`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccessStructure {
+    levels: Vec<AccessLevel>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AccessStructureBuilder {
+    levels: Vec<AccessLevel>,
+}
+
+impl AccessStructureBuilder {
+    pub fn new() -> Self {
+        AccessStructureBuilder { levels: Vec::new() }
+    }
+
+    /// Adds a level below the previous ones, whose members together with those above need
+    /// `threshold` of them to reconstruct.
+    pub fn level(mut self, publickeys: &[BigInt], threshold: u32) -> Self {
+        self.levels.push(AccessLevel {
+            publickeys: publickeys.to_vec(),
+            threshold,
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<AccessStructure, VssError> {
+        let access = AccessStructure {
+            levels: self.levels,
+        };
+
+        access.validate()?;
+
+        Ok(access)
+    }
+}
+
+impl AccessStructure {
+    pub fn builder() -> AccessStructureBuilder {
+        AccessStructureBuilder::new()
+    }
+
+    pub fn levels(&self) -> &[AccessLevel] {
+        &self.levels
+    }
+
+    /// The threshold of the most junior level, the number of shares any reconstruction takes.
+    pub fn threshold(&self) -> u32 {
+        self.levels.last().map_or(0, |level| level.threshold)
+    }
+
+    /// Every recipient, level by level.
+    pub fn publickeys(&self) -> Vec<BigInt> {
+        self.levels
+            .iter()
+            .flat_map(|level| level.publickeys.iter().cloned())
+            .collect()
+    }
+
+    /// The level of `publickey`, 0 the most senior.
+    pub fn level_of(&self, publickey: &BigInt) -> Option<usize> {
+        self.levels
+            .iter()
+            .position(|level| level.publickeys.contains(publickey))
+    }
+
+    // thresholds must grow level by level, and each must be reachable by the members so far
+    fn validate(&self) -> Result<(), VssError> {
+        if self.levels.is_empty() {
+            return Err(VssError::NoRecipients);
+        }
+
+        let mut seen = BTreeSet::new();
+        let mut previous = 0;
+
+        for level in &self.levels {
+            for publickey in &level.publickeys {
+                if !seen.insert(publickey) {
+                    return Err(VssError::DuplicatePublicKey {
+                        publickey: publickey.clone(),
+                    });
+                }
+            }
+
+            if level.threshold <= previous || level.threshold as usize > seen.len() {
+                return Err(VssError::InvalidThreshold {
+                    threshold: level.threshold,
+                    participants: seen.len(),
+                });
+            }
+
+            previous = level.threshold;
+        }
+
+        Ok(())
+    }
+
+    // the derivative each recipient is dealt: the threshold of the level above its own
+    pub(crate) fn orders(&self) -> BTreeMap<BigInt, u32> {
+        let mut orders = BTreeMap::new();
+        let mut order = 0;
+
+        for level in &self.levels {
+            for publickey in &level.publickeys {
+                orders.insert(publickey.clone(), order);
+            }
+
+            order = level.threshold;
+        }
+
+        orders
+    }
+
+    /// Checks that `publickeys` hold enough members at every level and above, failing with the
+    /// first level short of its threshold.
+    pub fn check_authorized(&self, publickeys: &[BigInt]) -> Result<(), VssError> {
+        let publickeys: BTreeSet<&BigInt> = publickeys.iter().collect();
+        let mut provided = 0;
+
+        for level in &self.levels {
+            provided += level
+                .publickeys
+                .iter()
+                .filter(|publickey| publickeys.contains(publickey))
+                .count();
+
+            if provided < level.threshold as usize {
+                return Err(VssError::ThresholdNotMet {
+                    required: level.threshold as usize,
+                    provided,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_authorized(&self, publickeys: &[BigInt]) -> bool {
+        self.check_authorized(publickeys).is_ok()
+    }
+}
+
+/// A distribution over an `AccessStructure`. Its shares are extracted and proven from
+/// `distribution` as usual, but only `verify` and `reconstruct` here know the derivatives.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HierarchicalDistribution {
+    pub distribution: DistributionShareBox,
+    pub access: AccessStructure,
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Like `distribute_secret` over the levels of `access` rather than a flat threshold, the
+    /// recipients at positions `1..=n` in level order.
+    pub fn distribute_secret_with_access(
+        &mut self,
+        secret: &BigInt,
+        access: &AccessStructure,
+    ) -> Result<HierarchicalDistribution, VssError> {
+        access.validate()?;
+
+        let rng = &mut rand::thread_rng();
+        let publickeys = access.publickeys();
+        let threshold = access.threshold();
+        let orders = access.orders();
+        let mut polynomial = Polynomial::new();
+
+        self.validate_inputs(
+            secret,
+            &publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            None,
+        )?;
+        polynomial.init_with_rng(
+            (threshold - 1) as i32,
+            &self.vss.q.to_bigint().unwrap(),
+            rng,
+        );
+
+        let w = rng.gen_biguint_below(&self.vss.q.to_biguint().unwrap());
+        let distribution = self.distribute_with_orders(
+            secret,
+            &publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &polynomial,
+            &w.to_bigint().unwrap(),
+            &[],
+            None,
+            &orders,
+        )?;
+
+        if self.verify_after_sign
+            && !self
+                .vss
+                .verify_distribution_with_orders(&distribution, &[], &orders)
+        {
+            return Err(VssError::InvalidProof);
+        }
+
+        Ok(HierarchicalDistribution {
+            distribution,
+            access: access.clone(),
+        })
+    }
+}
+
+// lambda with sum_i lambda_i rows_i = (1, 0, ..., 0), so that sum_i lambda_i p^(k_i)(x_i) = p(0),
+// by fraction-free Gauss-Jordan elimination on the transposed system; each lambda_i as a
+// numerator and a denominator, `None` for a singular system
+fn birkhoff_coefficients(rows: &[Vec<BigInt>]) -> Option<Vec<(BigInt, BigInt)>> {
+    let t = rows.len();
+    let mut matrix: Vec<Vec<BigInt>> = (0..t)
+        .map(|j| {
+            let mut equation: Vec<BigInt> = rows.iter().map(|row| row[j].clone()).collect();
+
+            equation.push(if j == 0 {
+                BigInt::one()
+            } else {
+                BigInt::zero()
+            });
+            equation
+        })
+        .collect();
+
+    for column in 0..t {
+        let pivot = (column..t).find(|&r| !matrix[r][column].is_zero())?;
+
+        matrix.swap(column, pivot);
+
+        for r in 0..t {
+            if r == column || matrix[r][column].is_zero() {
+                continue;
+            }
+
+            let (scale, factor) = (matrix[column][column].clone(), matrix[r][column].clone());
+            let reduced: Vec<BigInt> = matrix[r]
+                .iter()
+                .zip(&matrix[column])
+                .map(|(entry, pivot_entry)| entry * &scale - pivot_entry * &factor)
+                .collect();
+            let gcd = reduced
+                .iter()
+                .fold(BigInt::zero(), |gcd, entry| gcd.gcd(entry));
+
+            matrix[r] = if gcd.is_zero() {
+                reduced
+            } else {
+                reduced.into_iter().map(|entry| entry / &gcd).collect()
+            };
+        }
+    }
+
+    Some(
+        matrix
+            .into_iter()
+            .enumerate()
+            .map(|(i, equation)| (equation[t].clone(), equation[i].clone()))
+            .collect(),
+    )
+}
+
+impl HierarchicalDistribution {
+    /// Verifies the distribution with every share at the derivative of its level, and that the
+    /// recipients are those of `access` at positions `1..=n` in level order.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let publickeys = self.access.publickeys();
+
+        self.access.validate().is_ok()
+            && self.distribution.publickeys == publickeys
+            && publickeys
+                .iter()
+                .zip(sequential_positions(publickeys.len()))
+                .all(|(publickey, position)| {
+                    self.distribution.positions.get(publickey) == Some(&position)
+                })
+            && vss.verify_distribution_with_orders(&self.distribution, &[], &self.access.orders())
+    }
+
+    /// Reconstructs the secret from the share boxes of an authorized set, see
+    /// `AccessStructure::check_authorized`. Of more shares than needed, the most senior are used.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        vss.check_params(&self.distribution)?;
+        self.distribution.validate()?;
+
+        // a shareholder submitting twice counts once
+        let mut shares = BTreeMap::new();
+
+        for share_box in share_boxes {
+            let position = self
+                .distribution
+                .positions
+                .get(&share_box.publickey)
+                .ok_or_else(|| VssError::UnknownShareholder {
+                    publickey: share_box.publickey.clone(),
+                })?;
+
+            if share_box.share <= BigInt::zero() || share_box.share >= vss.q {
+                return Err(VssError::InvalidShare {
+                    publickey: share_box.publickey.clone(),
+                });
+            }
+
+            shares.insert(position, share_box);
+        }
+
+        let holders: Vec<BigInt> = shares
+            .values()
+            .map(|share_box| share_box.publickey.clone())
+            .collect();
+
+        self.access.check_authorized(&holders)?;
+
+        // positions follow the levels, so the lowest are the most senior
+        let orders = self.access.orders();
+        let threshold = self.access.threshold() as usize;
+        let selected: Vec<(&BigInt, &ShareBox)> = shares.into_iter().take(threshold).collect();
+        let rows: Vec<Vec<BigInt>> = selected
+            .iter()
+            .map(|(position, share_box)| {
+                derivative_row(threshold, position, orders[&share_box.publickey])
+            })
+            .collect();
+        let coefficients = birkhoff_coefficients(&rows).ok_or(VssError::NonInvertible)?;
+        let mut shared_value = BigInt::one();
+
+        for ((position, share_box), coefficient) in selected.into_iter().zip(coefficients) {
+            let factor = vss.raise_to_coefficient(position, &share_box.share, coefficient)?;
+
+            shared_value = (shared_value * factor) % &vss.q;
+        }
+
+        vss.unmask(&shared_value, &self.distribution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::AccessStructure;
+
+    #[test]
+    fn test_hierarchical_distribution() {
+        let ceremony = fixed_ceremony(5, 3, 61).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        // a director and two managers, or two directors and a manager
+        let access = AccessStructure::builder()
+            .level(&publickeys[..2], 1)
+            .level(&publickeys[2..], 3)
+            .build()
+            .unwrap();
+        let secret = BigInt::from(1234567);
+        let hierarchical = ceremony
+            .dealer
+            .clone()
+            .distribute_secret_with_access(&secret, &access)
+            .unwrap();
+
+        assert!(hierarchical.verify(vss));
+        // the managers' derivative shares do not pass as values
+        assert!(!vss.verify_distribution_shares(&hierarchical.distribution));
+
+        let shares: Vec<_> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&hierarchical.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(shares.iter().all(|share| vss.verify_share(
+            share,
+            &hierarchical.distribution,
+            &share.publickey
+        )));
+
+        let director_and_managers = [shares[1].clone(), shares[2].clone(), shares[4].clone()];
+        let directors_and_manager = [shares[0].clone(), shares[1].clone(), shares[3].clone()];
+
+        assert_eq!(
+            hierarchical.reconstruct(vss, &director_and_managers),
+            Ok(secret.clone())
+        );
+        assert_eq!(
+            hierarchical.reconstruct(vss, &directors_and_manager),
+            Ok(secret)
+        );
+
+        // however many managers, none of them is a director
+        assert_eq!(
+            hierarchical.reconstruct(vss, &shares[2..]),
+            Err(VssError::ThresholdNotMet {
+                required: 1,
+                provided: 0
+            })
+        );
+        assert_eq!(
+            hierarchical.reconstruct(vss, &shares[1..3]),
+            Err(VssError::ThresholdNotMet {
+                required: 3,
+                provided: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_access_structure() {
+        let ceremony = fixed_ceremony(3, 2, 67).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let publickeys: Vec<BigInt> = ceremony
+            .participants
+            .iter()
+            .map(|p| p.publickey.clone())
+            .collect();
+
+        assert_eq!(
+            AccessStructure::builder().build(),
+            Err(VssError::NoRecipients)
+        );
+        // thresholds must grow from level to level
+        assert_eq!(
+            AccessStructure::builder()
+                .level(&publickeys[..1], 1)
+                .level(&publickeys[1..], 1)
+                .build(),
+            Err(VssError::InvalidThreshold {
+                threshold: 1,
+                participants: 3
+            })
+        );
+        assert_eq!(
+            AccessStructure::builder()
+                .level(&publickeys[..1], 2)
+                .level(&publickeys[1..], 3)
+                .build(),
+            Err(VssError::InvalidThreshold {
+                threshold: 2,
+                participants: 1
+            })
+        );
+        assert_eq!(
+            AccessStructure::builder()
+                .level(&publickeys[..2], 1)
+                .level(&publickeys[1..], 2)
+                .build(),
+            Err(VssError::DuplicatePublicKey {
+                publickey: publickeys[1].clone()
+            })
+        );
+
+        let access = AccessStructure::builder()
+            .level(&publickeys[..1], 1)
+            .level(&publickeys[1..], 2)
+            .build()
+            .unwrap();
+        let mut hierarchical = ceremony
+            .dealer
+            .clone()
+            .distribute_secret_with_access(&BigInt::from(9), &access)
+            .unwrap();
+
+        assert!(hierarchical.verify(vss));
+
+        // a junior passed off as senior
+        hierarchical.distribution.publickeys.swap(0, 1);
+
+        assert!(!hierarchical.verify(vss));
+    }
+}
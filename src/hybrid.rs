@@ -0,0 +1,157 @@
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305,
+};
+use num_bigint::BigInt;
+use rand::RngCore;
+
+use crate::{
+    bytes_to_secret,
+    error::VssError,
+    participant::Participant,
+    registry::TranscriptRegistry,
+    secret_to_bytes,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+};
+
+// Hybrid sharing
+
+// Payloads of any size are encrypted under a fresh 256-bit key with ChaCha20-Poly1305, and only the
+// key is shared. The ciphertext is authenticated together with the distribution's transcript hash,
+// so it only opens with the key reconstructed from that distribution.
+
+pub(crate) const KEY_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SealedPayload {
+    /// the distribution of the payload key
+    pub distribution: DistributionShareBox,
+    pub nonce: [u8; 12],
+    pub ciphertext: Vec<u8>,
+}
+
+pub(crate) fn cipher(key: &[u8]) -> ChaCha20Poly1305 {
+    ChaCha20Poly1305::new_from_slice(key).unwrap()
+}
+
+// a fresh key, shared among `publickeys`
+pub(crate) fn share_key<D: Transcript>(
+    dealer: &mut Participant<D>,
+    publickeys: &[BigInt],
+    threshold: u32,
+) -> Result<([u8; KEY_LEN], DistributionShareBox), VssError> {
+    let mut key = [0; KEY_LEN];
+
+    rand::thread_rng().fill_bytes(&mut key);
+
+    let distribution = dealer.distribute_secret(&bytes_to_secret(&key), publickeys, threshold)?;
+
+    Ok((key, distribution))
+}
+
+pub(crate) fn recover_key<D: Transcript>(
+    participant: &Participant<D>,
+    share_boxes: &[ShareBox],
+    distribution: &DistributionShareBox,
+) -> Result<Vec<u8>, VssError> {
+    let key = participant.reconstruct(share_boxes, distribution)?;
+
+    secret_to_bytes(&key, KEY_LEN).map_err(|_| VssError::InvalidCiphertext)
+}
+
+pub(crate) fn seal<D: Transcript>(
+    dealer: &mut Participant<D>,
+    payload: &[u8],
+    publickeys: &[BigInt],
+    threshold: u32,
+) -> Result<SealedPayload, VssError> {
+    let (key, distribution) = share_key(dealer, publickeys, threshold)?;
+    let mut nonce = [0; 12];
+
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let aad = TranscriptRegistry::transcript_hash(&distribution)?;
+    let ciphertext = cipher(&key)
+        .encrypt(
+            &nonce.into(),
+            Payload {
+                msg: payload,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| VssError::InvalidCiphertext)?;
+
+    Ok(SealedPayload {
+        distribution,
+        nonce,
+        ciphertext,
+    })
+}
+
+pub(crate) fn open<D: Transcript>(
+    participant: &Participant<D>,
+    share_boxes: &[ShareBox],
+    sealed: &SealedPayload,
+) -> Result<Vec<u8>, VssError> {
+    let key = recover_key(participant, share_boxes, &sealed.distribution)?;
+    let aad = TranscriptRegistry::transcript_hash(&sealed.distribution)?;
+
+    cipher(&key)
+        .decrypt(
+            &sealed.nonce.into(),
+            Payload {
+                msg: &sealed.ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| VssError::InvalidCiphertext)
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, participant::Participant, testkit::test_participant};
+
+    #[test]
+    fn test_seal_and_open() {
+        let committee: Vec<Participant> = [7901, 4801, 1453].map(test_participant).to_vec();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut dealer = test_participant(105929);
+        let payload = vec![0x42; 10_000];
+        let sealed = dealer.seal_payload(&payload, &publickeys, 2).unwrap();
+        let share_boxes: Vec<_> = committee[1..]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&sealed.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(committee[0].verify_distribution_shares(&sealed.distribution));
+        assert_eq!(
+            committee[0].open_payload(&share_boxes, &sealed).unwrap(),
+            payload
+        );
+
+        let mut tampered = sealed.clone();
+
+        tampered.ciphertext[0] ^= 1;
+        assert_eq!(
+            committee[0].open_payload(&share_boxes, &tampered),
+            Err(VssError::InvalidCiphertext)
+        );
+
+        // the key of another distribution does not open this payload
+        let other = dealer.seal_payload(&payload, &publickeys, 2).unwrap();
+        let mut swapped = other.clone();
+
+        swapped.distribution = sealed.distribution.clone();
+        assert_eq!(
+            committee[0].open_payload(&share_boxes, &swapped),
+            Err(VssError::InvalidCiphertext)
+        );
+    }
+}
@@ -1,14 +1,161 @@
 use num_bigint::{BigInt, BigUint, ToBigInt};
 
+mod access;
+mod arith;
+mod armor;
+mod beacon;
+#[cfg(feature = "bls12_381")]
+mod bls;
+#[cfg(feature = "bls12_381")]
+mod bls_sign;
+mod committee;
+mod compartment;
+#[cfg(feature = "serde")]
+pub mod compat;
+mod coordinator;
+mod delivery;
+mod diagnostics;
+mod dkg;
 mod dleq;
+mod enroll;
+mod error;
+mod feldman;
+mod freshness;
+mod group;
+mod hierarchy;
+#[cfg(feature = "hybrid")]
+mod hybrid;
+mod light;
+mod manifest;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "mnemonic")]
+mod mnemonic;
+mod multi;
+mod packed;
+mod params;
 mod participant;
+mod pedersen;
+mod planner;
 mod polynomial;
+mod precompute;
+mod product_proof;
+#[cfg(feature = "proto")]
+pub mod proto;
+#[cfg(feature = "qr")]
+mod qr;
+mod record;
+mod refresh;
+mod registry;
+mod repair;
+mod reshare;
+mod schnorr;
+mod scrape;
+mod secret_key;
+#[cfg(feature = "serde")]
+mod serde_utils;
+mod session;
 mod sharebox;
+mod sigma;
+#[cfg(feature = "ed25519")]
+mod signed;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod store;
+#[cfg(feature = "hybrid")]
+mod stream;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+mod threshold;
+mod transcript;
+mod usage;
 mod util;
+mod vector_commitment;
+#[cfg(feature = "bls12_381")]
+mod vrf;
 mod vss;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod weighted;
+mod wire;
 
+pub use access::AuthorizedSubsets;
+pub use beacon::BeaconRound;
+/// BLAKE3 as the challenge hash, e.g. `Participant::new().with_digest::<Blake3>()`.
+#[cfg(feature = "blake3")]
+pub use blake3::Hasher as Blake3;
+#[cfg(feature = "bls12_381")]
+pub use bls::Bls12381G1;
+#[cfg(feature = "bls12_381")]
+pub use bls_sign::{BlsKeyShare, BlsSignatureShare, BoundedKeyCommitments, BLS_DST};
+pub use committee::{Committee, Member};
+pub use compartment::{Compartment, CompartmentedDistribution};
+pub use coordinator::{CeremonyOutcome, Complaint, Coordinator, Dealer, Shareholder};
+pub use delivery::ShareDelivery;
+pub use diagnostics::DistributionFault;
+pub use dkg::{
+    JointDistribution, KeyComplaint, KeyDealing, KeyGeneration, KeyGenerationMessage,
+    KeyGenerationPhase,
+};
+pub use dleq::DleqProof;
+/// The Ed25519 key types of `SignedShareBox` and `SignedDistribution`.
+#[cfg(feature = "ed25519")]
+pub use ed25519_dalek::{SigningKey as Ed25519SigningKey, VerifyingKey as Ed25519VerifyingKey};
+pub use enroll::{EnrolledDistribution, Enrollment};
+pub use error::VssError;
+pub use feldman::{FeldmanDistribution, FeldmanShare};
+pub use freshness::{Freshness, FreshnessPolicy};
+pub use group::{CyclicGroup, HashEncoding, ModpGroup};
+pub use hierarchy::{
+    AccessLevel, AccessStructure, AccessStructureBuilder, HierarchicalDistribution,
+};
+#[cfg(feature = "hybrid")]
+pub use hybrid::SealedPayload;
+pub use light::{EntryDigest, LightDistribution};
+pub use manifest::{CeremonyManifest, MANIFEST_HASH};
+#[cfg(feature = "metrics")]
+pub use metrics::{Check, VssMetrics, CONTENT_TYPE as OPENMETRICS_CONTENT_TYPE, SCRAPE_TIMEOUT};
+#[cfg(feature = "mnemonic")]
+pub use mnemonic::MnemonicShare;
+pub use multi::{MaskedSecret, MultiDistribution};
+pub use packed::PackedDistribution;
+pub use params::{VssParamsBuilder, MIN_BITS};
 pub use participant::Participant;
-pub use sharebox::{DistributionShareBox, ShareBox};
+pub use pedersen::{PedersenDistribution, PedersenShare};
+pub use planner::{AvailabilityHint, ReconstructionPlanner};
+pub use precompute::PrecomputedBase;
+#[cfg(feature = "qr")]
+pub use qr::{encode_chunks, ChunkReassembler};
+pub use record::{RecordDistribution, RecordShares, SecretRecord};
+pub use refresh::RefreshedDistribution;
+pub use registry::{Redeal, TranscriptRegistry};
+pub use repair::{RepairContribution, RepairRequest};
+pub use reshare::{ReshareContribution, ResharedDistribution};
+pub use secret_key::SecretKey;
+pub use session::SessionId;
+pub use sharebox::{DistributionShareBox, ShareBox, ShareCommitments};
+pub use sigma::{AndProof, DleqStatement, OrProof};
+#[cfg(feature = "ed25519")]
+pub use signed::{SignedDistribution, SignedShareBox};
+#[cfg(feature = "sqlite")]
+pub use sqlite::{AuditEntry, SqliteStore};
+#[cfg(feature = "serde")]
+pub use store::FileStore;
+pub use store::{MemoryStore, VssStore};
+pub use threshold::{ThresholdCiphertext, ThresholdKey};
+#[cfg(feature = "merlin")]
+pub use transcript::MerlinTranscript;
+pub use transcript::Transcript;
+pub use usage::{ReleaseRequest, UsageLedger};
+pub use vector_commitment::{CommitmentOpening, CommitmentTree, CompactDistribution};
+#[cfg(feature = "bls12_381")]
+pub use vrf::{BlsVrfEvaluation, BlsVrfShare, VRF_DST};
+pub use vss::VSS;
+pub use weighted::WeightedDistribution;
+// arithmetic helpers, public for the benches
+#[doc(hidden)]
+pub use util::Util;
+pub use wire::FORMAT_VERSION;
 
 pub fn string_to_secret(message: &str) -> BigInt {
     BigUint::from_bytes_be(message.as_bytes())
@@ -19,3 +166,28 @@ pub fn string_to_secret(message: &str) -> BigInt {
 pub fn string_from_secret(secret: &BigInt) -> String {
     String::from_utf8(secret.to_biguint().unwrap().to_bytes_be()).unwrap()
 }
+
+/// The secret for arbitrary bytes, e.g. a 32-byte key. Leading zero bytes are not kept in the
+/// secret, so pass the length back to `secret_to_bytes`.
+pub fn bytes_to_secret(bytes: &[u8]) -> BigInt {
+    BigUint::from_bytes_be(bytes).to_bigint().unwrap()
+}
+
+/// The bytes of `secret`, left padded with zeros to `len` bytes.
+pub fn secret_to_bytes(secret: &BigInt, len: usize) -> Result<Vec<u8>, VssError> {
+    let bytes = secret
+        .to_biguint()
+        .ok_or(VssError::SecretTooLarge { length: len })?
+        .to_bytes_be();
+    let bytes = if bytes == [0] { Vec::new() } else { bytes };
+
+    if bytes.len() > len {
+        return Err(VssError::SecretTooLarge { length: len });
+    }
+
+    let mut padded = vec![0; len - bytes.len()];
+
+    padded.extend_from_slice(&bytes);
+
+    Ok(padded)
+}
@@ -0,0 +1,247 @@
+use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Signed};
+use rand::Rng;
+
+use crate::{
+    arith,
+    error::VssError,
+    scrape,
+    session::{session_context, SessionId},
+    sharebox::DistributionShareBox,
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Light client verification
+
+// A full verifier recomputes X_i from the commitments for every participant, which costs n * t
+// exponentiations. A light client only checks its own entry in full, checks that the challenge is
+// the hash over the per-entry digests it was handed, and spot checks that a few randomly sampled
+// digests lie on the committed polynomial.
+
+/// The values one entry contributes to the challenge hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryDigest {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::position"))]
+    pub position: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub x: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub encrypted_share: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub a1: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub a2: BigInt,
+    /// the recipient's key, which transcripts with labels absorb as context; digests from before
+    /// it leave it zero and only verify with plain hashes
+    #[cfg_attr(feature = "serde", serde(default, with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LightDistribution {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub commitments: Vec<BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response: BigInt,
+    pub index: usize,
+    pub digests: Vec<EntryDigest>,
+    /// `DistributionShareBox::session_id` of the distribution, which the challenge is bound to
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub session_id: Option<SessionId>,
+}
+
+impl LightDistribution {
+    fn digest<D: Transcript>(
+        vss: &VSS<D>,
+        commitments: &[BigInt],
+        challenge: &BigInt,
+        position: &BigInt,
+        publickey: &BigInt,
+        encrypted_share: &BigInt,
+        response: &BigInt,
+    ) -> EntryDigest {
+        let x = vss.evaluate_commitments(commitments, position);
+        let a1 = (arith::modpow(&vss.g, response, &vss.q) * arith::modpow(&x, challenge, &vss.q))
+            % &vss.q;
+        let a2 = (arith::modpow(publickey, response, &vss.q)
+            * arith::modpow(encrypted_share, challenge, &vss.q))
+            % &vss.q;
+
+        EntryDigest {
+            position: position.clone(),
+            x,
+            encrypted_share: encrypted_share.clone(),
+            a1,
+            a2,
+            publickey: publickey.clone(),
+        }
+    }
+
+    // built by a full node for the light client owning `publickey`
+    pub fn new<D: Transcript>(
+        vss: &VSS<D>,
+        distribution_sharebox: &DistributionShareBox,
+        publickey: &BigInt,
+    ) -> Result<Self, VssError> {
+        let mut digests = Vec::new();
+
+        for key in &distribution_sharebox.publickeys {
+            let missing = || VssError::MissingShare {
+                publickey: key.clone(),
+            };
+            let position = distribution_sharebox
+                .positions
+                .get(key)
+                .ok_or_else(missing)?;
+            let response = distribution_sharebox
+                .responses
+                .get(key)
+                .ok_or_else(missing)?;
+            let encrypted_share = distribution_sharebox.shares.get(key).ok_or_else(missing)?;
+
+            digests.push(LightDistribution::digest(
+                vss,
+                &distribution_sharebox.commitments,
+                &distribution_sharebox.challenge,
+                position,
+                key,
+                encrypted_share,
+                response,
+            ));
+        }
+
+        let index = distribution_sharebox
+            .publickeys
+            .iter()
+            .position(|key| key == publickey)
+            .ok_or_else(|| VssError::MissingShare {
+                publickey: publickey.clone(),
+            })?;
+
+        Ok(LightDistribution {
+            commitments: distribution_sharebox.commitments.clone(),
+            challenge: distribution_sharebox.challenge.clone(),
+            publickey: publickey.clone(),
+            response: distribution_sharebox.responses[publickey].clone(),
+            index,
+            digests,
+            session_id: distribution_sharebox.session_id,
+        })
+    }
+
+    pub fn encrypted_share(&self) -> Option<&BigInt> {
+        self.digests
+            .get(self.index)
+            .map(|digest| &digest.encrypted_share)
+    }
+
+    // verifies the own entry, the challenge, and `samples` randomly chosen digests against the commitments
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>, samples: usize) -> bool {
+        if !self.verify_entry_and_challenge(vss) {
+            return false;
+        }
+
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..samples {
+            let digest = &self.digests[rng.gen_range(0, self.digests.len())];
+
+            if vss.evaluate_commitments(&self.commitments, &digest.position) != digest.x {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Like `verify`, checking every digest instead of a sample: the own entry ties the digests
+    /// to the commitments, and the SCRAPE test that all of them lie on one polynomial of degree
+    /// below the threshold takes one exponentiation per digest. `q` must be a safe prime.
+    pub fn verify_low_degree<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let points: Vec<(BigInt, BigInt)> = self
+            .digests
+            .iter()
+            .map(|digest| (digest.position.clone(), digest.x.clone()))
+            .collect();
+
+        self.verify_entry_and_challenge(vss)
+            && scrape::is_low_degree(
+                vss,
+                &points,
+                self.commitments.len(),
+                &mut rand::thread_rng(),
+            )
+    }
+
+    fn verify_entry_and_challenge<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let own = match self.digests.get(self.index) {
+            Some(own) => own,
+            None => return false,
+        };
+
+        if !vss.in_exponent_range(&self.challenge)
+            || !vss.in_exponent_range(&self.response)
+            || !self.digests.iter().all(|digest| {
+                [&digest.x, &digest.encrypted_share, &digest.a1, &digest.a2]
+                    .into_iter()
+                    .all(|element| vss.in_group(element))
+                    && !digest.publickey.is_negative()
+            })
+        {
+            return false;
+        }
+
+        let expected = LightDistribution::digest(
+            vss,
+            &self.commitments,
+            &self.challenge,
+            &own.position,
+            &self.publickey,
+            &own.encrypted_share,
+            &self.response,
+        );
+
+        if &expected != own {
+            return false;
+        }
+
+        let mut challenge_hasher =
+            vss.context_hasher(&session_context(self.session_id.as_ref(), &[]));
+
+        for digest in &self.digests {
+            let width = vss.q.bits().div_ceil(8);
+
+            challenge_hasher.append_bound_context(
+                vss.binds_context(),
+                b"g1",
+                &vss.encoding.to_bytes(&vss.g, width),
+            );
+            vss.update_hash(&mut challenge_hasher, b"h1", &digest.x);
+            challenge_hasher.append_bound_context(
+                vss.binds_context(),
+                b"g2",
+                &vss.encoding.to_bytes(&digest.publickey, width),
+            );
+            vss.update_hash(&mut challenge_hasher, b"h2", &digest.encrypted_share);
+            vss.update_hash(&mut challenge_hasher, b"a1", &digest.a1);
+            vss.update_hash(&mut challenge_hasher, b"a2", &digest.a2);
+        }
+
+        let challenge_hash = challenge_hasher.challenge();
+        let challenge_big_uint = BigUint::from_bytes_be(&challenge_hash[..])
+            .mod_floor(&(vss.q.to_biguint().unwrap() - BigUint::one()));
+
+        challenge_big_uint.to_bigint().unwrap() == self.challenge
+    }
+}
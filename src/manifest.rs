@@ -0,0 +1,286 @@
+use num_bigint::BigInt;
+use num_traits::Zero;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::VssError, participant::Participant, schnorr, secret_key::SecretKey,
+    transcript::Transcript, vss::VSS,
+};
+
+// Ceremony manifests
+
+// Parties to a ceremony used to agree on the group, the recipients and the threshold out of band.
+// A manifest states all of it explicitly, together with the access policy, the deadlines and a
+// session id, and is signed by the dealer. Every party validates the manifest against its own
+// parameters before joining; joining binds the manifest hash into the protocol label, so every
+// proof produced or verified in the ceremony references the manifest by hash.
+
+/// The only transcript hash currently supported.
+pub const MANIFEST_HASH: &str = "sha256";
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CeremonyManifest {
+    pub session_id: String,
+    /// group parameters and protocol label every party must use
+    pub params: VSS,
+    /// name of the transcript hash
+    pub hash: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub publickeys: Vec<BigInt>,
+    pub threshold: u32,
+    /// free-form access policy for reconstruction, e.g. which parties may request it
+    pub policy: String,
+    /// seconds since the unix epoch by which the distribution must be published
+    pub distribution_deadline: Option<u64>,
+    /// seconds since the unix epoch after which no share box is released
+    pub reconstruction_deadline: Option<u64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub dealer: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub commitment: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response: BigInt,
+}
+
+impl CeremonyManifest {
+    /// An unsigned manifest without policy or deadlines.
+    pub fn new(params: &VSS, session_id: &str, publickeys: &[BigInt], threshold: u32) -> Self {
+        CeremonyManifest {
+            session_id: session_id.to_string(),
+            params: params.clone(),
+            hash: MANIFEST_HASH.to_string(),
+            publickeys: publickeys.to_vec(),
+            threshold,
+            policy: String::new(),
+            distribution_deadline: None,
+            reconstruction_deadline: None,
+            dealer: BigInt::zero(),
+            commitment: BigInt::zero(),
+            response: BigInt::zero(),
+        }
+    }
+
+    /// Hash of everything in the manifest except the signature.
+    pub fn manifest_hash(&self) -> [u8; 32] {
+        fn update_str(hasher: &mut Sha256, value: &str) {
+            hasher.update((value.len() as u64).to_be_bytes());
+            hasher.update(value.as_bytes());
+        }
+
+        fn update_deadline(hasher: &mut Sha256, deadline: Option<u64>) {
+            if let Some(deadline) = deadline {
+                hasher.update([1]);
+                hasher.update(deadline.to_be_bytes());
+            } else {
+                hasher.update([0]);
+            }
+        }
+
+        let params = &self.params;
+        let mut hasher = params.hasher();
+
+        update_str(&mut hasher, "ceremony-manifest");
+        update_str(&mut hasher, &self.session_id);
+        params.update_hash(&mut hasher, b"q", &params.q);
+        params.update_hash(&mut hasher, b"g", &params.g);
+        params.update_hash(&mut hasher, b"G", &params.G);
+        hasher.update(params.length.to_be_bytes());
+        hasher.update([params.encoding as u8]);
+        update_str(&mut hasher, &self.hash);
+        hasher.update((self.publickeys.len() as u64).to_be_bytes());
+
+        for publickey in &self.publickeys {
+            params.update_hash(&mut hasher, b"publickey", publickey);
+        }
+
+        hasher.update(self.threshold.to_be_bytes());
+        update_str(&mut hasher, &self.policy);
+        update_deadline(&mut hasher, self.distribution_deadline);
+        update_deadline(&mut hasher, self.reconstruction_deadline);
+        params.update_hash(&mut hasher, b"dealer", &self.dealer);
+
+        hasher.finalize().into()
+    }
+
+    /// The protocol label parties use once they joined the ceremony.
+    pub fn bound_label(&self) -> String {
+        let hash: String = self
+            .manifest_hash()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        format!("{}/manifest:{}", self.params.label, hash)
+    }
+
+    // signed with the manifest's own parameters, so it verifies under SHA-256 whatever the
+    // signer's challenge hash
+    pub(crate) fn sign(&mut self, privatekey: &SecretKey) {
+        self.dealer = self.params.generate_public_key(privatekey);
+
+        let (commitment, response) = schnorr::sign(&self.params, privatekey, &self.manifest_hash());
+
+        self.commitment = commitment;
+        self.response = response;
+    }
+
+    pub fn verify_signature(&self) -> bool {
+        schnorr::verify(
+            &self.params,
+            &self.dealer,
+            &self.manifest_hash(),
+            &self.commitment,
+            &self.response,
+        )
+    }
+
+    /// Checks the manifest on behalf of `participant` at time `now`: the dealer's signature, the
+    /// group parameters against the participant's own, the threshold, the participant's place in
+    /// the ceremony and the distribution deadline.
+    pub(crate) fn validate<D: Transcript>(
+        &self,
+        participant: &Participant<D>,
+        now: u64,
+    ) -> Result<(), VssError> {
+        let own = &participant.vss;
+        let params = &self.params;
+
+        if self.hash != MANIFEST_HASH {
+            return Err(VssError::ManifestMismatch { field: "hash" });
+        }
+
+        if params.q != own.q
+            || params.g != own.g
+            || params.G != own.G
+            || params.length != own.length
+            || params.encoding != own.encoding
+        {
+            return Err(VssError::ManifestMismatch { field: "params" });
+        }
+
+        if params.label != own.label {
+            return Err(VssError::ManifestMismatch { field: "label" });
+        }
+
+        if self.threshold == 0 || self.threshold as usize > self.publickeys.len() {
+            return Err(VssError::InvalidThreshold {
+                threshold: self.threshold,
+                participants: self.publickeys.len(),
+            });
+        }
+
+        if participant.publickey != self.dealer && !self.publickeys.contains(&participant.publickey)
+        {
+            return Err(VssError::UnknownShareholder {
+                publickey: participant.publickey.clone(),
+            });
+        }
+
+        if !self.verify_signature() {
+            return Err(VssError::InvalidProof);
+        }
+
+        match self.distribution_deadline {
+            Some(deadline) if now > deadline => Err(VssError::DeadlinePassed { deadline }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, participant::Participant, testkit::test_participant};
+
+    use super::CeremonyManifest;
+
+    #[test]
+    fn test_ceremony() {
+        let mut dealer = test_participant(105929);
+        let mut committee: Vec<Participant> = [7901, 4801, 1453].map(test_participant).to_vec();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut manifest = CeremonyManifest::new(&dealer.vss, "session-1", &publickeys, 2);
+
+        manifest.policy = String::from("any two trustees");
+        manifest.distribution_deadline = Some(1000);
+        dealer.sign_manifest(&mut manifest);
+
+        // a party that has not joined does not accept proofs bound to the manifest
+        let outsider = committee[0].clone();
+
+        dealer.join_ceremony(&manifest, 500).unwrap();
+
+        for member in committee.iter_mut() {
+            member.join_ceremony(&manifest, 500).unwrap();
+        }
+
+        let distribution = dealer
+            .distribute_ceremony_secret(&manifest, &BigInt::from(1234567890))
+            .unwrap();
+
+        assert!(committee[0].verify_distribution_shares(&distribution));
+        assert!(!outsider.verify_distribution_shares(&distribution));
+
+        let shares: Vec<_> = committee[..2]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            committee[2].reconstruct(&shares, &distribution).unwrap(),
+            BigInt::from(1234567890)
+        );
+    }
+
+    #[test]
+    fn test_validation() {
+        let dealer = test_participant(105929);
+        let mut member = test_participant(7901);
+        let publickeys = vec![member.publickey.clone(), test_participant(4801).publickey];
+        let mut manifest = CeremonyManifest::new(&dealer.vss, "session-1", &publickeys, 2);
+
+        manifest.distribution_deadline = Some(1000);
+        dealer.sign_manifest(&mut manifest);
+
+        assert_eq!(
+            member.clone().join_ceremony(&manifest, 1001),
+            Err(VssError::DeadlinePassed { deadline: 1000 })
+        );
+        assert_eq!(
+            test_participant(1453).join_ceremony(&manifest, 0),
+            Err(VssError::UnknownShareholder {
+                publickey: test_participant(1453).publickey
+            })
+        );
+
+        let mut tampered = manifest.clone();
+
+        tampered.threshold = 1;
+        assert_eq!(
+            member.clone().join_ceremony(&tampered, 0),
+            Err(VssError::InvalidProof)
+        );
+
+        let mut other = member.clone();
+
+        other.set_protocol_label("otherapp");
+        assert_eq!(
+            other.join_ceremony(&manifest, 0),
+            Err(VssError::ManifestMismatch { field: "label" })
+        );
+
+        member.join_ceremony(&manifest, 0).unwrap();
+        assert_eq!(member.vss.label, manifest.bound_label());
+        assert_eq!(
+            test_participant(105929)
+                .distribute_ceremony_secret(&manifest, &BigInt::from(1))
+                .unwrap_err(),
+            VssError::ManifestMismatch { field: "label" }
+        );
+    }
+}
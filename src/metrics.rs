@@ -0,0 +1,326 @@
+use std::{
+    fmt,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use prometheus_client::{
+    encoding::{text::encode, EncodeLabelSet, EncodeLabelValue},
+    metrics::{
+        counter::Counter,
+        family::Family,
+        gauge::Gauge,
+        histogram::{exponential_buckets, Histogram},
+    },
+    registry::Registry,
+};
+
+// Verification counters, failures, latencies and reconstruction progress in OpenMetrics format, for
+// services that expose them on a scrape endpoint. A `VSS` given metrics with `with_metrics`
+// records into them, and `serve` answers scrapers over plain HTTP.
+
+pub const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// How long `serve` waits on a scraper to send its request or take the response before hanging
+/// up on it, so that a stalled connection cannot hold up the next scrape.
+pub const SCRAPE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// the most of a request read, request line and headers together
+const MAX_REQUEST_LEN: u64 = 8192;
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, EncodeLabelValue)]
+pub enum Check {
+    Distribution,
+    Share,
+    ShareBatch,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct CheckLabels {
+    check: Check,
+}
+
+pub struct VssMetrics {
+    registry: Registry,
+    verifications: Family<CheckLabels, Counter>,
+    failures: Family<CheckLabels, Counter>,
+    latency: Family<CheckLabels, Histogram>,
+    shares_received: Gauge,
+    shares_required: Gauge,
+}
+
+impl fmt::Debug for VssMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VssMetrics").finish_non_exhaustive()
+    }
+}
+
+impl Default for VssMetrics {
+    fn default() -> Self {
+        VssMetrics::new()
+    }
+}
+
+impl VssMetrics {
+    pub fn new() -> Self {
+        let mut registry = Registry::with_prefix("vss");
+        let verifications = Family::<CheckLabels, Counter>::default();
+        let failures = Family::<CheckLabels, Counter>::default();
+        let latency = Family::<CheckLabels, Histogram>::new_with_constructor(|| {
+            Histogram::new(exponential_buckets(0.001, 2.0, 16))
+        });
+        let shares_received = Gauge::default();
+        let shares_required = Gauge::default();
+
+        registry.register(
+            "verifications",
+            "Number of verifications performed",
+            verifications.clone(),
+        );
+        registry.register(
+            "verification_failures",
+            "Number of verifications that rejected their input",
+            failures.clone(),
+        );
+        registry.register(
+            "verification_seconds",
+            "Time spent per verification",
+            latency.clone(),
+        );
+        registry.register(
+            "reconstruction_shares_received",
+            "Valid share boxes collected for the running reconstruction",
+            shares_received.clone(),
+        );
+        registry.register(
+            "reconstruction_shares_required",
+            "Share boxes needed by the running reconstruction",
+            shares_required.clone(),
+        );
+
+        VssMetrics {
+            registry,
+            verifications,
+            failures,
+            latency,
+            shares_received,
+            shares_required,
+        }
+    }
+
+    /// Runs `verify`, recording its outcome and duration under `check`.
+    pub fn observe<F: FnOnce() -> bool>(&self, check: Check, verify: F) -> bool {
+        let labels = CheckLabels { check };
+        let start = Instant::now();
+        let valid = verify();
+
+        self.latency
+            .get_or_create(&labels)
+            .observe(start.elapsed().as_secs_f64());
+        self.verifications.get_or_create(&labels).inc();
+
+        if !valid {
+            self.failures.get_or_create(&labels).inc();
+        }
+
+        valid
+    }
+
+    pub fn set_reconstruction_progress(&self, received: usize, required: usize) {
+        self.shares_received.set(received as i64);
+        self.shares_required.set(required as i64);
+    }
+
+    /// Current values in the OpenMetrics text format, served with `CONTENT_TYPE`.
+    pub fn encode(&self) -> String {
+        let mut buffer = String::new();
+
+        encode(&mut buffer, &self.registry).expect("writing to a String cannot fail");
+
+        buffer
+    }
+
+    /// Answers `GET /metrics` on `listener` with `encode` from a background thread, one
+    /// connection at a time, and every other request with 404. Connections are dropped after
+    /// `SCRAPE_TIMEOUT` without progress.
+    pub fn serve(self: Arc<Self>, listener: TcpListener) -> JoinHandle<()> {
+        self.serve_with_timeout(listener, SCRAPE_TIMEOUT)
+    }
+
+    /// Like `serve`, dropping connections after `timeout` without progress.
+    pub fn serve_with_timeout(
+        self: Arc<Self>,
+        listener: TcpListener,
+        timeout: Duration,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // a scraper that hangs up early, stalls or sends too much only loses its own
+                // response
+                let _ = self.respond(stream, timeout);
+            }
+        })
+    }
+
+    fn respond(&self, mut stream: TcpStream, timeout: Duration) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+
+        let mut reader = BufReader::new((&stream).take(MAX_REQUEST_LEN));
+        let mut request_line = String::new();
+
+        reader.read_line(&mut request_line)?;
+
+        // the headers are of no interest, but are read so the client sees its request consumed
+        let mut header = String::new();
+
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let (status, content_type, body) = match request_line.split_whitespace().nth(1) {
+            Some("/metrics") if request_line.starts_with("GET ") => {
+                ("200 OK", CONTENT_TYPE, self.encode())
+            }
+            _ => ("404 Not Found", "text/plain", String::from("not found\n")),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            content_type,
+            body.len(),
+            body
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use crate::testkit::fixed_ceremony;
+
+    use super::{Check, VssMetrics};
+
+    #[test]
+    fn test_observe() {
+        let metrics = VssMetrics::new();
+
+        assert!(metrics.observe(Check::Distribution, || true));
+        assert!(!metrics.observe(Check::Share, || false));
+
+        metrics.set_reconstruction_progress(2, 3);
+
+        let encoded = metrics.encode();
+
+        assert!(encoded.contains("vss_verifications_total{check=\"Distribution\"} 1"));
+        assert!(encoded.contains("vss_verification_failures_total{check=\"Share\"} 1"));
+        assert!(!encoded.contains("vss_verification_failures_total{check=\"Distribution\"}"));
+        assert!(encoded.contains("vss_reconstruction_shares_received 2"));
+        assert!(encoded.contains("vss_reconstruction_shares_required 3"));
+        assert!(encoded.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn test_recorded_by_vss() {
+        let ceremony = fixed_ceremony(5, 3, 5).unwrap();
+        let metrics = Arc::new(VssMetrics::new());
+        let vss = ceremony.dealer.vss.clone().with_metrics(metrics.clone());
+        let mut shareboxes = ceremony.shares.clone();
+
+        shareboxes[4].response += 1;
+
+        assert!(vss.verify_distribution_shares(&ceremony.distribution));
+        assert_eq!(
+            vss.reconstruct_verified(&shareboxes, &ceremony.distribution),
+            Ok((
+                ceremony.secret.clone(),
+                vec![shareboxes[4].publickey.clone()]
+            ))
+        );
+
+        let encoded = metrics.encode();
+
+        assert!(encoded.contains("vss_verifications_total{check=\"Distribution\"} 1"));
+        assert!(encoded.contains("vss_verifications_total{check=\"Share\"} 5"));
+        assert!(encoded.contains("vss_verification_failures_total{check=\"Share\"} 1"));
+        assert!(encoded.contains("vss_verification_seconds_count{check=\"Share\"} 5"));
+        assert!(encoded.contains("vss_verifications_total{check=\"ShareBatch\"} 1"));
+        assert!(encoded.contains("vss_verification_failures_total{check=\"ShareBatch\"} 1"));
+        assert!(encoded.contains("vss_reconstruction_shares_received 4"));
+        assert!(encoded.contains("vss_reconstruction_shares_required 3"));
+        // parameters without metrics record nothing
+        assert!(ceremony
+            .dealer
+            .vss
+            .verify_distribution_shares(&ceremony.distribution));
+        assert!(metrics
+            .encode()
+            .contains("vss_verifications_total{check=\"Distribution\"} 1"));
+    }
+
+    #[test]
+    fn test_serve() {
+        let metrics = Arc::new(VssMetrics::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        metrics.observe(Check::Share, || true);
+        metrics.clone().serve(listener);
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            let mut response = String::new();
+
+            write!(stream, "GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).unwrap();
+            stream.read_to_string(&mut response).unwrap();
+
+            response
+        };
+        let response = get("/metrics");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(super::CONTENT_TYPE));
+        assert!(response.contains("vss_verifications_total{check=\"Share\"} 1"));
+        assert!(response.ends_with("# EOF\n"));
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn test_serve_stalled_client() {
+        let metrics = Arc::new(VssMetrics::new());
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        metrics
+            .clone()
+            .serve_with_timeout(listener, Duration::from_millis(100));
+
+        // stops in the middle of its headers and never sends the rest
+        let mut stalled = TcpStream::connect(address).unwrap();
+
+        write!(stalled, "GET /metrics HTTP/1.1\r\nHost: loc").unwrap();
+
+        let mut stream = TcpStream::connect(address).unwrap();
+        let mut response = String::new();
+
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        write!(stream, "GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
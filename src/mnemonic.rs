@@ -0,0 +1,218 @@
+use bip39::Language;
+use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_traits::ToPrimitive;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::VssError,
+    sharebox::{DistributionShareBox, ShareBox},
+};
+
+// Mnemonic shares
+
+// A decrypted share with the metadata needed to use it, written as words of the BIP-39 English
+// list in the manner of SLIP-0039: the position and the threshold as 4 bytes each, the length of
+// the share as 2 bytes and its big-endian magnitude, followed by the first 4 bytes of the SHA-256
+// of all that. The bits are read 11 at a time into words, the last one padded with zeros.
+
+const WORD_BITS: usize = 11;
+const CHECKSUM_LEN: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MnemonicShare {
+    /// the position of the shareholder in the distribution
+    pub position: u32,
+    /// the number of shares needed to reconstruct
+    pub threshold: u32,
+    /// the decrypted share, as in `ShareBox::share`
+    pub share: BigInt,
+}
+
+impl MnemonicShare {
+    /// The share in `sharebox` with its position and threshold in `distribution`.
+    pub fn new(sharebox: &ShareBox, distribution: &DistributionShareBox) -> Result<Self, VssError> {
+        let position = distribution
+            .positions
+            .get(&sharebox.publickey)
+            .and_then(|position| position.to_u32())
+            .ok_or_else(|| VssError::UnknownShareholder {
+                publickey: sharebox.publickey.clone(),
+            })?;
+
+        Ok(MnemonicShare {
+            position,
+            threshold: distribution.threshold() as u32,
+            share: sharebox.share.clone(),
+        })
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let (_, share) = self.share.to_bytes_be();
+        let share = if share == [0] { Vec::new() } else { share };
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&self.position.to_be_bytes());
+        bytes.extend_from_slice(&self.threshold.to_be_bytes());
+        bytes.extend_from_slice(&(share.len() as u16).to_be_bytes());
+        bytes.extend_from_slice(&share);
+
+        let checksum = Sha256::digest(&bytes);
+
+        bytes.extend_from_slice(&checksum[..CHECKSUM_LEN]);
+
+        bytes
+    }
+
+    /// The share as space separated words.
+    pub fn to_mnemonic(&self) -> String {
+        let words = Language::English.word_list();
+        let bytes = self.to_bytes();
+        let mut mnemonic = Vec::new();
+        let (mut accumulator, mut bits) = (0u32, 0);
+
+        for byte in bytes {
+            accumulator = (accumulator << 8) | byte as u32;
+            bits += 8;
+
+            while bits >= WORD_BITS {
+                bits -= WORD_BITS;
+                mnemonic.push(words[((accumulator >> bits) & 0x7ff) as usize]);
+            }
+        }
+
+        if bits > 0 {
+            mnemonic.push(words[((accumulator << (WORD_BITS - bits)) & 0x7ff) as usize]);
+        }
+
+        mnemonic.join(" ")
+    }
+
+    /// Parses `to_mnemonic`, ignoring case and extra whitespace.
+    pub fn from_mnemonic(mnemonic: &str) -> Result<Self, VssError> {
+        let mut bytes = Vec::new();
+        let (mut accumulator, mut bits) = (0u32, 0);
+        let words: Vec<&str> = mnemonic.split_whitespace().collect();
+
+        for word in &words {
+            let index = Language::English
+                .find_word(&word.to_lowercase())
+                .ok_or(VssError::MalformedEncoding)?;
+
+            accumulator = (accumulator << WORD_BITS) | index as u32;
+            bits += WORD_BITS;
+
+            while bits >= 8 {
+                bits -= 8;
+                bytes.push((accumulator >> bits) as u8);
+            }
+
+            accumulator &= (1 << bits) - 1;
+        }
+
+        if accumulator != 0 || bytes.len() < 10 + CHECKSUM_LEN {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        let share_len = u16::from_be_bytes([bytes[8], bytes[9]]) as usize;
+        let data_len = 10 + share_len;
+
+        // padding of 8 or more bits leaves a zero byte after the checksum
+        if words.len() != ((data_len + CHECKSUM_LEN) * 8).div_ceil(WORD_BITS)
+            || bytes.len() < data_len + CHECKSUM_LEN
+            || bytes[data_len + CHECKSUM_LEN..]
+                .iter()
+                .any(|byte| *byte != 0)
+        {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        if Sha256::digest(&bytes[..data_len])[..CHECKSUM_LEN]
+            != bytes[data_len..data_len + CHECKSUM_LEN]
+        {
+            return Err(VssError::ChecksumMismatch);
+        }
+
+        Ok(MnemonicShare {
+            position: u32::from_be_bytes(bytes[..4].try_into().unwrap()),
+            threshold: u32::from_be_bytes(bytes[4..8].try_into().unwrap()),
+            share: BigUint::from_bytes_be(&bytes[10..data_len])
+                .to_bigint()
+                .unwrap(),
+        })
+    }
+
+    /// A share box for `Participant::reconstruct` over `distribution`. It has no proof, so it
+    /// won't pass `verify_share`.
+    pub fn to_sharebox(&self, distribution: &DistributionShareBox) -> Result<ShareBox, VssError> {
+        let publickey = distribution
+            .positions
+            .iter()
+            .find(|(_, position)| **position == BigInt::from(self.position))
+            .map(|(publickey, _)| publickey.clone())
+            .ok_or_else(|| VssError::UnknownPosition {
+                position: BigInt::from(self.position),
+            })?;
+        let mut sharebox = ShareBox::new();
+
+        sharebox.publickey = publickey;
+        sharebox.share = self.share.clone();
+
+        Ok(sharebox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::MnemonicShare;
+
+    #[test]
+    fn test_mnemonic() {
+        let ceremony = fixed_ceremony(3, 2, 13).unwrap();
+        let mnemonics: Vec<String> = ceremony
+            .shares
+            .iter()
+            .map(|sharebox| {
+                MnemonicShare::new(sharebox, &ceremony.distribution)
+                    .unwrap()
+                    .to_mnemonic()
+            })
+            .collect();
+        let share = MnemonicShare::from_mnemonic(&mnemonics[0].to_uppercase()).unwrap();
+
+        assert_eq!(share.threshold, 2);
+        assert_eq!(
+            share,
+            MnemonicShare::new(&ceremony.shares[0], &ceremony.distribution).unwrap()
+        );
+
+        let shareboxes = mnemonics[1..]
+            .iter()
+            .map(|mnemonic| {
+                MnemonicShare::from_mnemonic(mnemonic)
+                    .unwrap()
+                    .to_sharebox(&ceremony.distribution)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            ceremony.participants[0].reconstruct(&shareboxes, &ceremony.distribution),
+            Ok(ceremony.secret)
+        );
+
+        let mut words: Vec<&str> = mnemonics[0].split(' ').collect();
+
+        words[8] = if words[8] == "zoo" { "abandon" } else { "zoo" };
+
+        assert_eq!(
+            MnemonicShare::from_mnemonic(&words.join(" ")),
+            Err(VssError::ChecksumMismatch)
+        );
+        assert_eq!(
+            MnemonicShare::from_mnemonic("abandon ability notaword"),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+}
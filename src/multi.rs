@@ -0,0 +1,225 @@
+use num_bigint::{BigInt, ToBigInt};
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Multi-secret distributions
+
+// Related secrets for the same committee need not take a ceremony each. One polynomial is dealt
+// and proven once, and every secret is masked under its own mask derived from G^p(0) and its
+// index, see `VSS::secret_mask_at`. The encrypted shares, their proof and the decrypted shares
+// are those of a single distribution whatever the number of secrets, which only adds a masked
+// value each. The secrets stand or fall together: whoever reconstructs one can unmask them all.
+// Secrets that need separate release belong in a record, see `RecordDistribution`.
+
+/// One masked secret of a `MultiDistribution`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MaskedSecret {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub u: BigInt,
+    pub secret_len: usize,
+}
+
+/// Several secrets under one distribution, which masks nothing itself. Shareholders extract and
+/// prove their shares from `distribution` as usual.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiDistribution {
+    pub distribution: DistributionShareBox,
+    pub secrets: Vec<MaskedSecret>,
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Distributes `secrets` to `publickeys` with `threshold`, in one distribution.
+    pub fn distribute_secrets(
+        &mut self,
+        secrets: &[BigInt],
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<MultiDistribution, VssError> {
+        self.distribute_secrets_with_rng(secrets, publickeys, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `distribute_secrets`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_secrets_with_rng(
+        &mut self,
+        secrets: &[BigInt],
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<MultiDistribution, VssError> {
+        let secrets = secrets
+            .iter()
+            .map(|secret| secret.to_biguint().ok_or(VssError::NegativeSecret))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (distribution, polynomial) = self.deal_polynomial(
+            &BigInt::zero(),
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &[],
+            Some(0),
+            rng,
+        )?;
+        let shared_value = self.vss.exp_G(
+            &self
+                .vss
+                .blind(&polynomial.get_value_mod(&BigInt::zero(), &(&self.vss.q - BigInt::one()))),
+        );
+        let secrets = secrets
+            .into_iter()
+            .enumerate()
+            .map(|(index, secret)| {
+                let secret_len = secret.to_bytes_be().len();
+
+                MaskedSecret {
+                    u: (secret ^ self.vss.secret_mask_at(&shared_value, index, secret_len))
+                        .to_bigint()
+                        .unwrap(),
+                    secret_len,
+                }
+            })
+            .collect();
+
+        Ok(MultiDistribution {
+            distribution,
+            secrets,
+        })
+    }
+}
+
+impl MultiDistribution {
+    /// The number of secrets.
+    pub fn len(&self) -> usize {
+        self.secrets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    /// Verifies the distribution, once for all secrets.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        vss.verify_distribution_shares(&self.distribution)
+    }
+
+    /// Reconstructs the secret at `index` from at least `threshold` share boxes.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+        index: usize,
+    ) -> Result<BigInt, VssError> {
+        let masked = self
+            .secrets
+            .get(index)
+            .ok_or_else(|| VssError::UnknownField {
+                label: index.to_string(),
+            })?;
+        let shared_value = vss.interpolate(share_boxes, &self.distribution)?;
+
+        unmask(vss, &shared_value, index, masked)
+    }
+
+    /// Reconstructs every secret, in order, from at least `threshold` share boxes.
+    pub fn reconstruct_all<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<Vec<BigInt>, VssError> {
+        let shared_value = vss.interpolate(share_boxes, &self.distribution)?;
+
+        self.secrets
+            .iter()
+            .enumerate()
+            .map(|(index, masked)| unmask(vss, &shared_value, index, masked))
+            .collect()
+    }
+}
+
+fn unmask<D: Transcript>(
+    vss: &VSS<D>,
+    shared_value: &BigInt,
+    index: usize,
+    masked: &MaskedSecret,
+) -> Result<BigInt, VssError> {
+    let u = masked.u.to_biguint().ok_or(VssError::NegativeValue)?;
+
+    Ok(
+        (vss.secret_mask_at(shared_value, index, masked.secret_len) ^ u)
+            .to_bigint()
+            .unwrap(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    #[test]
+    fn test_multi_distribution() {
+        let ceremony = fixed_ceremony(3, 2, 43).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let secrets: Vec<BigInt> = (0..20).map(|i| BigInt::from(1000 + i * 7919)).collect();
+        let multi = ceremony
+            .dealer
+            .clone()
+            .distribute_secrets(&secrets, &publickeys, 2)
+            .unwrap();
+
+        assert_eq!(multi.len(), 20);
+        assert!(multi.verify(vss));
+
+        // one share per shareholder covers every secret
+        let shares: Vec<_> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&multi.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            multi.reconstruct_all(vss, &shares[..2]),
+            Ok(secrets.clone())
+        );
+        assert_eq!(
+            multi.reconstruct(vss, &shares[1..], 13),
+            Ok(secrets[13].clone())
+        );
+
+        // equal secrets are masked apart
+        let twins = ceremony
+            .dealer
+            .clone()
+            .distribute_secrets(&[BigInt::from(5), BigInt::from(5)], &publickeys, 2)
+            .unwrap();
+
+        assert_ne!(twins.secrets[0].u, twins.secrets[1].u);
+        assert_eq!(
+            multi.reconstruct(vss, &shares, 20),
+            Err(VssError::UnknownField {
+                label: "20".to_string()
+            })
+        );
+        assert_eq!(
+            ceremony
+                .dealer
+                .clone()
+                .distribute_secrets(&[BigInt::from(-1)], &publickeys, 2),
+            Err(VssError::NegativeSecret)
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use num_bigint::{BigInt, ToBigInt};
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    error::VssError,
+    multi::MaskedSecret,
+    participant::{sequential_positions, Participant},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Packed secret sharing
+
+// Franklin-Yung packing: k secrets ride on one polynomial of degree t - 1, secret j masked under
+// G^p(-j), so that positions 1..=n stay with the shareholders. The distribution costs what one
+// does. The price is a privacy ramp rather than a threshold: `threshold` shares reconstruct every
+// secret, up to `threshold - k` shares reveal nothing about any of them, and the coalitions in
+// between learn something. With k = 1 this is an ordinary distribution. Unlike a
+// `MultiDistribution`, whose secrets hang off one shared value, each secret here has its own.
+
+/// Secrets packed into one distribution, which masks nothing itself. See
+/// `Participant::distribute_packed` for the privacy ramp.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackedDistribution {
+    pub distribution: DistributionShareBox,
+    /// secret j, masked under G^p(-j)
+    pub secrets: Vec<MaskedSecret>,
+}
+
+// the point secret `index` is packed at
+fn point(index: usize) -> BigInt {
+    -BigInt::from(index)
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Packs `secrets` into one distribution to `publickeys` that `threshold` shares reconstruct.
+    ///
+    /// This is weaker than a threshold: only coalitions of at most `threshold - secrets.len()`
+    /// shareholders learn nothing, and larger ones below `threshold` may learn part of the
+    /// secrets. At least one share must stay private, so there must be fewer secrets than
+    /// `threshold`.
+    pub fn distribute_packed(
+        &mut self,
+        secrets: &[BigInt],
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<PackedDistribution, VssError> {
+        self.distribute_packed_with_rng(secrets, publickeys, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `distribute_packed`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_packed_with_rng(
+        &mut self,
+        secrets: &[BigInt],
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<PackedDistribution, VssError> {
+        if secrets.is_empty() || secrets.len() >= threshold as usize {
+            return Err(VssError::InvalidThreshold {
+                threshold,
+                participants: publickeys.len(),
+            });
+        }
+
+        let secrets = secrets
+            .iter()
+            .map(|secret| secret.to_biguint().ok_or(VssError::NegativeSecret))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (distribution, polynomial) = self.deal_polynomial(
+            &BigInt::zero(),
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &[],
+            Some(0),
+            rng,
+        )?;
+        let order = &self.vss.q - BigInt::one();
+        let secrets = secrets
+            .into_iter()
+            .enumerate()
+            .map(|(index, secret)| {
+                let shared_value = self.vss.exp_G(
+                    &self
+                        .vss
+                        .blind(&polynomial.get_value_mod(&point(index), &order)),
+                );
+                let secret_len = secret.to_bytes_be().len();
+
+                MaskedSecret {
+                    u: (secret ^ self.vss.secret_mask_at(&shared_value, index, secret_len))
+                        .to_bigint()
+                        .unwrap(),
+                    secret_len,
+                }
+            })
+            .collect();
+
+        Ok(PackedDistribution {
+            distribution,
+            secrets,
+        })
+    }
+}
+
+impl PackedDistribution {
+    /// The number of secrets.
+    pub fn len(&self) -> usize {
+        self.secrets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.is_empty()
+    }
+
+    /// The largest number of shareholders that learn nothing about any secret. Coalitions from
+    /// here up to the threshold are not covered.
+    pub fn privacy_threshold(&self) -> usize {
+        self.distribution.threshold().saturating_sub(self.len())
+    }
+
+    /// Verifies the distribution, once for all secrets.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        vss.verify_distribution_shares(&self.distribution)
+    }
+
+    /// Reconstructs the secret at `index` from at least `threshold` share boxes.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+        index: usize,
+    ) -> Result<BigInt, VssError> {
+        let masked = self
+            .secrets
+            .get(index)
+            .ok_or_else(|| VssError::UnknownField {
+                label: index.to_string(),
+            })?;
+        let shared_value = vss.interpolate_at(share_boxes, &self.distribution, &point(index))?;
+
+        Ok((vss.secret_mask_at(&shared_value, index, masked.secret_len)
+            ^ masked.u.to_biguint().unwrap())
+        .to_bigint()
+        .unwrap())
+    }
+
+    /// Reconstructs every secret, in order, from at least `threshold` share boxes.
+    pub fn reconstruct_all<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<Vec<BigInt>, VssError> {
+        (0..self.len())
+            .map(|index| self.reconstruct(vss, share_boxes, index))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    #[test]
+    fn test_packed_distribution() {
+        let ceremony = fixed_ceremony(3, 3, 47).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let secrets = [BigInt::from(271828), BigInt::from(314159)];
+        let packed = ceremony
+            .dealer
+            .clone()
+            .distribute_packed(&secrets, &publickeys, 3)
+            .unwrap();
+
+        assert_eq!(packed.privacy_threshold(), 1);
+        assert!(packed.verify(vss));
+
+        let shares: Vec<_> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&packed.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(packed.reconstruct_all(vss, &shares), Ok(secrets.to_vec()));
+        assert_eq!(
+            packed.reconstruct(vss, &shares[..2], 1),
+            Err(VssError::ThresholdNotMet {
+                required: 3,
+                provided: 2
+            })
+        );
+
+        // as many secrets as the threshold would leave no share private
+        assert_eq!(
+            ceremony.dealer.clone().distribute_packed(
+                &[BigInt::from(1), BigInt::from(2), BigInt::from(3)],
+                &publickeys,
+                3
+            ),
+            Err(VssError::InvalidThreshold {
+                threshold: 3,
+                participants: 3
+            })
+        );
+    }
+}
@@ -0,0 +1,199 @@
+#![allow(non_snake_case)]
+
+use num_bigint::{BigInt, BigUint};
+use num_primes::Verification;
+use num_traits::{One, Zero};
+
+use crate::{error::VssError, group::HashEncoding, vss::VSS};
+
+// Parameter validation
+
+// Fields of `VSS` can be set to anything, and a broken group still deals and verifies without a
+// complaint while leaking the secret. The builder only hands out parameters over a safe prime
+// `q = 2p + 1` of at least `min_bits` bits, whose generators `g` and `G` are distinct and have
+// order `p` or `2p`, the only large orders there are in that group.
+
+/// the smallest accepted modulus unless `VssParamsBuilder::min_bits` lowers it
+pub const MIN_BITS: u64 = 2048;
+
+#[derive(Debug, Clone)]
+pub struct VssParamsBuilder {
+    q: Option<BigInt>,
+    g: Option<BigInt>,
+    G: Option<BigInt>,
+    length: Option<u32>,
+    encoding: HashEncoding,
+    min_bits: u64,
+}
+
+impl Default for VssParamsBuilder {
+    fn default() -> Self {
+        VssParamsBuilder::new()
+    }
+}
+
+impl VssParamsBuilder {
+    pub fn new() -> Self {
+        VssParamsBuilder {
+            q: None,
+            g: None,
+            G: None,
+            length: None,
+            encoding: HashEncoding::default(),
+            min_bits: MIN_BITS,
+        }
+    }
+
+    /// The safe prime modulus.
+    pub fn q(mut self, q: BigInt) -> Self {
+        self.q = Some(q);
+        self
+    }
+
+    pub fn g(mut self, g: BigInt) -> Self {
+        self.g = Some(g);
+        self
+    }
+
+    pub fn G(mut self, G: BigInt) -> Self {
+        self.G = Some(G);
+        self
+    }
+
+    /// The bit length of proof nonces, at least that of `q`, which is also the default.
+    pub fn length(mut self, length: u32) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// How integers are hashed, decimal digits unless set.
+    pub fn encoding(mut self, encoding: HashEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Accepts moduli down to `bits` bits. Anything below `MIN_BITS` is for tests only.
+    pub fn min_bits(mut self, bits: u64) -> Self {
+        self.min_bits = bits;
+        self
+    }
+
+    fn check_generator(
+        q: &BigInt,
+        generator: &BigInt,
+        field: &'static str,
+    ) -> Result<(), VssError> {
+        // in a group of order 2p, only 1 and q - 1 have an order below p
+        if generator <= &BigInt::one() || generator >= &(q - BigInt::one()) {
+            return Err(VssError::InvalidParameters { field });
+        }
+
+        Ok(())
+    }
+
+    pub fn build(self) -> Result<VSS, VssError> {
+        let q = self.q.ok_or(VssError::InvalidParameters { field: "q" })?;
+        let g = self.g.ok_or(VssError::InvalidParameters { field: "g" })?;
+        let G = self.G.ok_or(VssError::InvalidParameters { field: "G" })?;
+
+        if q <= BigInt::zero() || q.bits() < self.min_bits as usize {
+            return Err(VssError::InvalidParameters { field: "q" });
+        }
+
+        let q_unsigned = q.to_biguint().unwrap();
+        let p = (&q_unsigned - BigUint::one()) >> 1;
+
+        if !Verification::is_prime(&q_unsigned) || !Verification::is_prime(&p) {
+            return Err(VssError::InvalidParameters { field: "q" });
+        }
+
+        VssParamsBuilder::check_generator(&q, &g, "g")?;
+        VssParamsBuilder::check_generator(&q, &G, "G")?;
+
+        if g == G {
+            return Err(VssError::InvalidParameters { field: "G" });
+        }
+
+        let length = self.length.unwrap_or(q.bits() as u32);
+
+        if (length as usize) < q.bits() {
+            return Err(VssError::InvalidParameters { field: "length" });
+        }
+
+        let mut vss = VSS::default();
+
+        vss.q = q;
+        vss.g = g;
+        vss.G = G;
+        vss.length = length;
+        vss.encoding = self.encoding;
+
+        Ok(vss)
+    }
+}
+
+impl VSS {
+    pub fn builder() -> VssParamsBuilder {
+        VssParamsBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, group::HashEncoding, vss::VSS};
+
+    use super::VssParamsBuilder;
+
+    fn small() -> VssParamsBuilder {
+        VSS::builder()
+            .q(BigInt::from(179426879))
+            .g(BigInt::from(89713439))
+            .G(BigInt::from(2))
+            .min_bits(16)
+    }
+
+    #[test]
+    fn test_builder() {
+        let vss = small().build().unwrap();
+
+        assert_eq!(vss.length, 28);
+        assert_eq!(small().length(64).build().unwrap().length, 64);
+        assert_eq!(vss.encoding, HashEncoding::Decimal);
+        assert_eq!(
+            small()
+                .encoding(HashEncoding::FixedWidth)
+                .build()
+                .unwrap()
+                .encoding,
+            HashEncoding::FixedWidth
+        );
+
+        let default = VSS::new();
+
+        assert_eq!(
+            VSS::builder()
+                .q(default.q.clone())
+                .g(default.g.clone())
+                .G(default.G.clone())
+                .build(),
+            Ok(default)
+        );
+    }
+
+    #[test]
+    fn test_rejects_insecure() {
+        let invalid = |field| Err(VssError::InvalidParameters { field });
+
+        assert_eq!(small().min_bits(2048).build(), invalid("q"));
+        // prime but not safe, as in the test parameters used elsewhere
+        assert_eq!(small().q(BigInt::from(179426549)).build(), invalid("q"));
+        assert_eq!(small().q(BigInt::from(179426881)).build(), invalid("q"));
+        assert_eq!(small().g(BigInt::from(1)).build(), invalid("g"));
+        assert_eq!(small().G(BigInt::from(179426878)).build(), invalid("G"));
+        assert_eq!(small().G(BigInt::from(89713439)).build(), invalid("G"));
+        assert_eq!(small().length(16).build(), invalid("length"));
+        assert_eq!(VSS::builder().build(), invalid("q"));
+    }
+}
@@ -1,90 +1,296 @@
 #![allow(non_snake_case)]
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
 
 use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
 use num_integer::Integer;
 use num_primes::Generator;
-use num_traits::{One, Zero};
-use sha2::{Digest, Sha256};
+use num_traits::{One, Signed, Zero};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
 
 use crate::{
+    arith,
+    delivery::ShareDelivery,
+    diagnostics::DistributionFault,
     dleq::DLEQ,
+    error::VssError,
+    freshness::Freshness,
+    light::{EntryDigest, LightDistribution},
+    manifest::CeremonyManifest,
     polynomial::Polynomial,
-    sharebox::{DistributionShareBox, ShareBox},
+    record::{self, RecordDistribution, RecordShares, SecretRecord},
+    refresh::remaining_recipients,
+    secret_key::SecretKey,
+    session::{session_context, SessionId},
+    sharebox::{DistributionShareBox, ShareBox, ShareCommitments},
+    transcript::Transcript,
+    usage::ReleaseRequest,
     util::Util,
+    vector_commitment::{CommitmentOpening, CommitmentTree, CompactDistribution},
     vss::VSS,
 };
+#[cfg(feature = "hybrid")]
+use crate::{
+    hybrid::{self, SealedPayload},
+    stream,
+};
+
+/// The positions `1..=n`, assigned in recipient order when the dealer names none.
+pub(crate) fn sequential_positions(n: usize) -> Vec<BigInt> {
+    (1..=n).map(BigInt::from).collect()
+}
 
-#[derive(Debug, Clone, Default)]
-pub struct Participant {
-    vss: VSS,
-    pub privatekey: BigInt,
+/// A dealer or shareholder. `D` is the challenge hash, see `VSS`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct Participant<D = Sha256> {
+    pub(crate) vss: VSS<D>,
+    pub privatekey: SecretKey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub publickey: BigInt,
+    /// re-verify every proof right after producing it, see `set_verify_after_sign`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub verify_after_sign: bool,
+}
+
+// implemented by hand so that `D` needs none of these traits
+
+impl<D> fmt::Debug for Participant<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Participant")
+            .field("vss", &self.vss)
+            .field("privatekey", &self.privatekey)
+            .field("publickey", &self.publickey)
+            .field("verify_after_sign", &self.verify_after_sign)
+            .finish()
+    }
+}
+
+impl<D> Clone for Participant<D> {
+    fn clone(&self) -> Self {
+        Participant {
+            vss: self.vss.clone(),
+            privatekey: self.privatekey.clone(),
+            publickey: self.publickey.clone(),
+            verify_after_sign: self.verify_after_sign,
+        }
+    }
+}
+
+impl<D> Default for Participant<D> {
+    fn default() -> Self {
+        Participant {
+            vss: VSS::default(),
+            privatekey: SecretKey::default(),
+            publickey: BigInt::zero(),
+            verify_after_sign: false,
+        }
+    }
 }
 
 impl Participant {
     pub fn new() -> Self {
         Participant {
             vss: VSS::new(),
-            privatekey: BigInt::zero(),
+            privatekey: SecretKey::default(),
+            publickey: BigInt::zero(),
+            verify_after_sign: false,
+        }
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// A participant over the group `vss`, with no key pair yet, see `initialize`.
+    pub fn with_params(vss: VSS<D>) -> Self {
+        Participant {
+            vss,
+            privatekey: SecretKey::default(),
             publickey: BigInt::zero(),
+            verify_after_sign: false,
+        }
+    }
+
+    /// A participant over the group `vss` holding `privatekey` and its public key.
+    pub fn from_private_key(vss: VSS<D>, privatekey: SecretKey) -> Self {
+        let publickey = vss.generate_public_key(&privatekey);
+
+        Participant {
+            vss,
+            privatekey,
+            publickey,
+            verify_after_sign: false,
+        }
+    }
+
+    /// The same participant with challenges hashed by `E`.
+    pub fn with_digest<E: Transcript>(self) -> Participant<E> {
+        Participant {
+            vss: self.vss.with_digest(),
+            privatekey: self.privatekey,
+            publickey: self.publickey,
+            verify_after_sign: self.verify_after_sign,
         }
     }
 
+    /// When enabled, distributions and extracted shares are verified before they are released, catching
+    /// RNG or arithmetic faults (including injected ones) at the cost of one extra verification.
+    pub fn set_verify_after_sign(&mut self, enabled: bool) {
+        self.verify_after_sign = enabled;
+    }
+
+    /// When enabled, the private key, its inverse and the proof nonces are blinded with a random
+    /// multiple of the group order in every exponentiation, see `VSS::exponent_blinding`.
+    pub fn set_exponent_blinding(&mut self, enabled: bool) {
+        self.vss.exponent_blinding = enabled;
+    }
+
+    /// Sets the application label mixed into every hash. All parties of a ceremony must use the same label.
+    pub fn set_protocol_label(&mut self, label: &str) {
+        self.vss.label = label.to_string();
+    }
+
     pub fn initialize(&mut self) {
         self.privatekey = self.vss.generate_private_key();
         self.publickey = self.vss.generate_public_key(&self.privatekey);
     }
 
-    fn distribute(
+    // everything a dealing needs from its caller, checked before any randomness is spent
+    pub(crate) fn validate_inputs(
+        &self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        positions: &[BigInt],
+        threshold: u32,
+        secret_len: Option<usize>,
+    ) -> Result<(), VssError> {
+        if publickeys.is_empty() {
+            return Err(VssError::NoRecipients);
+        }
+
+        if threshold == 0 || threshold > publickeys.len() as u32 {
+            return Err(VssError::InvalidThreshold {
+                threshold,
+                participants: publickeys.len(),
+            });
+        }
+
+        let secret = secret.to_biguint().ok_or(VssError::NegativeSecret)?;
+
+        // the mask covers `secret_len` bytes, so a longer secret would be partly sent in the clear
+        if let Some(secret_len) = secret_len {
+            if !secret.is_zero() && secret.to_bytes_be().len() > secret_len {
+                return Err(VssError::SecretTooLarge { length: secret_len });
+            }
+        }
+
+        // zero and one fail validation; a repeated key would collapse entries of the maps below
+        let mut seen = BTreeSet::new();
+
+        for publickey in publickeys {
+            self.vss.validate_public_key(publickey)?;
+
+            if !seen.insert(publickey) {
+                return Err(VssError::DuplicatePublicKey {
+                    publickey: publickey.clone(),
+                });
+            }
+        }
+
+        // shares are evaluations at the positions, and the secret is the one at zero
+        let mut seen = BTreeSet::new();
+
+        for position in positions {
+            if !position.is_positive() {
+                return Err(VssError::InvalidPosition {
+                    position: position.clone(),
+                });
+            }
+
+            if !seen.insert(position) {
+                return Err(VssError::DuplicatePosition {
+                    position: position.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn distribute(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        positions: &[BigInt],
+        threshold: u32,
+        polynomial: &Polynomial,
+        w: &BigInt,
+        context: &[u8],
+        secret_len: Option<usize>,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_with_orders(
+            secret,
+            publickeys,
+            positions,
+            threshold,
+            polynomial,
+            w,
+            context,
+            secret_len,
+            &BTreeMap::new(),
+        )
+    }
+
+    // `distribute`, dealing recipients listed in `orders` the derivative of that order of the
+    // polynomial at their position rather than its value
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn distribute_with_orders(
         &mut self,
         secret: &BigInt,
         publickeys: &[BigInt],
+        positions: &[BigInt],
         threshold: u32,
         polynomial: &Polynomial,
         w: &BigInt,
-    ) -> DistributionShareBox {
-        assert!(threshold <= publickeys.len() as u32);
+        context: &[u8],
+        secret_len: Option<usize>,
+        orders: &BTreeMap<BigInt, u32>,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.validate_inputs(secret, publickeys, positions, threshold, secret_len)?;
 
         let mut commitments = Vec::new();
-        let mut positions = BTreeMap::new();
+        let mut position_map = BTreeMap::new();
         let mut X = BTreeMap::new();
         let mut shares = BTreeMap::new();
-        let mut challenge_hasher = Sha256::new();
+        let mut challenge_hasher = self.vss.context_hasher(context);
 
         let mut sampling_points = BTreeMap::new();
-        let mut a = BTreeMap::new();
         let mut dleq_w = BTreeMap::new();
-        let mut position: i64 = 1;
 
         for j in 0..threshold {
-            commitments.push(
-                self.vss
-                    .g
-                    .modpow(&polynomial.coefficients[j as usize], &self.vss.q),
-            )
+            commitments.push(self.vss.exp_g(&polynomial.coefficients[j as usize]))
         }
 
-        for publickey in publickeys {
-            positions.insert(publickey.clone(), position);
+        for (publickey, position) in publickeys.iter().zip(positions) {
+            position_map.insert(publickey.clone(), position.clone());
 
+            let order = orders.get(publickey).copied().unwrap_or(0);
             let secret_share =
-                polynomial.get_value(&BigInt::from(position)) % (&self.vss.q - BigInt::one());
+                polynomial.get_derivative_mod(order, position, &(&self.vss.q - BigInt::one()));
 
             sampling_points.insert(publickey.clone(), secret_share.clone());
 
-            let mut x = BigInt::one();
-            let mut exponent = BigInt::one();
-
-            for j in 0..=threshold - 1 {
-                x = (x * commitments[j as usize].modpow(&exponent, &self.vss.q)) % &self.vss.q;
-                exponent = (exponent * BigInt::from(position)) % (&self.vss.q - BigInt::one());
-            }
+            let x = self
+                .vss
+                .evaluate_derivative_commitments(&commitments, position, order);
 
             X.insert(publickey.clone(), x.clone());
 
-            let encrypted_secret_share = publickey.modpow(&secret_share, &self.vss.q);
+            let encrypted_secret_share = arith::modpow(publickey, &secret_share, &self.vss.q);
 
             shares.insert(publickey.clone(), encrypted_secret_share.clone());
 
@@ -95,45 +301,21 @@ impl Participant {
                 x.clone(),
                 publickey.clone(),
                 encrypted_secret_share.clone(),
-                self.vss.q.clone(),
+                self.vss.group(),
                 secret_share.clone(),
                 w.clone(),
             );
 
             dleq_w.insert(publickey.clone(), dleq.w.clone());
 
-            a.insert(publickey.clone(), (dleq.get_a1(), dleq.get_a2()));
-
-            challenge_hasher.update(x.to_biguint().unwrap().to_str_radix(10).as_bytes());
-
-            challenge_hasher.update(
-                encrypted_secret_share
-                    .to_biguint()
-                    .unwrap()
-                    .to_str_radix(10)
-                    .as_bytes(),
-            );
-
-            challenge_hasher.update(
-                dleq.get_a1()
-                    .to_biguint()
-                    .unwrap()
-                    .to_str_radix(10)
-                    .as_bytes(),
-            );
-
-            challenge_hasher.update(
-                dleq.get_a2()
-                    .to_biguint()
-                    .unwrap()
-                    .to_str_radix(10)
-                    .as_bytes(),
+            // g1 = g, whose table spares the exponentiation of a1
+            dleq.append_recomputed(
+                &[self.vss.exp_g(&self.vss.blind(w)), dleq.get_a2()],
+                &mut challenge_hasher,
             );
-
-            position += 1;
         }
 
-        let challenge_hash = challenge_hasher.finalize();
+        let challenge_hash = challenge_hasher.challenge();
         let challenge_big_uint = BigUint::from_bytes_be(&challenge_hash[..])
             .mod_floor(&(self.vss.q.to_biguint().unwrap() - BigUint::one()));
         let mut responses: BTreeMap<BigInt, BigInt> = BTreeMap::new();
@@ -150,7 +332,7 @@ impl Participant {
                 x_i.clone(),
                 publickey.clone(),
                 encrypted_secret_share.clone(),
-                self.vss.q.clone(),
+                self.vss.group(),
                 secret_share.clone(),
                 w.clone(),
             );
@@ -162,36 +344,32 @@ impl Participant {
             responses.insert(publickey.clone(), response);
         }
 
-        let shared_value = self.vss.G.modpow(
-            &polynomial
-                .get_value(&BigInt::zero())
-                .mod_floor(&(self.vss.q.to_bigint().unwrap() - BigInt::one())),
+        let shared_value = arith::modpow(
+            &self.vss.G,
+            &polynomial.get_value_mod(&BigInt::zero(), &(&self.vss.q - BigInt::one())),
             &self.vss.q,
         );
-        let sha256_hash = sha2::Sha256::digest(
-            shared_value
-                .to_biguint()
-                .unwrap()
-                .to_str_radix(10)
-                .as_bytes(),
-        );
-        let hash_big_uint =
-            BigUint::from_bytes_be(&sha256_hash[..]).mod_floor(&self.vss.q.to_biguint().unwrap());
-        let u = secret.to_biguint().unwrap() ^ hash_big_uint;
+        let secret = secret.to_biguint().unwrap();
+        let secret_len = secret_len.unwrap_or_else(|| secret.to_bytes_be().len());
+        let u = secret ^ self.vss.secret_mask(&shared_value, Some(secret_len));
 
         let mut shares_box = DistributionShareBox::new();
 
         shares_box.init(
             &commitments,
-            positions,
+            position_map,
             shares,
             publickeys,
             &challenge_big_uint.to_bigint().unwrap(),
             responses,
             &u.to_bigint().unwrap(),
         );
+        shares_box.secret_len = Some(secret_len);
+        shares_box.params_fingerprint = Some(self.vss.params_fingerprint());
+        shares_box.threshold = Some(threshold as usize);
+        shares_box.n = Some(publickeys.len());
 
-        shares_box
+        Ok(shares_box)
     }
 
     pub fn distribute_secret(
@@ -199,255 +377,2014 @@ impl Participant {
         secret: &BigInt,
         publickeys: &[BigInt],
         threshold: u32,
-    ) -> DistributionShareBox {
-        let mut polynomial = Polynomial::new();
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_secret_with_rng(secret, publickeys, threshold, &mut rand::thread_rng())
+    }
 
-        polynomial.init((threshold - 1) as i32, &self.vss.q.to_bigint().unwrap());
+    /// Like `distribute_secret`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_secret_with_rng(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        self.deal(
+            secret,
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &[],
+            None,
+            rng,
+        )
+    }
 
-        let mut rng = rand::thread_rng();
-        let w = rng.gen_biguint_below(&self.vss.q.to_biguint().unwrap());
+    /// Like `distribute_secret` with the position of every recipient chosen by the dealer, so that a
+    /// committee keeps its positions across distributions whatever the order of its keys. Positions
+    /// must be positive and distinct, given as `i64` or, e.g. when derived from a hash, `BigInt`.
+    pub fn distribute_secret_with_positions<P: Clone + Into<BigInt>>(
+        &mut self,
+        secret: &BigInt,
+        recipients: &[(BigInt, P)],
+        threshold: u32,
+    ) -> Result<DistributionShareBox, VssError> {
+        let (publickeys, positions): (Vec<BigInt>, Vec<BigInt>) = recipients
+            .iter()
+            .map(|(publickey, position)| (publickey.clone(), position.clone().into()))
+            .unzip();
 
-        self.distribute(
+        self.deal(
             secret,
-            publickeys,
+            &publickeys,
+            &positions,
             threshold,
-            &polynomial,
-            &w.to_bigint().unwrap(),
+            &[],
+            None,
+            &mut rand::thread_rng(),
         )
     }
 
-    fn extract_share(
-        &self,
-        share_box: &DistributionShareBox,
-        private_key: &BigInt,
-        w: &BigInt,
-    ) -> Option<ShareBox> {
-        let public_key = self.vss.generate_public_key(private_key);
-        let encrypted_secret_share = share_box.shares.get(&public_key).unwrap();
-        let privatekey_inverse =
-            Util::mod_inverse(private_key, &(&self.vss.q - BigInt::one())).unwrap();
-        let decrypted_share = encrypted_secret_share.modpow(&privatekey_inverse, &self.vss.q);
-        let mut dleq = DLEQ::new();
+    /// Like `distribute_secret`, binding the proofs to `context`, e.g. a session identifier. The
+    /// distribution only verifies with `verify_distribution_shares_with_context` and the same
+    /// context, so it cannot be replayed in another session or under other parameters.
+    pub fn distribute_secret_with_context(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+        context: &[u8],
+    ) -> Result<DistributionShareBox, VssError> {
+        self.deal(
+            secret,
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            context,
+            None,
+            &mut rand::thread_rng(),
+        )
+    }
 
-        dleq.init2(
-            self.vss.G.clone(),
-            public_key.clone(),
-            decrypted_share.clone(),
-            encrypted_secret_share.clone(),
-            self.vss.q.clone(),
-            private_key.clone(),
-            w.clone(),
-        );
+    /// Like `distribute_secret` within `session`: the distribution carries the session, and its
+    /// proofs and those of the share boxes extracted from it are bound to it, see `SessionId`.
+    pub fn distribute_secret_in_session(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+        session: &SessionId,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_secret_in_session_with_rng(
+            secret,
+            publickeys,
+            threshold,
+            session,
+            &mut rand::thread_rng(),
+        )
+    }
 
-        let mut challenge_hasher = Sha256::new();
+    /// Like `distribute_secret_in_session`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_secret_in_session_with_rng(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+        session: &SessionId,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        let mut distribution_sharebox = self.deal(
+            secret,
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &session.bind(&[]),
+            None,
+            rng,
+        )?;
 
-        challenge_hasher.update(public_key.to_biguint().unwrap().to_str_radix(10).as_bytes());
+        distribution_sharebox.session_id = Some(*session);
 
-        challenge_hasher.update(
-            encrypted_secret_share
-                .to_biguint()
-                .unwrap()
-                .to_str_radix(10)
-                .as_bytes(),
-        );
+        Ok(distribution_sharebox)
+    }
 
-        challenge_hasher.update(
-            dleq.get_a1()
-                .to_biguint()
-                .unwrap()
-                .to_str_radix(10)
-                .as_bytes(),
-        );
+    /// Like `distribute_secret` for a secret given as bytes, which reconstructs to exactly these
+    /// bytes with `secret_to_bytes` and the distribution's `secret_len`, leading zeros included.
+    pub fn distribute_secret_bytes(
+        &mut self,
+        secret: &[u8],
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_secret_bytes_with_rng(
+            secret,
+            publickeys,
+            threshold,
+            &mut rand::thread_rng(),
+        )
+    }
 
-        challenge_hasher.update(
-            dleq.get_a2()
-                .to_biguint()
-                .unwrap()
-                .to_str_radix(10)
-                .as_bytes(),
-        );
+    /// Like `distribute_secret_bytes`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_secret_bytes_with_rng(
+        &mut self,
+        secret: &[u8],
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        self.deal(
+            &crate::bytes_to_secret(secret),
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &[],
+            Some(secret.len()),
+            rng,
+        )
+    }
 
-        let challenge_hash = challenge_hasher.finalize();
-        let challenge_big_uint = BigUint::from_bytes_be(&challenge_hash[..])
-            .mod_floor(&(self.vss.q.to_biguint().unwrap() - BigUint::one()));
+    #[allow(clippy::too_many_arguments)]
+    fn deal(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        positions: &[BigInt],
+        threshold: u32,
+        context: &[u8],
+        secret_len: Option<usize>,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        self.deal_polynomial(
+            secret, publickeys, positions, threshold, context, secret_len, rng,
+        )
+        .map(|(distribution_sharebox, _)| distribution_sharebox)
+    }
 
-        dleq.c = Some(challenge_big_uint.to_bigint().unwrap());
+    // `deal`, also handing back the polynomial for proofs made after the distribution
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn deal_polynomial(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        positions: &[BigInt],
+        threshold: u32,
+        context: &[u8],
+        secret_len: Option<usize>,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<(DistributionShareBox, Polynomial), VssError> {
+        self.validate_inputs(secret, publickeys, positions, threshold, secret_len)?;
 
-        let mut share_box = ShareBox::new();
+        let mut polynomial = Polynomial::new();
 
-        share_box.init(
-            public_key,
-            decrypted_share,
-            challenge_big_uint.to_bigint().unwrap(),
-            dleq.get_r().unwrap(),
+        polynomial.init_with_rng(
+            (threshold - 1) as i32,
+            &self.vss.q.to_bigint().unwrap(),
+            rng,
         );
 
-        Some(share_box)
-    }
-
-    pub fn extract_secret_share(
-        &self,
-        share_box: &DistributionShareBox,
-        private_key: &BigInt,
-    ) -> Option<ShareBox> {
-        let w = Generator::new_uint(self.vss.length as usize)
-            .mod_floor(&self.vss.q.to_biguint().unwrap());
+        let w = rng.gen_biguint_below(&self.vss.q.to_biguint().unwrap());
+        let distribution_sharebox = self.distribute(
+            secret,
+            publickeys,
+            positions,
+            threshold,
+            &polynomial,
+            &w.to_bigint().unwrap(),
+            context,
+            secret_len,
+        )?;
+
+        if self.verify_after_sign
+            && !self
+                .vss
+                .verify_distribution_shares_with_context(&distribution_sharebox, context)
+        {
+            return Err(VssError::InvalidProof);
+        }
 
-        self.extract_share(share_box, private_key, &w.to_bigint().unwrap())
+        Ok((distribution_sharebox, polynomial))
     }
 
-    pub fn verify_distribution_shares(&self, distribution_sharebox: &DistributionShareBox) -> bool {
-        self.vss.verify_distribution_shares(distribution_sharebox)
+    /// Deals a refresh of `distribution_sharebox`: a sharing of zero to its recipients at their
+    /// positions with its threshold, proven with its fingerprint as context. Any participant can
+    /// deal one; see `RefreshedDistribution` for collecting and applying them.
+    pub fn distribute_refresh(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_refresh_with_rng(distribution_sharebox, &mut rand::thread_rng())
     }
 
-    pub fn verify_share(
-        &self,
-        sharebox: &ShareBox,
+    /// Like `distribute_refresh`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_refresh_with_rng(
+        &mut self,
         distribution_sharebox: &DistributionShareBox,
-        publickey: &BigInt,
-    ) -> bool {
-        self.vss
-            .verify_share(sharebox, distribution_sharebox, publickey)
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_revocation_with_rng(distribution_sharebox, &[], rng)
     }
 
-    pub fn reconstruct(
-        &self,
-        share_boxes: &[ShareBox],
+    /// Deals a refresh of `distribution_sharebox` that leaves out the `revoked` recipients: a
+    /// sharing of zero to the others at their positions with the same threshold. Once applied,
+    /// see `RefreshedDistribution::revoke`, the shares of the revoked recipients no longer
+    /// combine with anyone's.
+    pub fn distribute_revocation(
+        &mut self,
         distribution_sharebox: &DistributionShareBox,
-    ) -> Option<BigInt> {
-        self.vss.reconstruct(share_boxes, distribution_sharebox)
+        revoked: &[BigInt],
+    ) -> Result<DistributionShareBox, VssError> {
+        self.distribute_revocation_with_rng(distribution_sharebox, revoked, &mut rand::thread_rng())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::collections::BTreeMap;
+    /// Like `distribute_revocation`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_revocation_with_rng(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        revoked: &[BigInt],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        self.vss.check_params(distribution_sharebox)?;
+        distribution_sharebox.validate()?;
+
+        let publickeys = remaining_recipients(distribution_sharebox, revoked)?;
+        let positions: Vec<BigInt> = publickeys
+            .iter()
+            .map(|publickey| {
+                distribution_sharebox
+                    .positions
+                    .get(publickey)
+                    .cloned()
+                    .ok_or_else(|| VssError::MissingShare {
+                        publickey: publickey.clone(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+        let threshold = distribution_sharebox.threshold() as u32;
+
+        self.validate_inputs(&BigInt::zero(), &publickeys, &positions, threshold, None)?;
 
-    use num_bigint::BigInt;
-    use num_traits::{One, Zero};
+        let mut polynomial = Polynomial::new();
 
-    use crate::{
-        polynomial::Polynomial,
-        sharebox::{DistributionShareBox, ShareBox},
-        vss::VSS,
-    };
+        polynomial.init_with_rng(
+            (threshold - 1) as i32,
+            &self.vss.q.to_bigint().unwrap(),
+            rng,
+        );
+        polynomial.coefficients[0] = BigInt::zero();
 
-    use super::Participant;
+        let w = rng.gen_biguint_below(&self.vss.q.to_biguint().unwrap());
+        let refresh = self.distribute(
+            &BigInt::zero(),
+            &publickeys,
+            &positions,
+            threshold,
+            &polynomial,
+            &w.to_bigint().unwrap(),
+            &distribution_sharebox.fingerprint(),
+            None,
+        )?;
+
+        if self.verify_after_sign
+            && !self
+                .vss
+                .verify_revocation(distribution_sharebox, revoked, &refresh)
+        {
+            return Err(VssError::InvalidProof);
+        }
 
-    struct Setup {
-        pub vss: VSS,
-        pub privatekey: BigInt,
-        pub secret: BigInt,
+        Ok(refresh)
     }
 
-    impl Setup {
-        fn new() -> Self {
-            let q = BigInt::from(179426549);
+    /// Like `distribute_secret`, also cutting a `ShareDelivery` for every recipient, which is all
+    /// a recipient needs to verify and extract its share. The deliveries are in the order of
+    /// `publickeys`.
+    pub fn distribute_secret_with_deliveries(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<(DistributionShareBox, Vec<ShareDelivery>), VssError> {
+        self.deal_with_deliveries(secret, publickeys, threshold, None)
+    }
+
+    /// Like `distribute_secret_with_deliveries` within `session`, see
+    /// `distribute_secret_in_session`. The deliveries carry the session as well.
+    pub fn distribute_secret_in_session_with_deliveries(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+        session: &SessionId,
+    ) -> Result<(DistributionShareBox, Vec<ShareDelivery>), VssError> {
+        self.deal_with_deliveries(secret, publickeys, threshold, Some(session))
+    }
+
+    fn deal_with_deliveries(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+        session: Option<&SessionId>,
+    ) -> Result<(DistributionShareBox, Vec<ShareDelivery>), VssError> {
+        let mut rng = rand::thread_rng();
+        let (mut distribution_sharebox, polynomial) = self.deal_polynomial(
+            secret,
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            &session_context(session, &[]),
+            None,
+            &mut rng,
+        )?;
+
+        distribution_sharebox.session_id = session.copied();
+
+        let q = self.vss.q.to_biguint().unwrap();
+        let deliveries = publickeys
+            .iter()
+            .map(|publickey| {
+                let w = rng.gen_biguint_below(&q).to_bigint().unwrap();
+
+                ShareDelivery::new(
+                    &self.vss,
+                    &distribution_sharebox,
+                    &polynomial,
+                    publickey,
+                    &w,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok((distribution_sharebox, deliveries))
+    }
+
+    pub(crate) fn extract_share(
+        &self,
+        share_box: &DistributionShareBox,
+        private_key: &SecretKey,
+        w: &BigInt,
+        context: &[u8],
+    ) -> Result<ShareBox, VssError> {
+        self.extract_share_with_freshness(share_box, private_key, w, context, None)
+    }
+
+    // `extract_share`, for a share box carrying `freshness` in its proof
+    pub(crate) fn extract_share_with_freshness(
+        &self,
+        share_box: &DistributionShareBox,
+        private_key: &SecretKey,
+        w: &BigInt,
+        context: &[u8],
+        freshness: Option<Freshness>,
+    ) -> Result<ShareBox, VssError> {
+        self.vss.check_params(share_box)?;
+
+        let public_key = self.vss.generate_public_key(private_key);
+        let encrypted_secret_share =
+            share_box
+                .shares
+                .get(&public_key)
+                .ok_or_else(|| VssError::MissingShare {
+                    publickey: public_key.clone(),
+                })?;
+
+        self.decrypt_share(
+            encrypted_secret_share,
+            private_key,
+            w,
+            &share_box.proof_context(context),
+            freshness,
+        )
+    }
+
+    // decrypts the share and proves log_G y = log_share encrypted_share
+    fn decrypt_share(
+        &self,
+        encrypted_secret_share: &BigInt,
+        private_key: &SecretKey,
+        w: &BigInt,
+        context: &[u8],
+        freshness: Option<Freshness>,
+    ) -> Result<ShareBox, VssError> {
+        let public_key = self.vss.generate_public_key(private_key);
+        let privatekey_inverse =
+            Util::mod_inverse(private_key.expose(), &(&self.vss.q - BigInt::one()))
+                .ok_or(VssError::NonInvertible)?;
+        let decrypted_share = arith::modpow(
+            encrypted_secret_share,
+            &self.vss.blind(&privatekey_inverse),
+            &self.vss.q,
+        );
+        let mut dleq = DLEQ::new();
+
+        dleq.init2(
+            self.vss.G.clone(),
+            public_key.clone(),
+            decrypted_share.clone(),
+            encrypted_secret_share.clone(),
+            self.vss.group(),
+            private_key.expose().clone(),
+            w.clone(),
+        );
+
+        let mut challenge_hasher = self.vss.context_hasher(context);
+        let commitments = ShareCommitments {
+            a1: self.vss.exp_G(&self.vss.blind(w)),
+            a2: dleq.get_a2(),
+        };
+
+        dleq.append_recomputed(
+            &[commitments.a1.clone(), commitments.a2.clone()],
+            &mut challenge_hasher,
+        );
+
+        if let Some(freshness) = &freshness {
+            freshness.append_to(&mut challenge_hasher);
+        }
+
+        let challenge_hash = challenge_hasher.challenge();
+        let challenge_big_uint = BigUint::from_bytes_be(&challenge_hash[..])
+            .mod_floor(&(self.vss.q.to_biguint().unwrap() - BigUint::one()));
+
+        dleq.c = Some(challenge_big_uint.to_bigint().unwrap());
+
+        let mut share_box = ShareBox::new();
+
+        share_box.init(
+            public_key,
+            decrypted_share,
+            challenge_big_uint.to_bigint().unwrap(),
+            dleq.get_r().unwrap(),
+        );
+        share_box.freshness = freshness;
+        share_box.commitments = Some(commitments);
+
+        Ok(share_box)
+    }
+
+    pub fn extract_secret_share(
+        &self,
+        share_box: &DistributionShareBox,
+        private_key: &SecretKey,
+    ) -> Result<ShareBox, VssError> {
+        self.extract_secret_share_with_context(share_box, private_key, &[])
+    }
+
+    /// Like `extract_secret_share`, binding the proof to `context`. The share box only verifies
+    /// with `verify_share_with_context` and the same context.
+    pub fn extract_secret_share_with_context(
+        &self,
+        share_box: &DistributionShareBox,
+        private_key: &SecretKey,
+        context: &[u8],
+    ) -> Result<ShareBox, VssError> {
+        let w = Generator::new_uint(self.vss.length as usize)
+            .mod_floor(&self.vss.q.to_biguint().unwrap());
+
+        self.extract_checked(share_box, private_key, &w.to_bigint().unwrap(), context)
+    }
+
+    /// Like `extract_secret_share`, drawing the proof nonce from `rng`.
+    pub fn extract_secret_share_with_rng(
+        &self,
+        share_box: &DistributionShareBox,
+        private_key: &SecretKey,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<ShareBox, VssError> {
+        let w = rng.gen_biguint_below(&self.vss.q.to_biguint().unwrap());
+
+        self.extract_checked(share_box, private_key, &w.to_bigint().unwrap(), &[])
+    }
+
+    fn extract_checked(
+        &self,
+        share_box: &DistributionShareBox,
+        private_key: &SecretKey,
+        w: &BigInt,
+        context: &[u8],
+    ) -> Result<ShareBox, VssError> {
+        let sharebox = self.extract_share(share_box, private_key, w, context)?;
+
+        if self.verify_after_sign
+            && !self.vss.verify_share_with_context(
+                &sharebox,
+                share_box,
+                &sharebox.publickey,
+                context,
+            )
+        {
+            return Err(VssError::InvalidProof);
+        }
+
+        Ok(sharebox)
+    }
+
+    /// Every reason `distribution_sharebox` fails to verify, see `VSS::diagnose_distribution`.
+    pub fn diagnose_distribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        reference: Option<&[EntryDigest]>,
+    ) -> Vec<DistributionFault> {
+        self.vss
+            .diagnose_distribution(distribution_sharebox, &[], reference)
+    }
+
+    /// Checks a share box this participant extracted before broadcasting it: that it carries the
+    /// participant's own public key, that the distribution holds a share for that key, and that
+    /// the decrypted share and its proof match that encrypted share.
+    pub fn check_own_share(
+        &self,
+        sharebox: &ShareBox,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), VssError> {
+        if sharebox.publickey != self.publickey {
+            return Err(VssError::ForeignShare {
+                publickey: sharebox.publickey.clone(),
+            });
+        }
+
+        self.vss.check_params(distribution_sharebox)?;
+
+        let encrypted_share = distribution_sharebox
+            .shares
+            .get(&self.publickey)
+            .ok_or_else(|| VssError::MissingShare {
+                publickey: self.publickey.clone(),
+            })?;
+
+        if !sharebox.share.is_positive() || sharebox.share >= self.vss.q {
+            return Err(VssError::InvalidShare {
+                publickey: self.publickey.clone(),
+            });
+        }
+
+        if !self.vss.verify_with_context(
+            sharebox,
+            encrypted_share,
+            &distribution_sharebox.proof_context(&[]),
+        ) {
+            return Err(VssError::InvalidProof);
+        }
+
+        Ok(())
+    }
+
+    pub fn verify_distribution_shares(&self, distribution_sharebox: &DistributionShareBox) -> bool {
+        self.vss.verify_distribution_shares(distribution_sharebox)
+    }
+
+    pub fn verify_distribution_shares_with_context(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        context: &[u8],
+    ) -> bool {
+        self.vss
+            .verify_distribution_shares_with_context(distribution_sharebox, context)
+    }
+
+    pub fn verify_share(
+        &self,
+        sharebox: &ShareBox,
+        distribution_sharebox: &DistributionShareBox,
+        publickey: &BigInt,
+    ) -> bool {
+        self.vss
+            .verify_share(sharebox, distribution_sharebox, publickey)
+    }
+
+    /// Like `verify_share` for many share boxes, see `VSS::verify_shares`.
+    pub fn verify_shares(
+        &self,
+        shareboxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Vec<bool> {
+        self.vss.verify_shares(shareboxes, distribution_sharebox)
+    }
+
+    pub fn verify_share_with_context(
+        &self,
+        sharebox: &ShareBox,
+        distribution_sharebox: &DistributionShareBox,
+        publickey: &BigInt,
+        context: &[u8],
+    ) -> bool {
+        self.vss
+            .verify_share_with_context(sharebox, distribution_sharebox, publickey, context)
+    }
+
+    pub fn reconstruct(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<BigInt, VssError> {
+        self.vss.reconstruct(share_boxes, distribution_sharebox)
+    }
+
+    /// Like `reconstruct`, leaving out and naming the shareholders whose proofs fail, see
+    /// `VSS::reconstruct_verified`.
+    pub fn reconstruct_verified(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(BigInt, Vec<BigInt>), VssError> {
+        self.vss
+            .reconstruct_verified(share_boxes, distribution_sharebox)
+    }
+
+    /// Like `reconstruct_verified`, falling back to a search for a majority of agreeing share
+    /// boxes, see `VSS::reconstruct_robust`.
+    pub fn reconstruct_robust(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(BigInt, Vec<BigInt>), VssError> {
+        self.vss
+            .reconstruct_robust(share_boxes, distribution_sharebox)
+    }
+    /// Signs a request for the share box `share_id`, to be kept by the releasing shareholder's
+    /// `UsageLedger`. `at` is the request time in seconds since the unix epoch.
+    pub fn sign_release_request(&self, share_id: &str, at: u64) -> ReleaseRequest {
+        ReleaseRequest::sign(&self.vss, &self.privatekey, share_id, at)
+    }
+
+    /// Signs `manifest` as the dealer of the ceremony it describes.
+    pub fn sign_manifest(&self, manifest: &mut CeremonyManifest) {
+        manifest.sign(&self.privatekey);
+    }
+
+    /// Validates `manifest` at time `now`, in seconds since the unix epoch, and binds its hash
+    /// into the protocol label, so that every proof of the ceremony references the manifest.
+    pub fn join_ceremony(&mut self, manifest: &CeremonyManifest, now: u64) -> Result<(), VssError> {
+        manifest.validate(self, now)?;
+        self.vss.label = manifest.bound_label();
+
+        Ok(())
+    }
+
+    /// Distributes `secret` to the recipients of a joined ceremony with its threshold.
+    pub fn distribute_ceremony_secret(
+        &mut self,
+        manifest: &CeremonyManifest,
+        secret: &BigInt,
+    ) -> Result<DistributionShareBox, VssError> {
+        if self.vss.label != manifest.bound_label() {
+            return Err(VssError::ManifestMismatch { field: "label" });
+        }
+
+        self.distribute_secret(secret, &manifest.publickeys, manifest.threshold)
+    }
+
+    /// Deals every field of `record` on its own polynomial to the same recipients.
+    pub fn distribute_record(
+        &mut self,
+        record: &SecretRecord,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<RecordDistribution, VssError> {
+        let thresholds = record
+            .keys()
+            .map(|label| (label.clone(), threshold))
+            .collect();
+
+        record::distribute(self, record, publickeys, &thresholds)
+    }
+
+    /// Like `distribute_record`, with a threshold for every field.
+    pub fn distribute_record_with_thresholds(
+        &mut self,
+        record: &SecretRecord,
+        publickeys: &[BigInt],
+        thresholds: &BTreeMap<String, u32>,
+    ) -> Result<RecordDistribution, VssError> {
+        record::distribute(self, record, publickeys, thresholds)
+    }
+
+    pub fn verify_record_distribution(&self, distribution: &RecordDistribution) -> bool {
+        record::verify(self, distribution)
+    }
+
+    pub fn extract_record_shares(
+        &self,
+        distribution: &RecordDistribution,
+        private_key: &SecretKey,
+    ) -> Result<RecordShares, VssError> {
+        let labels = distribution.labels();
+
+        record::extract(self, distribution, &labels, private_key)
+    }
+
+    /// Extracts the shares of the fields `labels` only, for a partial reveal.
+    pub fn extract_field_shares(
+        &self,
+        distribution: &RecordDistribution,
+        labels: &[&str],
+        private_key: &SecretKey,
+    ) -> Result<RecordShares, VssError> {
+        record::extract(self, distribution, labels, private_key)
+    }
+
+    /// Reconstructs the field `label` only; the other fields stay hidden.
+    pub fn reconstruct_field(
+        &self,
+        label: &str,
+        shares: &[RecordShares],
+        distribution: &RecordDistribution,
+    ) -> Result<BigInt, VssError> {
+        record::reconstruct_field(self, label, shares, distribution)
+    }
+
+    pub fn reconstruct_record(
+        &self,
+        shares: &[RecordShares],
+        distribution: &RecordDistribution,
+    ) -> Result<SecretRecord, VssError> {
+        record::reconstruct(self, shares, distribution)
+    }
+
+    /// Encrypts `payload` with ChaCha20-Poly1305 under a fresh 256-bit key and shares the key.
+    #[cfg(feature = "hybrid")]
+    pub fn seal_payload(
+        &mut self,
+        payload: &[u8],
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<SealedPayload, VssError> {
+        hybrid::seal(self, payload, publickeys, threshold)
+    }
+
+    /// Reconstructs the key of `sealed` from `share_boxes` and decrypts the payload.
+    #[cfg(feature = "hybrid")]
+    pub fn open_payload(
+        &self,
+        share_boxes: &[ShareBox],
+        sealed: &SealedPayload,
+    ) -> Result<Vec<u8>, VssError> {
+        hybrid::open(self, share_boxes, sealed)
+    }
+
+    /// Like `seal_payload` for input too large for memory: returns the distribution of the key
+    /// and a reader of the encrypted stream.
+    #[cfg(feature = "hybrid")]
+    pub fn split_reader<R: std::io::Read>(
+        &mut self,
+        reader: R,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<(DistributionShareBox, impl std::io::Read), VssError> {
+        stream::split(self, reader, publickeys, threshold)
+    }
+
+    /// Reconstructs the key of `distribution` and returns a reader decrypting the stream written
+    /// by `split_reader`. Reads fail with `InvalidData` if the stream was tampered with or cut.
+    #[cfg(feature = "hybrid")]
+    pub fn reconstruct_reader<R: std::io::Read>(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution: &DistributionShareBox,
+        reader: R,
+    ) -> Result<impl std::io::Read, VssError> {
+        stream::reconstruct(self, share_boxes, distribution, reader)
+    }
+
+    pub fn light_distribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        publickey: &BigInt,
+    ) -> Result<LightDistribution, VssError> {
+        LightDistribution::new(&self.vss, distribution_sharebox, publickey)
+    }
+
+    pub fn verify_light_distribution(&self, light: &LightDistribution, samples: usize) -> bool {
+        light.verify(&self.vss, samples)
+    }
+
+    /// See `LightDistribution::verify_low_degree`.
+    pub fn verify_light_distribution_low_degree(&self, light: &LightDistribution) -> bool {
+        light.verify_low_degree(&self.vss)
+    }
+
+    /// Checks the proof of a delivery, see `distribute_secret_with_deliveries`.
+    pub fn verify_share_delivery(&self, delivery: &ShareDelivery) -> bool {
+        delivery.verify(&self.vss)
+    }
+
+    /// Like `extract_secret_share` from a delivery. The share box verifies against the full
+    /// distribution as usual, or with `verify_delivered_share`.
+    pub fn extract_delivered_share(
+        &self,
+        delivery: &ShareDelivery,
+        private_key: &SecretKey,
+    ) -> Result<ShareBox, VssError> {
+        let publickey = self.vss.generate_public_key(private_key);
+
+        if delivery.publickey != publickey {
+            return Err(VssError::MissingShare { publickey });
+        }
+
+        let w = Generator::new_uint(self.vss.length as usize)
+            .mod_floor(&self.vss.q.to_biguint().unwrap());
+
+        self.decrypt_share(
+            &delivery.encrypted_share,
+            private_key,
+            &w.to_bigint().unwrap(),
+            &delivery.proof_context(&[]),
+            None,
+        )
+    }
+
+    /// Like `verify_share` against the delivery of the share box's owner.
+    pub fn verify_delivered_share(&self, sharebox: &ShareBox, delivery: &ShareDelivery) -> bool {
+        sharebox.publickey == delivery.publickey
+            && self.vss.verify_with_context(
+                sharebox,
+                &delivery.encrypted_share,
+                &delivery.proof_context(&[]),
+            )
+    }
+
+    /// Replaces the commitments of a distribution with the root of a hash tree over them, for
+    /// distributions with large thresholds.
+    pub fn compact_distribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> CompactDistribution {
+        CompactDistribution::new(&self.vss, distribution_sharebox)
+    }
+
+    /// Opening of the commitment at `index`, served by whoever holds the full distribution.
+    pub fn open_commitment(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        index: usize,
+    ) -> Option<CommitmentOpening> {
+        CommitmentTree::new(&self.vss, &distribution_sharebox.commitments).open(index)
+    }
+
+    pub fn verify_commitment_opening(
+        &self,
+        compact: &CompactDistribution,
+        opening: &CommitmentOpening,
+    ) -> bool {
+        compact.verify_opening(&self.vss, opening)
+    }
+
+    pub fn expand_distribution(
+        &self,
+        compact: &CompactDistribution,
+        commitments: &[BigInt],
+    ) -> Result<DistributionShareBox, VssError> {
+        compact.expand(&self.vss, commitments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use num_bigint::BigInt;
+    use num_traits::{One, Zero};
+    use rand::{prng::ChaChaRng, SeedableRng};
+    use sha2::Sha256;
+    use sha3::Sha3_256;
+
+    use crate::{
+        error::VssError,
+        polynomial::Polynomial,
+        sharebox::{DistributionShareBox, ShareBox},
+        vss::VSS,
+    };
+
+    use super::{sequential_positions, Participant, RecordShares, SecretKey, SecretRecord};
+
+    struct Setup {
+        pub vss: VSS,
+        pub privatekey: SecretKey,
+        pub secret: BigInt,
+    }
+
+    impl Setup {
+        #[allow(clippy::needless_return)]
+        fn new() -> Self {
+            let q = BigInt::from(179426549);
             let g = BigInt::from(1301081);
             let G = BigInt::from(15486487);
             let length: i64 = 64_i64;
 
-            let mut vss = VSS::new();
+            let mut vss = VSS::new();
+
+            vss.q = q;
+            vss.g = g;
+            vss.G = G;
+            vss.length = length as u32;
+
+            return Setup {
+                vss,
+                privatekey: SecretKey::new(BigInt::from(105929)),
+                secret: BigInt::from(1234567890),
+            };
+        }
+    }
+
+    #[allow(
+        clippy::bool_assert_comparison,
+        clippy::needless_return,
+        clippy::useless_vec
+    )]
+    fn dealer_distribute_share_box() -> DistributionShareBox {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+
+        dealer.vss = setup.vss.clone();
+        dealer.privatekey = setup.privatekey.clone();
+        dealer.publickey = setup.vss.generate_public_key(&setup.privatekey);
+
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_coefficients(&vec![
+            BigInt::from(164102006),
+            BigInt::from(43489589),
+            BigInt::from(98100795),
+        ]);
+
+        let threshold = 3;
+        let privatekeys = [7901, 4801, 1453].map(|key| SecretKey::new(BigInt::from(key)));
+        let mut publickeys = vec![];
+        let w = BigInt::from(6345);
+
+        for key in privatekeys.iter() {
+            publickeys.push(setup.vss.generate_public_key(key));
+        }
+
+        return dealer
+            .distribute(
+                &setup.secret,
+                &publickeys,
+                &sequential_positions(publickeys.len()),
+                threshold,
+                &polynomial,
+                &w,
+                &[],
+                None,
+            )
+            .unwrap();
+    }
+
+    fn get_share_box() -> ShareBox {
+        let distribution_share_box = dealer_distribute_share_box();
+        let private_key = SecretKey::new(BigInt::from(7901));
+        let w = BigInt::from(1337);
+        let mut participant = Participant::new();
+        let setup = Setup::new();
+
+        participant.vss = setup.vss.clone();
+        participant.privatekey = private_key.clone();
+        participant.publickey = setup.vss.generate_public_key(&private_key);
+
+        participant
+            .extract_share(&distribution_share_box, &private_key, &w, &[])
+            .unwrap()
+    }
+
+    #[test]
+    #[allow(clippy::useless_vec)]
+    fn test_distribution() {
+        let distribution = dealer_distribute_share_box();
+        let commitments = vec![
+            BigInt::from(92318234),
+            BigInt::from(76602245),
+            BigInt::from(63484157),
+        ];
+        let mut shares: BTreeMap<BigInt, BigInt> = BTreeMap::new();
+
+        shares.insert(distribution.publickeys[0].clone(), BigInt::from(42478042));
+        shares.insert(distribution.publickeys[1].clone(), BigInt::from(80117658));
+        shares.insert(distribution.publickeys[2].clone(), BigInt::from(86941725));
+
+        let challenge = BigInt::from(41963410);
+        let mut responses: BTreeMap<BigInt, BigInt> = BTreeMap::new();
+
+        responses.insert(distribution.publickeys[0].clone(), BigInt::from(151565889));
+        responses.insert(distribution.publickeys[1].clone(), BigInt::from(146145105));
+        responses.insert(distribution.publickeys[2].clone(), BigInt::from(71350321));
+
+        assert_eq!(distribution.challenge, challenge);
+
+        for i in 0..=2 {
+            assert_eq!(distribution.commitments[i], commitments[i]);
+            assert_eq!(
+                distribution.shares[&distribution.publickeys[i]],
+                shares[&distribution.publickeys[i]]
+            );
+            assert_eq!(
+                distribution.responses[&distribution.publickeys[i]],
+                responses[&distribution.publickeys[i]]
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn test_verify_distribution() {
+        let setup = Setup::new();
+        let distribution = dealer_distribute_share_box();
+
+        assert_eq!(setup.vss.verify_distribution_shares(&distribution), true);
+    }
+
+    #[test]
+    fn test_protocol_label() {
+        let mut setup = Setup::new();
+        let mut dealer = Participant::new();
+        let mut polynomial = Polynomial::new();
+
+        setup.vss.label = String::from("myapp-vss-v1");
+        dealer.vss = setup.vss.clone();
+        polynomial.init_coefficients(&[BigInt::from(164102006), BigInt::from(43489589)]);
+
+        let publickeys: Vec<BigInt> = [7901, 4801]
+            .iter()
+            .map(|key| {
+                setup
+                    .vss
+                    .generate_public_key(&SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let distribution = dealer
+            .distribute(
+                &setup.secret,
+                &publickeys,
+                &sequential_positions(publickeys.len()),
+                2,
+                &polynomial,
+                &BigInt::from(6345),
+                &[],
+                None,
+            )
+            .unwrap();
+
+        assert!(setup.vss.verify_distribution_shares(&distribution));
+
+        setup.vss.label = String::from("otherapp-vss-v1");
+        assert!(!setup.vss.verify_distribution_shares(&distribution));
+
+        setup.vss.label = String::new();
+        assert!(!setup.vss.verify_distribution_shares(&distribution));
+    }
+
+    #[test]
+    fn test_verify_light_distribution() {
+        let setup = Setup::new();
+        let distribution = dealer_distribute_share_box();
+        let mut participant = Participant::new();
+
+        participant.vss = setup.vss.clone();
+
+        let light = participant
+            .light_distribution(&distribution, &distribution.publickeys[1])
+            .unwrap();
+
+        assert_eq!(light.index, 1);
+        assert_eq!(
+            light.encrypted_share(),
+            distribution.shares.get(&distribution.publickeys[1])
+        );
+        assert!(participant.verify_light_distribution(&light, 3));
+
+        let mut tampered = light.clone();
+        tampered.response += BigInt::one();
+        assert!(!participant.verify_light_distribution(&tampered, 0));
+
+        let mut tampered = light.clone();
+        tampered.digests[2].a1 += BigInt::one();
+        assert!(!participant.verify_light_distribution(&tampered, 0));
+
+        assert_eq!(
+            participant
+                .light_distribution(&distribution, &BigInt::from(42))
+                .unwrap_err(),
+            VssError::MissingShare {
+                publickey: BigInt::from(42)
+            }
+        );
+    }
+
+    #[test]
+    fn test_share_delivery() {
+        let setup = Setup::new();
+        let committee: Vec<Participant> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                Participant::from_private_key(setup.vss.clone(), SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut dealer = Participant::with_params(setup.vss.clone());
+        let (distribution, deliveries) = dealer
+            .distribute_secret_with_deliveries(&setup.secret, &publickeys, 2)
+            .unwrap();
+
+        assert_eq!(deliveries.len(), 3);
+        assert_eq!(deliveries[2].distribution, distribution.fingerprint());
+
+        let shares: Vec<ShareBox> = committee
+            .iter()
+            .zip(&deliveries)
+            .map(|(p, delivery)| {
+                assert!(p.verify_share_delivery(delivery));
+
+                p.extract_delivered_share(delivery, &p.privatekey).unwrap()
+            })
+            .collect();
+
+        assert!(committee[0].verify_delivered_share(&shares[1], &deliveries[1]));
+        assert!(!committee[0].verify_delivered_share(&shares[1], &deliveries[2]));
+        assert!(committee[0].verify_share(&shares[1], &distribution, &publickeys[1]));
+        assert_eq!(
+            committee[0].reconstruct(&shares[1..], &distribution),
+            Ok(setup.secret.clone())
+        );
+
+        let mut tampered = deliveries[0].clone();
+
+        tampered.encrypted_share += BigInt::one();
+
+        assert!(!committee[0].verify_share_delivery(&tampered));
+
+        let mut tampered = deliveries[0].clone();
+
+        tampered.position = BigInt::from(2);
+
+        assert!(!committee[0].verify_share_delivery(&tampered));
+        assert_eq!(
+            committee[0].extract_delivered_share(&deliveries[1], &committee[0].privatekey),
+            Err(VssError::MissingShare {
+                publickey: publickeys[0].clone()
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_light_distribution_low_degree() {
+        // SCRAPE needs a safe prime
+        let vss = VSS::builder()
+            .q(BigInt::from(179426879))
+            .g(BigInt::from(89713439))
+            .G(BigInt::from(2))
+            .min_bits(16)
+            .length(64)
+            .build()
+            .unwrap();
+        let publickeys: Vec<BigInt> = [7901, 4801, 1453, 2179, 3001]
+            .iter()
+            .map(|key| vss.generate_public_key(&SecretKey::new(BigInt::from(*key))))
+            .collect();
+        let mut dealer = Participant::with_params(vss);
+        let distribution = dealer
+            .distribute_secret(&BigInt::from(1234567890), &publickeys, 3)
+            .unwrap();
+        let light = dealer
+            .light_distribution(&distribution, &publickeys[1])
+            .unwrap();
+
+        assert!(dealer.verify_light_distribution_low_degree(&light));
+
+        let mut tampered = light.clone();
+
+        tampered.digests[3].x = (&tampered.digests[3].x * &dealer.vss.g) % &dealer.vss.q;
+
+        assert!(!dealer.verify_light_distribution_low_degree(&tampered));
+    }
+
+    #[test]
+    fn test_verify_after_sign() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let mut participant = Participant::new();
+
+        assert!(!dealer.verify_after_sign);
+
+        dealer.vss = setup.vss.clone();
+        dealer.set_verify_after_sign(true);
+        participant.vss = setup.vss.clone();
+        participant.privatekey = SecretKey::new(BigInt::from(7901));
+        participant.publickey = setup.vss.generate_public_key(&participant.privatekey);
+        participant.set_verify_after_sign(true);
+
+        let publickeys = [
+            participant.publickey.clone(),
+            setup
+                .vss
+                .generate_public_key(&SecretKey::new(BigInt::from(4801))),
+        ];
+        let distribution = dealer
+            .distribute_secret(&setup.secret, &publickeys, 2)
+            .unwrap();
+        let sharebox = participant
+            .extract_secret_share(&distribution, &participant.privatekey)
+            .unwrap();
+
+        assert!(setup
+            .vss
+            .verify_share(&sharebox, &distribution, &participant.publickey));
+    }
+
+    #[test]
+    fn test_compact_distribution() {
+        let setup = Setup::new();
+        let distribution = dealer_distribute_share_box();
+        let mut participant = Participant::new();
+
+        participant.vss = setup.vss.clone();
+
+        let compact = participant.compact_distribution(&distribution);
+        let opening = participant.open_commitment(&distribution, 0).unwrap();
+
+        assert_eq!(compact.threshold, 3);
+        assert_eq!(opening.commitment, distribution.commitments[0]);
+        assert!(participant.verify_commitment_opening(&compact, &opening));
+
+        let expanded = participant
+            .expand_distribution(&compact, &distribution.commitments)
+            .unwrap();
+
+        assert!(participant.verify_distribution_shares(&expanded));
+        assert_eq!(
+            participant
+                .expand_distribution(&compact, &distribution.commitments[..2])
+                .unwrap_err(),
+            VssError::InvalidProof
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let committee: Vec<Participant> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                let mut participant = Participant::new();
+
+                participant.vss = setup.vss.clone();
+                participant.privatekey = SecretKey::new(BigInt::from(*key));
+                participant.publickey = setup.vss.generate_public_key(&participant.privatekey);
+
+                participant
+            })
+            .collect();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut record = SecretRecord::new();
+
+        dealer.vss = setup.vss.clone();
+        record.insert(String::from("db_password"), BigInt::from(1234567890));
+        record.insert(String::from("signing_key"), BigInt::from(987654321));
+
+        let distribution = dealer.distribute_record(&record, &publickeys, 2).unwrap();
+
+        assert_eq!(distribution.labels(), vec!["db_password", "signing_key"]);
+        assert!(committee[0].verify_record_distribution(&distribution));
+
+        let shares: Vec<RecordShares> = committee[..2]
+            .iter()
+            .map(|p| {
+                p.extract_record_shares(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            committee[2]
+                .reconstruct_field("signing_key", &shares, &distribution)
+                .unwrap(),
+            BigInt::from(987654321)
+        );
+        assert_eq!(
+            committee[2]
+                .reconstruct_record(&shares, &distribution)
+                .unwrap(),
+            record
+        );
+        assert_eq!(
+            committee[2]
+                .reconstruct_field("api_token", &shares, &distribution)
+                .unwrap_err(),
+            VssError::UnknownField {
+                label: String::from("api_token")
+            }
+        );
+    }
+
+    #[test]
+    fn test_partial_reveal() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let committee: Vec<Participant> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                let mut participant = Participant::new();
+
+                participant.vss = setup.vss.clone();
+                participant.privatekey = SecretKey::new(BigInt::from(*key));
+                participant.publickey = setup.vss.generate_public_key(&participant.privatekey);
+
+                participant
+            })
+            .collect();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut record = SecretRecord::new();
+        let mut thresholds = BTreeMap::new();
+
+        dealer.vss = setup.vss.clone();
+        record.insert(String::from("db_password"), BigInt::from(1234567890));
+        record.insert(String::from("signing_key"), BigInt::from(987654321));
+        thresholds.insert(String::from("db_password"), 1);
+        thresholds.insert(String::from("signing_key"), 3);
+
+        let distribution = dealer
+            .distribute_record_with_thresholds(&record, &publickeys, &thresholds)
+            .unwrap();
+
+        assert_eq!(distribution.threshold("db_password").unwrap(), 1);
+        assert_eq!(distribution.threshold("signing_key").unwrap(), 3);
+
+        // one shareholder releases only the password, which is enough on its own
+        let shares = [committee[0]
+            .extract_field_shares(&distribution, &["db_password"], &committee[0].privatekey)
+            .unwrap()];
+
+        assert_eq!(shares[0].fields.len(), 1);
+        assert_eq!(
+            committee[1]
+                .reconstruct_field("db_password", &shares, &distribution)
+                .unwrap(),
+            BigInt::from(1234567890)
+        );
+        assert_eq!(
+            committee[1]
+                .reconstruct_field("signing_key", &shares, &distribution)
+                .unwrap_err(),
+            VssError::ThresholdNotMet {
+                required: 3,
+                provided: 0
+            }
+        );
+
+        let shares: Vec<RecordShares> = committee
+            .iter()
+            .map(|p| {
+                p.extract_field_shares(&distribution, &["signing_key"], &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            committee[0]
+                .reconstruct_field("signing_key", &shares, &distribution)
+                .unwrap(),
+            BigInt::from(987654321)
+        );
+
+        thresholds.remove("signing_key");
+
+        assert_eq!(
+            dealer
+                .distribute_record_with_thresholds(&record, &publickeys, &thresholds)
+                .unwrap_err(),
+            VssError::UnknownField {
+                label: String::from("signing_key")
+            }
+        );
+    }
+
+    #[test]
+    fn test_distribute_with_rng() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let publickeys: Vec<BigInt> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                setup
+                    .vss
+                    .generate_public_key(&SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
 
-            vss.q = q;
-            vss.g = g;
-            vss.G = G;
-            vss.length = length as u32;
+        dealer.vss = setup.vss.clone();
 
-            return Setup {
-                vss,
-                privatekey: BigInt::from(105929),
-                secret: BigInt::from(1234567890),
-            };
-        }
+        let mut distribute = |seed| {
+            dealer
+                .distribute_secret_with_rng(
+                    &BigInt::from(1234567890),
+                    &publickeys,
+                    2,
+                    &mut ChaChaRng::seed_from_u64(seed),
+                )
+                .unwrap()
+        };
+        let distribution = distribute(1);
+
+        assert_eq!(distribution.commitments, distribute(1).commitments);
+        assert_eq!(distribution.responses, distribute(1).responses);
+        assert_ne!(distribution.commitments, distribute(2).commitments);
+
+        let privatekey = setup
+            .vss
+            .generate_private_key_with_rng(&mut ChaChaRng::seed_from_u64(1));
+
+        assert_eq!(
+            privatekey.expose(),
+            setup
+                .vss
+                .generate_private_key_with_rng(&mut ChaChaRng::seed_from_u64(1))
+                .expose()
+        );
     }
 
-    fn dealer_distribute_share_box() -> DistributionShareBox {
+    #[test]
+    fn test_context() {
         let setup = Setup::new();
         let mut dealer = Participant::new();
+        let mut shareholder = Participant::new();
 
         dealer.vss = setup.vss.clone();
-        dealer.privatekey = setup.privatekey.clone();
-        dealer.publickey = setup.vss.generate_public_key(&setup.privatekey);
+        shareholder.vss = setup.vss.clone();
+        shareholder.privatekey = SecretKey::new(BigInt::from(7901));
+        shareholder.publickey = setup.vss.generate_public_key(&shareholder.privatekey);
 
-        let mut polynomial = Polynomial::new();
+        let publickeys = std::slice::from_ref(&shareholder.publickey);
+        let distribution = dealer
+            .distribute_secret_with_context(&setup.secret, publickeys, 1, b"session-1")
+            .unwrap();
 
-        polynomial.init_coefficients(&vec![
-            BigInt::from(164102006),
-            BigInt::from(43489589),
-            BigInt::from(98100795),
-        ]);
+        assert!(shareholder.verify_distribution_shares_with_context(&distribution, b"session-1"));
+        assert!(!shareholder.verify_distribution_shares_with_context(&distribution, b"session-2"));
+        assert!(!shareholder.verify_distribution_shares(&distribution));
 
-        let threshold = 3;
-        let privatekeys = [BigInt::from(7901), BigInt::from(4801), BigInt::from(1453)];
-        let mut publickeys = vec![];
-        let w = BigInt::from(6345);
+        let sharebox = shareholder
+            .extract_secret_share_with_context(&distribution, &shareholder.privatekey, b"session-1")
+            .unwrap();
+        let publickey = &shareholder.publickey;
+
+        assert!(shareholder.verify_share_with_context(
+            &sharebox,
+            &distribution,
+            publickey,
+            b"session-1"
+        ));
+        assert!(!shareholder.verify_share_with_context(
+            &sharebox,
+            &distribution,
+            publickey,
+            b"session-2"
+        ));
+        assert!(!shareholder.verify_share(&sharebox, &distribution, publickey));
+
+        // the same proofs under another generator g
+        let mut other = shareholder.clone();
+
+        other.vss.g = BigInt::from(1301082);
+        assert!(!other.verify_distribution_shares_with_context(&distribution, b"session-1"));
+        assert_eq!(
+            shareholder.reconstruct(&[sharebox], &distribution).unwrap(),
+            setup.secret
+        );
+    }
 
-        for key in privatekeys.iter() {
-            publickeys.push(setup.vss.generate_public_key(key));
+    #[test]
+    fn test_long_secret() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let mut shareholder = Participant::new();
+
+        dealer.vss = setup.vss.clone();
+        shareholder.vss = setup.vss.clone();
+        shareholder.privatekey = SecretKey::new(BigInt::from(7901));
+        shareholder.publickey = setup.vss.generate_public_key(&shareholder.privatekey);
+
+        // far longer than both the 28-bit test group and a SHA-256 hash
+        let secret = crate::bytes_to_secret(&[0xc3; 100]);
+        let distribution = dealer
+            .distribute_secret(&secret, std::slice::from_ref(&shareholder.publickey), 1)
+            .unwrap();
+        let sharebox = shareholder
+            .extract_secret_share(&distribution, &shareholder.privatekey)
+            .unwrap();
+
+        assert_eq!(distribution.secret_len, Some(100));
+        assert_eq!(
+            shareholder
+                .reconstruct(std::slice::from_ref(&sharebox), &distribution)
+                .unwrap(),
+            secret
+        );
+
+        // the mask covers the whole secret, not only its low bits
+        assert_ne!(distribution.u.to_biguint().unwrap().bits(), 0);
+        assert_ne!(&distribution.u >> 256usize, &secret >> 256usize);
+    }
+
+    #[test]
+    fn test_exponent_blinding() {
+        let setup = Setup::new();
+        let mut committee: Vec<Participant> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                Participant::from_private_key(setup.vss.clone(), SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let mut dealer = Participant::with_params(setup.vss.clone());
+
+        dealer.set_exponent_blinding(true);
+
+        for participant in &mut committee {
+            let publickey = participant.publickey.clone();
+
+            participant.set_exponent_blinding(true);
+
+            assert_eq!(
+                participant.vss.generate_public_key(&participant.privatekey),
+                publickey
+            );
+        }
+
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let distribution = dealer
+            .distribute_secret(&setup.secret, &publickeys, 2)
+            .unwrap();
+
+        assert!(committee[0].verify_distribution_shares(&distribution));
+
+        let shares: Vec<ShareBox> = committee[1..]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        for share in &shares {
+            assert!(committee[0].verify_share(share, &distribution, &share.publickey));
         }
 
-        return dealer.distribute(&setup.secret, &publickeys, threshold, &polynomial, &w);
+        assert_eq!(
+            committee[0].reconstruct(&shares, &distribution),
+            Ok(setup.secret.clone())
+        );
     }
 
-    fn get_share_box() -> ShareBox {
-        let distribution_share_box = dealer_distribute_share_box();
-        let private_key = BigInt::from(7901);
-        let w = BigInt::from(1337);
-        let mut participant = Participant::new();
+    #[test]
+    fn test_distribute_with_positions() {
         let setup = Setup::new();
+        let committee: Vec<Participant> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                Participant::from_private_key(setup.vss.clone(), SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let recipients: Vec<(BigInt, i64)> = committee
+            .iter()
+            .zip([5, 3, 8])
+            .map(|(p, position)| (p.publickey.clone(), position))
+            .collect();
+        let mut dealer = Participant::with_params(setup.vss.clone());
+        let distribution = dealer
+            .distribute_secret_with_positions(&setup.secret, &recipients, 2)
+            .unwrap();
 
-        participant.vss = setup.vss.clone();
-        participant.privatekey = private_key.clone();
-        participant.publickey = setup.vss.generate_public_key(&private_key);
+        assert!(committee[0].verify_distribution_shares(&distribution));
+        assert_eq!(
+            distribution.positions[&committee[2].publickey],
+            BigInt::from(8)
+        );
 
-        participant
-            .extract_share(&distribution_share_box, &private_key, &w)
-            .unwrap()
+        let shares: Vec<ShareBox> = committee[1..]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            committee[0].reconstruct(&shares, &distribution),
+            Ok(setup.secret.clone())
+        );
+
+        // the same committee in another order keeps its positions
+        let reversed: Vec<(BigInt, i64)> = recipients.iter().rev().cloned().collect();
+
+        assert_eq!(
+            dealer
+                .distribute_secret_with_positions(&setup.secret, &reversed, 2)
+                .unwrap()
+                .positions,
+            distribution.positions
+        );
+
+        // positions beyond an i64, e.g. derived from key fingerprints
+        let wide: Vec<(BigInt, BigInt)> = committee
+            .iter()
+            .zip([5, 1, 4])
+            .map(|(p, offset)| (p.publickey.clone(), (BigInt::from(1) << 64) + offset))
+            .collect();
+        let distribution = dealer
+            .distribute_secret_with_positions(&setup.secret, &wide, 2)
+            .unwrap();
+
+        assert!(committee[0].verify_distribution_shares(&distribution));
+
+        let shares: Vec<ShareBox> = committee[1..]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            committee[0].reconstruct(&shares, &distribution),
+            Ok(setup.secret.clone())
+        );
+
+        let mut invalid = recipients.clone();
+
+        invalid[1].1 = 0;
+
+        assert_eq!(
+            dealer.distribute_secret_with_positions(&setup.secret, &invalid, 2),
+            Err(VssError::InvalidPosition {
+                position: BigInt::zero()
+            })
+        );
+
+        invalid[1].1 = 8;
+
+        assert_eq!(
+            dealer.distribute_secret_with_positions(&setup.secret, &invalid, 2),
+            Err(VssError::DuplicatePosition {
+                position: BigInt::from(8)
+            })
+        );
     }
 
     #[test]
-    fn test_distribution() {
-        let distribution = dealer_distribute_share_box();
-        let commitments = vec![
-            BigInt::from(92318234),
-            BigInt::from(76602245),
-            BigInt::from(63484157),
-        ];
-        let mut shares: BTreeMap<BigInt, BigInt> = BTreeMap::new();
+    fn test_digest() {
+        let setup = Setup::new();
+        let committee: Vec<Participant<Sha3_256>> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                let mut participant = Participant::new();
+
+                participant.vss = setup.vss.clone();
+                participant.privatekey = SecretKey::new(BigInt::from(*key));
+                participant.publickey = setup.vss.generate_public_key(&participant.privatekey);
+
+                participant.with_digest::<Sha3_256>()
+            })
+            .collect();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut dealer = Participant::new();
 
-        shares.insert(distribution.publickeys[0].clone(), BigInt::from(42478042));
-        shares.insert(distribution.publickeys[1].clone(), BigInt::from(80117658));
-        shares.insert(distribution.publickeys[2].clone(), BigInt::from(86941725));
+        dealer.vss = setup.vss.clone();
 
-        let challenge = BigInt::from(41963410);
-        let mut responses: BTreeMap<BigInt, BigInt> = BTreeMap::new();
+        let mut dealer = dealer.with_digest::<Sha3_256>();
+        let distribution = dealer
+            .distribute_secret(&BigInt::from(1234567890), &publickeys, 2)
+            .unwrap();
 
-        responses.insert(distribution.publickeys[0].clone(), BigInt::from(151565889));
-        responses.insert(distribution.publickeys[1].clone(), BigInt::from(146145105));
-        responses.insert(distribution.publickeys[2].clone(), BigInt::from(71350321));
+        assert!(committee[0].verify_distribution_shares(&distribution));
 
-        assert_eq!(distribution.challenge, challenge);
+        let shares: Vec<ShareBox> = committee[..2]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
 
-        for i in 0..=2 {
-            assert_eq!(distribution.commitments[i], commitments[i]);
-            assert_eq!(
-                distribution.shares[&distribution.publickeys[i]],
-                shares[&distribution.publickeys[i]]
-            );
+        assert!(committee[2].verify_share(&shares[0], &distribution, &publickeys[0]));
+        assert_eq!(
+            committee[2].reconstruct(&shares, &distribution).unwrap(),
+            BigInt::from(1234567890)
+        );
+
+        // the light, compact and record paths take the same challenge hash
+        let light = dealer
+            .light_distribution(&distribution, &publickeys[1])
+            .unwrap();
+
+        assert!(committee[1].verify_light_distribution(&light, 3));
+
+        let compact = dealer.compact_distribution(&distribution);
+        let opening = dealer.open_commitment(&distribution, 1).unwrap();
+
+        assert!(committee[2].verify_commitment_opening(&compact, &opening));
+        assert!(committee[2].verify_distribution_shares(
+            &committee[2]
+                .expand_distribution(&compact, &distribution.commitments)
+                .unwrap()
+        ));
+
+        let record: SecretRecord = [("pin".to_string(), BigInt::from(4321))].into();
+        let record_distribution = dealer.distribute_record(&record, &publickeys, 2).unwrap();
+
+        assert!(committee[2].verify_record_distribution(&record_distribution));
+
+        // a SHA-256 verifier recomputes different challenges
+        let verifier = committee[2].clone().with_digest::<Sha256>();
+
+        assert!(!verifier.verify_distribution_shares(&distribution));
+        assert!(!verifier.verify_share(&shares[0], &distribution, &publickeys[0]));
+        assert!(!verifier.verify_light_distribution(&light, 3));
+        assert!(!verifier.verify_record_distribution(&record_distribution));
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn test_blake3() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let mut shareholder = Participant::new();
+
+        dealer.vss = setup.vss.clone();
+        shareholder.vss = setup.vss.clone();
+        shareholder.privatekey = SecretKey::new(BigInt::from(7901));
+        shareholder.publickey = setup.vss.generate_public_key(&shareholder.privatekey);
+
+        let mut dealer = dealer.with_digest::<crate::Blake3>();
+        let shareholder = shareholder.with_digest::<crate::Blake3>();
+        let distribution = dealer
+            .distribute_secret(
+                &BigInt::from(1234567890),
+                std::slice::from_ref(&shareholder.publickey),
+                1,
+            )
+            .unwrap();
+        let sharebox = shareholder
+            .extract_secret_share(&distribution, &shareholder.privatekey)
+            .unwrap();
+
+        assert!(shareholder.verify_distribution_shares(&distribution));
+        assert_eq!(
+            shareholder.reconstruct(&[sharebox], &distribution).unwrap(),
+            BigInt::from(1234567890)
+        );
+    }
+
+    #[cfg(feature = "merlin")]
+    #[test]
+    fn test_merlin() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let mut shareholder = Participant::new();
+
+        dealer.vss = setup.vss.clone();
+        shareholder.vss = setup.vss.clone();
+        shareholder.privatekey = SecretKey::new(BigInt::from(7901));
+        shareholder.publickey = setup.vss.generate_public_key(&shareholder.privatekey);
+
+        let mut dealer = dealer.with_digest::<crate::MerlinTranscript>();
+        let verifier = shareholder.clone();
+        let shareholder = shareholder.with_digest::<crate::MerlinTranscript>();
+        let distribution = dealer
+            .distribute_secret(
+                &BigInt::from(1234567890),
+                std::slice::from_ref(&shareholder.publickey),
+                1,
+            )
+            .unwrap();
+        let sharebox = shareholder
+            .extract_secret_share(&distribution, &shareholder.privatekey)
+            .unwrap();
+
+        assert!(shareholder.verify_distribution_shares(&distribution));
+        assert!(shareholder.verify_share(&sharebox, &distribution, &shareholder.publickey));
+        assert!(!verifier.verify_distribution_shares(&distribution));
+        assert!(!verifier.verify_share(&sharebox, &distribution, &shareholder.publickey));
+
+        // labeled transcripts absorb the recipient keys the light digests carry
+        let light = dealer
+            .light_distribution(&distribution, &shareholder.publickey)
+            .unwrap();
+
+        assert!(shareholder.verify_light_distribution(&light, 1));
+        assert!(!verifier.verify_light_distribution(&light, 1));
+        assert_eq!(
+            shareholder.reconstruct(&[sharebox], &distribution).unwrap(),
+            BigInt::from(1234567890)
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let setup = Setup::new();
+        let mut dealer = Participant::new();
+        let mut participant = Participant::new();
+
+        dealer.vss = setup.vss.clone();
+        participant.vss = setup.vss.clone();
+
+        let publickeys = [
+            setup
+                .vss
+                .generate_public_key(&SecretKey::new(BigInt::from(7901))),
+            setup
+                .vss
+                .generate_public_key(&SecretKey::new(BigInt::from(4801))),
+        ];
+
+        assert_eq!(
+            dealer.distribute_secret(&setup.secret, &[], 1).unwrap_err(),
+            VssError::NoRecipients
+        );
+        assert_eq!(
+            dealer
+                .distribute_secret(&-setup.secret.clone(), &publickeys, 2)
+                .unwrap_err(),
+            VssError::NegativeSecret
+        );
+        assert_eq!(
+            dealer
+                .deal(
+                    &BigInt::from(0x010000),
+                    &publickeys,
+                    &sequential_positions(publickeys.len()),
+                    2,
+                    &[],
+                    Some(2),
+                    &mut rand::thread_rng()
+                )
+                .unwrap_err(),
+            VssError::SecretTooLarge { length: 2 }
+        );
+
+        for threshold in [0, 3] {
             assert_eq!(
-                distribution.responses[&distribution.publickeys[i]],
-                responses[&distribution.publickeys[i]]
+                dealer
+                    .distribute_secret(&setup.secret, &publickeys, threshold)
+                    .unwrap_err(),
+                VssError::InvalidThreshold {
+                    threshold,
+                    participants: 2
+                }
             );
         }
+
+        assert_eq!(
+            dealer
+                .distribute_secret(&setup.secret, &[publickeys[0].clone(), BigInt::one()], 2)
+                .unwrap_err(),
+            VssError::InvalidPublicKey {
+                publickey: BigInt::one()
+            }
+        );
+        assert_eq!(
+            dealer
+                .distribute_secret(
+                    &setup.secret,
+                    &[publickeys[0].clone(), publickeys[0].clone()],
+                    2
+                )
+                .unwrap_err(),
+            VssError::DuplicatePublicKey {
+                publickey: publickeys[0].clone()
+            }
+        );
+
+        let distribution = dealer_distribute_share_box();
+
+        assert_eq!(
+            participant
+                .extract_secret_share(&distribution, &SecretKey::new(BigInt::from(1453 * 2)))
+                .unwrap_err(),
+            VssError::MissingShare {
+                publickey: setup
+                    .vss
+                    .generate_public_key(&SecretKey::new(BigInt::from(1453 * 2)))
+            }
+        );
+
+        let sharebox = get_share_box();
+
+        assert_eq!(
+            participant
+                .reconstruct(&[sharebox.clone(), sharebox.clone()], &distribution)
+                .unwrap_err(),
+            VssError::ThresholdNotMet {
+                required: 3,
+                provided: 2
+            }
+        );
+
+        let mut stranger = sharebox.clone();
+
+        stranger.publickey = BigInt::from(42);
+
+        assert_eq!(
+            participant
+                .reconstruct(
+                    &[sharebox.clone(), sharebox.clone(), stranger],
+                    &distribution
+                )
+                .unwrap_err(),
+            VssError::UnknownShareholder {
+                publickey: BigInt::from(42)
+            }
+        );
+        assert_eq!(
+            participant
+                .reconstruct(
+                    &[sharebox.clone(), sharebox.clone(), sharebox.clone()],
+                    &distribution
+                )
+                .unwrap_err(),
+            VssError::ThresholdNotMet {
+                required: 3,
+                provided: 1
+            }
+        );
+
+        let mut zero = sharebox.clone();
+
+        zero.share = BigInt::zero();
+
+        assert_eq!(
+            participant
+                .reconstruct(&[sharebox.clone(), sharebox, zero], &distribution)
+                .unwrap_err(),
+            VssError::InvalidShare {
+                publickey: setup
+                    .vss
+                    .generate_public_key(&SecretKey::new(BigInt::from(7901)))
+            }
+        );
     }
 
     #[test]
-    fn test_verify_distribution() {
+    fn test_check_own_share() {
         let setup = Setup::new();
         let distribution = dealer_distribute_share_box();
+        let participant =
+            Participant::from_private_key(setup.vss.clone(), SecretKey::new(BigInt::from(7901)));
+        let sharebox = participant
+            .extract_secret_share(&distribution, &participant.privatekey)
+            .unwrap();
 
-        assert_eq!(setup.vss.verify_distribution_shares(&distribution), true);
+        assert_eq!(
+            participant.check_own_share(&sharebox, &distribution),
+            Ok(())
+        );
+
+        let mut tampered = sharebox.clone();
+
+        tampered.share += 1;
+
+        assert_eq!(
+            participant.check_own_share(&tampered, &distribution),
+            Err(VssError::InvalidProof)
+        );
+
+        let other =
+            Participant::from_private_key(setup.vss.clone(), SecretKey::new(BigInt::from(4801)));
+
+        assert_eq!(
+            other.check_own_share(&sharebox, &distribution),
+            Err(VssError::ForeignShare {
+                publickey: participant.publickey.clone()
+            })
+        );
+
+        let mut missing = distribution.clone();
+
+        missing.shares.remove(&participant.publickey);
+
+        assert_eq!(
+            participant.check_own_share(&sharebox, &missing),
+            Err(VssError::MissingShare {
+                publickey: participant.publickey.clone()
+            })
+        );
     }
 
     #[test]
@@ -460,8 +2397,9 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::bool_assert_comparison, clippy::single_match)]
     fn test_verify_share() {
-        let private_key = BigInt::from(7901);
+        let private_key = SecretKey::new(BigInt::from(7901));
         let distribution_share_box = dealer_distribute_share_box();
         let sharebox = get_share_box();
 
@@ -511,6 +2449,7 @@ mod tests {
 
     // threshold secret reconstruct where 1 out of 4 participants is not available
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_secret_reconstruction_with_sub_group() {
         let share_box1 = get_share_box();
         let mut share_box2 = ShareBox::new();
@@ -534,9 +2473,9 @@ mod tests {
 
         let mut positions = BTreeMap::new();
 
-        positions.insert(share_box1.clone().publickey, 1_i64);
-        positions.insert(share_box2.clone().publickey, 2_i64);
-        positions.insert(share_box4.clone().publickey, 4_i64);
+        positions.insert(share_box1.clone().publickey, BigInt::from(1));
+        positions.insert(share_box2.clone().publickey, BigInt::from(2));
+        positions.insert(share_box4.clone().publickey, BigInt::from(4));
 
         let mut distribution_share_box = DistributionShareBox::new();
 
@@ -0,0 +1,215 @@
+use num_bigint::{BigInt, Sign};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
+
+use crate::{
+    arith,
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    polynomial::Polynomial,
+    transcript::Transcript,
+    vss::VSS,
+    wire::Writer,
+};
+
+// Pedersen VSS
+
+// Feldman's commitments g^a_j reveal g^secret, so whoever breaks the discrete logarithm, now or
+// later, learns the secret from the public commitments alone. Pedersen's E_j = g^a_j h^b_j, for a
+// second polynomial r with coefficients b_j, are uniform whatever the secret: the polynomial is
+// hidden information-theoretically, and binding only rests on nobody knowing log_g h. h is
+// hashed from the group parameters, so that nobody does. Holder i receives p(i) and r(i) over a
+// private channel, as with Feldman, and checks g^p(i) h^r(i) against the commitments evaluated at
+// its position. Encrypting the shares to public keys would give that hiding away again, so there
+// is no publicly verifiable variant.
+
+/// A Pedersen share: p(position) and its blinding r(position), in the clear.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PedersenShare {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub position: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub value: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub blinding: BigInt,
+}
+
+/// The hiding commitments E_j = g^a_j h^b_j of a Pedersen dealing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PedersenDistribution {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub commitments: Vec<BigInt>,
+}
+
+impl PedersenDistribution {
+    pub fn threshold(&self) -> usize {
+        self.commitments.len()
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// The second generator h of Pedersen commitments: a square modulo q hashed from q, g and G,
+    /// whose logarithm to any base nobody knows.
+    pub fn pedersen_generator(&self) -> BigInt {
+        let width = self.q.bits().div_ceil(8);
+
+        (0u64..)
+            .map(|counter| {
+                let mut writer = Writer::new();
+                let mut xof = Shake256::default();
+                // 16 bytes over the width of q make the reduction close to uniform
+                let mut bytes = vec![0; width + 16];
+
+                writer.raw(b"pedersen h");
+                writer.bigint(&self.q);
+                writer.bigint(&self.g);
+                writer.bigint(&self.G);
+                writer.u64(counter);
+                xof.update(writer.into_bytes());
+                xof.finalize_xof().read(&mut bytes);
+
+                let root = BigInt::from_bytes_be(Sign::Plus, &bytes).mod_floor(&self.q);
+
+                arith::modpow(&root, &BigInt::from(2), &self.q)
+            })
+            .find(|h| h > &BigInt::one())
+            .unwrap()
+    }
+
+    /// Checks a Pedersen share against the commitments: g^value h^blinding = prod_j E_j^(position^j).
+    pub fn verify_pedersen_share(
+        &self,
+        distribution: &PedersenDistribution,
+        share: &PedersenShare,
+    ) -> bool {
+        let order = &self.q - BigInt::one();
+        let opening = self.exp_g(&share.value.mod_floor(&order))
+            * arith::modpow(
+                &self.pedersen_generator(),
+                &share.blinding.mod_floor(&order),
+                &self.q,
+            )
+            % &self.q;
+
+        share.position > BigInt::zero()
+            && !distribution.commitments.is_empty()
+            && opening == self.evaluate_commitments(&distribution.commitments, &share.position)
+    }
+
+    /// Interpolates the secret from at least `threshold` Pedersen shares, which are taken as
+    /// they are: check them with `verify_pedersen_share` first.
+    pub fn reconstruct_pedersen(
+        &self,
+        distribution: &PedersenDistribution,
+        shares: &[PedersenShare],
+    ) -> Result<BigInt, VssError> {
+        self.interpolate_clear(
+            shares.iter().map(|share| (&share.position, &share.value)),
+            distribution.threshold(),
+        )
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Deals `secret`, below q - 1, to `n` holders at positions `1..=n` with `threshold`, under
+    /// hiding commitments, as clear shares to be sent over private channels. Share i goes to the
+    /// holder at position i + 1.
+    pub fn distribute_pedersen(
+        &self,
+        secret: &BigInt,
+        n: usize,
+        threshold: u32,
+    ) -> Result<(PedersenDistribution, Vec<PedersenShare>), VssError> {
+        self.distribute_pedersen_with_rng(secret, n, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `distribute_pedersen`, drawing both polynomials from `rng`.
+    pub fn distribute_pedersen_with_rng(
+        &self,
+        secret: &BigInt,
+        n: usize,
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<(PedersenDistribution, Vec<PedersenShare>), VssError> {
+        let order = &self.vss.q - BigInt::one();
+
+        self.vss.validate_clear_dealing(secret, n, threshold)?;
+
+        let mut polynomial = Polynomial::new();
+        let mut blinding = Polynomial::new();
+
+        polynomial.init_with_rng((threshold - 1) as i32, &order, rng);
+        polynomial.coefficients[0] = secret.clone();
+        blinding.init_with_rng((threshold - 1) as i32, &order, rng);
+
+        let h = self.vss.pedersen_generator();
+        let commitments = polynomial
+            .coefficients
+            .iter()
+            .zip(&blinding.coefficients)
+            .map(|(a, b)| self.vss.exp_g(a) * arith::modpow(&h, b, &self.vss.q) % &self.vss.q)
+            .collect();
+        let shares = sequential_positions(n)
+            .into_iter()
+            .map(|position| PedersenShare {
+                value: polynomial.get_value_mod(&position, &order),
+                blinding: blinding.get_value_mod(&position, &order),
+                position,
+            })
+            .collect();
+
+        Ok((PedersenDistribution { commitments }, shares))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    #[test]
+    fn test_pedersen() {
+        let ceremony = fixed_ceremony(3, 2, 89).unwrap();
+        let dealer = &ceremony.dealer;
+        let vss = &dealer.vss;
+        let secret = BigInt::from(27182);
+        let (distribution, shares) = dealer.distribute_pedersen(&secret, 5, 2).unwrap();
+
+        // unlike Feldman's, the first commitment is not g^secret
+        assert_ne!(distribution.commitments[0], vss.exp_g(&secret));
+        assert_ne!(vss.pedersen_generator(), vss.g);
+        assert!(shares
+            .iter()
+            .all(|share| vss.verify_pedersen_share(&distribution, share)));
+        assert_eq!(
+            vss.reconstruct_pedersen(&distribution, &shares[3..]),
+            Ok(secret.clone())
+        );
+
+        // the same secret twice is committed to differently
+        let (other, _) = dealer.distribute_pedersen(&secret, 5, 2).unwrap();
+
+        assert_ne!(other.commitments[0], distribution.commitments[0]);
+
+        let mut share = shares[0].clone();
+
+        share.blinding += 1;
+
+        assert!(!vss.verify_pedersen_share(&distribution, &share));
+        assert_eq!(
+            vss.reconstruct_pedersen(&distribution, &shares[..1]),
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use num_bigint::BigInt;
+
+use crate::{
+    error::VssError,
+    participant::Participant,
+    sharebox::{DistributionShareBox, ShareBox},
+};
+
+// Reconstruction planning
+
+// A coordinator needs `threshold` valid share boxes and would rather ask the recipients that are
+// online and answer quickly. The planner ranks recipients by the availability hints it was given,
+// requests the best subset, and hands out the next best recipient whenever a request times out or
+// returns a share box that fails to verify.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AvailabilityHint {
+    pub online: bool,
+    /// expected round trip, `None` if unknown
+    pub latency: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconstructionPlanner {
+    distribution: DistributionShareBox,
+    hints: BTreeMap<BigInt, AvailabilityHint>,
+    requested: BTreeSet<BigInt>,
+    failed: BTreeSet<BigInt>,
+    received: BTreeMap<BigInt, ShareBox>,
+}
+
+impl ReconstructionPlanner {
+    pub fn new(distribution: &DistributionShareBox) -> Self {
+        ReconstructionPlanner {
+            distribution: distribution.clone(),
+            hints: BTreeMap::new(),
+            requested: BTreeSet::new(),
+            failed: BTreeSet::new(),
+            received: BTreeMap::new(),
+        }
+    }
+
+    /// Recipients without a hint are assumed online with unknown latency.
+    pub fn set_hint(&mut self, publickey: &BigInt, hint: AvailabilityHint) {
+        self.hints.insert(publickey.clone(), hint);
+    }
+
+    // recipients not yet asked, best first: known latency ascending, then unknown latency.
+    // Recipients hinted offline are never proposed.
+    fn candidates(&self) -> Vec<BigInt> {
+        let mut candidates: Vec<(Option<Duration>, usize, &BigInt)> = self
+            .distribution
+            .publickeys
+            .iter()
+            .enumerate()
+            .filter(|(_, publickey)| {
+                !self.requested.contains(*publickey) && !self.failed.contains(*publickey)
+            })
+            .filter_map(|(i, publickey)| match self.hints.get(publickey) {
+                Some(hint) if !hint.online => None,
+                Some(hint) => Some((hint.latency, i, publickey)),
+                None => Some((None, i, publickey)),
+            })
+            .collect();
+
+        candidates.sort_by_key(|(latency, i, _)| (latency.is_none(), *latency, *i));
+        candidates
+            .into_iter()
+            .map(|(_, _, publickey)| publickey.clone())
+            .collect()
+    }
+
+    fn outstanding(&self) -> usize {
+        self.requested
+            .iter()
+            .filter(|publickey| !self.received.contains_key(*publickey))
+            .count()
+    }
+
+    /// Selects the recipients to request share boxes from, enough that the threshold is met if all
+    /// of them answer. Calling it again only tops up requests lost to failures.
+    pub fn plan(&mut self) -> Result<Vec<BigInt>, VssError> {
+        let required = self.distribution.threshold();
+        let missing = required.saturating_sub(self.received.len() + self.outstanding());
+        let candidates = self.candidates();
+
+        if candidates.len() < missing {
+            return Err(VssError::ThresholdNotMet {
+                required,
+                provided: self.received.len() + self.outstanding() + candidates.len(),
+            });
+        }
+
+        let selected: Vec<BigInt> = candidates.into_iter().take(missing).collect();
+
+        self.requested.extend(selected.iter().cloned());
+
+        Ok(selected)
+    }
+
+    /// Marks the request to `publickey` as failed and returns the alternate to ask instead, or
+    /// `None` if no recipient is left.
+    pub fn timeout(&mut self, publickey: &BigInt) -> Option<BigInt> {
+        self.requested.remove(publickey);
+        self.failed.insert(publickey.clone());
+
+        let alternate = self.candidates().into_iter().next()?;
+
+        self.requested.insert(alternate.clone());
+
+        Some(alternate)
+    }
+
+    /// Verifies a share box returned by a recipient and keeps it if valid. A share box that fails
+    /// to verify counts as a failed request.
+    pub fn accept(&mut self, verifier: &Participant, sharebox: ShareBox) -> Result<(), VssError> {
+        if !self
+            .distribution
+            .positions
+            .contains_key(&sharebox.publickey)
+        {
+            return Err(VssError::UnknownShareholder {
+                publickey: sharebox.publickey,
+            });
+        }
+
+        if !verifier.verify_share(&sharebox, &self.distribution, &sharebox.publickey) {
+            self.requested.remove(&sharebox.publickey);
+            self.failed.insert(sharebox.publickey);
+
+            return Err(VssError::InvalidProof);
+        }
+
+        self.requested.insert(sharebox.publickey.clone());
+        self.received.insert(sharebox.publickey.clone(), sharebox);
+
+        Ok(())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.received.len() >= self.distribution.threshold()
+    }
+
+    /// The valid share boxes collected so far.
+    pub fn share_boxes(&self) -> Vec<ShareBox> {
+        self.received.values().cloned().collect()
+    }
+
+    pub fn reconstruct(&self, participant: &Participant) -> Result<BigInt, VssError> {
+        participant.reconstruct(&self.share_boxes(), &self.distribution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, participant::Participant, testkit::test_participant};
+
+    use super::{AvailabilityHint, ReconstructionPlanner};
+
+    fn hint(online: bool, millis: u64) -> AvailabilityHint {
+        AvailabilityHint {
+            online,
+            latency: Some(Duration::from_millis(millis)),
+        }
+    }
+
+    #[test]
+    fn test_plan_and_retry() {
+        let committee: Vec<Participant> = [7901, 4801, 1453, 2179].map(test_participant).to_vec();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut dealer = test_participant(105929);
+        let distribution = dealer
+            .distribute_secret(&BigInt::from(1234567890), &publickeys, 2)
+            .unwrap();
+        let mut planner = ReconstructionPlanner::new(&distribution);
+
+        planner.set_hint(&publickeys[0], hint(false, 1));
+        planner.set_hint(&publickeys[1], hint(true, 300));
+        planner.set_hint(&publickeys[2], hint(true, 20));
+
+        // the offline recipient is skipped, the unhinted one comes after the known latencies
+        assert_eq!(
+            planner.plan().unwrap(),
+            vec![publickeys[2].clone(), publickeys[1].clone()]
+        );
+        assert!(planner.plan().unwrap().is_empty());
+        assert_eq!(planner.timeout(&publickeys[1]), Some(publickeys[3].clone()));
+
+        let mut bad = committee[2]
+            .extract_secret_share(&distribution, &committee[2].privatekey)
+            .unwrap();
+
+        bad.response += 1;
+
+        assert_eq!(
+            planner.accept(&committee[0], bad),
+            Err(VssError::InvalidProof)
+        );
+        assert_eq!(
+            planner.plan(),
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 1
+            })
+        );
+
+        for i in [2, 3] {
+            let sharebox = committee[i]
+                .extract_secret_share(&distribution, &committee[i].privatekey)
+                .unwrap();
+
+            planner.accept(&committee[0], sharebox).unwrap();
+        }
+
+        assert!(planner.is_complete());
+        assert_eq!(
+            planner.reconstruct(&committee[0]).unwrap(),
+            BigInt::from(1234567890)
+        );
+    }
+}
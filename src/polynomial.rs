@@ -1,11 +1,29 @@
-use std::ops::Mul;
-
 use num_bigint::{BigInt, RandBigInt, ToBigInt};
-use num_traits::pow::Pow;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
 
 // Based on Shamir's Secret Sharing (SSS) scheme.
 // p(X)= s + p1X + ⋯ + pfXf
 
+/// The factors of the `len` coefficients in the `order`-th derivative at `x`: j!/(j - order)!
+/// x^(j - order) for coefficient j, zero below `order`. With order 0 these are the powers of x.
+pub(crate) fn derivative_row(len: usize, x: &BigInt, order: u32) -> Vec<BigInt> {
+    let order = order as usize;
+
+    (0..len)
+        .map(|j| {
+            if j < order {
+                return BigInt::zero();
+            }
+
+            let falling = (j - order + 1..=j).fold(BigInt::one(), |acc, k| acc * BigInt::from(k));
+
+            falling * num_traits::pow(x.clone(), j - order)
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Polynomial {
     pub coefficients: Vec<BigInt>,
@@ -22,9 +40,14 @@ impl Polynomial {
         self.coefficients = coefficients.to_vec();
     }
 
+    #[cfg(any(test, feature = "testkit"))]
     pub fn init(&mut self, degree: i32, q: &BigInt) {
+        self.init_with_rng(degree, q, &mut rand::thread_rng());
+    }
+
+    /// Like `init`, drawing the coefficients from `rng`.
+    pub fn init_with_rng(&mut self, degree: i32, q: &BigInt, rng: &mut (impl CryptoRng + RngCore)) {
         let mut coefficients = vec![];
-        let mut rng = rand::thread_rng();
 
         for _ in 0..=degree {
             let coefficient = rng
@@ -38,21 +61,49 @@ impl Polynomial {
         self.init_coefficients(&coefficients);
     }
 
-    // get p(X) = value
+    // get p(X) = value, by Horner's rule
     pub fn get_value(&self, x: &BigInt) -> BigInt {
-        let mut result = self.coefficients[0].clone();
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |result, coefficient| {
+                result * x + coefficient
+            })
+    }
 
-        for i in 1..self.coefficients.len() {
-            result += self.coefficients[i].clone().mul(x.pow(i));
+    /// p(x) mod `modulus`, reduced at every step of Horner's rule so that the intermediate values
+    /// stay below modulus^2 whatever the degree.
+    pub fn get_value_mod(&self, x: &BigInt, modulus: &BigInt) -> BigInt {
+        let x = x.mod_floor(modulus);
+
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(BigInt::zero(), |result, coefficient| {
+                (result * &x + coefficient).mod_floor(modulus)
+            })
+    }
+
+    /// The `order`-th derivative of p at x, mod `modulus`.
+    pub fn get_derivative_mod(&self, order: u32, x: &BigInt, modulus: &BigInt) -> BigInt {
+        if order == 0 {
+            return self.get_value_mod(x, modulus);
         }
 
-        result
+        derivative_row(self.coefficients.len(), x, order)
+            .iter()
+            .zip(&self.coefficients)
+            .fold(BigInt::zero(), |result, (factor, coefficient)| {
+                (result + factor * coefficient).mod_floor(modulus)
+            })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use num_bigint::{BigInt, ToBigInt};
+    use num_integer::Integer;
+    use num_traits::Zero;
 
     use super::Polynomial;
 
@@ -67,6 +118,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::useless_vec)]
     fn test_get_value() {
         let mut polynomial = Polynomial::new();
 
@@ -102,6 +154,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_derivative() {
+        let mut polynomial = Polynomial::new();
+        let modulus = BigInt::from(1000);
+
+        polynomial.init_coefficients(&[
+            3.to_bigint().unwrap(),
+            2.to_bigint().unwrap(),
+            2.to_bigint().unwrap(),
+            4.to_bigint().unwrap(),
+        ]);
+
+        // p'(X) = 2 + 4X + 12X^2, p''(X) = 4 + 24X, p'''(X) = 24
+        let x = BigInt::from(2);
+
+        assert_eq!(
+            polynomial.get_derivative_mod(0, &x, &modulus),
+            BigInt::from(47)
+        );
+        assert_eq!(
+            polynomial.get_derivative_mod(1, &x, &modulus),
+            BigInt::from(58)
+        );
+        assert_eq!(
+            polynomial.get_derivative_mod(2, &x, &modulus),
+            BigInt::from(52)
+        );
+        assert_eq!(
+            polynomial.get_derivative_mod(3, &x, &modulus),
+            BigInt::from(24)
+        );
+        assert_eq!(
+            polynomial.get_derivative_mod(4, &x, &modulus),
+            BigInt::zero()
+        );
+    }
+
     #[test]
     fn test_get_value2() {
         let q = BigInt::from(15486967);
@@ -118,6 +207,11 @@ mod tests {
 
         polynomial.init_coefficients(&coefficients);
 
-        assert_eq!(polynomial.get_value(&x) % q, BigInt::from(4115179));
+        assert_eq!(polynomial.get_value(&x) % &q, BigInt::from(4115179));
+        assert_eq!(polynomial.get_value_mod(&x, &q), BigInt::from(4115179));
+        assert_eq!(
+            polynomial.get_value_mod(&-x.clone(), &q),
+            polynomial.get_value(&-x).mod_floor(&q)
+        );
     }
 }
@@ -0,0 +1,162 @@
+use std::{
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use num_bigint::{BigInt, Sign};
+use num_traits::One;
+
+use crate::arith::{self, Arithmetic, Backend};
+
+// Fixed-base precomputation
+
+// The generators g and G are raised to a fresh exponent in every key, commitment and proof. For a
+// fixed base, base^(d * 16^k) can be tabled for every 4-bit digit d and window k once, after which
+// an exponentiation is one multiplication per window and no squarings. A table for a 2048-bit
+// modulus holds 512 * 15 elements, about 2 MB.
+
+const WINDOW: usize = 4;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct PrecomputedBase {
+    base: BigInt,
+    modulus: BigInt,
+    // windows[k][d - 1] = base^(d * 16^k)
+    windows: Vec<Vec<BigInt>>,
+}
+
+impl fmt::Debug for PrecomputedBase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrecomputedBase")
+            .field("base", &self.base)
+            .field("modulus", &self.modulus)
+            .field("windows", &self.windows.len())
+            .finish()
+    }
+}
+
+impl PrecomputedBase {
+    /// Tables `base` for exponents below 2^bits(modulus), which covers every exponent reduced
+    /// modulo the group order.
+    pub fn new(base: &BigInt, modulus: &BigInt) -> Self {
+        let mut windows = Vec::new();
+        let mut power = base % modulus;
+
+        for _ in 0..modulus.bits().div_ceil(WINDOW) {
+            let mut window = vec![power.clone()];
+
+            for d in 1..(1 << WINDOW) - 1 {
+                window.push((&window[d - 1] * &power) % modulus);
+            }
+
+            // base^(16^(k + 1)) = base^(15 * 16^k) * base^(16^k)
+            power = (&window[(1 << WINDOW) - 2] * &power) % modulus;
+            windows.push(window);
+        }
+
+        PrecomputedBase {
+            base: base.clone(),
+            modulus: modulus.clone(),
+            windows,
+        }
+    }
+
+    pub fn base(&self) -> &BigInt {
+        &self.base
+    }
+
+    pub fn modulus(&self) -> &BigInt {
+        &self.modulus
+    }
+
+    /// base^exponent mod modulus. Exponents beyond the table fall back to `modpow`.
+    pub fn exp(&self, exponent: &BigInt) -> BigInt {
+        let (sign, bytes) = exponent.to_bytes_le();
+
+        if sign == Sign::Minus {
+            return arith::modpow(&self.base, exponent, &self.modulus);
+        }
+
+        let mut result = BigInt::one() % &self.modulus;
+        let digits = bytes.iter().flat_map(|byte| [byte & 0x0f, byte >> WINDOW]);
+
+        for (k, digit) in digits.enumerate() {
+            if digit == 0 {
+                continue;
+            }
+
+            match self.windows.get(k) {
+                Some(window) => result = (result * &window[digit as usize - 1]) % &self.modulus,
+                None => return arith::modpow(&self.base, exponent, &self.modulus),
+            }
+        }
+
+        result
+    }
+}
+
+// The table of one generator inside `VSS`. The generators are public fields, so the table is
+// built on first use and rebuilt whenever it no longer matches them. Clones share the table.
+#[derive(Default)]
+pub(crate) struct BaseCache(RwLock<Option<Arc<PrecomputedBase>>>);
+
+impl Clone for BaseCache {
+    fn clone(&self) -> Self {
+        BaseCache(RwLock::new(self.0.read().unwrap().clone()))
+    }
+}
+
+impl BaseCache {
+    pub(crate) fn exp(&self, base: &BigInt, modulus: &BigInt, exponent: &BigInt) -> BigInt {
+        if !Backend::TABLES {
+            return arith::modpow(base, exponent, modulus);
+        }
+
+        if let Some(table) = self.0.read().unwrap().as_ref() {
+            if table.base() == base && table.modulus() == modulus {
+                return table.exp(exponent);
+            }
+        }
+
+        let table = Arc::new(PrecomputedBase::new(base, modulus));
+        let result = table.exp(exponent);
+
+        *self.0.write().unwrap() = Some(table);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use super::{BaseCache, PrecomputedBase};
+
+    #[test]
+    fn test_precomputed_base() {
+        let q = BigInt::from(179426549);
+        let table = PrecomputedBase::new(&BigInt::from(1301081), &q);
+
+        // the last two are beyond the table
+        for exponent in [0, 1, 15, 16, 255, 65537, 179426547, 1 << 40, i64::MAX] {
+            let exponent = BigInt::from(exponent);
+
+            assert_eq!(
+                table.exp(&exponent),
+                BigInt::from(1301081).modpow(&exponent, &q)
+            );
+        }
+
+        let cache = BaseCache::default();
+
+        assert_eq!(
+            cache.exp(&BigInt::from(2), &q, &BigInt::from(1000)),
+            BigInt::from(2).modpow(&BigInt::from(1000), &q)
+        );
+        assert_eq!(
+            cache.exp(&BigInt::from(3), &q, &BigInt::from(1000)),
+            BigInt::from(3).modpow(&BigInt::from(1000), &q)
+        );
+    }
+}
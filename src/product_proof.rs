@@ -0,0 +1,109 @@
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{arith, transcript::Transcript, vss::VSS};
+
+// Key product proofs
+
+// Resharing and repair both have a shareholder with private key x and a fresh random value r
+// publish E = A^x and a value T = C^x * F^(r x) derived from its share, and show that they are
+// formed that way without revealing x or r. With z = r x the statement is linear: a Schnorr proof
+// of knowledge of (x, z) with y = G^x, E = A^x, E = B^z and T = C^x * F^z, the last relation tying
+// T to the shareholder's encrypted share.
+
+/// The public values of a key product proof, for the relations above.
+pub(crate) struct Statement<'a> {
+    pub publickey: &'a BigInt,
+    pub a: &'a BigInt,
+    pub b: &'a BigInt,
+    pub c: &'a BigInt,
+    pub f: &'a BigInt,
+    pub target: &'a BigInt,
+    pub blinded_key: &'a BigInt,
+}
+
+/// A key product proof: the challenge and the responses for x and z.
+pub(crate) struct Proof {
+    pub challenge: BigInt,
+    pub response_key: BigInt,
+    pub response_product: BigInt,
+}
+
+fn challenge<D: Transcript>(
+    vss: &VSS<D>,
+    context: &[u8],
+    statement: &Statement,
+    nonces: &[BigInt; 4],
+) -> BigInt {
+    let mut hasher = vss.context_hasher(context);
+
+    vss.update_hash(&mut hasher, b"publickey", statement.publickey);
+    vss.update_hash(&mut hasher, b"a", statement.a);
+    vss.update_hash(&mut hasher, b"b", statement.b);
+    vss.update_hash(&mut hasher, b"c", statement.c);
+    vss.update_hash(&mut hasher, b"f", statement.f);
+    vss.update_hash(&mut hasher, b"target", statement.target);
+    vss.update_hash(&mut hasher, b"blinded_key", statement.blinded_key);
+
+    for nonce in nonces {
+        vss.update_hash(&mut hasher, b"nonce", nonce);
+    }
+
+    BigUint::from_bytes_be(&hasher.challenge())
+        .mod_floor(&(vss.q.to_biguint().unwrap() - BigUint::one()))
+        .to_bigint()
+        .unwrap()
+}
+
+/// Proves `statement` for the private key `x` and the product `z`, both in `0..q - 1`.
+pub(crate) fn prove<D: Transcript>(
+    vss: &VSS<D>,
+    context: &[u8],
+    statement: &Statement,
+    x: &BigInt,
+    z: &BigInt,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> Proof {
+    let order = &vss.q - BigInt::one();
+    let power =
+        |base: &BigInt, exponent: &BigInt| arith::modpow(base, &vss.blind(exponent), &vss.q);
+    let k1 = rng.gen_bigint_range(&BigInt::zero(), &order);
+    let k2 = rng.gen_bigint_range(&BigInt::zero(), &order);
+    let nonces = [
+        vss.exp_G(&vss.blind(&k1)),
+        power(statement.a, &k1),
+        power(statement.b, &k2),
+        (power(statement.c, &k1) * power(statement.f, &k2)) % &vss.q,
+    ];
+    let challenge = challenge(vss, context, statement, &nonces);
+
+    Proof {
+        response_key: (k1 - &challenge * x).mod_floor(&order),
+        response_product: (k2 - &challenge * z).mod_floor(&order),
+        challenge,
+    }
+}
+
+/// Checks `proof` against `statement`.
+pub(crate) fn verify<D: Transcript>(
+    vss: &VSS<D>,
+    context: &[u8],
+    statement: &Statement,
+    proof: &Proof,
+) -> bool {
+    let power = |base: &BigInt, exponent: &BigInt| arith::modpow(base, exponent, &vss.q);
+    let c = &proof.challenge;
+    let s1 = &proof.response_key;
+    let s2 = &proof.response_product;
+    let nonces = [
+        (power(&vss.G, s1) * power(statement.publickey, c)) % &vss.q,
+        (power(statement.a, s1) * power(statement.blinded_key, c)) % &vss.q,
+        (power(statement.b, s2) * power(statement.blinded_key, c)) % &vss.q,
+        (power(statement.c, s1) * power(statement.f, s2) % &vss.q * power(statement.target, c))
+            % &vss.q,
+    ];
+
+    challenge(vss, context, statement, &nonces) == *c
+}
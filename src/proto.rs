@@ -0,0 +1,362 @@
+use std::collections::BTreeMap;
+
+use num_bigint::{BigInt, BigUint, ToBigInt};
+use num_traits::ToPrimitive;
+
+use crate::{
+    freshness::Freshness,
+    group,
+    session::SessionId,
+    sharebox::{self, ShareCommitments},
+    vss::VSS,
+};
+
+// Protobuf messages
+
+// The messages of `proto/vss.proto`, for participants in other languages. They are written out
+// as prost-build generates them, so that building the crate needs no `protoc`; change both
+// together. Integers are unsigned big-endian magnitudes and maps keyed by public key are entry
+// lists in key order.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum HashEncoding {
+    FixedWidth = 0,
+    Decimal = 1,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupParameters {
+    #[prost(bytes = "vec", tag = "1")]
+    pub q: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub g: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub big_g: Vec<u8>,
+    #[prost(uint32, tag = "4")]
+    pub length: u32,
+    #[prost(string, tag = "5")]
+    pub label: String,
+    #[prost(enumeration = "HashEncoding", tag = "6")]
+    pub encoding: i32,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub publickey: Vec<u8>,
+    #[prost(int64, tag = "2")]
+    pub position: i64,
+    #[prost(bytes = "vec", optional, tag = "3")]
+    pub wide_position: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValueEntry {
+    #[prost(bytes = "vec", tag = "1")]
+    pub publickey: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DistributionShareBox {
+    #[prost(bytes = "vec", repeated, tag = "1")]
+    pub commitments: Vec<Vec<u8>>,
+    #[prost(message, repeated, tag = "2")]
+    pub positions: Vec<PositionEntry>,
+    #[prost(message, repeated, tag = "3")]
+    pub shares: Vec<ValueEntry>,
+    #[prost(bytes = "vec", repeated, tag = "4")]
+    pub publickeys: Vec<Vec<u8>>,
+    #[prost(bytes = "vec", tag = "5")]
+    pub challenge: Vec<u8>,
+    #[prost(message, repeated, tag = "6")]
+    pub responses: Vec<ValueEntry>,
+    #[prost(bytes = "vec", tag = "7")]
+    pub u: Vec<u8>,
+    #[prost(uint64, optional, tag = "8")]
+    pub secret_len: Option<u64>,
+    #[prost(bytes = "vec", optional, tag = "9")]
+    pub params_fingerprint: Option<Vec<u8>>,
+    #[prost(uint32, optional, tag = "10")]
+    pub threshold: Option<u32>,
+    #[prost(uint32, optional, tag = "11")]
+    pub n: Option<u32>,
+    #[prost(bytes = "vec", optional, tag = "12")]
+    pub session_id: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ShareBox {
+    #[prost(bytes = "vec", tag = "1")]
+    pub publickey: Vec<u8>,
+    #[prost(bytes = "vec", tag = "2")]
+    pub share: Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub challenge: Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub response: Vec<u8>,
+    #[prost(bytes = "vec", optional, tag = "5")]
+    pub nonce: Option<Vec<u8>>,
+    #[prost(uint64, optional, tag = "6")]
+    pub issued_at: Option<u64>,
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub a1: Option<Vec<u8>>,
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub a2: Option<Vec<u8>>,
+}
+
+fn to_bytes(value: &BigInt) -> Vec<u8> {
+    let (_, bytes) = value.to_bytes_be();
+
+    if bytes == [0] {
+        Vec::new()
+    } else {
+        bytes
+    }
+}
+
+fn from_bytes(bytes: &[u8]) -> BigInt {
+    BigUint::from_bytes_be(bytes).to_bigint().unwrap()
+}
+
+fn to_entries(map: &BTreeMap<BigInt, BigInt>) -> Vec<ValueEntry> {
+    map.iter()
+        .map(|(publickey, value)| ValueEntry {
+            publickey: to_bytes(publickey),
+            value: to_bytes(value),
+        })
+        .collect()
+}
+
+fn from_entries(entries: &[ValueEntry]) -> BTreeMap<BigInt, BigInt> {
+    entries
+        .iter()
+        .map(|entry| (from_bytes(&entry.publickey), from_bytes(&entry.value)))
+        .collect()
+}
+
+impl From<group::HashEncoding> for HashEncoding {
+    fn from(encoding: group::HashEncoding) -> Self {
+        match encoding {
+            group::HashEncoding::FixedWidth => HashEncoding::FixedWidth,
+            group::HashEncoding::Decimal => HashEncoding::Decimal,
+        }
+    }
+}
+
+impl From<HashEncoding> for group::HashEncoding {
+    fn from(encoding: HashEncoding) -> Self {
+        match encoding {
+            HashEncoding::FixedWidth => group::HashEncoding::FixedWidth,
+            HashEncoding::Decimal => group::HashEncoding::Decimal,
+        }
+    }
+}
+
+impl<D> From<&VSS<D>> for GroupParameters {
+    fn from(vss: &VSS<D>) -> Self {
+        GroupParameters {
+            q: to_bytes(&vss.q),
+            g: to_bytes(&vss.g),
+            big_g: to_bytes(&vss.G),
+            length: vss.length,
+            label: vss.label.clone(),
+            encoding: HashEncoding::from(vss.encoding) as i32,
+        }
+    }
+}
+
+/// An unknown hash encoding becomes fixed width, the first value of the proto enum.
+impl<D> From<&GroupParameters> for VSS<D> {
+    fn from(parameters: &GroupParameters) -> Self {
+        let mut vss = VSS::default();
+
+        vss.q = from_bytes(&parameters.q);
+        vss.g = from_bytes(&parameters.g);
+        vss.G = from_bytes(&parameters.big_g);
+        vss.length = parameters.length;
+        vss.label = parameters.label.clone();
+        vss.encoding = parameters.encoding().into();
+
+        vss
+    }
+}
+
+impl From<&sharebox::DistributionShareBox> for DistributionShareBox {
+    fn from(distribution: &sharebox::DistributionShareBox) -> Self {
+        DistributionShareBox {
+            commitments: distribution.commitments.iter().map(to_bytes).collect(),
+            positions: distribution
+                .positions
+                .iter()
+                .map(|(publickey, position)| PositionEntry {
+                    publickey: to_bytes(publickey),
+                    position: position.to_i64().unwrap_or(0),
+                    wide_position: position.to_i64().is_none().then(|| to_bytes(position)),
+                })
+                .collect(),
+            shares: to_entries(&distribution.shares),
+            publickeys: distribution.publickeys.iter().map(to_bytes).collect(),
+            challenge: to_bytes(&distribution.challenge),
+            responses: to_entries(&distribution.responses),
+            u: to_bytes(&distribution.u),
+            secret_len: distribution.secret_len.map(|len| len as u64),
+            params_fingerprint: distribution
+                .params_fingerprint
+                .map(|fingerprint| fingerprint.to_vec()),
+            threshold: distribution.threshold.map(|threshold| threshold as u32),
+            n: distribution.n.map(|n| n as u32),
+            session_id: distribution
+                .session_id
+                .map(|session_id| session_id.as_bytes().to_vec()),
+        }
+    }
+}
+
+impl From<&DistributionShareBox> for sharebox::DistributionShareBox {
+    fn from(distribution: &DistributionShareBox) -> Self {
+        sharebox::DistributionShareBox {
+            commitments: distribution
+                .commitments
+                .iter()
+                .map(|c| from_bytes(c))
+                .collect(),
+            positions: distribution
+                .positions
+                .iter()
+                .map(|entry| {
+                    let position = match &entry.wide_position {
+                        Some(position) => from_bytes(position),
+                        None => BigInt::from(entry.position),
+                    };
+
+                    (from_bytes(&entry.publickey), position)
+                })
+                .collect(),
+            shares: from_entries(&distribution.shares),
+            publickeys: distribution
+                .publickeys
+                .iter()
+                .map(|p| from_bytes(p))
+                .collect(),
+            challenge: from_bytes(&distribution.challenge),
+            responses: from_entries(&distribution.responses),
+            u: from_bytes(&distribution.u),
+            secret_len: distribution.secret_len.map(|len| len as usize),
+            // a fingerprint of another length is cut or zero padded to 8 bytes, so that it is
+            // still checked rather than dropped
+            params_fingerprint: distribution.params_fingerprint.as_ref().map(|fingerprint| {
+                let mut padded = [0; 8];
+                let len = fingerprint.len().min(8);
+
+                padded[..len].copy_from_slice(&fingerprint[..len]);
+
+                padded
+            }),
+            threshold: distribution.threshold.map(|threshold| threshold as usize),
+            n: distribution.n.map(|n| n as usize),
+            // padded or cut like the fingerprint, so that proofs are still checked against it
+            session_id: distribution.session_id.as_ref().map(|session_id| {
+                let mut padded = [0; 32];
+                let len = session_id.len().min(32);
+
+                padded[..len].copy_from_slice(&session_id[..len]);
+
+                SessionId(padded)
+            }),
+        }
+    }
+}
+
+impl From<&sharebox::ShareBox> for ShareBox {
+    fn from(sharebox: &sharebox::ShareBox) -> Self {
+        ShareBox {
+            publickey: to_bytes(&sharebox.publickey),
+            share: to_bytes(&sharebox.share),
+            challenge: to_bytes(&sharebox.challenge),
+            response: to_bytes(&sharebox.response),
+            nonce: sharebox.freshness.map(|freshness| freshness.nonce.to_vec()),
+            issued_at: sharebox.freshness.map(|freshness| freshness.issued_at),
+            a1: sharebox.commitments.as_ref().map(|c| to_bytes(&c.a1)),
+            a2: sharebox.commitments.as_ref().map(|c| to_bytes(&c.a2)),
+        }
+    }
+}
+
+impl From<&ShareBox> for sharebox::ShareBox {
+    fn from(sharebox: &ShareBox) -> Self {
+        sharebox::ShareBox {
+            publickey: from_bytes(&sharebox.publickey),
+            share: from_bytes(&sharebox.share),
+            challenge: from_bytes(&sharebox.challenge),
+            response: from_bytes(&sharebox.response),
+            // a nonce without an issue time is dropped, which fails the proof it was part of
+            freshness: match (&sharebox.nonce, sharebox.issued_at) {
+                (Some(nonce), Some(issued_at)) => {
+                    let mut padded = [0; 16];
+                    let len = nonce.len().min(16);
+
+                    padded[..len].copy_from_slice(&nonce[..len]);
+
+                    Some(Freshness {
+                        nonce: padded,
+                        issued_at,
+                    })
+                }
+                _ => None,
+            },
+            // the commitments are only a hint for batch verification, so half of them is dropped
+            commitments: match (&sharebox.a1, &sharebox.a2) {
+                (Some(a1), Some(a2)) => Some(ShareCommitments {
+                    a1: from_bytes(a1),
+                    a2: from_bytes(a2),
+                }),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use crate::{group, sharebox, testkit::fixed_ceremony, vss::VSS};
+
+    use super::{DistributionShareBox, GroupParameters, ShareBox};
+
+    #[test]
+    fn test_round_trip() {
+        let ceremony = fixed_ceremony(3, 2, 11).unwrap();
+        let bytes = DistributionShareBox::from(&ceremony.distribution).encode_to_vec();
+        let distribution = DistributionShareBox::decode(bytes.as_slice()).unwrap();
+
+        assert_eq!(
+            sharebox::DistributionShareBox::from(&distribution),
+            ceremony.distribution
+        );
+
+        for share in &ceremony.shares {
+            let bytes = ShareBox::from(share).encode_to_vec();
+
+            assert_eq!(
+                sharebox::ShareBox::from(&ShareBox::decode(bytes.as_slice()).unwrap()),
+                *share
+            );
+        }
+
+        let mut vss = ceremony.dealer.vss.clone();
+
+        vss.label = String::from("wallet");
+        vss.encoding = group::HashEncoding::FixedWidth;
+
+        let bytes = GroupParameters::from(&vss).encode_to_vec();
+
+        assert_eq!(
+            VSS::from(&GroupParameters::decode(bytes.as_slice()).unwrap()),
+            vss
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::{error::VssError, sharebox::ShareBox};
+
+// QR chunks
+
+// Payloads too large for one QR code are cut into chunks of at most `chunk_len` bytes. Every
+// chunk carries a header of the first 4 bytes of the SHA-256 of the whole payload, its sequence
+// number and the chunk count, both 2 bytes big-endian, and is base45 encoded, which fits the
+// alphanumeric mode of QR codes. Chunks can be scanned in any order; the reassembled payload is
+// checked against the hash.
+
+const ID_LEN: usize = 4;
+const HEADER_LEN: usize = ID_LEN + 4;
+
+fn payload_id(payload: &[u8]) -> [u8; ID_LEN] {
+    Sha256::digest(payload)[..ID_LEN].try_into().unwrap()
+}
+
+/// Splits `payload` into base45 chunks of at most `chunk_len` payload bytes each.
+///
+/// # Panics
+///
+/// If `chunk_len` is zero or the payload needs more than 65535 chunks.
+pub fn encode_chunks(payload: &[u8], chunk_len: usize) -> Vec<String> {
+    assert!(chunk_len > 0, "chunk length must be positive");
+
+    let id = payload_id(payload);
+    let parts: Vec<&[u8]> = if payload.is_empty() {
+        vec![&[]]
+    } else {
+        payload.chunks(chunk_len).collect()
+    };
+    let total = u16::try_from(parts.len()).expect("more than 65535 chunks");
+
+    parts
+        .iter()
+        .enumerate()
+        .map(|(sequence, part)| {
+            let mut chunk = Vec::with_capacity(HEADER_LEN + part.len());
+
+            chunk.extend_from_slice(&id);
+            chunk.extend_from_slice(&(sequence as u16).to_be_bytes());
+            chunk.extend_from_slice(&total.to_be_bytes());
+            chunk.extend_from_slice(part);
+
+            base45::encode(chunk)
+        })
+        .collect()
+}
+
+/// Collects scanned chunks of one payload until it is complete.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkReassembler {
+    id: Option<[u8; ID_LEN]>,
+    total: u16,
+    parts: BTreeMap<u16, Vec<u8>>,
+}
+
+impl ChunkReassembler {
+    pub fn new() -> Self {
+        ChunkReassembler {
+            id: None,
+            total: 0,
+            parts: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a scanned chunk, returning the payload once every chunk is in. Scanning a chunk twice
+    /// is harmless; a chunk of a different payload is rejected.
+    pub fn add(&mut self, chunk: &str) -> Result<Option<Vec<u8>>, VssError> {
+        let bytes = base45::decode(chunk.trim()).map_err(|_| VssError::MalformedEncoding)?;
+
+        if bytes.len() < HEADER_LEN {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        let id: [u8; ID_LEN] = bytes[..ID_LEN].try_into().unwrap();
+        let sequence = u16::from_be_bytes([bytes[ID_LEN], bytes[ID_LEN + 1]]);
+        let total = u16::from_be_bytes([bytes[ID_LEN + 2], bytes[ID_LEN + 3]]);
+
+        if sequence >= total {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        match self.id {
+            Some(expected) if expected != id || self.total != total => {
+                return Err(VssError::MalformedEncoding)
+            }
+            Some(_) => {}
+            None => {
+                self.id = Some(id);
+                self.total = total;
+            }
+        }
+
+        self.parts.insert(sequence, bytes[HEADER_LEN..].to_vec());
+
+        if self.parts.len() < self.total as usize {
+            return Ok(None);
+        }
+
+        let payload = self.parts.values().flatten().copied().collect::<Vec<u8>>();
+
+        if payload_id(&payload) != id {
+            return Err(VssError::ChecksumMismatch);
+        }
+
+        Ok(Some(payload))
+    }
+
+    /// The number of distinct chunks added so far.
+    pub fn received(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// The chunk count announced by the chunks, zero before the first.
+    pub fn total(&self) -> usize {
+        self.total as usize
+    }
+}
+
+impl ShareBox {
+    /// `to_bytes` as base45 chunks for QR codes, see `encode_chunks`.
+    pub fn to_qr_chunks(&self, chunk_len: usize) -> Vec<String> {
+        encode_chunks(&self.to_bytes(), chunk_len)
+    }
+
+    /// The share box from its complete set of QR chunks, in any order.
+    pub fn from_qr_chunks<S: AsRef<str>>(chunks: &[S]) -> Result<Self, VssError> {
+        let mut reassembler = ChunkReassembler::new();
+
+        for chunk in chunks {
+            if let Some(payload) = reassembler.add(chunk.as_ref())? {
+                return ShareBox::from_bytes(&payload);
+            }
+        }
+
+        Err(VssError::MalformedEncoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::VssError, sharebox::ShareBox, testkit::fixed_ceremony};
+
+    use super::{encode_chunks, ChunkReassembler};
+
+    #[test]
+    fn test_chunks() {
+        let sharebox = fixed_ceremony(3, 2, 9).unwrap().shares[1].clone();
+        let mut chunks = sharebox.to_qr_chunks(5);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|chunk| chunk
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase() || " $%*+-./:".contains(c))));
+
+        chunks.reverse();
+
+        assert_eq!(ShareBox::from_qr_chunks(&chunks), Ok(sharebox));
+        assert_eq!(
+            ShareBox::from_qr_chunks(&chunks[1..]),
+            Err(VssError::MalformedEncoding)
+        );
+
+        let mut reassembler = ChunkReassembler::new();
+
+        assert_eq!(reassembler.add(&chunks[0]), Ok(None));
+        assert_eq!(reassembler.add(&chunks[0]), Ok(None));
+        assert_eq!(reassembler.received(), 1);
+        assert_eq!(reassembler.total(), chunks.len());
+        assert_eq!(
+            reassembler.add(&encode_chunks(b"another payload", 5)[0]),
+            Err(VssError::MalformedEncoding)
+        );
+
+        let mut reassembler = ChunkReassembler::new();
+
+        assert_eq!(
+            reassembler.add(&encode_chunks(&[], 5)[0]),
+            Ok(Some(Vec::new()))
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+
+use crate::{
+    error::VssError,
+    participant::Participant,
+    secret_key::SecretKey,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+};
+
+// Structured secrets
+
+// A record maps labels such as "db_password" or "signing_key" to secrets. Every field is dealt on
+// its own polynomial to the same committee in one ceremony, so a field can be reconstructed on
+// its own without revealing the others, or the whole record at once. Each field has its own
+// threshold, and shareholders may release shares for only the fields a request asks for.
+
+pub type SecretRecord = BTreeMap<String, BigInt>;
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordDistribution {
+    pub fields: BTreeMap<String, DistributionShareBox>,
+}
+
+/// The share boxes one participant extracted from a record distribution, by label.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordShares {
+    pub fields: BTreeMap<String, ShareBox>,
+}
+
+impl RecordDistribution {
+    /// The labels of the record, its schema.
+    pub fn labels(&self) -> Vec<&str> {
+        self.fields.keys().map(String::as_str).collect()
+    }
+
+    pub fn field(&self, label: &str) -> Result<&DistributionShareBox, VssError> {
+        self.fields
+            .get(label)
+            .ok_or_else(|| VssError::UnknownField {
+                label: label.to_string(),
+            })
+    }
+
+    /// The number of shares needed to reconstruct the field `label`.
+    pub fn threshold(&self, label: &str) -> Result<usize, VssError> {
+        Ok(self.field(label)?.threshold())
+    }
+}
+
+pub(crate) fn distribute<D: Transcript>(
+    dealer: &mut Participant<D>,
+    record: &SecretRecord,
+    publickeys: &[BigInt],
+    thresholds: &BTreeMap<String, u32>,
+) -> Result<RecordDistribution, VssError> {
+    let mut fields = BTreeMap::new();
+
+    for (label, secret) in record {
+        let threshold = thresholds
+            .get(label)
+            .ok_or_else(|| VssError::UnknownField {
+                label: label.clone(),
+            })?;
+
+        fields.insert(
+            label.clone(),
+            dealer.distribute_secret(secret, publickeys, *threshold)?,
+        );
+    }
+
+    Ok(RecordDistribution { fields })
+}
+
+pub(crate) fn verify<D: Transcript>(
+    participant: &Participant<D>,
+    distribution: &RecordDistribution,
+) -> bool {
+    distribution
+        .fields
+        .values()
+        .all(|field| participant.verify_distribution_shares(field))
+}
+
+pub(crate) fn extract<D: Transcript>(
+    participant: &Participant<D>,
+    distribution: &RecordDistribution,
+    labels: &[&str],
+    private_key: &SecretKey,
+) -> Result<RecordShares, VssError> {
+    let mut fields = BTreeMap::new();
+
+    for label in labels {
+        fields.insert(
+            label.to_string(),
+            participant.extract_secret_share(distribution.field(label)?, private_key)?,
+        );
+    }
+
+    Ok(RecordShares { fields })
+}
+
+pub(crate) fn reconstruct_field<D: Transcript>(
+    participant: &Participant<D>,
+    label: &str,
+    shares: &[RecordShares],
+    distribution: &RecordDistribution,
+) -> Result<BigInt, VssError> {
+    let field = distribution.field(label)?;
+    let share_boxes: Vec<ShareBox> = shares
+        .iter()
+        .filter_map(|record_shares| record_shares.fields.get(label).cloned())
+        .collect();
+
+    participant.reconstruct(&share_boxes, field)
+}
+
+pub(crate) fn reconstruct<D: Transcript>(
+    participant: &Participant<D>,
+    shares: &[RecordShares],
+    distribution: &RecordDistribution,
+) -> Result<SecretRecord, VssError> {
+    let mut record = SecretRecord::new();
+
+    for label in distribution.fields.keys() {
+        record.insert(
+            label.clone(),
+            reconstruct_field(participant, label, shares, distribution)?,
+        );
+    }
+
+    Ok(record)
+}
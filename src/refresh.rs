@@ -0,0 +1,359 @@
+use std::collections::BTreeSet;
+
+use num_bigint::BigInt;
+use num_traits::One;
+
+use crate::{error::VssError, sharebox::DistributionShareBox, transcript::Transcript, vss::VSS};
+
+// Proactive refresh
+
+// Shares leaked over time add up: once an attacker has collected t of them, the secret is gone,
+// however long ago each was taken. A refresh re-randomizes every share without changing the
+// secret. Shareholders each deal a sharing of zero, a polynomial d with d(0) = 0, to the same
+// recipients at the same positions, proven like any distribution and bound to the base by its
+// fingerprint. Multiplying the commitments and the encrypted shares of the base and of every
+// refresh gives a distribution of p + sum d, which has the same value at zero. Decrypted shares
+// of the old and the new distribution do not combine, so shares taken before the refresh are
+// worthless after it, as long as one refresh was dealt honestly.
+//
+// Revoking a recipient is a refresh that leaves it out: the others move on to p + sum d, while the
+// revoked share stays a share of p, so it no longer combines with anything.
+
+// the recipients of `distribution_sharebox` without the `revoked` ones, in order
+pub(crate) fn remaining_recipients(
+    distribution_sharebox: &DistributionShareBox,
+    revoked: &[BigInt],
+) -> Result<Vec<BigInt>, VssError> {
+    let mut seen = BTreeSet::new();
+
+    for publickey in revoked {
+        if !distribution_sharebox.positions.contains_key(publickey) {
+            return Err(VssError::UnknownShareholder {
+                publickey: publickey.clone(),
+            });
+        }
+
+        if !seen.insert(publickey) {
+            return Err(VssError::DuplicatePublicKey {
+                publickey: publickey.clone(),
+            });
+        }
+    }
+
+    Ok(distribution_sharebox
+        .publickeys
+        .iter()
+        .filter(|publickey| !seen.contains(publickey))
+        .cloned()
+        .collect())
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks that `refresh` is a proven sharing of zero to exactly the recipients, positions and
+    /// threshold of `distribution_sharebox`, bound to it, see `Participant::distribute_refresh`.
+    pub fn verify_refresh(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        refresh: &DistributionShareBox,
+    ) -> bool {
+        self.verify_revocation(distribution_sharebox, &[], refresh)
+    }
+
+    /// Like `verify_refresh`, for a refresh to every recipient but the `revoked` ones, see
+    /// `Participant::distribute_revocation`.
+    pub fn verify_revocation(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        revoked: &[BigInt],
+        refresh: &DistributionShareBox,
+    ) -> bool {
+        let publickeys = match remaining_recipients(distribution_sharebox, revoked) {
+            Ok(publickeys) => publickeys,
+            Err(_) => return false,
+        };
+
+        refresh.publickeys == publickeys
+            && refresh.positions.len() == publickeys.len()
+            && publickeys.iter().all(|publickey| {
+                refresh.positions.get(publickey) == distribution_sharebox.positions.get(publickey)
+            })
+            && refresh.commitments.len() == distribution_sharebox.commitments.len()
+            && refresh
+                .commitments
+                .first()
+                .is_some_and(|commitment| commitment.is_one())
+            && self.verify_distribution_shares_with_context(
+                refresh,
+                &distribution_sharebox.fingerprint(),
+            )
+    }
+}
+
+/// A distribution together with the refreshes dealt for it. Anyone can check it with `verify`;
+/// the recipients extract and reconstruct from `distribution`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RefreshedDistribution {
+    pub base: DistributionShareBox,
+    pub refreshes: Vec<DistributionShareBox>,
+    /// recipients of the base the refreshes leave out, see `revoke`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "crate::serde_utils::bigint_vec")
+    )]
+    pub revoked: Vec<BigInt>,
+}
+
+impl RefreshedDistribution {
+    pub fn new(base: DistributionShareBox) -> Self {
+        RefreshedDistribution {
+            base,
+            refreshes: Vec::new(),
+            revoked: Vec::new(),
+        }
+    }
+
+    /// A refreshed distribution of `base` over its recipients but the `revoked` ones, which must
+    /// leave at least the threshold. It takes revocation refreshes, see
+    /// `Participant::distribute_revocation`, and needs one before it verifies.
+    pub fn revoke(base: DistributionShareBox, revoked: &[BigInt]) -> Result<Self, VssError> {
+        let remaining = remaining_recipients(&base, revoked)?;
+
+        if remaining.len() < base.threshold() {
+            return Err(VssError::InvalidThreshold {
+                threshold: base.threshold() as u32,
+                participants: remaining.len(),
+            });
+        }
+
+        Ok(RefreshedDistribution {
+            base,
+            refreshes: Vec::new(),
+            revoked: revoked.to_vec(),
+        })
+    }
+
+    /// Adds `refresh` if it passes `VSS::verify_revocation` against the base and the revoked
+    /// recipients, which for none is `VSS::verify_refresh`.
+    pub fn add<D: Transcript>(
+        &mut self,
+        vss: &VSS<D>,
+        refresh: DistributionShareBox,
+    ) -> Result<(), VssError> {
+        if !vss.verify_revocation(&self.base, &self.revoked, &refresh) {
+            return Err(VssError::InvalidProof);
+        }
+
+        self.refreshes.push(refresh);
+
+        Ok(())
+    }
+
+    /// Verifies the base distribution, dealt without a context, and every refresh against it. A
+    /// revocation without a refresh does not verify, as it would leave the revoked shares valid.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        (self.revoked.is_empty() || !self.refreshes.is_empty())
+            && vss.verify_distribution_shares(&self.base)
+            && self
+                .refreshes
+                .iter()
+                .all(|refresh| vss.verify_revocation(&self.base, &self.revoked, refresh))
+    }
+
+    /// The distribution the recipients hold shares of after the refreshes, for
+    /// `extract_secret_share`, `verify_share` and `reconstruct` as usual. Revoked recipients are
+    /// left out of it. It keeps the challenge and responses of the base, which do not prove it;
+    /// check `verify` instead.
+    pub fn distribution<D: Transcript>(&self, vss: &VSS<D>) -> DistributionShareBox {
+        let mut distribution = self.base.clone();
+
+        for publickey in &self.revoked {
+            distribution.positions.remove(publickey);
+            distribution.shares.remove(publickey);
+            distribution.responses.remove(publickey);
+        }
+
+        distribution
+            .publickeys
+            .retain(|publickey| !self.revoked.contains(publickey));
+        distribution.n = distribution.n.map(|_| distribution.publickeys.len());
+
+        for refresh in &self.refreshes {
+            for (commitment, refreshed) in distribution
+                .commitments
+                .iter_mut()
+                .zip(&refresh.commitments)
+            {
+                *commitment = (&*commitment * refreshed) % &vss.q;
+            }
+
+            for (publickey, share) in distribution.shares.iter_mut() {
+                if let Some(refreshed) = refresh.shares.get(publickey) {
+                    *share = (&*share * refreshed) % &vss.q;
+                }
+            }
+        }
+
+        distribution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, sharebox::ShareBox, testkit::fixed_ceremony};
+
+    use super::RefreshedDistribution;
+
+    #[test]
+    fn test_refresh() {
+        let ceremony = fixed_ceremony(4, 2, 7).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut refreshed = RefreshedDistribution::new(ceremony.distribution.clone());
+
+        for participant in ceremony.participants[..2].iter() {
+            let refresh = participant
+                .clone()
+                .distribute_refresh(&ceremony.distribution)
+                .unwrap();
+
+            assert!(vss.verify_refresh(&ceremony.distribution, &refresh));
+
+            refreshed.add(vss, refresh).unwrap();
+        }
+
+        assert!(refreshed.verify(vss));
+
+        let distribution = refreshed.distribution(vss);
+        let shareboxes: Vec<ShareBox> = ceremony.participants[..2]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(shareboxes.iter().all(|sharebox| vss.verify_share(
+            sharebox,
+            &distribution,
+            &sharebox.publickey
+        )));
+        assert_ne!(shareboxes[0].share, ceremony.shares[0].share);
+        assert_eq!(
+            vss.reconstruct(&shareboxes, &distribution),
+            Ok(ceremony.secret.clone())
+        );
+
+        // a share from before the refresh no longer combines with the new ones
+        assert_ne!(
+            vss.reconstruct(
+                &[ceremony.shares[0].clone(), shareboxes[1].clone()],
+                &distribution
+            ),
+            Ok(ceremony.secret.clone())
+        );
+    }
+
+    #[test]
+    fn test_revoke() {
+        let ceremony = fixed_ceremony(4, 2, 7).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let revoked = &ceremony.distribution.publickeys[3..];
+        let mut refreshed =
+            RefreshedDistribution::revoke(ceremony.distribution.clone(), revoked).unwrap();
+
+        assert!(!refreshed.verify(vss));
+
+        // a refresh to every recipient is not a revocation
+        let refresh = ceremony.participants[0]
+            .clone()
+            .distribute_refresh(&ceremony.distribution)
+            .unwrap();
+
+        assert_eq!(refreshed.add(vss, refresh), Err(VssError::InvalidProof));
+
+        for participant in ceremony.participants[..2].iter() {
+            let refresh = participant
+                .clone()
+                .distribute_revocation(&ceremony.distribution, revoked)
+                .unwrap();
+
+            refreshed.add(vss, refresh).unwrap();
+        }
+
+        assert!(refreshed.verify(vss));
+
+        let distribution = refreshed.distribution(vss);
+
+        assert_eq!(distribution.n(), 3);
+        assert!(!distribution.positions.contains_key(&revoked[0]));
+
+        let shareboxes: Vec<ShareBox> = ceremony.participants[1..3]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            vss.reconstruct(&shareboxes, &distribution),
+            Ok(ceremony.secret.clone())
+        );
+
+        // the revoked share is worthless next to a current one
+        assert_ne!(
+            vss.reconstruct(
+                &[ceremony.shares[3].clone(), shareboxes[0].clone()],
+                &ceremony.distribution
+            ),
+            Ok(ceremony.secret.clone())
+        );
+
+        assert_eq!(
+            RefreshedDistribution::revoke(
+                ceremony.distribution.clone(),
+                &ceremony.distribution.publickeys[1..]
+            ),
+            Err(VssError::InvalidThreshold {
+                threshold: 2,
+                participants: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_refresh() {
+        let ceremony = fixed_ceremony(4, 2, 7).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut refreshed = RefreshedDistribution::new(ceremony.distribution.clone());
+        let mut dealer = ceremony.dealer.clone();
+
+        // a fresh dealing of a non-zero secret is not a refresh
+        let other = dealer
+            .distribute_secret(
+                &BigInt::from(5),
+                &ceremony.distribution.publickeys,
+                ceremony.distribution.threshold() as u32,
+            )
+            .unwrap();
+
+        assert_eq!(refreshed.add(vss, other), Err(VssError::InvalidProof));
+
+        let refresh = dealer.distribute_refresh(&ceremony.distribution).unwrap();
+        let mut other_base = ceremony.distribution.clone();
+
+        other_base.u += 1;
+
+        assert!(!vss.verify_refresh(&other_base, &refresh));
+
+        let mut tampered = refresh.clone();
+        let publickey = &ceremony.distribution.publickeys[1];
+
+        *tampered.shares.get_mut(publickey).unwrap() += 1;
+
+        assert_eq!(refreshed.add(vss, tampered), Err(VssError::InvalidProof));
+        assert!(refreshed.refreshes.is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+use sha2::{Digest, Sha256};
+
+use crate::{error::VssError, sharebox::DistributionShareBox};
+
+// Redeal detection
+
+// A dealer that replays an old polynomial (or reuses the secret mask) across sessions leaks
+// information to anyone holding shares from both sessions. A shareholder keeps a registry of every
+// distribution it has taken part in, and flags distributions whose commitments or mask `u` were
+// already seen under a different session.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Redeal {
+    /// the commitments to the polynomial were already used in `session`
+    Commitments { session: String },
+    /// the secret mask `u` was already used in `session`
+    Mask { session: String },
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptRegistry {
+    distributions: BTreeMap<Vec<u8>, String>,
+    commitments: BTreeMap<Vec<u8>, String>,
+    masks: BTreeMap<Vec<u8>, String>,
+}
+
+impl TranscriptRegistry {
+    pub fn new() -> Self {
+        TranscriptRegistry {
+            distributions: BTreeMap::new(),
+            commitments: BTreeMap::new(),
+            masks: BTreeMap::new(),
+        }
+    }
+
+    fn hash_values(values: &[BigInt]) -> Result<Vec<u8>, VssError> {
+        let mut hasher = Sha256::new();
+
+        for value in values {
+            let bytes = value
+                .to_biguint()
+                .ok_or(VssError::NegativeValue)?
+                .to_bytes_be();
+
+            hasher.update((bytes.len() as u64).to_be_bytes());
+            hasher.update(&bytes);
+        }
+
+        Ok(hasher.finalize().to_vec())
+    }
+
+    /// Hash of everything the dealer published in `distribution_sharebox`. Fails on negative
+    /// values, which no dealer publishes.
+    pub fn transcript_hash(
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<Vec<u8>, VssError> {
+        let mut values = distribution_sharebox.commitments.clone();
+
+        for publickey in &distribution_sharebox.publickeys {
+            values.push(publickey.clone());
+
+            if let Some(share) = distribution_sharebox.shares.get(publickey) {
+                values.push(share.clone());
+            }
+
+            if let Some(response) = distribution_sharebox.responses.get(publickey) {
+                values.push(response.clone());
+            }
+        }
+
+        values.push(distribution_sharebox.challenge.clone());
+        values.push(distribution_sharebox.u.clone());
+
+        TranscriptRegistry::hash_values(&values)
+    }
+
+    /// Records `distribution_sharebox` under `session` and returns every reuse of its
+    /// commitments or mask from a different session. An empty result means the distribution is fresh.
+    pub fn record(
+        &mut self,
+        session: &str,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<Vec<Redeal>, VssError> {
+        let mut redeals = Vec::new();
+        let transcript_hash = TranscriptRegistry::transcript_hash(distribution_sharebox)?;
+        let commitments_hash = TranscriptRegistry::hash_values(&distribution_sharebox.commitments)?;
+        let mask_hash =
+            TranscriptRegistry::hash_values(std::slice::from_ref(&distribution_sharebox.u))?;
+
+        match self.commitments.get(&commitments_hash) {
+            Some(previous) if previous != session => redeals.push(Redeal::Commitments {
+                session: previous.clone(),
+            }),
+            Some(_) => {}
+            None => {
+                self.commitments
+                    .insert(commitments_hash, session.to_string());
+            }
+        }
+
+        match self.masks.get(&mask_hash) {
+            Some(previous) if previous != session => redeals.push(Redeal::Mask {
+                session: previous.clone(),
+            }),
+            Some(_) => {}
+            None => {
+                self.masks.insert(mask_hash, session.to_string());
+            }
+        }
+
+        self.distributions
+            .entry(transcript_hash)
+            .or_insert_with(|| session.to_string());
+
+        Ok(redeals)
+    }
+
+    /// Session under which this exact distribution was first recorded.
+    pub fn session_of(&self, distribution_sharebox: &DistributionShareBox) -> Option<&String> {
+        self.distributions
+            .get(&TranscriptRegistry::transcript_hash(distribution_sharebox).ok()?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.distributions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.distributions.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, sharebox::DistributionShareBox};
+
+    use super::{Redeal, TranscriptRegistry};
+
+    fn distribution(commitments: &[i64], u: i64) -> DistributionShareBox {
+        let commitments: Vec<BigInt> = commitments.iter().map(|c| BigInt::from(*c)).collect();
+        let mut distribution_share_box = DistributionShareBox::new();
+
+        distribution_share_box.init(
+            &commitments,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            &[],
+            &BigInt::from(7),
+            BTreeMap::new(),
+            &BigInt::from(u),
+        );
+
+        distribution_share_box
+    }
+
+    #[test]
+    fn test_fresh_distributions() {
+        let mut registry = TranscriptRegistry::new();
+
+        assert!(registry.is_empty());
+        assert!(registry
+            .record("session1", &distribution(&[1, 2, 3], 10))
+            .unwrap()
+            .is_empty());
+        assert!(registry
+            .record("session2", &distribution(&[4, 5, 6], 11))
+            .unwrap()
+            .is_empty());
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn test_same_session_is_not_a_redeal() {
+        let mut registry = TranscriptRegistry::new();
+        let distribution = distribution(&[1, 2, 3], 10);
+
+        assert_eq!(registry.record("session1", &distribution), Ok(vec![]));
+        assert_eq!(registry.record("session1", &distribution), Ok(vec![]));
+        assert_eq!(registry.len(), 1);
+        assert_eq!(
+            registry.session_of(&distribution),
+            Some(&String::from("session1"))
+        );
+    }
+
+    #[test]
+    fn test_redeal_detection() {
+        let mut registry = TranscriptRegistry::new();
+
+        registry
+            .record("session1", &distribution(&[1, 2, 3], 10))
+            .unwrap();
+
+        assert_eq!(
+            registry.record("session2", &distribution(&[1, 2, 3], 10)),
+            Ok(vec![
+                Redeal::Commitments {
+                    session: String::from("session1")
+                },
+                Redeal::Mask {
+                    session: String::from("session1")
+                }
+            ])
+        );
+        assert_eq!(
+            registry.record("session3", &distribution(&[7, 8, 9], 10)),
+            Ok(vec![Redeal::Mask {
+                session: String::from("session1")
+            }])
+        );
+        assert_eq!(
+            registry.record("session4", &distribution(&[1, 2, 3], 12)),
+            Ok(vec![Redeal::Commitments {
+                session: String::from("session1")
+            }])
+        );
+
+        // a negative mask is reported, not recorded
+        assert_eq!(
+            registry.record("session5", &distribution(&[1, 2, 3], -10)),
+            Err(VssError::NegativeValue)
+        );
+        assert_eq!(registry.len(), 3);
+    }
+}
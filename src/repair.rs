@@ -0,0 +1,747 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::{BigInt, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    arith,
+    error::VssError,
+    participant::Participant,
+    polynomial::Polynomial,
+    product_proof::{self, Proof, Statement},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    util::Util,
+    vss::VSS,
+    wire::Writer,
+};
+
+// Share repair
+
+// A shareholder that loses its private key can no longer decrypt its share, and nothing in the
+// distribution helps it. Any t other shareholders, the helpers, hold enough to interpolate its
+// share S_r = G^p(r) as prod S_i^lambda_i(r) over their positions, but each factor reveals the
+// helper's own share and their product reveals S_r to whoever sees it. The repair hides both.
+//
+// First every helper deals a sharing of zero at r among the helpers, a polynomial d with
+// d(r) = 0, proven like any distribution and bound to the request. Then every helper decrypts its
+// share and its shares of the blindings, B_i = S_i * G^(sum d(i)), and encrypts B_i^lambda_i(r)
+// with ElGamal to the recovering party's new key. The blindings interpolate to one at r, so the
+// product of the ciphertexts decrypts to S_r; a single ciphertext only shows a share of p + sum d,
+// which says nothing about p as long as one blinding was dealt honestly. Each helper proves its
+// ciphertext (c1, c2) = (G^k, B_i^lambda_i * y_r^k) with a key product proof for x its private
+// key, E = c1^x and z = k x: y = G^x, E = c1^x, E = G^z and Y*^lambda_i = c2^x * y_r^-z, where
+// Y* is the helper's encrypted share times its encrypted blinding shares, which is B_i^x.
+
+/// What a share repair recovers and for whom, see `Participant::repair_contribution`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RepairRequest {
+    /// the shareholder whose share is repaired, or the key being enrolled, see
+    /// `EnrolledDistribution`
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub position: BigInt,
+    /// the key the repaired share is encrypted to
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub recipient: BigInt,
+    /// the shareholders taking part, at least the threshold of them
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub helpers: Vec<BigInt>,
+}
+
+/// One helper's encrypted part of a repaired share, see `Participant::repair_contribution`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RepairContribution {
+    /// the helper's public key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+    /// G to the ElGamal nonce
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub ephemeral: BigInt,
+    /// the blinded, Lagrange weighted share times the recipient's key to the nonce
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub ciphertext: BigInt,
+    /// `ephemeral` raised to the private key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub blinded_key: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response_key: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response_product: BigInt,
+}
+
+impl RepairRequest {
+    /// A request to repair the share of `publickey` in `distribution_sharebox` for `recipient`,
+    /// with the help of `helpers`.
+    pub fn new(
+        distribution_sharebox: &DistributionShareBox,
+        publickey: &BigInt,
+        recipient: &BigInt,
+        helpers: &[BigInt],
+    ) -> Result<Self, VssError> {
+        let position = distribution_sharebox
+            .positions
+            .get(publickey)
+            .ok_or_else(|| VssError::UnknownShareholder {
+                publickey: publickey.clone(),
+            })?;
+        let request = RepairRequest {
+            publickey: publickey.clone(),
+            position: position.clone(),
+            recipient: recipient.clone(),
+            helpers: helpers.to_vec(),
+        };
+
+        request.helper_positions(distribution_sharebox)?;
+
+        Ok(request)
+    }
+
+    // the helpers' positions, in the order of `helpers`, once the request is checked against
+    // the distribution
+    pub(crate) fn helper_positions(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<Vec<BigInt>, VssError> {
+        distribution_sharebox.validate()?;
+
+        // a shareholder's own position, or for an enrollment a position nobody holds yet
+        match distribution_sharebox.positions.get(&self.publickey) {
+            Some(position) if position == &self.position => {}
+            Some(_) => {
+                return Err(VssError::UnknownShareholder {
+                    publickey: self.publickey.clone(),
+                })
+            }
+            None if !self.position.is_positive() => {
+                return Err(VssError::InvalidPosition {
+                    position: self.position.clone(),
+                })
+            }
+            None if distribution_sharebox
+                .positions
+                .values()
+                .any(|position| position == &self.position) =>
+            {
+                return Err(VssError::DuplicatePosition {
+                    position: self.position.clone(),
+                })
+            }
+            None => {}
+        }
+
+        if self.helpers.len() < distribution_sharebox.threshold() {
+            return Err(VssError::ThresholdNotMet {
+                required: distribution_sharebox.threshold(),
+                provided: self.helpers.len(),
+            });
+        }
+
+        let mut seen = BTreeSet::new();
+
+        // the shareholder being repaired cannot help
+        seen.insert(&self.publickey);
+
+        self.helpers
+            .iter()
+            .map(|helper| {
+                if !seen.insert(helper) {
+                    return Err(VssError::DuplicatePublicKey {
+                        publickey: helper.clone(),
+                    });
+                }
+
+                distribution_sharebox
+                    .positions
+                    .get(helper)
+                    .cloned()
+                    .ok_or_else(|| VssError::UnknownShareholder {
+                        publickey: helper.clone(),
+                    })
+            })
+            .collect()
+    }
+
+    // blindings and contributions are bound to the distribution and to the whole request
+    pub(crate) fn context(&self, distribution_sharebox: &DistributionShareBox) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.raw(b"repair");
+        writer.raw(&distribution_sharebox.fingerprint());
+        writer.bigint(&self.publickey);
+        writer.bigint(&self.position);
+        writer.bigint(&self.recipient);
+        writer.bigint_vec(&self.helpers);
+        writer.into_bytes()
+    }
+}
+
+// the helper's encrypted share times its encrypted shares of the blindings
+fn blinded_share<D: Transcript>(
+    vss: &VSS<D>,
+    distribution_sharebox: &DistributionShareBox,
+    blindings: &[DistributionShareBox],
+    publickey: &BigInt,
+) -> Result<BigInt, VssError> {
+    let missing = || VssError::MissingShare {
+        publickey: publickey.clone(),
+    };
+    let mut product = distribution_sharebox
+        .shares
+        .get(publickey)
+        .ok_or_else(missing)?
+        .clone();
+
+    for blinding in blindings {
+        product = (product * blinding.shares.get(publickey).ok_or_else(missing)?) % &vss.q;
+    }
+
+    Ok(product)
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Deals this helper's blinding for `request`: a sharing of zero at the repaired position to
+    /// the helpers, with the number of helpers as threshold. Every helper deals one, and each
+    /// should only contribute if the blindings it is given include its own.
+    pub fn repair_blinding(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+    ) -> Result<DistributionShareBox, VssError> {
+        self.repair_blinding_with_rng(distribution_sharebox, request, &mut rand::thread_rng())
+    }
+
+    /// Like `repair_blinding`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn repair_blinding_with_rng(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<DistributionShareBox, VssError> {
+        self.vss.check_params(distribution_sharebox)?;
+
+        let positions = request.helper_positions(distribution_sharebox)?;
+        let threshold = request.helpers.len() as u32;
+        let order = &self.vss.q - BigInt::one();
+
+        self.validate_inputs(
+            &BigInt::zero(),
+            &request.helpers,
+            &positions,
+            threshold,
+            None,
+        )?;
+
+        // d(x) = (x - r) e(x) for a random e of one degree less
+        let mut factor = Polynomial::new();
+
+        factor.init_with_rng(threshold as i32 - 2, &self.vss.q, rng);
+
+        let mut coefficients = vec![BigInt::zero(); threshold as usize];
+
+        for (j, coefficient) in factor.coefficients.iter().enumerate() {
+            coefficients[j] -= &request.position * coefficient;
+            coefficients[j + 1] += coefficient;
+        }
+
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_coefficients(
+            &coefficients
+                .iter()
+                .map(|coefficient| coefficient.mod_floor(&order))
+                .collect::<Vec<_>>(),
+        );
+
+        let w = rng.gen_bigint_range(&BigInt::zero(), &self.vss.q);
+
+        self.distribute(
+            &BigInt::zero(),
+            &request.helpers,
+            &positions,
+            threshold,
+            &polynomial,
+            &w,
+            &request.context(distribution_sharebox),
+            None,
+        )
+    }
+
+    /// This helper's contribution to `request`, given the blindings of all helpers, see
+    /// `repair_blinding`. The recovering party combines the contributions of every helper with
+    /// `repair_share`.
+    pub fn repair_contribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        blindings: &[DistributionShareBox],
+    ) -> Result<RepairContribution, VssError> {
+        self.repair_contribution_with_rng(
+            distribution_sharebox,
+            request,
+            blindings,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Like `repair_contribution`, drawing the ElGamal and proof nonces from `rng`.
+    pub fn repair_contribution_with_rng(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        blindings: &[DistributionShareBox],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<RepairContribution, VssError> {
+        let vss = &self.vss;
+        let positions = vss.check_blindings(distribution_sharebox, request, blindings)?;
+        let position = request
+            .helpers
+            .iter()
+            .position(|helper| helper == &self.publickey)
+            .map(|index| &positions[index])
+            .ok_or_else(|| VssError::UnknownShareholder {
+                publickey: self.publickey.clone(),
+            })?;
+        let encrypted = blinded_share(vss, distribution_sharebox, blindings, &self.publickey)?;
+        let privatekey = self.privatekey.expose();
+        let order = &vss.q - BigInt::one();
+        let inverse = Util::mod_inverse(privatekey, &order).ok_or(VssError::NonInvertible)?;
+        let blinded = arith::modpow(&encrypted, &vss.blind(&inverse), &vss.q);
+        let weighted = vss.compute_factor_at(position, &blinded, &positions, &request.position)?;
+        let target = vss.compute_factor_at(position, &encrypted, &positions, &request.position)?;
+        let inverse_recipient =
+            Util::mod_inverse(&request.recipient, &vss.q).ok_or(VssError::NonInvertible)?;
+
+        let nonce = rng.gen_bigint_range(&BigInt::zero(), &order);
+        let ephemeral = vss.exp_G(&vss.blind(&nonce));
+        let ciphertext =
+            (weighted * arith::modpow(&request.recipient, &vss.blind(&nonce), &vss.q)) % &vss.q;
+        let blinded_key = arith::modpow(&ephemeral, &vss.blind(privatekey), &vss.q);
+        let product = (&nonce * privatekey).mod_floor(&order);
+        let statement = Statement {
+            publickey: &self.publickey,
+            a: &ephemeral,
+            b: &vss.G,
+            c: &ciphertext,
+            f: &inverse_recipient,
+            target: &target,
+            blinded_key: &blinded_key,
+        };
+        let proof = product_proof::prove(
+            vss,
+            &request.context(distribution_sharebox),
+            &statement,
+            privatekey,
+            &product,
+            rng,
+        );
+
+        Ok(RepairContribution {
+            publickey: self.publickey.clone(),
+            challenge: proof.challenge,
+            response_key: proof.response_key,
+            response_product: proof.response_product,
+            ephemeral,
+            ciphertext,
+            blinded_key,
+        })
+    }
+
+    /// Recovers the share repaired by `request` from the contributions of all its helpers; this
+    /// participant must hold the recipient key. The share box carries the repaired shareholder's
+    /// public key and the decrypted share but no proof: `VSS::reconstruct` accepts it,
+    /// `VSS::verify_share` does not.
+    pub fn repair_share(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        blindings: &[DistributionShareBox],
+        contributions: &[RepairContribution],
+    ) -> Result<ShareBox, VssError> {
+        if request.recipient != self.publickey {
+            return Err(VssError::ForeignShare {
+                publickey: request.recipient.clone(),
+            });
+        }
+
+        let vss = &self.vss;
+        let positions = vss.check_blindings(distribution_sharebox, request, blindings)?;
+        let mut by_helper = BTreeMap::new();
+
+        for contribution in contributions {
+            if by_helper
+                .insert(&contribution.publickey, contribution)
+                .is_some()
+            {
+                return Err(VssError::DuplicatePublicKey {
+                    publickey: contribution.publickey.clone(),
+                });
+            }
+        }
+
+        let invalid: Vec<BigInt> = contributions
+            .iter()
+            .filter(|contribution| {
+                !vss.verify_contribution_proof(
+                    distribution_sharebox,
+                    request,
+                    &positions,
+                    blindings,
+                    contribution,
+                )
+            })
+            .map(|contribution| contribution.publickey.clone())
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(VssError::InvalidShares {
+                publickeys: invalid,
+            });
+        }
+
+        let mut ephemeral = BigInt::one();
+        let mut ciphertext = BigInt::one();
+
+        for helper in &request.helpers {
+            let contribution = by_helper
+                .get(helper)
+                .ok_or_else(|| VssError::MissingShare {
+                    publickey: helper.clone(),
+                })?;
+
+            ephemeral = (ephemeral * &contribution.ephemeral) % &vss.q;
+            ciphertext = (ciphertext * &contribution.ciphertext) % &vss.q;
+        }
+
+        let mask = arith::modpow(&ephemeral, &vss.blind(self.privatekey.expose()), &vss.q);
+        let unmask = Util::mod_inverse(&mask, &vss.q).ok_or(VssError::NonInvertible)?;
+        let mut sharebox = ShareBox::new();
+
+        sharebox.publickey = request.publickey.clone();
+        sharebox.share = (ciphertext * unmask) % &vss.q;
+
+        Ok(sharebox)
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks that `blinding` is a proven sharing of zero at the repaired position to exactly the
+    /// helpers of `request`, at their positions, bound to the request.
+    pub fn verify_repair_blinding(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        blinding: &DistributionShareBox,
+    ) -> bool {
+        match request.helper_positions(distribution_sharebox) {
+            Ok(positions) => {
+                self.blinding_matches(distribution_sharebox, request, &positions, blinding)
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Checks a helper's contribution to `request`: the blindings, and the proof that the
+    /// contribution encrypts the helper's blinded share, weighted for the repaired position, to
+    /// the recipient.
+    pub fn verify_repair_contribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        blindings: &[DistributionShareBox],
+        contribution: &RepairContribution,
+    ) -> bool {
+        match self.check_blindings(distribution_sharebox, request, blindings) {
+            Ok(positions) => self.verify_contribution_proof(
+                distribution_sharebox,
+                request,
+                &positions,
+                blindings,
+                contribution,
+            ),
+            Err(_) => false,
+        }
+    }
+
+    fn blinding_matches(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        positions: &[BigInt],
+        blinding: &DistributionShareBox,
+    ) -> bool {
+        blinding.publickeys == request.helpers
+            && blinding.positions.len() == positions.len()
+            && request
+                .helpers
+                .iter()
+                .zip(positions)
+                .all(|(helper, position)| blinding.positions.get(helper) == Some(position))
+            && blinding.commitments.len() == request.helpers.len()
+            && self
+                .evaluate_commitments(&blinding.commitments, &request.position)
+                .is_one()
+            && self.verify_distribution_shares_with_context(
+                blinding,
+                &request.context(distribution_sharebox),
+            )
+    }
+
+    // the helpers' positions if the request holds and there is one valid blinding per helper
+    pub(crate) fn check_blindings(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        blindings: &[DistributionShareBox],
+    ) -> Result<Vec<BigInt>, VssError> {
+        self.check_params(distribution_sharebox)?;
+
+        let positions = request.helper_positions(distribution_sharebox)?;
+
+        if blindings.len() != request.helpers.len() {
+            return Err(VssError::ThresholdNotMet {
+                required: request.helpers.len(),
+                provided: blindings.len(),
+            });
+        }
+
+        if !blindings.iter().all(|blinding| {
+            self.blinding_matches(distribution_sharebox, request, &positions, blinding)
+        }) {
+            return Err(VssError::InvalidProof);
+        }
+
+        Ok(positions)
+    }
+
+    pub(crate) fn verify_contribution_proof(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        request: &RepairRequest,
+        positions: &[BigInt],
+        blindings: &[DistributionShareBox],
+        contribution: &RepairContribution,
+    ) -> bool {
+        let in_group = |element: &BigInt| element > &BigInt::zero() && element < &self.q;
+        let position = match request
+            .helpers
+            .iter()
+            .position(|helper| helper == &contribution.publickey)
+        {
+            Some(index) => &positions[index],
+            None => return false,
+        };
+
+        if !in_group(&request.recipient)
+            || !in_group(&contribution.ephemeral)
+            || !in_group(&contribution.ciphertext)
+            || !in_group(&contribution.blinded_key)
+        {
+            return false;
+        }
+
+        let target = match blinded_share(
+            self,
+            distribution_sharebox,
+            blindings,
+            &contribution.publickey,
+        )
+        .and_then(|encrypted| {
+            self.compute_factor_at(position, &encrypted, positions, &request.position)
+        }) {
+            Ok(target) => target,
+            Err(_) => return false,
+        };
+        let inverse_recipient = match Util::mod_inverse(&request.recipient, &self.q) {
+            Some(inverse) => inverse,
+            None => return false,
+        };
+        let statement = Statement {
+            publickey: &contribution.publickey,
+            a: &contribution.ephemeral,
+            b: &self.G,
+            c: &contribution.ciphertext,
+            f: &inverse_recipient,
+            target: &target,
+            blinded_key: &contribution.blinded_key,
+        };
+        let proof = Proof {
+            challenge: contribution.challenge.clone(),
+            response_key: contribution.response_key.clone(),
+            response_product: contribution.response_product.clone(),
+        };
+
+        product_proof::verify(
+            self,
+            &request.context(distribution_sharebox),
+            &statement,
+            &proof,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{
+        error::VssError, participant::Participant, polynomial::Polynomial, secret_key::SecretKey,
+        testkit::fixed_ceremony,
+    };
+
+    use super::RepairRequest;
+
+    #[test]
+    fn test_repair() {
+        let ceremony = fixed_ceremony(4, 2, 5).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let distribution = &ceremony.distribution;
+        let recovering =
+            Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(7901)));
+        let helpers = &ceremony.participants[..2];
+        let request = RepairRequest::new(
+            distribution,
+            &ceremony.participants[3].publickey,
+            &recovering.publickey,
+            &helpers
+                .iter()
+                .map(|p| p.publickey.clone())
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let blindings: Vec<_> = helpers
+            .iter()
+            .map(|p| p.clone().repair_blinding(distribution, &request).unwrap())
+            .collect();
+
+        assert!(blindings.iter().all(|blinding| vss.verify_repair_blinding(
+            distribution,
+            &request,
+            blinding
+        )));
+
+        let contributions: Vec<_> = helpers
+            .iter()
+            .map(|p| {
+                p.repair_contribution(distribution, &request, &blindings)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(contributions
+            .iter()
+            .all(|contribution| vss.verify_repair_contribution(
+                distribution,
+                &request,
+                &blindings,
+                contribution
+            )));
+
+        let repaired = recovering
+            .repair_share(distribution, &request, &blindings, &contributions)
+            .unwrap();
+
+        assert_eq!(repaired.share, ceremony.shares[3].share);
+        assert_eq!(
+            vss.reconstruct(&[ceremony.shares[0].clone(), repaired], distribution),
+            Ok(ceremony.secret.clone())
+        );
+
+        // only the recipient can decrypt
+        assert_eq!(
+            helpers[0].repair_share(distribution, &request, &blindings, &contributions),
+            Err(VssError::ForeignShare {
+                publickey: recovering.publickey.clone()
+            })
+        );
+
+        // every helper must contribute, and every proof must hold
+        assert_eq!(
+            recovering.repair_share(distribution, &request, &blindings, &contributions[..1]),
+            Err(VssError::MissingShare {
+                publickey: helpers[1].publickey.clone()
+            })
+        );
+
+        let mut tampered = contributions.clone();
+
+        tampered[1].ciphertext = (&tampered[1].ciphertext * &vss.G) % &vss.q;
+
+        assert_eq!(
+            recovering.repair_share(distribution, &request, &blindings, &tampered),
+            Err(VssError::InvalidShares {
+                publickeys: vec![helpers[1].publickey.clone()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_repair_request() {
+        let ceremony = fixed_ceremony(4, 2, 5).unwrap();
+        let distribution = &ceremony.distribution;
+        let publickeys = &distribution.publickeys;
+
+        assert_eq!(
+            RepairRequest::new(
+                distribution,
+                &publickeys[3],
+                &publickeys[3],
+                &publickeys[..1]
+            ),
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 1
+            })
+        );
+        assert_eq!(
+            RepairRequest::new(
+                distribution,
+                &publickeys[3],
+                &publickeys[3],
+                &publickeys[2..]
+            ),
+            Err(VssError::DuplicatePublicKey {
+                publickey: publickeys[3].clone()
+            })
+        );
+
+        // a proven sharing that is not zero at the repaired position is no blinding
+        let request = RepairRequest::new(
+            distribution,
+            &publickeys[3],
+            &publickeys[3],
+            &publickeys[..2],
+        )
+        .unwrap();
+        let mut helper = ceremony.participants[0].clone();
+        let blinding = helper.repair_blinding(distribution, &request).unwrap();
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_coefficients(&[BigInt::from(5), BigInt::from(7)]);
+
+        let other = helper
+            .distribute(
+                &BigInt::from(0),
+                &request.helpers,
+                &[BigInt::from(1), BigInt::from(2)],
+                2,
+                &polynomial,
+                &BigInt::from(11),
+                &request.context(distribution),
+                None,
+            )
+            .unwrap();
+        let vss = &helper.vss;
+
+        assert!(vss.verify_repair_blinding(distribution, &request, &blinding));
+        assert!(vss.verify_distribution_shares_with_context(&other, &request.context(distribution)));
+        assert!(!vss.verify_repair_blinding(distribution, &request, &other));
+    }
+}
@@ -0,0 +1,596 @@
+use std::collections::BTreeSet;
+
+use num_bigint::{BigInt, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    arith,
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    polynomial::Polynomial,
+    product_proof::{self, Proof, Statement},
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    util::Util,
+    vss::VSS,
+};
+
+// Resharing
+
+// A shareholder's decrypted share is the group element S_i = G^p(i), not p(i), so it cannot be
+// dealt again as the constant term of a new polynomial. Instead each old shareholder deals a fresh
+// random polynomial r_i to the new committee, as an ordinary distribution with commitment
+// C_0 = g^r_i(0), and publishes the offset D_i = S_i * G^-r_i(0), which hides S_i. Interpolating
+// the offsets and the new distributions over the old positions gives D = G^p(0) * G^-r(0) and a
+// distribution of r = sum lambda_i r_i, so the new committee reconstructs G^p(0) as G^r(0) * D and
+// the old mask `u` still applies. Nobody ever holds G^p(0).
+//
+// That the offset matches the shareholder's share is proven without revealing it, by a key product
+// proof with E = C_0^x, x the private key, and z = r_i(0) * x: y = G^x, E = C_0^x, E = g^z and
+// Y_i = D_i^x * G^z, the last being S_i^x for S_i = D_i * G^r_i(0).
+// The proof and the new distribution are bound to the old distribution's fingerprint.
+
+/// One old shareholder's part of a resharing, see `Participant::reshare`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReshareContribution {
+    /// the old shareholder's public key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub publickey: BigInt,
+    /// the sharing of a random value to the new committee
+    pub distribution: DistributionShareBox,
+    /// the old share times G to the minus the random value
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub offset: BigInt,
+    /// the first commitment of `distribution` raised to the private key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub blinded_key: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response_key: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response_product: BigInt,
+}
+
+/// A distribution whose secret is masked by `offset` on top of `u`: the new committee
+/// reconstructs with `reconstruct` rather than `VSS::reconstruct`. An ordinary distribution is
+/// one with offset 1, see `From<DistributionShareBox>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResharedDistribution {
+    /// the distribution to the current committee. Its challenge and responses are empty, the
+    /// contributions it was combined from prove it.
+    pub distribution: DistributionShareBox,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub offset: BigInt,
+}
+
+impl From<DistributionShareBox> for ResharedDistribution {
+    fn from(distribution: DistributionShareBox) -> Self {
+        ResharedDistribution {
+            distribution,
+            offset: BigInt::one(),
+        }
+    }
+}
+
+// proofs of a resharing are bound to the distribution being reshared
+fn context(distribution_sharebox: &DistributionShareBox) -> Vec<u8> {
+    [&b"reshare"[..], &distribution_sharebox.fingerprint()].concat()
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Reshares this participant's share of `distribution_sharebox` to `publickeys`, at positions
+    /// `1..=n`, with a new `threshold`. The contributions of at least the old threshold of
+    /// shareholders are combined with `ResharedDistribution::reshare`.
+    pub fn reshare(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<ReshareContribution, VssError> {
+        self.reshare_with_rng(
+            distribution_sharebox,
+            publickeys,
+            threshold,
+            &mut rand::thread_rng(),
+        )
+    }
+
+    /// Like `reshare`, drawing the polynomial and the proof nonces from `rng`.
+    pub fn reshare_with_rng(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        publickeys: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<ReshareContribution, VssError> {
+        self.reshare_at(
+            distribution_sharebox,
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            rng,
+        )
+    }
+
+    /// Reshares this participant's share of `distribution_sharebox` to the same recipients at
+    /// the same positions, with a new `threshold`, higher or lower. The contributions of at least
+    /// the old threshold of shareholders are combined with
+    /// `ResharedDistribution::change_threshold`.
+    pub fn change_threshold(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        threshold: u32,
+    ) -> Result<ReshareContribution, VssError> {
+        self.change_threshold_with_rng(distribution_sharebox, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `change_threshold`, drawing the polynomial and the proof nonces from `rng`.
+    pub fn change_threshold_with_rng(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<ReshareContribution, VssError> {
+        let publickeys = &distribution_sharebox.publickeys;
+        let positions: Vec<BigInt> = publickeys
+            .iter()
+            .map(|publickey| {
+                distribution_sharebox
+                    .positions
+                    .get(publickey)
+                    .cloned()
+                    .ok_or_else(|| VssError::MissingShare {
+                        publickey: publickey.clone(),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        self.reshare_at(
+            distribution_sharebox,
+            publickeys,
+            &positions,
+            threshold,
+            rng,
+        )
+    }
+
+    fn reshare_at(
+        &mut self,
+        distribution_sharebox: &DistributionShareBox,
+        publickeys: &[BigInt],
+        positions: &[BigInt],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<ReshareContribution, VssError> {
+        self.vss.check_params(distribution_sharebox)?;
+        distribution_sharebox.validate()?;
+
+        let encrypted_share = distribution_sharebox
+            .shares
+            .get(&self.publickey)
+            .ok_or_else(|| VssError::MissingShare {
+                publickey: self.publickey.clone(),
+            })?;
+        let order = &self.vss.q - BigInt::one();
+        let inverse =
+            Util::mod_inverse(self.privatekey.expose(), &order).ok_or(VssError::NonInvertible)?;
+        let share = arith::modpow(encrypted_share, &self.vss.blind(&inverse), &self.vss.q);
+
+        let context = context(distribution_sharebox);
+
+        self.validate_inputs(&BigInt::zero(), publickeys, positions, threshold, None)?;
+
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_with_rng((threshold - 1) as i32, &self.vss.q, rng);
+
+        let w = rng.gen_bigint_range(&BigInt::zero(), &self.vss.q);
+        let distribution = self.distribute(
+            &BigInt::zero(),
+            publickeys,
+            positions,
+            threshold,
+            &polynomial,
+            &w,
+            &context,
+            None,
+        )?;
+
+        let vss = &self.vss;
+        let privatekey = self.privatekey.expose();
+        let value = polynomial.coefficients[0].mod_floor(&order);
+        let commitment = &distribution.commitments[0];
+        let offset = (share * arith::modpow(&vss.G, &(&order - &value), &vss.q)) % &vss.q;
+        let blinded_key = arith::modpow(commitment, &vss.blind(privatekey), &vss.q);
+        let product = (&value * privatekey).mod_floor(&order);
+        let statement = Statement {
+            publickey: &self.publickey,
+            a: commitment,
+            b: &vss.g,
+            c: &offset,
+            f: &vss.G,
+            target: encrypted_share,
+            blinded_key: &blinded_key,
+        };
+        let proof = product_proof::prove(vss, &context, &statement, privatekey, &product, rng);
+
+        Ok(ReshareContribution {
+            publickey: self.publickey.clone(),
+            challenge: proof.challenge,
+            response_key: proof.response_key,
+            response_product: proof.response_product,
+            distribution,
+            offset,
+            blinded_key,
+        })
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks a contribution to resharing `distribution_sharebox`: its distribution to the new
+    /// committee, and the proof that its offset hides the contributor's share.
+    pub fn verify_reshare_contribution(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        contribution: &ReshareContribution,
+    ) -> bool {
+        let context = context(distribution_sharebox);
+        let (encrypted_share, commitment) = match (
+            distribution_sharebox.shares.get(&contribution.publickey),
+            contribution.distribution.commitments.first(),
+        ) {
+            (Some(encrypted_share), Some(commitment)) => (encrypted_share, commitment),
+            _ => return false,
+        };
+        let in_group = |element: &BigInt| element > &BigInt::zero() && element < &self.q;
+
+        if !in_group(&contribution.offset)
+            || !in_group(&contribution.blinded_key)
+            || !self.verify_distribution_shares_with_context(&contribution.distribution, &context)
+        {
+            return false;
+        }
+
+        let statement = Statement {
+            publickey: &contribution.publickey,
+            a: commitment,
+            b: &self.g,
+            c: &contribution.offset,
+            f: &self.G,
+            target: encrypted_share,
+            blinded_key: &contribution.blinded_key,
+        };
+        let proof = Proof {
+            challenge: contribution.challenge.clone(),
+            response_key: contribution.response_key.clone(),
+            response_product: contribution.response_product.clone(),
+        };
+
+        product_proof::verify(self, &context, &statement, &proof)
+    }
+}
+
+impl ResharedDistribution {
+    /// Combines the contributions of old shareholders into the distribution the new committee
+    /// holds, for the same secret. Anyone can run it: every contribution is verified, at least the
+    /// threshold of them are needed, and all must deal to the same committee and threshold.
+    pub fn reshare<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        contributions: &[ReshareContribution],
+    ) -> Result<ResharedDistribution, VssError> {
+        let base = &self.distribution;
+
+        if contributions.len() < base.threshold() {
+            return Err(VssError::ThresholdNotMet {
+                required: base.threshold(),
+                provided: contributions.len(),
+            });
+        }
+
+        let mut seen = BTreeSet::new();
+
+        for contribution in contributions {
+            if !seen.insert(&contribution.publickey) {
+                return Err(VssError::DuplicatePublicKey {
+                    publickey: contribution.publickey.clone(),
+                });
+            }
+        }
+
+        let invalid: Vec<BigInt> = contributions
+            .iter()
+            .filter(|contribution| !vss.verify_reshare_contribution(base, contribution))
+            .map(|contribution| contribution.publickey.clone())
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(VssError::InvalidShares {
+                publickeys: invalid,
+            });
+        }
+
+        let first = &contributions[0].distribution;
+
+        if contributions.iter().any(|contribution| {
+            contribution.distribution.publickeys != first.publickeys
+                || contribution.distribution.positions != first.positions
+                || contribution.distribution.commitments.len() != first.commitments.len()
+        }) {
+            return Err(VssError::InconsistentDistribution);
+        }
+
+        let positions: Vec<BigInt> = contributions
+            .iter()
+            .map(|contribution| base.positions[&contribution.publickey].clone())
+            .collect();
+        let mut commitments = vec![BigInt::one(); first.commitments.len()];
+        let mut shares = first.shares.clone();
+        let mut offset = self.offset.clone();
+
+        shares.values_mut().for_each(|share| *share = BigInt::one());
+
+        for (contribution, position) in contributions.iter().zip(&positions) {
+            let interpolate = |element: &BigInt| vss.compute_factor(position, element, &positions);
+
+            for (commitment, dealt) in commitments
+                .iter_mut()
+                .zip(&contribution.distribution.commitments)
+            {
+                *commitment = (&*commitment * interpolate(dealt)?) % &vss.q;
+            }
+
+            for (publickey, share) in shares.iter_mut() {
+                *share =
+                    (&*share * interpolate(&contribution.distribution.shares[publickey])?) % &vss.q;
+            }
+
+            offset = (offset * interpolate(&contribution.offset)?) % &vss.q;
+        }
+
+        let mut distribution = DistributionShareBox::new();
+
+        distribution.commitments = commitments;
+        distribution.positions = first.positions.clone();
+        distribution.shares = shares;
+        distribution.publickeys = first.publickeys.clone();
+        distribution.u = base.u.clone();
+        distribution.secret_len = base.secret_len;
+        distribution.params_fingerprint = Some(vss.params_fingerprint());
+        distribution.threshold = Some(first.commitments.len());
+
+        Ok(ResharedDistribution {
+            distribution,
+            offset,
+        })
+    }
+
+    /// Combines the contributions of shareholders changing the threshold of `distribution` to
+    /// `threshold`, see `Participant::change_threshold`. Like `reshare`, also checking that every
+    /// contribution deals to the current recipients at their positions with that threshold.
+    pub fn change_threshold<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        threshold: u32,
+        contributions: &[ReshareContribution],
+    ) -> Result<ResharedDistribution, VssError> {
+        if contributions.iter().any(|contribution| {
+            contribution.distribution.publickeys != self.distribution.publickeys
+                || contribution.distribution.positions != self.distribution.positions
+                || contribution.distribution.commitments.len() != threshold as usize
+        }) {
+            return Err(VssError::InconsistentDistribution);
+        }
+
+        self.reshare(vss, contributions)
+    }
+
+    /// Reconstructs the secret from share boxes extracted from `distribution`.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        let shared_value = vss.interpolate(share_boxes, &self.distribution)?;
+
+        vss.unmask(
+            &((shared_value * &self.offset) % &vss.q),
+            &self.distribution,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{
+        error::VssError, participant::Participant, secret_key::SecretKey, sharebox::ShareBox,
+        testkit::fixed_ceremony,
+    };
+
+    use super::ResharedDistribution;
+
+    #[test]
+    fn test_reshare() {
+        let ceremony = fixed_ceremony(4, 2, 11).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let committee: Vec<Participant> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let contributions: Vec<_> = ceremony.participants[..2]
+            .iter()
+            .map(|p| {
+                p.clone()
+                    .reshare(&ceremony.distribution, &publickeys, 3)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(contributions
+            .iter()
+            .all(|contribution| vss
+                .verify_reshare_contribution(&ceremony.distribution, contribution)));
+
+        let reshared = ResharedDistribution::from(ceremony.distribution.clone())
+            .reshare(vss, &contributions)
+            .unwrap();
+        let shareboxes: Vec<ShareBox> = committee
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&reshared.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(reshared.distribution.threshold(), 3);
+        assert_eq!(
+            reshared.reconstruct(vss, &shareboxes),
+            Ok(ceremony.secret.clone())
+        );
+        assert!(reshared.reconstruct(vss, &shareboxes[..2]).is_err());
+
+        // the new committee rotates again, down to a threshold of two
+        let contributions: Vec<_> = committee
+            .iter()
+            .map(|p| {
+                p.clone()
+                    .reshare(&reshared.distribution, &ceremony.distribution.publickeys, 2)
+                    .unwrap()
+            })
+            .collect();
+        let rotated = reshared.reshare(vss, &contributions).unwrap();
+        let shareboxes: Vec<ShareBox> = ceremony.participants[..2]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&rotated.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            rotated.reconstruct(vss, &shareboxes),
+            Ok(ceremony.secret.clone())
+        );
+    }
+
+    #[test]
+    fn test_change_threshold() {
+        let ceremony = fixed_ceremony(4, 2, 13).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let current = ResharedDistribution::from(ceremony.distribution.clone());
+        let contributions: Vec<_> = ceremony.participants[..2]
+            .iter()
+            .map(|p| {
+                p.clone()
+                    .change_threshold(&ceremony.distribution, 3)
+                    .unwrap()
+            })
+            .collect();
+
+        // the contributions raise the threshold, not to something else
+        assert_eq!(
+            current.change_threshold(vss, 4, &contributions),
+            Err(VssError::InconsistentDistribution)
+        );
+
+        let raised = current.change_threshold(vss, 3, &contributions).unwrap();
+        let shareboxes: Vec<ShareBox> = ceremony.participants[..3]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&raised.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(raised.distribution.threshold(), 3);
+        assert_eq!(
+            raised.distribution.positions,
+            ceremony.distribution.positions
+        );
+        assert_eq!(
+            raised.reconstruct(vss, &shareboxes),
+            Ok(ceremony.secret.clone())
+        );
+        assert!(raised.reconstruct(vss, &shareboxes[..2]).is_err());
+
+        // and back down, which takes three of them now
+        let contributions: Vec<_> = ceremony.participants[..3]
+            .iter()
+            .map(|p| p.clone().change_threshold(&raised.distribution, 2).unwrap())
+            .collect();
+
+        assert_eq!(
+            raised.change_threshold(vss, 2, &contributions[..2]),
+            Err(VssError::ThresholdNotMet {
+                required: 3,
+                provided: 2
+            })
+        );
+
+        let lowered = raised.change_threshold(vss, 2, &contributions).unwrap();
+        let shareboxes: Vec<ShareBox> = ceremony.participants[..2]
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&lowered.distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            lowered.reconstruct(vss, &shareboxes),
+            Ok(ceremony.secret.clone())
+        );
+    }
+
+    #[test]
+    fn test_invalid_contribution() {
+        let ceremony = fixed_ceremony(4, 2, 11).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let base = ResharedDistribution::from(ceremony.distribution.clone());
+        let publickeys = &ceremony.distribution.publickeys;
+        let mut contributions: Vec<_> = ceremony.participants[..2]
+            .iter()
+            .map(|p| {
+                p.clone()
+                    .reshare(&ceremony.distribution, publickeys, 2)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(
+            base.reshare(vss, &contributions[..1]),
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 1
+            })
+        );
+
+        // an offset that does not hide the contributor's share
+        contributions[1].offset = (&contributions[1].offset * BigInt::from(2)) % &vss.q;
+
+        assert!(!vss.verify_reshare_contribution(&ceremony.distribution, &contributions[1]));
+        assert_eq!(
+            base.reshare(vss, &contributions),
+            Err(VssError::InvalidShares {
+                publickeys: vec![contributions[1].publickey.clone()]
+            })
+        );
+
+        // a contribution for another distribution
+        let mut other = ceremony.distribution.clone();
+
+        other.u += 1;
+
+        assert!(!vss.verify_reshare_contribution(&other, &contributions[0]));
+    }
+}
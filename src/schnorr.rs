@@ -0,0 +1,62 @@
+use crate::{arith, secret_key::SecretKey, transcript::Transcript, vss::VSS};
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::One;
+
+// Schnorr signatures over (q, G) with the participants' key pairs, used for requests and
+// manifests signed outside of a distribution. The challenge hashes the commitment, the signer's
+// public key and the message.
+
+fn challenge<D: Transcript>(
+    vss: &VSS<D>,
+    commitment: &BigInt,
+    publickey: &BigInt,
+    message: &[u8],
+) -> BigInt {
+    let mut hasher = vss.hasher();
+
+    vss.update_hash(&mut hasher, b"commitment", commitment);
+    vss.update_hash(&mut hasher, b"publickey", publickey);
+    hasher.append(b"message", message);
+
+    let hash = hasher.challenge();
+
+    BigUint::from_bytes_be(&hash[..])
+        .mod_floor(&(vss.q.to_biguint().unwrap() - BigUint::one()))
+        .to_bigint()
+        .unwrap()
+}
+
+/// Returns the commitment and the response of a signature on `message`.
+pub(crate) fn sign<D: Transcript>(
+    vss: &VSS<D>,
+    privatekey: &SecretKey,
+    message: &[u8],
+) -> (BigInt, BigInt) {
+    let order = &vss.q - BigInt::one();
+    let k = rand::thread_rng()
+        .gen_biguint_below(&order.to_biguint().unwrap())
+        .to_bigint()
+        .unwrap();
+    let publickey = vss.generate_public_key(privatekey);
+    let commitment = arith::modpow(&vss.G, &k, &vss.q);
+    let challenge = challenge(vss, &commitment, &publickey, message);
+    let response = (k - privatekey.expose() * challenge).mod_floor(&order);
+
+    (commitment, response)
+}
+
+/// Checks G^response * publickey^challenge == commitment.
+pub(crate) fn verify<D: Transcript>(
+    vss: &VSS<D>,
+    publickey: &BigInt,
+    message: &[u8],
+    commitment: &BigInt,
+    response: &BigInt,
+) -> bool {
+    let challenge = challenge(vss, commitment, publickey, message);
+
+    (arith::modpow(&vss.G, response, &vss.q) * arith::modpow(publickey, &challenge, &vss.q))
+        .mod_floor(&vss.q)
+        == *commitment
+}
@@ -0,0 +1,125 @@
+use num_bigint::{BigInt, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{arith, polynomial::Polynomial, util::Util, vss::VSS};
+
+// SCRAPE low-degree test
+
+// From SCRAPE (Cascudo and David, 2017): the points (i, X_i) with X_i = g^p(i) lie on a
+// polynomial of degree below t exactly when prod X_i^(v_i m(i)) = 1 for every polynomial m of
+// degree below n - t, where v_i = prod_{j != i} 1 / (i - j) are the coefficients of the dual code.
+// One random m catches any other set of points except with probability 1 / p, at the cost of n
+// exponentiations rather than the n * t of evaluating the commitments at every position.
+//
+// The exponents live modulo the order of g, where the differences i - j must be invertible. Over
+// a safe prime q = 2p + 1, such as `VssParamsBuilder` hands out, the points are squared into the
+// subgroup of prime order p first, so the test holds up to the sign of each X_i.
+
+/// Whether the points `(position, x)` lie on `g^f` for some `f` of degree below `threshold`.
+/// Positions must be distinct and `q` a safe prime.
+pub(crate) fn is_low_degree<D>(
+    vss: &VSS<D>,
+    points: &[(BigInt, BigInt)],
+    threshold: usize,
+    rng: &mut (impl CryptoRng + RngCore),
+) -> bool {
+    let n = points.len();
+
+    if threshold == 0 {
+        return false;
+    }
+
+    // every set of at most t points lies on a polynomial of degree below t
+    if n <= threshold {
+        return true;
+    }
+
+    let p: BigInt = (&vss.q - BigInt::one()) >> 1;
+    let mut m = Polynomial::new();
+
+    m.init_coefficients(
+        &(0..n - threshold)
+            .map(|_| {
+                rng.gen_biguint_below(&p.to_biguint().unwrap())
+                    .to_bigint()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    let mut product = BigInt::one();
+
+    for (i, (position, x)) in points.iter().enumerate() {
+        let mut denominator = BigInt::one();
+
+        for (j, (other, _)) in points.iter().enumerate() {
+            if i != j {
+                denominator = (denominator * (position - other)).mod_floor(&p);
+            }
+        }
+
+        let v = match Util::mod_inverse(&denominator, &p) {
+            Some(v) if !denominator.is_zero() => v,
+            _ => return false,
+        };
+        let exponent = (v * m.get_value(position)).mod_floor(&p);
+        let squared = (x * x) % &vss.q;
+
+        product = (product * arith::modpow(&squared, &exponent, &vss.q)) % &vss.q;
+    }
+
+    product.is_one()
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{polynomial::Polynomial, vss::VSS};
+
+    use super::is_low_degree;
+
+    fn points(vss: &VSS, coefficients: &[i64], positions: &[i64]) -> Vec<(BigInt, BigInt)> {
+        let mut polynomial = Polynomial::new();
+
+        polynomial.init_coefficients(
+            &coefficients
+                .iter()
+                .map(|c| BigInt::from(*c))
+                .collect::<Vec<_>>(),
+        );
+
+        positions
+            .iter()
+            .map(|position| {
+                let value = polynomial.get_value(&BigInt::from(*position));
+
+                (BigInt::from(*position), vss.g.modpow(&value, &vss.q))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_low_degree() {
+        let vss = VSS::builder()
+            .q(BigInt::from(179426879))
+            .g(BigInt::from(89713439))
+            .G(BigInt::from(2))
+            .min_bits(16)
+            .build()
+            .unwrap();
+        let mut rng = rand::thread_rng();
+        let mut points = points(&vss, &[1234, 99, 7], &[1, 2, 3, 5, 8, 13]);
+
+        assert!(is_low_degree(&vss, &points, 3, &mut rng));
+        assert!(!is_low_degree(&vss, &points, 2, &mut rng));
+        assert!(is_low_degree(&vss, &points[..3], 3, &mut rng));
+
+        points[4].1 = (&points[4].1 * &vss.g) % &vss.q;
+
+        assert!(!is_low_degree(&vss, &points, 3, &mut rng));
+        assert!(is_low_degree(&vss, &points[..4], 3, &mut rng));
+    }
+}
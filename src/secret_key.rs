@@ -0,0 +1,99 @@
+use std::fmt;
+
+use num_bigint::{BigInt, Sign};
+use num_traits::Zero;
+use secrecy::{zeroize::Zeroize, CloneableSecret, DebugSecret, ExposeSecret, Secret};
+
+// Private keys
+
+// A private key is a bare BigInt everywhere it is computed with, which makes it easy to print by
+// accident, e.g. through the derived Debug of a struct holding it. SecretKey keeps it behind
+// secrecy's Secret: reading it takes an explicit `expose()`, Debug prints a placeholder, and the
+// limbs are overwritten when the key is dropped.
+
+// num-bigint has no Zeroize, so the key is wiped by assigning zero limbs over its own buffer.
+#[derive(Clone)]
+struct Scalar(BigInt);
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        let len = self.0.bits().div_ceil(32);
+
+        self.0.assign_from_slice(Sign::Plus, &vec![0; len]);
+    }
+}
+
+impl CloneableSecret for Scalar {}
+
+impl DebugSecret for Scalar {}
+
+/// A private key. The value is only reachable through `expose()` and is wiped on drop.
+#[derive(Clone)]
+pub struct SecretKey(Secret<Scalar>);
+
+impl SecretKey {
+    pub fn new(value: BigInt) -> Self {
+        SecretKey(Secret::new(Scalar(value)))
+    }
+
+    /// The key itself, for the arithmetic that needs it.
+    pub fn expose(&self) -> &BigInt {
+        &self.0.expose_secret().0
+    }
+}
+
+impl From<BigInt> for SecretKey {
+    fn from(value: BigInt) -> Self {
+        SecretKey::new(value)
+    }
+}
+
+impl Default for SecretKey {
+    fn default() -> Self {
+        SecretKey::new(BigInt::zero())
+    }
+}
+
+impl fmt::Debug for SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretKey([REDACTED])")
+    }
+}
+
+// written like any other big integer, so stored participants keep their format
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for SecretKey {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        crate::serde_utils::bigint::serialize(self.expose(), serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SecretKey {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        crate::serde_utils::bigint::deserialize(deserializer).map(SecretKey::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_debug() {
+        let key = SecretKey::new(BigInt::from(105929));
+
+        assert_eq!(format!("{:?}", key), "SecretKey([REDACTED])");
+        assert_eq!(key.expose(), &BigInt::from(105929));
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut scalar = Scalar(BigInt::from(105929) << 100);
+
+        scalar.zeroize();
+
+        assert!(scalar.0.is_zero());
+    }
+}
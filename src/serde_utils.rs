@@ -0,0 +1,147 @@
+// serde helpers that write big integers as decimal strings, so serialized boxes stay readable
+// and map keys are valid JSON object keys
+
+// None of the integers is negative: they are group elements, exponents, positions and masks, and
+// a negative one would only make the arithmetic panic later.
+fn parse<E: serde::de::Error>(value: &str) -> Result<num_bigint::BigInt, E> {
+    let integer: num_bigint::BigInt = value
+        .parse()
+        .map_err(|_| E::custom(format!("invalid integer {}", value)))?;
+
+    if num_traits::Signed::is_negative(&integer) {
+        return Err(E::custom(format!("negative integer {}", value)));
+    }
+
+    Ok(integer)
+}
+
+pub mod bigint {
+    use num_bigint::BigInt;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        super::parse(&String::deserialize(deserializer)?)
+    }
+}
+
+pub mod bigint_vec {
+    use num_bigint::BigInt;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(values: &[BigInt], serializer: S) -> Result<S::Ok, S::Error> {
+        values
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<String>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<BigInt>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|value| super::parse(value))
+            .collect()
+    }
+}
+
+pub mod bigint_map {
+    use std::collections::BTreeMap;
+
+    use num_bigint::BigInt;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        values: &BTreeMap<BigInt, BigInt>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        values
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<BTreeMap<String, String>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<BigInt, BigInt>, D::Error> {
+        BTreeMap::<String, String>::deserialize(deserializer)?
+            .iter()
+            .map(|(key, value)| Ok((super::parse(key)?, super::parse(value)?)))
+            .collect()
+    }
+}
+
+// a position as a number where it fits in an i64, as positions were serialized before they could
+// be larger, and as a decimal string otherwise
+pub mod position {
+    use num_bigint::BigInt;
+    use num_traits::ToPrimitive;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Position {
+        Small(i64),
+        Large(String),
+    }
+
+    impl Position {
+        pub(super) fn new(value: &BigInt) -> Self {
+            match value.to_i64() {
+                Some(value) => Position::Small(value),
+                None => Position::Large(value.to_string()),
+            }
+        }
+
+        pub(super) fn parse<E: serde::de::Error>(self) -> Result<BigInt, E> {
+            match self {
+                Position::Small(value) => Ok(BigInt::from(value)),
+                Position::Large(value) => super::parse(&value),
+            }
+        }
+    }
+
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        Position::new(value).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        Position::deserialize(deserializer)?.parse()
+    }
+}
+
+// positions keyed by public key, each as in `position`
+pub mod positions {
+    use std::collections::BTreeMap;
+
+    use num_bigint::BigInt;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::position::Position;
+
+    pub fn serialize<S: Serializer>(
+        values: &BTreeMap<BigInt, BigInt>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        values
+            .iter()
+            .map(|(key, value)| (key.to_string(), Position::new(value)))
+            .collect::<BTreeMap<String, Position>>()
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BTreeMap<BigInt, BigInt>, D::Error> {
+        BTreeMap::<String, Position>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(key, value)| Ok((super::parse(&key)?, value.parse()?)))
+            .collect()
+    }
+}
@@ -0,0 +1,228 @@
+use rand::{CryptoRng, RngCore};
+
+// Session identifiers
+
+// A distribution proves its shares under a challenge that knows nothing of the ceremony it was
+// dealt for, so the same distribution, or a share box extracted from it, verifies in any other
+// ceremony over the same keys. Binding proofs to a caller's `context` fixes that, but only for
+// verifiers that are told the context out of band. A `SessionId` travels with the distribution
+// instead: the dealer's proof and the proofs of the share boxes extracted from it are hashed
+// with it, and verification always uses the one the distribution carries. A
+// distribution moved to another session, or stripped of its session, no longer verifies, and a
+// share box from one session never verifies against a distribution of another.
+
+const SESSION_TAG: &[u8] = b"vss session";
+
+/// 32 bytes naming one ceremony, drawn at random or agreed on by the participants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionId(pub [u8; 32]);
+
+impl SessionId {
+    pub fn new(bytes: [u8; 32]) -> Self {
+        SessionId(bytes)
+    }
+
+    /// A fresh identifier from the thread's generator.
+    pub fn random() -> Self {
+        SessionId::random_with_rng(&mut rand::thread_rng())
+    }
+
+    /// A fresh identifier drawn from `rng`.
+    pub fn random_with_rng(rng: &mut (impl CryptoRng + RngCore)) -> Self {
+        let mut bytes = [0; 32];
+
+        rng.fill_bytes(&mut bytes);
+
+        SessionId(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    // the context of proofs in this session: the tag and the identifier, both of fixed length,
+    // then the caller's context
+    pub(crate) fn bind(&self, context: &[u8]) -> Vec<u8> {
+        let mut bound = SESSION_TAG.to_vec();
+
+        bound.extend_from_slice(&self.0);
+        bound.extend_from_slice(context);
+
+        bound
+    }
+}
+
+impl From<[u8; 32]> for SessionId {
+    fn from(bytes: [u8; 32]) -> Self {
+        SessionId(bytes)
+    }
+}
+
+// `context` bound to `session`, or left as it is outside of sessions
+pub(crate) fn session_context(session: Option<&SessionId>, context: &[u8]) -> Vec<u8> {
+    match session {
+        Some(session) => session.bind(context),
+        None => context.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{prng::ChaChaRng, SeedableRng};
+
+    use crate::{error::VssError, sharebox::DistributionShareBox, testkit::fixed_ceremony};
+
+    use super::SessionId;
+
+    #[test]
+    fn test_session() {
+        let ceremony = fixed_ceremony(3, 2, 107).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let publickeys: Vec<_> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let mut rng = ChaChaRng::from_seed([11; 32]);
+        let session = SessionId::random_with_rng(&mut rng);
+        let other = SessionId::random_with_rng(&mut rng);
+        let distribution = ceremony
+            .dealer
+            .clone()
+            .distribute_secret_in_session_with_rng(
+                &num_bigint::BigInt::from(4242),
+                &publickeys,
+                2,
+                &session,
+                &mut rng,
+            )
+            .unwrap();
+
+        assert_ne!(session, other);
+        assert_eq!(distribution.session_id, Some(session));
+        assert!(vss.verify_distribution_shares(&distribution));
+        assert_eq!(
+            DistributionShareBox::from_bytes(&distribution.to_bytes()),
+            Ok(distribution.clone())
+        );
+
+        let shares: Vec<_> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(vss.verify_shares(&shares, &distribution), vec![true; 3]);
+        assert_eq!(
+            vss.reconstruct(&shares, &distribution),
+            Ok(num_bigint::BigInt::from(4242))
+        );
+
+        // moved to another session, or out of sessions altogether
+        let mut moved = distribution.clone();
+
+        moved.session_id = Some(other);
+
+        assert!(!vss.verify_distribution_shares(&moved));
+        assert!(!vss.verify_share(&shares[0], &moved, &publickeys[0]));
+
+        moved.session_id = None;
+
+        assert!(!vss.verify_distribution_shares(&moved));
+        assert!(!vss.verify_share(&shares[0], &moved, &publickeys[0]));
+
+        // a share box from a distribution outside of sessions does not verify in one
+        let unbound = participants[0]
+            .extract_secret_share(&moved, &participants[0].privatekey)
+            .unwrap();
+
+        assert!(!vss.verify_share(&unbound, &distribution, &publickeys[0]));
+
+        // a session identifier cut short
+        let mut bytes = distribution.to_bytes();
+
+        bytes.pop();
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&bytes),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+
+    #[test]
+    fn test_session_paths() {
+        let ceremony = fixed_ceremony(3, 2, 107).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participant = &ceremony.participants[0];
+        let publickeys: Vec<_> = ceremony
+            .participants
+            .iter()
+            .map(|p| p.publickey.clone())
+            .collect();
+        let session = SessionId::new([1; 32]);
+        let other = SessionId::new([2; 32]);
+        let (distribution, deliveries) = ceremony
+            .dealer
+            .clone()
+            .distribute_secret_in_session_with_deliveries(
+                &num_bigint::BigInt::from(4242),
+                &publickeys,
+                2,
+                &session,
+            )
+            .unwrap();
+        let delivery = &deliveries[0];
+
+        // the delivery path
+        assert_eq!(delivery.session_id, Some(session));
+        assert!(vss.verify_distribution_shares(&distribution));
+        assert!(participant.verify_share_delivery(delivery));
+
+        let sharebox = participant
+            .extract_delivered_share(delivery, &participant.privatekey)
+            .unwrap();
+
+        assert!(participant.verify_delivered_share(&sharebox, delivery));
+        assert!(vss.verify_share(&sharebox, &distribution, &publickeys[0]));
+        assert!(vss.verify_in_session(&sharebox, &delivery.encrypted_share, &session));
+        assert!(!vss.verify_in_session(&sharebox, &delivery.encrypted_share, &other));
+        assert!(!vss.verify(&sharebox, &delivery.encrypted_share));
+
+        let mut moved = delivery.clone();
+
+        moved.session_id = Some(other);
+
+        assert!(!participant.verify_share_delivery(&moved));
+        assert!(!participant.verify_delivered_share(&sharebox, &moved));
+
+        moved.session_id = None;
+
+        assert!(!participant.verify_share_delivery(&moved));
+        assert!(!participant.verify_delivered_share(&sharebox, &moved));
+
+        // the light path
+        let mut light = participant
+            .light_distribution(&distribution, &publickeys[0])
+            .unwrap();
+
+        assert_eq!(light.session_id, Some(session));
+        assert!(participant.verify_light_distribution(&light, 3));
+
+        light.session_id = Some(other);
+
+        assert!(!participant.verify_light_distribution(&light, 3));
+
+        light.session_id = None;
+
+        assert!(!participant.verify_light_distribution(&light, 3));
+
+        // the compact form keeps the session through expansion
+        let compact = participant.compact_distribution(&distribution);
+        let expanded = participant
+            .expand_distribution(&compact, &distribution.commitments)
+            .unwrap();
+
+        assert_eq!(expanded, distribution);
+        assert!(vss.verify_distribution_shares(&expanded));
+    }
+}
@@ -1,14 +1,53 @@
 use std::collections::BTreeMap;
 
 use num_bigint::BigInt;
-use num_traits::Zero;
+use num_traits::{Signed, ToPrimitive, Zero};
+use sha2::{Digest, Sha256};
 
-#[derive(Debug, Clone, Default)]
+use crate::{
+    error::VssError,
+    freshness::Freshness,
+    session::{session_context, SessionId},
+    transcript::Transcript,
+    vss::VSS,
+    wire::{self, Reader, Writer, KIND_DISTRIBUTION, KIND_SHAREBOX},
+};
+
+/// The commitments a1 = G^w and a2 = share^w of a share proof. They add nothing to the proof
+/// itself, but let `VSS::verify_shares` check the proofs of many share boxes at once.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShareCommitments {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub a1: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub a2: BigInt,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShareBox {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub publickey: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub share: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub response: BigInt,
+    /// the nonce and issue time covered by the proof, `None` for share boxes that carry none,
+    /// see `FreshnessPolicy`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub freshness: Option<Freshness>,
+    /// the commitments of the proof, `None` for share boxes from before they were included
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub commitments: Option<ShareCommitments>,
 }
 
 impl ShareBox {
@@ -18,6 +57,8 @@ impl ShareBox {
             share: BigInt::zero(),
             challenge: BigInt::zero(),
             response: BigInt::zero(),
+            freshness: None,
+            commitments: None,
         }
     }
 
@@ -27,17 +68,142 @@ impl ShareBox {
         self.challenge = challenge;
         self.response = response;
     }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.bigint(&self.publickey);
+        writer.bigint(&self.share);
+        writer.bigint(&self.challenge);
+        writer.bigint(&self.response);
+
+        if let Some(freshness) = &self.freshness {
+            writer.u8(1);
+            writer.raw(&freshness.to_bytes());
+        }
+
+        if let Some(commitments) = &self.commitments {
+            writer.u8(2);
+            writer.bigint(&commitments.a1);
+            writer.bigint(&commitments.a2);
+        }
+
+        writer.into_bytes()
+    }
+
+    /// Decodes `to_bytes`: every field as a length-prefixed big-endian magnitude, then, for
+    /// fresh share boxes, a 1 byte, the 16-byte nonce and the issue time as 8 bytes big-endian,
+    /// then, for share boxes with commitments, a 2 byte, a1 and a2. Only the canonical encoding
+    /// is accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let mut reader = Reader::new(bytes);
+        let mut sharebox = ShareBox {
+            publickey: reader.bigint()?,
+            share: reader.bigint()?,
+            challenge: reader.bigint()?,
+            response: reader.bigint()?,
+            freshness: None,
+            commitments: None,
+        };
+        let mut tag = if reader.is_empty() {
+            None
+        } else {
+            Some(reader.u8()?)
+        };
+
+        if tag == Some(1) {
+            sharebox.freshness = Some(Freshness {
+                nonce: reader.raw(16)?.try_into().unwrap(),
+                issued_at: reader.u64()?,
+            });
+            tag = if reader.is_empty() {
+                None
+            } else {
+                Some(reader.u8()?)
+            };
+        }
+
+        match tag {
+            Some(2) => {
+                sharebox.commitments = Some(ShareCommitments {
+                    a1: reader.bigint()?,
+                    a2: reader.bigint()?,
+                });
+            }
+            Some(_) => return Err(VssError::MalformedEncoding),
+            None => {}
+        }
+
+        reader.finish()?;
+
+        Ok(sharebox)
+    }
+
+    /// `to_bytes` behind a header with the format version and the `group_id` of `vss`, for
+    /// share boxes stored for later.
+    pub fn to_versioned_bytes<D: Transcript>(&self, vss: &VSS<D>) -> Vec<u8> {
+        wire::seal(KIND_SHAREBOX, &vss.group_id(), self.to_bytes())
+    }
+
+    /// Decodes `to_versioned_bytes`, failing for newer format versions and other groups. Plain
+    /// `to_bytes` encodings from before versioning are still accepted.
+    pub fn from_versioned_bytes<D: Transcript>(
+        bytes: &[u8],
+        vss: &VSS<D>,
+    ) -> Result<Self, VssError> {
+        ShareBox::from_bytes(wire::open(bytes, KIND_SHAREBOX, &vss.group_id())?)
+    }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DistributionShareBox {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
     pub commitments: Vec<BigInt>,
-    pub positions: BTreeMap<BigInt, i64>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::positions"))]
+    pub positions: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_map"))]
     pub shares: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
     pub publickeys: Vec<BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_map"))]
     pub responses: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub u: BigInt,
+    /// byte length of the secret, `None` for distributions from before arbitrary-length secrets
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub secret_len: Option<usize>,
+    /// `VSS::params_fingerprint` of the dealer, `None` for distributions from before fingerprints
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub params_fingerprint: Option<[u8; 8]>,
+    /// shares needed to reconstruct as recorded by the dealer, `None` for distributions from
+    /// before it was recorded, see `threshold()`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub threshold: Option<usize>,
+    /// number of recipients as recorded by the dealer, see `n()`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub n: Option<usize>,
+    /// the session the proofs are bound to, `None` outside of sessions, see `SessionId`
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub session_id: Option<SessionId>,
 }
 
 impl DistributionShareBox {
@@ -50,13 +216,19 @@ impl DistributionShareBox {
             challenge: BigInt::zero(),
             responses: BTreeMap::new(),
             u: BigInt::zero(),
+            secret_len: None,
+            params_fingerprint: None,
+            threshold: None,
+            n: None,
+            session_id: None,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn init(
         &mut self,
         commitments: &[BigInt],
-        positions: BTreeMap<BigInt, i64>,
+        positions: BTreeMap<BigInt, BigInt>,
         shares: BTreeMap<BigInt, BigInt>,
         publickeys: &[BigInt],
         challenge: &BigInt,
@@ -71,4 +243,513 @@ impl DistributionShareBox {
         self.responses = responses;
         self.u = u.clone();
     }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.bigint_vec(&self.commitments);
+        // positions beyond an i64 are written as 0 here and listed after everything else
+        writer.map(&self.positions, |writer, position| {
+            writer.i64(position.to_i64().unwrap_or(0))
+        });
+        writer.bigint_map(&self.shares);
+        writer.bigint_vec(&self.publickeys);
+        writer.bigint(&self.challenge);
+        writer.bigint_map(&self.responses);
+        writer.bigint(&self.u);
+
+        match self.secret_len {
+            Some(secret_len) => {
+                writer.u8(1);
+                writer.u64(secret_len as u64);
+            }
+            None => writer.u8(0),
+        }
+
+        match self.params_fingerprint {
+            Some(fingerprint) => {
+                writer.u8(1);
+                writer.raw(&fingerprint);
+            }
+            None => writer.u8(0),
+        }
+
+        match (self.threshold, self.n) {
+            (Some(threshold), Some(n)) => {
+                writer.u8(1);
+                writer.u32(threshold as u32);
+                writer.u32(n as u32);
+            }
+            _ => writer.u8(0),
+        }
+
+        let wide_positions = self.wide_positions();
+
+        if !wide_positions.is_empty() {
+            writer.u8(1);
+            writer.bigint_map(&wide_positions);
+        }
+
+        if let Some(session_id) = &self.session_id {
+            writer.u8(2);
+            writer.raw(session_id.as_bytes());
+        }
+
+        writer.into_bytes()
+    }
+
+    // the positions `to_bytes` can't write as an i64
+    fn wide_positions(&self) -> BTreeMap<BigInt, BigInt> {
+        self.positions
+            .iter()
+            .filter(|(_, position)| position.to_i64().is_none())
+            .map(|(publickey, position)| (publickey.clone(), position.clone()))
+            .collect()
+    }
+
+    /// Decodes `to_bytes`: integers as length-prefixed big-endian magnitudes, lists and maps as a
+    /// count followed by their entries in order, and `secret_len`, `params_fingerprint` and the
+    /// pair of `threshold` and `n` behind presence bytes. Positions are 8-byte big-endian, those
+    /// beyond an i64 as 0 and then by key in a map behind a 1 byte, left out when there are none,
+    /// so that encodings of smaller positions are as they always were. The 32 bytes of
+    /// `session_id` follow behind a 2 byte, left out outside of sessions. Only the canonical
+    /// encoding of a consistent distribution, see `validate`, is accepted, except that encodings
+    /// from before fingerprints end after `secret_len` and those from before the recorded shape
+    /// after the fingerprint.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let mut reader = Reader::new(bytes);
+        let mut distribution = DistributionShareBox {
+            commitments: reader.bigint_vec()?,
+            positions: reader.map(|reader| Ok(BigInt::from(reader.i64()?)))?,
+            shares: reader.bigint_map()?,
+            publickeys: reader.bigint_vec()?,
+            challenge: reader.bigint()?,
+            responses: reader.bigint_map()?,
+            u: reader.bigint()?,
+            secret_len: match reader.u8()? {
+                0 => None,
+                1 => Some(usize::try_from(reader.u64()?).map_err(|_| VssError::MalformedEncoding)?),
+                _ => return Err(VssError::MalformedEncoding),
+            },
+            params_fingerprint: None,
+            threshold: None,
+            n: None,
+            session_id: None,
+        };
+
+        if !reader.is_empty() {
+            distribution.params_fingerprint = match reader.u8()? {
+                0 => None,
+                1 => Some(reader.raw(8)?.try_into().unwrap()),
+                _ => return Err(VssError::MalformedEncoding),
+            };
+        }
+
+        if !reader.is_empty() {
+            match reader.u8()? {
+                0 => {}
+                1 => {
+                    distribution.threshold = Some(reader.u32()? as usize);
+                    distribution.n = Some(reader.u32()? as usize);
+                }
+                _ => return Err(VssError::MalformedEncoding),
+            }
+        }
+
+        let mut tag = if reader.is_empty() {
+            None
+        } else {
+            Some(reader.u8()?)
+        };
+
+        if tag == Some(1) {
+            let wide_positions = reader.bigint_map()?;
+
+            if wide_positions.is_empty() {
+                return Err(VssError::MalformedEncoding);
+            }
+
+            // each one replaces a 0, and only where an i64 would not have done
+            for (publickey, position) in wide_positions {
+                match distribution.positions.get_mut(&publickey) {
+                    Some(placeholder) if placeholder.is_zero() && position.to_i64().is_none() => {
+                        *placeholder = position
+                    }
+                    _ => return Err(VssError::MalformedEncoding),
+                }
+            }
+
+            tag = if reader.is_empty() {
+                None
+            } else {
+                Some(reader.u8()?)
+            };
+        }
+
+        match tag {
+            None => {}
+            Some(2) => {
+                distribution.session_id = Some(SessionId(reader.raw(32)?.try_into().unwrap()))
+            }
+            Some(_) => return Err(VssError::MalformedEncoding),
+        }
+
+        reader.finish()?;
+        distribution.validate()?;
+
+        Ok(distribution)
+    }
+
+    /// `to_bytes` behind a header with the format version and the `group_id` of `vss`.
+    pub fn to_versioned_bytes<D: Transcript>(&self, vss: &VSS<D>) -> Vec<u8> {
+        wire::seal(KIND_DISTRIBUTION, &vss.group_id(), self.to_bytes())
+    }
+
+    /// Decodes `to_versioned_bytes`, as `ShareBox::from_versioned_bytes`.
+    pub fn from_versioned_bytes<D: Transcript>(
+        bytes: &[u8],
+        vss: &VSS<D>,
+    ) -> Result<Self, VssError> {
+        DistributionShareBox::from_bytes(wire::open(bytes, KIND_DISTRIBUTION, &vss.group_id())?)
+    }
+
+    /// Number of share boxes needed to reconstruct: the recorded `threshold`, or for older
+    /// distributions the degree of the polynomial plus one.
+    pub fn threshold(&self) -> usize {
+        self.threshold.unwrap_or(self.commitments.len())
+    }
+
+    /// Number of recipients: the recorded `n`, or for older distributions the number of keys.
+    pub fn n(&self) -> usize {
+        self.n.unwrap_or(self.publickeys.len())
+    }
+
+    /// Checks that every position is positive, and the recorded `threshold` and `n` against each
+    /// other and against the commitments and the per-recipient entries where they are recorded.
+    pub fn validate(&self) -> Result<(), VssError> {
+        if let Some(position) = self
+            .positions
+            .values()
+            .find(|position| !position.is_positive())
+        {
+            return Err(VssError::InvalidPosition {
+                position: position.clone(),
+            });
+        }
+
+        if let Some(threshold) = self.threshold {
+            if threshold == 0 || threshold > self.n() {
+                return Err(VssError::InvalidThreshold {
+                    threshold: threshold as u32,
+                    participants: self.n(),
+                });
+            }
+
+            if threshold != self.commitments.len() {
+                return Err(VssError::InconsistentDistribution);
+            }
+        }
+
+        if let Some(n) = self.n {
+            if [
+                self.publickeys.len(),
+                self.positions.len(),
+                self.shares.len(),
+                self.responses.len(),
+            ]
+            .iter()
+            .any(|len| *len != n)
+            {
+                return Err(VssError::InconsistentDistribution);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The encoding `fingerprint` hashes, the same bytes as `to_bytes`. Two parties hold the same
+    /// distribution exactly when their canonical bytes are equal.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    /// SHA-256 of `canonical_bytes`, for recipients to compare out of band before extracting.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        Sha256::digest(&self.canonical_bytes()).into()
+    }
+
+    // the context the proofs of this distribution and of its share boxes are hashed with:
+    // `context` bound to the session, if there is one
+    pub(crate) fn proof_context(&self, context: &[u8]) -> Vec<u8> {
+        session_context(self.session_id.as_ref(), context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use num_traits::Zero;
+    use sha2::{Digest, Sha256};
+
+    use crate::{error::VssError, testkit::fixed_ceremony, wire::Writer};
+
+    use super::{DistributionShareBox, ShareBox};
+
+    #[test]
+    fn test_round_trip() {
+        let ceremony = fixed_ceremony(3, 2, 7).unwrap();
+        let mut distribution = ceremony.distribution.clone();
+
+        for sharebox in &ceremony.shares {
+            assert!(sharebox.commitments.is_some());
+            assert_eq!(
+                ShareBox::from_bytes(&sharebox.to_bytes()),
+                Ok(sharebox.clone())
+            );
+        }
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&distribution.to_bytes()),
+            Ok(distribution.clone())
+        );
+
+        distribution.secret_len = Some(32);
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&distribution.to_bytes()),
+            Ok(distribution)
+        );
+        assert_eq!(
+            ShareBox::from_bytes(&ShareBox::new().to_bytes()),
+            Ok(ShareBox::new())
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let distribution = fixed_ceremony(3, 2, 7).unwrap().distribution;
+        let mut tampered = distribution.clone();
+
+        tampered.u += 1;
+
+        assert_eq!(
+            distribution.fingerprint(),
+            distribution.clone().fingerprint()
+        );
+        assert_ne!(distribution.fingerprint(), tampered.fingerprint());
+        assert_eq!(
+            distribution.fingerprint()[..],
+            Sha256::digest(&distribution.to_bytes())[..]
+        );
+    }
+
+    #[test]
+    fn test_versioned_bytes() {
+        let ceremony = fixed_ceremony(3, 2, 7).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let sharebox = &ceremony.shares[0];
+        let bytes = sharebox.to_versioned_bytes(vss);
+
+        assert_eq!(&bytes[..7], b"VSS\0\0\x01\x01");
+        assert_eq!(
+            ShareBox::from_versioned_bytes(&bytes, vss),
+            Ok(sharebox.clone())
+        );
+        assert_eq!(
+            ShareBox::from_versioned_bytes(&sharebox.to_bytes(), vss),
+            Ok(sharebox.clone())
+        );
+        assert_eq!(
+            DistributionShareBox::from_versioned_bytes(&bytes, vss),
+            Err(VssError::MalformedEncoding)
+        );
+
+        let mut other = vss.clone();
+
+        other.G += 1;
+
+        assert_eq!(
+            ShareBox::from_versioned_bytes(&bytes, &other),
+            Err(VssError::GroupMismatch)
+        );
+
+        let mut future = bytes.clone();
+
+        future[5] = 2;
+
+        assert_eq!(
+            ShareBox::from_versioned_bytes(&future, vss),
+            Err(VssError::UnsupportedVersion { version: 2 })
+        );
+
+        let distribution = &ceremony.distribution;
+
+        assert_eq!(
+            DistributionShareBox::from_versioned_bytes(&distribution.to_versioned_bytes(vss), vss),
+            Ok(distribution.clone())
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_canonical() {
+        let mut sharebox = ShareBox::new();
+
+        sharebox.publickey = BigInt::from(0x0102);
+
+        let bytes = sharebox.to_bytes();
+
+        assert_eq!(&bytes[..6], [0, 0, 0, 2, 1, 2]);
+        assert_eq!(
+            ShareBox::from_bytes(&[&[0, 0, 0, 3, 0, 1, 2], &bytes[6..]].concat()),
+            Err(VssError::MalformedEncoding)
+        );
+        assert_eq!(
+            ShareBox::from_bytes(&[&bytes[..], &[0]].concat()),
+            Err(VssError::MalformedEncoding)
+        );
+        assert_eq!(
+            ShareBox::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(VssError::MalformedEncoding)
+        );
+
+        let mut distribution = fixed_ceremony(3, 2, 7).unwrap().distribution;
+
+        distribution.params_fingerprint = None;
+        distribution.threshold = None;
+        distribution.n = None;
+
+        let mut bytes = distribution.to_bytes();
+        let last = bytes.len() - 1;
+
+        // encodings from before fingerprints end after `secret_len`, and from before the recorded
+        // shape after the fingerprint
+        assert_eq!(
+            DistributionShareBox::from_bytes(&bytes[..last - 1]),
+            Ok(distribution.clone())
+        );
+        assert_eq!(
+            DistributionShareBox::from_bytes(&bytes[..last]),
+            Ok(distribution.clone())
+        );
+
+        bytes[last] = 2;
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&bytes),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+
+    #[test]
+    fn test_wide_positions() {
+        let distribution = fixed_ceremony(3, 2, 7).unwrap().distribution;
+        let publickey = distribution.publickeys[1].clone();
+        let mut wide = distribution.clone();
+
+        wide.positions
+            .insert(publickey.clone(), BigInt::from(1) << 64);
+
+        let bytes = wide.to_bytes();
+
+        // the narrow encoding is a prefix, with the position written as 0
+        let mut narrow = distribution.clone();
+
+        narrow.positions.insert(publickey.clone(), BigInt::zero());
+
+        assert!(bytes.starts_with(&narrow.to_bytes()));
+        assert_eq!(DistributionShareBox::from_bytes(&bytes), Ok(wide.clone()));
+        assert_eq!(
+            DistributionShareBox::from_bytes(&narrow.to_bytes()),
+            Err(VssError::InvalidPosition {
+                position: BigInt::zero()
+            })
+        );
+
+        // a wide entry must replace a 0 and not fit in an i64
+        let mut replaced = narrow.clone();
+
+        replaced
+            .positions
+            .insert(publickey.clone(), BigInt::from(9));
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(
+                &[&replaced.to_bytes()[..], &bytes[narrow.to_bytes().len()..]].concat()
+            ),
+            Err(VssError::MalformedEncoding)
+        );
+
+        let mut writer = Writer::new();
+
+        writer.u8(1);
+        writer.bigint_map(&[(publickey, BigInt::from(9))].into_iter().collect());
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&[narrow.to_bytes(), writer.into_bytes()].concat()),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_rejects_negative_json() {
+        let mut sharebox = fixed_ceremony(3, 2, 7).unwrap().shares[0].clone();
+
+        sharebox.response = -sharebox.response;
+
+        let json = serde_json::to_string(&sharebox).unwrap();
+        let error = serde_json::from_str::<ShareBox>(&json).unwrap_err();
+
+        assert!(error.to_string().starts_with("negative integer"));
+    }
+
+    #[test]
+    fn test_shape() {
+        let ceremony = fixed_ceremony(4, 3, 7).unwrap();
+        let mut distribution = ceremony.distribution.clone();
+
+        assert_eq!(distribution.threshold, Some(3));
+        assert_eq!(distribution.n, Some(4));
+        assert_eq!(distribution.validate(), Ok(()));
+
+        distribution.threshold = None;
+        distribution.n = None;
+
+        assert_eq!((distribution.threshold(), distribution.n()), (3, 4));
+
+        let mut distribution = ceremony.distribution.clone();
+
+        distribution.threshold = Some(2);
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&distribution.to_bytes()),
+            Err(VssError::InconsistentDistribution)
+        );
+        assert!(!ceremony.participants[0].verify_distribution_shares(&distribution));
+
+        distribution.threshold = Some(5);
+
+        assert_eq!(
+            distribution.validate(),
+            Err(VssError::InvalidThreshold {
+                threshold: 5,
+                participants: 4
+            })
+        );
+
+        let mut distribution = ceremony.distribution.clone();
+        let publickey = distribution.publickeys[0].clone();
+
+        distribution.shares.remove(&publickey);
+
+        assert_eq!(
+            DistributionShareBox::from_bytes(&distribution.to_bytes()),
+            Err(VssError::InconsistentDistribution)
+        );
+        assert_eq!(
+            ceremony.participants[0].reconstruct(&ceremony.shares, &distribution),
+            Err(VssError::InconsistentDistribution)
+        );
+    }
 }
@@ -0,0 +1,394 @@
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    arith,
+    error::VssError,
+    transcript::Transcript,
+    vss::VSS,
+    wire::{Reader, Writer},
+};
+
+// Sigma protocols
+
+// DLEQ statements log_g1(h1) = log_g2(h2), composed. An AND proof runs one Chaum-Pedersen proof
+// per statement under a single challenge, the shape of the proof of a distribution, whose
+// challenge predates this toolkit and is kept as it is. An OR proof (Cramer, Damgard and
+// Schoenmakers) shows that one of the statements holds without telling which: the prover
+// simulates every other statement with a challenge of its choosing, and the challenges must add
+// up to the hash, which leaves exactly one free to answer honestly.
+//
+// Fiat-Shamir hashes, under the protocol label of the `VSS`, every statement in full, generators
+// included, followed by its commitments a1 = g1^w and a2 = g2^w, in order. `DleqProof` is the AND
+// proof of a single statement.
+
+/// The statement log_g1(h1) = log_g2(h2).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DleqStatement {
+    pub g1: BigInt,
+    pub h1: BigInt,
+    pub g2: BigInt,
+    pub h2: BigInt,
+}
+
+/// Proofs of several statements under one challenge.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AndProof {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    /// one per statement, in order
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub responses: Vec<BigInt>,
+}
+
+/// A proof that at least one of several statements holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrProof {
+    /// one per statement, summing to the hash
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub challenges: Vec<BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub responses: Vec<BigInt>,
+}
+
+impl DleqStatement {
+    pub fn new(g1: &BigInt, h1: &BigInt, g2: &BigInt, h2: &BigInt) -> Self {
+        DleqStatement {
+            g1: g1.clone(),
+            h1: h1.clone(),
+            g2: g2.clone(),
+            h2: h2.clone(),
+        }
+    }
+
+    fn in_group<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        [&self.g1, &self.h1, &self.g2, &self.h2]
+            .into_iter()
+            .all(|element| element > &BigInt::zero() && element < &vss.q)
+    }
+
+    // a1 = g1^w, a2 = g2^w
+    fn commit<D: Transcript>(&self, vss: &VSS<D>, w: &BigInt) -> [BigInt; 2] {
+        [
+            arith::modpow(&self.g1, w, &vss.q),
+            arith::modpow(&self.g2, w, &vss.q),
+        ]
+    }
+
+    // a1 = g1^r h1^c, a2 = g2^r h2^c, from a response and a challenge
+    fn recompute<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        response: &BigInt,
+        challenge: &BigInt,
+    ) -> [BigInt; 2] {
+        let (response, challenge) = (
+            response.mod_floor(&order(vss)),
+            challenge.mod_floor(&order(vss)),
+        );
+
+        [
+            arith::modpow(&self.g1, &response, &vss.q)
+                * arith::modpow(&self.h1, &challenge, &vss.q)
+                % &vss.q,
+            arith::modpow(&self.g2, &response, &vss.q)
+                * arith::modpow(&self.h2, &challenge, &vss.q)
+                % &vss.q,
+        ]
+    }
+}
+
+fn order<D: Transcript>(vss: &VSS<D>) -> BigInt {
+    &vss.q - BigInt::one()
+}
+
+fn random_scalar<D: Transcript>(vss: &VSS<D>, rng: &mut (impl CryptoRng + RngCore)) -> BigInt {
+    rng.gen_biguint_below(&order(vss).to_biguint().unwrap())
+        .to_bigint()
+        .unwrap()
+}
+
+/// The Fiat-Shamir challenge of `statements` with their commitments, modulo the group order.
+pub(crate) fn challenge<D: Transcript>(
+    vss: &VSS<D>,
+    statements: &[DleqStatement],
+    commitments: &[[BigInt; 2]],
+) -> BigInt {
+    let mut hasher = vss.hasher();
+
+    for (statement, [a1, a2]) in statements.iter().zip(commitments) {
+        vss.update_hash(&mut hasher, b"g1", &statement.g1);
+        vss.update_hash(&mut hasher, b"h1", &statement.h1);
+        vss.update_hash(&mut hasher, b"g2", &statement.g2);
+        vss.update_hash(&mut hasher, b"h2", &statement.h2);
+        vss.update_hash(&mut hasher, b"a1", a1);
+        vss.update_hash(&mut hasher, b"a2", a2);
+    }
+
+    BigUint::from_bytes_be(&hasher.challenge())
+        .mod_floor(&order(vss).to_biguint().unwrap())
+        .to_bigint()
+        .unwrap()
+}
+
+impl AndProof {
+    /// Proves every statement, each with its witness log_g1(h1) = log_g2(h2).
+    pub fn prove<D: Transcript>(
+        vss: &VSS<D>,
+        statements: &[(DleqStatement, BigInt)],
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Self {
+        let nonces: Vec<BigInt> = statements.iter().map(|_| random_scalar(vss, rng)).collect();
+        let commitments: Vec<[BigInt; 2]> = statements
+            .iter()
+            .zip(&nonces)
+            .map(|((statement, _), w)| statement.commit(vss, w))
+            .collect();
+        let (statements, witnesses): (Vec<DleqStatement>, Vec<&BigInt>) = statements
+            .iter()
+            .map(|(statement, witness)| (statement.clone(), witness))
+            .unzip();
+        let c = challenge(vss, &statements, &commitments);
+
+        AndProof {
+            responses: nonces
+                .iter()
+                .zip(witnesses)
+                .map(|(w, alpha)| (w - alpha * &c).mod_floor(&order(vss)))
+                .collect(),
+            challenge: c,
+        }
+    }
+
+    /// Checks the proof for `statements`, in the order they were proven in.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>, statements: &[DleqStatement]) -> bool {
+        if statements.is_empty()
+            || statements.len() != self.responses.len()
+            || !statements.iter().all(|statement| statement.in_group(vss))
+        {
+            return false;
+        }
+
+        let commitments: Vec<[BigInt; 2]> = statements
+            .iter()
+            .zip(&self.responses)
+            .map(|(statement, response)| statement.recompute(vss, response, &self.challenge))
+            .collect();
+
+        challenge(vss, statements, &commitments) == self.challenge
+    }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.bigint(&self.challenge);
+        writer.bigint_vec(&self.responses);
+
+        writer.into_bytes()
+    }
+
+    /// Decodes `to_bytes`: the challenge, then the counted responses. Only the canonical
+    /// encoding is accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let mut reader = Reader::new(bytes);
+        let proof = AndProof {
+            challenge: reader.bigint()?,
+            responses: reader.bigint_vec()?,
+        };
+
+        reader.finish()?;
+
+        Ok(proof)
+    }
+}
+
+impl OrProof {
+    /// Proves that one of `statements` holds, knowing the witness of the one at `known`.
+    ///
+    /// Panics if `known` is out of range.
+    pub fn prove<D: Transcript>(
+        vss: &VSS<D>,
+        statements: &[DleqStatement],
+        known: usize,
+        witness: &BigInt,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Self {
+        assert!(known < statements.len(), "no statement at {}", known);
+
+        let w = random_scalar(vss, rng);
+        let mut challenges: Vec<BigInt> = Vec::with_capacity(statements.len());
+        let mut responses: Vec<BigInt> = Vec::with_capacity(statements.len());
+        let mut commitments = Vec::with_capacity(statements.len());
+
+        // the others are simulated from a challenge and a response picked first
+        for (i, statement) in statements.iter().enumerate() {
+            if i == known {
+                challenges.push(BigInt::zero());
+                responses.push(BigInt::zero());
+                commitments.push(statement.commit(vss, &w));
+            } else {
+                let (c, r) = (random_scalar(vss, rng), random_scalar(vss, rng));
+
+                commitments.push(statement.recompute(vss, &r, &c));
+                challenges.push(c);
+                responses.push(r);
+            }
+        }
+
+        let simulated = challenges.iter().fold(BigInt::zero(), |sum, c| sum + c);
+        let c = (challenge(vss, statements, &commitments) - simulated).mod_floor(&order(vss));
+
+        responses[known] = (w - witness * &c).mod_floor(&order(vss));
+        challenges[known] = c;
+
+        OrProof {
+            challenges,
+            responses,
+        }
+    }
+
+    /// Checks that one of `statements` holds, in the order they were proven in.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>, statements: &[DleqStatement]) -> bool {
+        if statements.is_empty()
+            || statements.len() != self.challenges.len()
+            || statements.len() != self.responses.len()
+            || !statements.iter().all(|statement| statement.in_group(vss))
+        {
+            return false;
+        }
+
+        let commitments: Vec<[BigInt; 2]> = statements
+            .iter()
+            .zip(self.responses.iter().zip(&self.challenges))
+            .map(|(statement, (response, c))| statement.recompute(vss, response, c))
+            .collect();
+        let sum = self
+            .challenges
+            .iter()
+            .fold(BigInt::zero(), |sum, c| sum + c)
+            .mod_floor(&order(vss));
+
+        challenge(vss, statements, &commitments) == sum
+    }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.bigint_vec(&self.challenges);
+        writer.bigint_vec(&self.responses);
+
+        writer.into_bytes()
+    }
+
+    /// Decodes `to_bytes`: the counted challenges, then as many responses. Only the canonical
+    /// encoding is accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let mut reader = Reader::new(bytes);
+        let proof = OrProof {
+            challenges: reader.bigint_vec()?,
+            responses: reader.bigint_vec()?,
+        };
+
+        reader.finish()?;
+
+        if proof.challenges.len() != proof.responses.len() {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+    use rand::{prng::ChaChaRng, SeedableRng};
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::{AndProof, DleqStatement, OrProof};
+
+    #[test]
+    fn test_and_proof() {
+        let ceremony = fixed_ceremony(3, 2, 101).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut rng = ChaChaRng::from_seed([3; 32]);
+        let statements: Vec<(DleqStatement, BigInt)> = [11, 13, 17]
+            .iter()
+            .map(|&alpha| {
+                let alpha = BigInt::from(alpha);
+                let h2 = vss.exp_G(&alpha);
+
+                (
+                    DleqStatement::new(&vss.g, &vss.exp_g(&alpha), &vss.G, &h2),
+                    alpha,
+                )
+            })
+            .collect();
+        let proof = AndProof::prove(vss, &statements, &mut rng);
+        let mut public: Vec<DleqStatement> = statements.into_iter().map(|(s, _)| s).collect();
+
+        assert!(proof.verify(vss, &public));
+        assert!(!proof.verify(vss, &public[..2]));
+
+        public.swap(0, 1);
+
+        assert!(!proof.verify(vss, &public));
+        assert_eq!(AndProof::from_bytes(&proof.to_bytes()), Ok(proof));
+    }
+
+    #[test]
+    fn test_or_proof() {
+        let ceremony = fixed_ceremony(3, 2, 103).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut rng = ChaChaRng::from_seed([5; 32]);
+        let alpha = BigInt::from(29);
+        // only the second statement holds
+        let statements = [
+            DleqStatement::new(
+                &vss.g,
+                &vss.exp_g(&alpha),
+                &vss.G,
+                &vss.exp_G(&(&alpha + 1)),
+            ),
+            DleqStatement::new(&vss.g, &vss.exp_g(&alpha), &vss.G, &vss.exp_G(&alpha)),
+            DleqStatement::new(
+                &vss.g,
+                &vss.exp_g(&BigInt::from(2)),
+                &vss.G,
+                &vss.exp_G(&BigInt::from(3)),
+            ),
+        ];
+        let proof = OrProof::prove(vss, &statements, 1, &alpha, &mut rng);
+
+        assert!(proof.verify(vss, &statements));
+        // an honest-looking proof for a statement that does not hold does not verify
+        assert!(!OrProof::prove(vss, &statements, 0, &alpha, &mut rng).verify(vss, &statements));
+        assert!(!proof.verify(vss, &statements[..2]));
+
+        let mut forged = proof.clone();
+
+        forged.challenges[0] += 1;
+
+        assert!(!forged.verify(vss, &statements));
+
+        let bytes = proof.to_bytes();
+
+        assert_eq!(OrProof::from_bytes(&bytes), Ok(proof.clone()));
+
+        // a response short
+        forged = proof;
+        forged.responses.pop();
+
+        assert_eq!(
+            OrProof::from_bytes(&forged.to_bytes()),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+
+use crate::{
+    error::VssError,
+    sharebox::{DistributionShareBox, ShareBox},
+    wire::{Reader, Writer},
+};
+
+// Signed share boxes
+
+// The proofs of a distribution and of a share box show that the shares are consistent, not who
+// sent them: anybody can deal a valid distribution to the recipients' keys, and a share box proves
+// the decryption under the public key it names but is as good from whoever relays it. Ed25519
+// signatures from keys known to the other parties close that gap: the dealer signs the
+// distribution's fingerprint and every shareholder its share box, and a receiver checks the
+// signature against the key it expects of the sender before anything else. The signed encodings
+// are the signer's key and the signature, followed by the encoding of what was signed.
+
+const SHAREBOX_TAG: &[u8] = b"vss signed share box";
+const DISTRIBUTION_TAG: &[u8] = b"vss signed distribution";
+
+/// A share box signed by its holder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignedShareBox {
+    pub sharebox: ShareBox,
+    pub signer: VerifyingKey,
+    pub signature: Signature,
+}
+
+/// A distribution signed by its dealer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignedDistribution {
+    pub distribution: DistributionShareBox,
+    pub signer: VerifyingKey,
+    pub signature: Signature,
+}
+
+fn sharebox_message(sharebox: &ShareBox) -> Vec<u8> {
+    let mut message = SHAREBOX_TAG.to_vec();
+
+    message.extend(sharebox.to_bytes());
+
+    message
+}
+
+fn distribution_message(distribution: &DistributionShareBox) -> Vec<u8> {
+    let mut message = DISTRIBUTION_TAG.to_vec();
+
+    message.extend_from_slice(&distribution.fingerprint());
+
+    message
+}
+
+fn write_signature(signer: &VerifyingKey, signature: &Signature, body: Vec<u8>) -> Vec<u8> {
+    let mut writer = Writer::new();
+
+    writer.raw(signer.as_bytes());
+    writer.raw(&signature.to_bytes());
+    writer.raw(&body);
+
+    writer.into_bytes()
+}
+
+// the signer and the signature, and what follows them
+fn read_signature(bytes: &[u8]) -> Result<(VerifyingKey, Signature, &[u8]), VssError> {
+    let mut reader = Reader::new(bytes);
+    let signer = VerifyingKey::from_bytes(reader.raw(32)?.try_into().unwrap())
+        .map_err(|_| VssError::MalformedEncoding)?;
+    let signature = Signature::from_bytes(reader.raw(64)?.try_into().unwrap());
+
+    Ok((signer, signature, &bytes[96..]))
+}
+
+impl SignedShareBox {
+    pub fn sign(sharebox: &ShareBox, key: &SigningKey) -> Self {
+        SignedShareBox {
+            sharebox: sharebox.clone(),
+            signer: key.verifying_key(),
+            signature: key.sign(&sharebox_message(sharebox)),
+        }
+    }
+
+    /// Whether `signer`, the key expected of the share box's holder, signed it. The share box
+    /// itself still has to be verified against the distribution.
+    pub fn verify(&self, signer: &VerifyingKey) -> bool {
+        &self.signer == signer
+            && signer
+                .verify_strict(&sharebox_message(&self.sharebox), &self.signature)
+                .is_ok()
+    }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        write_signature(&self.signer, &self.signature, self.sharebox.to_bytes())
+    }
+
+    /// Decodes `to_bytes`: the 32-byte key of the signer, the 64-byte signature, then the share
+    /// box as in `ShareBox::from_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let (signer, signature, body) = read_signature(bytes)?;
+
+        Ok(SignedShareBox {
+            sharebox: ShareBox::from_bytes(body)?,
+            signer,
+            signature,
+        })
+    }
+}
+
+impl SignedDistribution {
+    pub fn sign(distribution: &DistributionShareBox, key: &SigningKey) -> Self {
+        SignedDistribution {
+            distribution: distribution.clone(),
+            signer: key.verifying_key(),
+            signature: key.sign(&distribution_message(distribution)),
+        }
+    }
+
+    /// Whether `signer`, the key expected of the dealer, signed the distribution. Its proofs
+    /// still have to be verified.
+    pub fn verify(&self, signer: &VerifyingKey) -> bool {
+        &self.signer == signer
+            && signer
+                .verify_strict(&distribution_message(&self.distribution), &self.signature)
+                .is_ok()
+    }
+
+    /// The compact binary encoding, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        write_signature(&self.signer, &self.signature, self.distribution.to_bytes())
+    }
+
+    /// Decodes `to_bytes`: the 32-byte key of the signer, the 64-byte signature, then the
+    /// distribution as in `DistributionShareBox::from_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let (signer, signature, body) = read_signature(bytes)?;
+
+        Ok(SignedDistribution {
+            distribution: DistributionShareBox::from_bytes(body)?,
+            signer,
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    use super::{SignedDistribution, SignedShareBox};
+
+    #[test]
+    fn test_signed_share_boxes() {
+        let ceremony = fixed_ceremony(3, 2, 113).unwrap();
+        let dealer_key = SigningKey::from_bytes(&[1; 32]);
+        let holder_key = SigningKey::from_bytes(&[2; 32]);
+        let signed = SignedDistribution::sign(&ceremony.distribution, &dealer_key);
+
+        assert!(signed.verify(&dealer_key.verifying_key()));
+        assert!(!signed.verify(&holder_key.verifying_key()));
+        assert_eq!(
+            SignedDistribution::from_bytes(&signed.to_bytes()),
+            Ok(signed.clone())
+        );
+
+        // tampered on the way
+        let mut tampered = signed.clone();
+
+        tampered.distribution.u += 1;
+
+        assert!(!tampered.verify(&dealer_key.verifying_key()));
+
+        let sharebox = &ceremony.shares[0];
+        let signed = SignedShareBox::sign(sharebox, &holder_key);
+
+        assert!(signed.verify(&holder_key.verifying_key()));
+        assert_eq!(
+            SignedShareBox::from_bytes(&signed.to_bytes()),
+            Ok(signed.clone())
+        );
+
+        // resigned by somebody else under the holder's share box
+        let impersonated = SignedShareBox::sign(sharebox, &dealer_key);
+
+        assert!(!impersonated.verify(&holder_key.verifying_key()));
+
+        let mut tampered = signed.clone();
+
+        tampered.sharebox.share += 1;
+
+        assert!(!tampered.verify(&holder_key.verifying_key()));
+        assert_eq!(
+            SignedShareBox::from_bytes(&signed.to_bytes()[..95]),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+}
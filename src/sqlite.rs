@@ -0,0 +1,383 @@
+use std::{
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{params, Connection, OptionalExtension, Params};
+
+use crate::{
+    participant::Participant,
+    sharebox::{DistributionShareBox, ShareBox},
+    store::VssStore,
+};
+
+// SQLite backed store for long-running coordinators. Boxes are kept as JSON next to the columns
+// needed to query them, and every write is appended to an audit log.
+
+const SCHEMA_V1: &str = "
+    CREATE TABLE ceremonies (
+        id TEXT PRIMARY KEY,
+        distribution TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE TABLE shares (
+        id TEXT PRIMARY KEY,
+        publickey TEXT NOT NULL,
+        sharebox TEXT NOT NULL,
+        received_at INTEGER NOT NULL
+    );
+    CREATE TABLE participants (
+        id TEXT PRIMARY KEY,
+        publickey TEXT NOT NULL,
+        state TEXT NOT NULL
+    );
+    CREATE TABLE audit_log (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        at INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        item_id TEXT NOT NULL
+    );";
+
+// each entry upgrades the schema by one version, tracked with `PRAGMA user_version`
+const MIGRATIONS: &[&str] = &[SCHEMA_V1];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub seq: i64,
+    pub at: i64,
+    pub action: String,
+    pub kind: String,
+    pub item_id: String,
+}
+
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        SqliteStore::migrate(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        SqliteStore::migrate(Connection::open_in_memory()?)
+    }
+
+    fn migrate(mut connection: Connection) -> rusqlite::Result<Self> {
+        let version: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        // written by a newer release, whose schema this one would misread
+        if version > MIGRATIONS.len() {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_ERROR),
+                Some(format!(
+                    "schema version {} is newer than the supported {}",
+                    version,
+                    MIGRATIONS.len()
+                )),
+            ));
+        }
+
+        let transaction = connection.transaction()?;
+
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", i + 1)?;
+        }
+
+        transaction.commit()?;
+
+        Ok(SqliteStore { connection })
+    }
+
+    pub fn schema_version(&self) -> rusqlite::Result<usize> {
+        self.connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+    }
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs() as i64)
+            .unwrap_or(0)
+    }
+
+    fn to_json<T: serde::Serialize>(value: &T) -> rusqlite::Result<String> {
+        serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(e.into()))
+    }
+
+    fn from_json<T: serde::de::DeserializeOwned>(
+        json: Option<String>,
+    ) -> rusqlite::Result<Option<T>> {
+        match json {
+            Some(json) => serde_json::from_str(&json).map(Some).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, e.into())
+            }),
+            None => Ok(None),
+        }
+    }
+
+    fn audit(
+        connection: &Connection,
+        action: &str,
+        kind: &str,
+        item_id: &str,
+    ) -> rusqlite::Result<()> {
+        connection.execute(
+            "INSERT INTO audit_log (at, action, kind, item_id) VALUES (?1, ?2, ?3, ?4)",
+            params![SqliteStore::now(), action, kind, item_id],
+        )?;
+
+        Ok(())
+    }
+
+    // writes a row and its audit entry in one transaction, so that neither lands without the other
+    fn put<P: Params>(
+        &mut self,
+        sql: &str,
+        params: P,
+        kind: &str,
+        id: &str,
+    ) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+
+        transaction.execute(sql, params)?;
+        SqliteStore::audit(&transaction, "put", kind, id)?;
+
+        transaction.commit()
+    }
+
+    fn ids(&self, sql: &str) -> rusqlite::Result<Vec<String>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let ids = statement.query_map([], |row| row.get(0))?;
+
+        ids.collect()
+    }
+
+    pub fn ceremony_ids(&self) -> rusqlite::Result<Vec<String>> {
+        self.ids("SELECT id FROM ceremonies ORDER BY created_at, id")
+    }
+
+    pub fn share_ids(&self) -> rusqlite::Result<Vec<String>> {
+        self.ids("SELECT id FROM shares ORDER BY received_at, id")
+    }
+
+    // ids of the share boxes received from the holder of `publickey`
+    pub fn shares_from(&self, publickey: &num_bigint::BigInt) -> rusqlite::Result<Vec<String>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT id FROM shares WHERE publickey = ?1 ORDER BY received_at, id")?;
+        let ids = statement.query_map([publickey.to_string()], |row| row.get(0))?;
+
+        ids.collect()
+    }
+
+    pub fn audit_log(&self) -> rusqlite::Result<Vec<AuditEntry>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT seq, at, action, kind, item_id FROM audit_log ORDER BY seq")?;
+        let entries = statement.query_map([], |row| {
+            Ok(AuditEntry {
+                seq: row.get(0)?,
+                at: row.get(1)?,
+                action: row.get(2)?,
+                kind: row.get(3)?,
+                item_id: row.get(4)?,
+            })
+        })?;
+
+        entries.collect()
+    }
+}
+
+impl VssStore for SqliteStore {
+    type Error = rusqlite::Error;
+
+    fn put_distribution(
+        &mut self,
+        id: &str,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), Self::Error> {
+        self.put(
+            "INSERT OR REPLACE INTO ceremonies (id, distribution, created_at) VALUES (?1, ?2, ?3)",
+            params![
+                id,
+                SqliteStore::to_json(distribution_sharebox)?,
+                SqliteStore::now()
+            ],
+            "distribution",
+            id,
+        )
+    }
+
+    fn get_distribution(&self, id: &str) -> Result<Option<DistributionShareBox>, Self::Error> {
+        SqliteStore::from_json(
+            self.connection
+                .query_row(
+                    "SELECT distribution FROM ceremonies WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        )
+    }
+
+    fn put_share(&mut self, id: &str, sharebox: &ShareBox) -> Result<(), Self::Error> {
+        self.put(
+            "INSERT OR REPLACE INTO shares (id, publickey, sharebox, received_at) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                id,
+                sharebox.publickey.to_string(),
+                SqliteStore::to_json(sharebox)?,
+                SqliteStore::now()
+            ],
+            "share",
+            id,
+        )
+    }
+
+    fn get_share(&self, id: &str) -> Result<Option<ShareBox>, Self::Error> {
+        SqliteStore::from_json(
+            self.connection
+                .query_row("SELECT sharebox FROM shares WHERE id = ?1", [id], |row| {
+                    row.get(0)
+                })
+                .optional()?,
+        )
+    }
+
+    fn put_participant(&mut self, id: &str, participant: &Participant) -> Result<(), Self::Error> {
+        self.put(
+            "INSERT OR REPLACE INTO participants (id, publickey, state) VALUES (?1, ?2, ?3)",
+            params![
+                id,
+                participant.publickey.to_string(),
+                SqliteStore::to_json(participant)?
+            ],
+            "participant",
+            id,
+        )
+    }
+
+    fn get_participant(&self, id: &str) -> Result<Option<Participant>, Self::Error> {
+        SqliteStore::from_json(
+            self.connection
+                .query_row(
+                    "SELECT state FROM participants WHERE id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()?,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{
+        participant::Participant,
+        secret_key::SecretKey,
+        sharebox::{DistributionShareBox, ShareBox},
+        store::VssStore,
+    };
+
+    use super::{SqliteStore, MIGRATIONS};
+
+    #[test]
+    fn test_migrations() {
+        let store = SqliteStore::open_in_memory().unwrap();
+
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+
+        // reopening an up to date database is a no-op
+        let store = SqliteStore::migrate(store.connection).unwrap();
+
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len());
+
+        // a database from a newer release is refused
+        store
+            .connection
+            .pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+
+        assert!(SqliteStore::migrate(store.connection).is_err());
+    }
+
+    #[test]
+    fn test_ceremonies_and_participants() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let mut distribution = DistributionShareBox::new();
+        let mut participant = Participant::new();
+
+        distribution.commitments = vec![BigInt::from(1), BigInt::from(2)];
+        participant.privatekey = SecretKey::new(BigInt::from(5));
+        participant.publickey = BigInt::from(6);
+
+        store.put_distribution("ceremony", &distribution).unwrap();
+        store.put_participant("alice", &participant).unwrap();
+
+        assert_eq!(store.ceremony_ids().unwrap(), vec!["ceremony"]);
+        assert_eq!(
+            store
+                .get_distribution("ceremony")
+                .unwrap()
+                .unwrap()
+                .commitments,
+            distribution.commitments
+        );
+        assert_eq!(
+            store
+                .get_participant("alice")
+                .unwrap()
+                .unwrap()
+                .privatekey
+                .expose(),
+            participant.privatekey.expose()
+        );
+        assert!(store.get_distribution("other").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_shares_and_audit_log() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let mut sharebox = ShareBox::new();
+
+        sharebox.init(
+            BigInt::from(11),
+            BigInt::from(21),
+            BigInt::from(22),
+            BigInt::from(23),
+        );
+
+        store.put_share("ceremony-1", &sharebox).unwrap();
+        store.put_share("ceremony-2", &sharebox).unwrap();
+
+        let stored = store.get_share("ceremony-1").unwrap().unwrap();
+
+        assert_eq!(stored.share, sharebox.share);
+        assert!(store.get_share("ceremony-3").unwrap().is_none());
+        assert_eq!(
+            store.shares_from(&BigInt::from(11)).unwrap(),
+            vec!["ceremony-1", "ceremony-2"]
+        );
+        assert!(store.shares_from(&BigInt::from(12)).unwrap().is_empty());
+
+        let log = store.audit_log().unwrap();
+
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].kind, "share");
+        assert_eq!(log[1].item_id, "ceremony-2");
+
+        // a write whose audit entry fails is rolled back with it
+        store
+            .connection
+            .execute("DROP TABLE audit_log", [])
+            .unwrap();
+
+        assert!(store.put_share("ceremony-3", &sharebox).is_err());
+        assert!(store.get_share("ceremony-3").unwrap().is_none());
+    }
+}
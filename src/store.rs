@@ -0,0 +1,301 @@
+use std::{collections::BTreeMap, convert::Infallible};
+
+use crate::{
+    participant::Participant,
+    sharebox::{DistributionShareBox, ShareBox},
+};
+
+/// Persistence of distributions, share boxes and participant state, keyed by caller-chosen ids.
+pub trait VssStore {
+    type Error;
+
+    fn put_distribution(
+        &mut self,
+        id: &str,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), Self::Error>;
+
+    fn get_distribution(&self, id: &str) -> Result<Option<DistributionShareBox>, Self::Error>;
+
+    fn put_share(&mut self, id: &str, sharebox: &ShareBox) -> Result<(), Self::Error>;
+
+    fn get_share(&self, id: &str) -> Result<Option<ShareBox>, Self::Error>;
+
+    fn put_participant(&mut self, id: &str, participant: &Participant) -> Result<(), Self::Error>;
+
+    fn get_participant(&self, id: &str) -> Result<Option<Participant>, Self::Error>;
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    distributions: BTreeMap<String, DistributionShareBox>,
+    shares: BTreeMap<String, ShareBox>,
+    participants: BTreeMap<String, Participant>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        MemoryStore {
+            distributions: BTreeMap::new(),
+            shares: BTreeMap::new(),
+            participants: BTreeMap::new(),
+        }
+    }
+}
+
+impl VssStore for MemoryStore {
+    type Error = Infallible;
+
+    fn put_distribution(
+        &mut self,
+        id: &str,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), Self::Error> {
+        self.distributions
+            .insert(id.to_string(), distribution_sharebox.clone());
+
+        Ok(())
+    }
+
+    fn get_distribution(&self, id: &str) -> Result<Option<DistributionShareBox>, Self::Error> {
+        Ok(self.distributions.get(id).cloned())
+    }
+
+    fn put_share(&mut self, id: &str, sharebox: &ShareBox) -> Result<(), Self::Error> {
+        self.shares.insert(id.to_string(), sharebox.clone());
+
+        Ok(())
+    }
+
+    fn get_share(&self, id: &str) -> Result<Option<ShareBox>, Self::Error> {
+        Ok(self.shares.get(id).cloned())
+    }
+
+    fn put_participant(&mut self, id: &str, participant: &Participant) -> Result<(), Self::Error> {
+        self.participants
+            .insert(id.to_string(), participant.clone());
+
+        Ok(())
+    }
+
+    fn get_participant(&self, id: &str) -> Result<Option<Participant>, Self::Error> {
+        Ok(self.participants.get(id).cloned())
+    }
+}
+
+/// Stores every entry as a JSON file below `root`, in one directory per kind.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    root: std::path::PathBuf,
+}
+
+#[cfg(feature = "serde")]
+impl FileStore {
+    pub fn new<P: Into<std::path::PathBuf>>(root: P) -> Self {
+        FileStore { root: root.into() }
+    }
+
+    fn path(&self, kind: &str, id: &str) -> std::io::Result<std::path::PathBuf> {
+        if id.is_empty() || id.starts_with('.') || id.contains(['/', '\\']) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid id {:?}", id),
+            ));
+        }
+
+        Ok(self.root.join(kind).join(format!("{}.json", id)))
+    }
+
+    fn write<T: serde::Serialize>(&self, kind: &str, id: &str, value: &T) -> std::io::Result<()> {
+        let path = self.path(kind, id)?;
+
+        std::fs::create_dir_all(self.root.join(kind))?;
+
+        let json = serde_json::to_vec_pretty(value)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        let tmp = path.with_extension("json.tmp");
+
+        // participants carry their private key, so every entry is created readable by the owner
+        // only, and a leftover temporary file is not reused with whatever mode it has
+        match std::fs::remove_file(&tmp) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+
+        let mut options = std::fs::OpenOptions::new();
+
+        options.write(true).create_new(true);
+
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let mut file = options.open(&tmp)?;
+
+        std::io::Write::write_all(&mut file, &json)?;
+        file.sync_all()?;
+        std::fs::rename(tmp, path)
+    }
+
+    fn read<T: serde::de::DeserializeOwned>(
+        &self,
+        kind: &str,
+        id: &str,
+    ) -> std::io::Result<Option<T>> {
+        match std::fs::read(self.path(kind, id)?) {
+            Ok(json) => serde_json::from_slice(&json)
+                .map(Some)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl VssStore for FileStore {
+    type Error = std::io::Error;
+
+    fn put_distribution(
+        &mut self,
+        id: &str,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), Self::Error> {
+        self.write("distributions", id, distribution_sharebox)
+    }
+
+    fn get_distribution(&self, id: &str) -> Result<Option<DistributionShareBox>, Self::Error> {
+        self.read("distributions", id)
+    }
+
+    fn put_share(&mut self, id: &str, sharebox: &ShareBox) -> Result<(), Self::Error> {
+        self.write("shares", id, sharebox)
+    }
+
+    fn get_share(&self, id: &str) -> Result<Option<ShareBox>, Self::Error> {
+        self.read("shares", id)
+    }
+
+    fn put_participant(&mut self, id: &str, participant: &Participant) -> Result<(), Self::Error> {
+        self.write("participants", id, participant)
+    }
+
+    fn get_participant(&self, id: &str) -> Result<Option<Participant>, Self::Error> {
+        self.read("participants", id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use num_bigint::BigInt;
+
+    use crate::{
+        participant::Participant,
+        secret_key::SecretKey,
+        sharebox::{DistributionShareBox, ShareBox},
+    };
+
+    use super::{MemoryStore, VssStore};
+
+    fn distribution() -> DistributionShareBox {
+        let mut positions = BTreeMap::new();
+        let mut shares = BTreeMap::new();
+        let mut responses = BTreeMap::new();
+
+        positions.insert(BigInt::from(11), BigInt::from(1));
+        shares.insert(BigInt::from(11), BigInt::from(12));
+        responses.insert(BigInt::from(11), BigInt::from(13));
+
+        let mut distribution_share_box = DistributionShareBox::new();
+
+        distribution_share_box.init(
+            &[BigInt::from(1), BigInt::from(2)],
+            positions,
+            shares,
+            &[BigInt::from(11)],
+            &BigInt::from(3),
+            responses,
+            &BigInt::from(4),
+        );
+
+        distribution_share_box
+    }
+
+    fn share() -> ShareBox {
+        let mut share_box = ShareBox::new();
+
+        share_box.init(
+            BigInt::from(11),
+            BigInt::from(21),
+            BigInt::from(22),
+            BigInt::from(23),
+        );
+
+        share_box
+    }
+
+    fn round_trip<S: VssStore>(store: &mut S)
+    where
+        S::Error: std::fmt::Debug,
+    {
+        let mut participant = Participant::new();
+
+        participant.privatekey = SecretKey::new(BigInt::from(5));
+        participant.publickey = BigInt::from(6);
+
+        assert!(store.get_distribution("ceremony").unwrap().is_none());
+
+        store.put_distribution("ceremony", &distribution()).unwrap();
+        store.put_share("ceremony-1", &share()).unwrap();
+        store.put_participant("alice", &participant).unwrap();
+
+        let stored = store.get_distribution("ceremony").unwrap().unwrap();
+
+        assert_eq!(stored.commitments, distribution().commitments);
+        assert_eq!(stored.positions, distribution().positions);
+        assert_eq!(stored.shares, distribution().shares);
+        assert_eq!(stored.responses, distribution().responses);
+        assert_eq!(stored.u, distribution().u);
+
+        let stored = store.get_share("ceremony-1").unwrap().unwrap();
+
+        assert_eq!(stored.share, share().share);
+        assert_eq!(stored.response, share().response);
+
+        let stored = store.get_participant("alice").unwrap().unwrap();
+
+        assert_eq!(stored.privatekey.expose(), participant.privatekey.expose());
+        assert_eq!(stored.publickey, participant.publickey);
+        assert!(store.get_participant("bob").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_memory_store() {
+        round_trip(&mut MemoryStore::new());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_file_store() {
+        let root = std::env::temp_dir().join(format!("rust-vss-store-{}", std::process::id()));
+        let mut store = super::FileStore::new(&root);
+
+        round_trip(&mut store);
+
+        assert!(store.put_share("../escape", &share()).is_err());
+        assert!(!root.join("participants/alice.json.tmp").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let metadata = std::fs::metadata(root.join("participants/alice.json")).unwrap();
+
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
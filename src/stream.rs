@@ -0,0 +1,283 @@
+use std::io::{self, Read};
+
+use chacha20poly1305::{
+    aead::{Aead, Payload},
+    ChaCha20Poly1305,
+};
+use num_bigint::BigInt;
+use rand::RngCore;
+
+use crate::{
+    error::VssError,
+    hybrid::{self, cipher},
+    participant::Participant,
+    registry::TranscriptRegistry,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+};
+
+// Streaming encryption
+
+// Files too large for memory are encrypted in chunks with the STREAM construction: a random 7-byte
+// nonce prefix is written first, then every 64 KiB chunk is sealed with ChaCha20-Poly1305 under
+// the nonce prefix || chunk counter (4 bytes) || last-chunk flag (1 byte). Reordered, dropped or
+// truncated chunks fail to authenticate. As with `SealedPayload`, only the key is shared, and the
+// distribution's transcript hash is authenticated with every chunk.
+//
+// The decrypting reader hands out each chunk once it authenticates, before the end of the stream
+// is reached; a caller must treat the output as incomplete until the reader returns end of file.
+
+const CHUNK_LEN: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+const PREFIX_LEN: usize = 7;
+
+struct Chunks {
+    cipher: ChaCha20Poly1305,
+    aad: Vec<u8>,
+    prefix: [u8; PREFIX_LEN],
+    counter: u32,
+}
+
+impl Chunks {
+    fn nonce(&self, last: bool) -> [u8; 12] {
+        let mut nonce = [0; 12];
+
+        nonce[..PREFIX_LEN].copy_from_slice(&self.prefix);
+        nonce[PREFIX_LEN..11].copy_from_slice(&self.counter.to_be_bytes());
+        nonce[11] = last as u8;
+
+        nonce
+    }
+
+    fn advance(&mut self) -> io::Result<()> {
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| io::Error::other("stream exceeds the maximum number of chunks"))?;
+
+        Ok(())
+    }
+
+    fn seal(&mut self, chunk: &[u8], last: bool) -> io::Result<Vec<u8>> {
+        let payload = Payload {
+            msg: chunk,
+            aad: &self.aad,
+        };
+        let sealed = self
+            .cipher
+            .encrypt(&self.nonce(last).into(), payload)
+            .map_err(|_| io::Error::other("chunk encryption failed"))?;
+
+        self.advance()?;
+
+        Ok(sealed)
+    }
+
+    fn open(&mut self, chunk: &[u8], last: bool) -> io::Result<Vec<u8>> {
+        let payload = Payload {
+            msg: chunk,
+            aad: &self.aad,
+        };
+        let opened = self
+            .cipher
+            .decrypt(&self.nonce(last).into(), payload)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, VssError::InvalidCiphertext))?;
+
+        self.advance()?;
+
+        Ok(opened)
+    }
+}
+
+// reads up to `len` bytes, fewer only at the end of `reader`
+fn read_chunk<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut chunk = Vec::with_capacity(len);
+
+    reader.take(len as u64).read_to_end(&mut chunk)?;
+
+    Ok(chunk)
+}
+
+// Chunks are processed one ahead, since only the chunk after it tells whether one is the last.
+struct ChunkReader<R> {
+    inner: R,
+    chunks: Chunks,
+    encrypt: bool,
+    pending: Option<Vec<u8>>,
+    output: Vec<u8>,
+    position: usize,
+    done: bool,
+}
+
+impl<R: Read> ChunkReader<R> {
+    fn input_len(&self) -> usize {
+        if self.encrypt {
+            CHUNK_LEN
+        } else {
+            CHUNK_LEN + TAG_LEN
+        }
+    }
+
+    fn refill(&mut self) -> io::Result<()> {
+        let len = self.input_len();
+        let current = match self.pending.take() {
+            Some(current) => current,
+            None => read_chunk(&mut self.inner, len)?,
+        };
+        let last = current.len() < len || {
+            let next = read_chunk(&mut self.inner, len)?;
+            let last = next.is_empty();
+
+            self.pending = Some(next);
+
+            last
+        };
+
+        self.output = if self.encrypt {
+            self.chunks.seal(&current, last)?
+        } else {
+            self.chunks.open(&current, last)?
+        };
+        self.position = 0;
+        self.done = last;
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ChunkReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.output.len() {
+            if self.done {
+                return Ok(0);
+            }
+
+            self.refill()?;
+        }
+
+        let len = buf.len().min(self.output.len() - self.position);
+
+        buf[..len].copy_from_slice(&self.output[self.position..self.position + len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+pub(crate) fn split<R: Read, D: Transcript>(
+    dealer: &mut Participant<D>,
+    reader: R,
+    publickeys: &[BigInt],
+    threshold: u32,
+) -> Result<(DistributionShareBox, impl Read), VssError> {
+    let (key, distribution) = hybrid::share_key(dealer, publickeys, threshold)?;
+    let mut prefix = [0; PREFIX_LEN];
+
+    rand::thread_rng().fill_bytes(&mut prefix);
+
+    let chunks = Chunks {
+        cipher: cipher(&key),
+        aad: TranscriptRegistry::transcript_hash(&distribution)?,
+        prefix,
+        counter: 0,
+    };
+    let encrypted = ChunkReader {
+        inner: reader,
+        chunks,
+        encrypt: true,
+        pending: None,
+        output: prefix.to_vec(),
+        position: 0,
+        done: false,
+    };
+
+    Ok((distribution, encrypted))
+}
+
+pub(crate) fn reconstruct<R: Read, D: Transcript>(
+    participant: &Participant<D>,
+    share_boxes: &[ShareBox],
+    distribution: &DistributionShareBox,
+    mut reader: R,
+) -> Result<impl Read, VssError> {
+    let key = hybrid::recover_key(participant, share_boxes, distribution)?;
+    let mut prefix = [0; PREFIX_LEN];
+
+    reader
+        .read_exact(&mut prefix)
+        .map_err(|_| VssError::InvalidCiphertext)?;
+
+    let chunks = Chunks {
+        cipher: cipher(&key),
+        aad: TranscriptRegistry::transcript_hash(distribution)?,
+        prefix,
+        counter: 0,
+    };
+
+    Ok(ChunkReader {
+        inner: reader,
+        chunks,
+        encrypt: false,
+        pending: None,
+        output: Vec::new(),
+        position: 0,
+        done: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use num_bigint::BigInt;
+
+    use crate::{participant::Participant, sharebox::ShareBox, testkit::test_participant};
+
+    use super::CHUNK_LEN;
+
+    #[test]
+    fn test_split_and_reconstruct() {
+        let committee: Vec<Participant> = [7901, 4801, 1453].map(test_participant).to_vec();
+        let publickeys: Vec<BigInt> = committee.iter().map(|p| p.publickey.clone()).collect();
+        let mut dealer = test_participant(105929);
+
+        for len in [0, 1, CHUNK_LEN, 2 * CHUNK_LEN + 5] {
+            let file: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let (distribution, mut encrypted) = dealer
+                .split_reader(Cursor::new(file.clone()), &publickeys, 2)
+                .unwrap();
+            let mut ciphertext = Vec::new();
+
+            encrypted.read_to_end(&mut ciphertext).unwrap();
+
+            let share_boxes: Vec<ShareBox> = committee[..2]
+                .iter()
+                .map(|p| {
+                    p.extract_secret_share(&distribution, &p.privatekey)
+                        .unwrap()
+                })
+                .collect();
+            let read = |ciphertext: &[u8]| {
+                let mut plaintext = Vec::new();
+
+                committee[2]
+                    .reconstruct_reader(&share_boxes, &distribution, Cursor::new(ciphertext))
+                    .unwrap()
+                    .read_to_end(&mut plaintext)
+                    .map(|_| plaintext)
+            };
+
+            assert_eq!(read(&ciphertext).unwrap(), file);
+
+            // a stream cut at a chunk boundary, or with a flipped bit, does not authenticate
+            if len > CHUNK_LEN {
+                assert!(read(&ciphertext[..7 + CHUNK_LEN + 16]).is_err());
+            }
+
+            let last = ciphertext.len() - 1;
+
+            ciphertext[last] ^= 1;
+            assert!(read(&ciphertext).is_err());
+        }
+    }
+}
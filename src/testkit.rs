@@ -0,0 +1,578 @@
+//! Test doubles for exercising the handling of protocol misbehaviour in downstream tests.
+
+use num_bigint::{BigInt, RandBigInt, ToBigInt};
+use num_traits::One;
+use rand::{prng::ChaChaRng, SeedableRng};
+
+use crate::{
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    polynomial::Polynomial,
+    secret_key::SecretKey,
+    sharebox::{DistributionShareBox, ShareBox},
+    util::Util,
+    vss::VSS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DealerFault {
+    /// the first commitment does not match the polynomial
+    WrongCommitment,
+    /// the positions of the first two recipients are swapped after proving; a single recipient
+    /// fails with `UnknownPosition` for the second
+    InconsistentPositions,
+    /// every distribution reuses the same proof nonce `w`; the proofs still verify
+    ReusedNonce,
+    /// the encrypted share of the recipient at this index is altered; an index past the
+    /// recipients fails with `UnknownPosition`, for the position the index would have
+    CorruptedShare(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareholderFault {
+    /// the decrypted share is altered after proving
+    CorruptedShare,
+    /// the DLEQ response is altered
+    InvalidProof,
+}
+
+/// A dealer that misbehaves in one specific way.
+#[derive(Debug, Clone)]
+pub struct MaliciousDealer {
+    pub dealer: Participant,
+    pub fault: DealerFault,
+    nonce: Option<BigInt>,
+}
+
+impl MaliciousDealer {
+    pub fn new(dealer: Participant, fault: DealerFault) -> Self {
+        MaliciousDealer {
+            dealer,
+            fault,
+            nonce: None,
+        }
+    }
+
+    pub fn distribute_secret(
+        &mut self,
+        secret: &BigInt,
+        publickeys: &[BigInt],
+        threshold: u32,
+    ) -> Result<DistributionShareBox, VssError> {
+        let q = self.dealer.vss.q.clone();
+
+        // the faults are injected into otherwise valid dealings only
+        self.dealer.validate_inputs(
+            secret,
+            publickeys,
+            &sequential_positions(publickeys.len()),
+            threshold,
+            None,
+        )?;
+
+        match self.fault {
+            DealerFault::ReusedNonce => {
+                let mut polynomial = Polynomial::new();
+
+                polynomial.init((threshold - 1) as i32, &q);
+
+                let w = self
+                    .nonce
+                    .get_or_insert_with(|| {
+                        rand::thread_rng()
+                            .gen_biguint_below(&q.to_biguint().unwrap())
+                            .to_bigint()
+                            .unwrap()
+                    })
+                    .clone();
+
+                self.dealer.distribute(
+                    secret,
+                    publickeys,
+                    &sequential_positions(publickeys.len()),
+                    threshold,
+                    &polynomial,
+                    &w,
+                    &[],
+                    None,
+                )
+            }
+            DealerFault::WrongCommitment => {
+                let mut distribution = self
+                    .dealer
+                    .distribute_secret(secret, publickeys, threshold)?;
+
+                distribution.commitments[0] =
+                    (&distribution.commitments[0] * &self.dealer.vss.g) % &q;
+
+                Ok(distribution)
+            }
+            DealerFault::InconsistentPositions => {
+                if publickeys.len() < 2 {
+                    return Err(VssError::UnknownPosition {
+                        position: BigInt::from(2),
+                    });
+                }
+
+                let mut distribution = self
+                    .dealer
+                    .distribute_secret(secret, publickeys, threshold)?;
+                let first = distribution.positions[&publickeys[0]].clone();
+                let second = distribution.positions[&publickeys[1]].clone();
+
+                distribution.positions.insert(publickeys[0].clone(), second);
+                distribution.positions.insert(publickeys[1].clone(), first);
+
+                Ok(distribution)
+            }
+            DealerFault::CorruptedShare(index) => {
+                let publickey = publickeys
+                    .get(index)
+                    .ok_or_else(|| VssError::UnknownPosition {
+                        position: BigInt::from(index + 1),
+                    })?;
+                let mut distribution = self
+                    .dealer
+                    .distribute_secret(secret, publickeys, threshold)?;
+
+                if let Some(share) = distribution.shares.get_mut(publickey) {
+                    *share = (&*share * BigInt::from(2)) % &q;
+                }
+
+                Ok(distribution)
+            }
+        }
+    }
+}
+
+/// A shareholder that submits a bad share box during reconstruction.
+#[derive(Debug, Clone)]
+pub struct MaliciousShareholder {
+    pub participant: Participant,
+    pub fault: ShareholderFault,
+}
+
+impl MaliciousShareholder {
+    pub fn new(participant: Participant, fault: ShareholderFault) -> Self {
+        MaliciousShareholder { participant, fault }
+    }
+
+    pub fn extract_secret_share(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<ShareBox, VssError> {
+        let mut share_box = self
+            .participant
+            .extract_secret_share(distribution_sharebox, &self.participant.privatekey)?;
+        let q = &self.participant.vss.q;
+
+        match self.fault {
+            ShareholderFault::CorruptedShare => {
+                share_box.share = (&share_box.share * BigInt::from(2)) % q;
+            }
+            ShareholderFault::InvalidProof => {
+                share_box.response = (&share_box.response + BigInt::one()) % (q - BigInt::one());
+            }
+        }
+
+        Ok(share_box)
+    }
+}
+
+/// A complete ceremony, identical for every call with the same arguments.
+#[derive(Debug, Clone)]
+pub struct FixedCeremony {
+    pub dealer: Participant,
+    pub participants: Vec<Participant>,
+    pub secret: BigInt,
+    pub distribution: DistributionShareBox,
+    /// the share box of every participant, in the order of `participants`
+    pub shares: Vec<ShareBox>,
+}
+
+/// The small group of the crate's own tests. Far too small to be secure, but fast.
+pub fn test_params() -> VSS {
+    let mut vss = VSS::new();
+
+    vss.q = BigInt::from(179426549);
+    vss.g = BigInt::from(1301081);
+    vss.G = BigInt::from(15486487);
+    vss.length = 64;
+
+    vss
+}
+
+fn fixed_participant(vss: &VSS, privatekey: &BigInt) -> Participant {
+    Participant::from_private_key(vss.clone(), SecretKey::new(privatekey.clone()))
+}
+
+/// A participant over `test_params()` with a known private key.
+pub fn test_participant(privatekey: i64) -> Participant {
+    fixed_participant(&test_params(), &BigInt::from(privatekey))
+}
+
+/// Deals `secret` on the polynomial with the given coefficients, constant term first, so the
+/// threshold is the number of coefficients. `w` is the proof nonce. With the same inputs the
+/// distribution is identical, which is what known-answer tests need.
+pub fn deal_with_nonce(
+    dealer: &mut Participant,
+    secret: &BigInt,
+    publickeys: &[BigInt],
+    coefficients: &[BigInt],
+    w: &BigInt,
+) -> Result<DistributionShareBox, VssError> {
+    let mut polynomial = Polynomial::new();
+
+    polynomial.init_coefficients(coefficients);
+
+    dealer.distribute(
+        secret,
+        publickeys,
+        &sequential_positions(publickeys.len()),
+        coefficients.len() as u32,
+        &polynomial,
+        w,
+        &[],
+        None,
+    )
+}
+
+/// Extracts the share box of `participant` with the proof nonce `w`.
+pub fn extract_with_nonce(
+    participant: &Participant,
+    distribution: &DistributionShareBox,
+    w: &BigInt,
+) -> Result<ShareBox, VssError> {
+    participant.extract_share(distribution, &participant.privatekey, w, &[])
+}
+
+/// A known-answer test vector: every input of a ceremony, including the randomness, and every
+/// value it produced. Other implementations deal and extract with the same inputs and compare.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestVector {
+    pub params: VSS,
+    pub seed: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub dealer_privatekey: BigInt,
+    /// private keys of the recipients, in the order of `distribution.publickeys`
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub privatekeys: Vec<BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub secret: BigInt,
+    /// coefficients of the dealer's polynomial, constant term first
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub coefficients: Vec<BigInt>,
+    /// the dealer's proof nonce
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub w: BigInt,
+    /// the proof nonce of every recipient's share box
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub share_nonces: Vec<BigInt>,
+    pub distribution: DistributionShareBox,
+    pub shares: Vec<ShareBox>,
+}
+
+impl TestVector {
+    /// Generates a `t`-of-`n` vector over `test_params()`, drawing every input from a ChaCha
+    /// generator seeded with `seed`.
+    pub fn generate(n: usize, t: u32, seed: u64) -> Result<Self, VssError> {
+        let params = test_params();
+        let q = params.q.to_biguint().unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(seed);
+        let mut random = || rng.gen_biguint_below(&q).to_bigint().unwrap();
+        let mut privatekey = || loop {
+            let privatekey = random();
+
+            if Util::mod_inverse(&privatekey, &(&params.q - BigInt::one())).is_some() {
+                return privatekey;
+            }
+        };
+        let dealer_privatekey = privatekey();
+        let privatekeys: Vec<BigInt> = (0..n).map(|_| privatekey()).collect();
+        let secret = random();
+        let coefficients: Vec<BigInt> = (0..t).map(|_| random()).collect();
+        let w = random();
+        let share_nonces: Vec<BigInt> = (0..n).map(|_| random()).collect();
+
+        TestVector::deal(
+            &params,
+            seed,
+            dealer_privatekey,
+            privatekeys,
+            secret,
+            coefficients,
+            w,
+            share_nonces,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn deal(
+        params: &VSS,
+        seed: u64,
+        dealer_privatekey: BigInt,
+        privatekeys: Vec<BigInt>,
+        secret: BigInt,
+        coefficients: Vec<BigInt>,
+        w: BigInt,
+        share_nonces: Vec<BigInt>,
+    ) -> Result<Self, VssError> {
+        let mut dealer = fixed_participant(params, &dealer_privatekey);
+        let participants: Vec<Participant> = privatekeys
+            .iter()
+            .map(|privatekey| fixed_participant(params, privatekey))
+            .collect();
+        let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let distribution = deal_with_nonce(&mut dealer, &secret, &publickeys, &coefficients, &w)?;
+        let shares = participants
+            .iter()
+            .zip(&share_nonces)
+            .map(|(participant, w)| extract_with_nonce(participant, &distribution, w))
+            .collect::<Result<Vec<ShareBox>, VssError>>()?;
+
+        Ok(TestVector {
+            params: params.clone(),
+            seed,
+            dealer_privatekey,
+            privatekeys,
+            secret,
+            coefficients,
+            w,
+            share_nonces,
+            distribution,
+            shares,
+        })
+    }
+
+    /// Re-deals from the recorded inputs and checks that every output matches.
+    pub fn check(&self) -> bool {
+        TestVector::deal(
+            &self.params,
+            self.seed,
+            self.dealer_privatekey.clone(),
+            self.privatekeys.clone(),
+            self.secret.clone(),
+            self.coefficients.clone(),
+            self.w.clone(),
+            self.share_nonces.clone(),
+        )
+        .is_ok_and(|vector| &vector == self)
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Deals a deterministic `t`-of-`n` ceremony over `test_params()`: keys, secret, polynomial and
+/// proof nonces are all drawn from a ChaCha generator seeded with `seed`, as for `TestVector`.
+pub fn fixed_ceremony(n: usize, t: u32, seed: u64) -> Result<FixedCeremony, VssError> {
+    let vector = TestVector::generate(n, t, seed)?;
+
+    Ok(FixedCeremony {
+        dealer: fixed_participant(&vector.params, &vector.dealer_privatekey),
+        participants: vector
+            .privatekeys
+            .iter()
+            .map(|privatekey| fixed_participant(&vector.params, privatekey))
+            .collect(),
+        secret: vector.secret,
+        distribution: vector.distribution,
+        shares: vector.shares,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::error::VssError;
+
+    use super::{
+        fixed_ceremony, test_participant, DealerFault, MaliciousDealer, MaliciousShareholder,
+        ShareholderFault, TestVector,
+    };
+
+    fn publickeys() -> Vec<BigInt> {
+        [7901, 4801, 1453]
+            .iter()
+            .map(|key| test_participant(*key).publickey)
+            .collect()
+    }
+
+    #[test]
+    fn test_malicious_dealer() {
+        let verifier = test_participant(7901);
+
+        for fault in [
+            DealerFault::WrongCommitment,
+            DealerFault::InconsistentPositions,
+            DealerFault::CorruptedShare(1),
+        ] {
+            let mut dealer = MaliciousDealer::new(test_participant(105929), fault);
+            let distribution = dealer
+                .distribute_secret(&BigInt::from(42), &publickeys(), 2)
+                .unwrap();
+
+            assert!(!verifier.verify_distribution_shares(&distribution));
+        }
+    }
+
+    #[test]
+    fn test_malicious_dealer_inputs() {
+        let publickeys = publickeys();
+
+        for fault in [
+            DealerFault::WrongCommitment,
+            DealerFault::InconsistentPositions,
+            DealerFault::ReusedNonce,
+            DealerFault::CorruptedShare(0),
+        ] {
+            let mut dealer = MaliciousDealer::new(test_participant(105929), fault);
+
+            assert_eq!(
+                dealer.distribute_secret(&BigInt::from(42), &publickeys, 0),
+                Err(VssError::InvalidThreshold {
+                    threshold: 0,
+                    participants: 3
+                })
+            );
+            assert_eq!(
+                dealer.distribute_secret(&BigInt::from(42), &[], 1),
+                Err(VssError::NoRecipients)
+            );
+        }
+
+        let mut dealer =
+            MaliciousDealer::new(test_participant(105929), DealerFault::InconsistentPositions);
+
+        assert_eq!(
+            dealer.distribute_secret(&BigInt::from(42), &publickeys[..1], 1),
+            Err(VssError::UnknownPosition {
+                position: BigInt::from(2)
+            })
+        );
+
+        let mut dealer =
+            MaliciousDealer::new(test_participant(105929), DealerFault::CorruptedShare(3));
+
+        assert_eq!(
+            dealer.distribute_secret(&BigInt::from(42), &publickeys, 2),
+            Err(VssError::UnknownPosition {
+                position: BigInt::from(4)
+            })
+        );
+    }
+
+    #[test]
+    fn test_reused_nonce() {
+        let verifier = test_participant(7901);
+        let mut dealer = MaliciousDealer::new(test_participant(105929), DealerFault::ReusedNonce);
+        let first = dealer
+            .distribute_secret(&BigInt::from(42), &publickeys(), 2)
+            .unwrap();
+        let second = dealer
+            .distribute_secret(&BigInt::from(43), &publickeys(), 2)
+            .unwrap();
+
+        assert!(verifier.verify_distribution_shares(&first));
+        assert!(verifier.verify_distribution_shares(&second));
+
+        // a1 = g^w is identical in both transcripts
+        let light_first = verifier
+            .light_distribution(&first, &publickeys()[0])
+            .unwrap();
+        let light_second = verifier
+            .light_distribution(&second, &publickeys()[0])
+            .unwrap();
+
+        assert_eq!(light_first.digests[0].a1, light_second.digests[0].a1);
+    }
+
+    #[test]
+    fn test_malicious_shareholder() {
+        let mut dealer = test_participant(105929);
+        let distribution = dealer
+            .distribute_secret(&BigInt::from(42), &publickeys(), 2)
+            .unwrap();
+        let verifier = test_participant(4801);
+
+        for fault in [
+            ShareholderFault::CorruptedShare,
+            ShareholderFault::InvalidProof,
+        ] {
+            let shareholder = MaliciousShareholder::new(test_participant(7901), fault);
+            let share_box = shareholder.extract_secret_share(&distribution).unwrap();
+
+            assert!(!verifier.verify_share(&share_box, &distribution, &publickeys()[0]));
+        }
+    }
+
+    #[test]
+    fn test_fixed_ceremony() {
+        let ceremony = fixed_ceremony(4, 3, 7).unwrap();
+        let again = fixed_ceremony(4, 3, 7).unwrap();
+        let verifier = &ceremony.participants[0];
+
+        assert_eq!(ceremony.shares.len(), 4);
+        assert_eq!(ceremony.secret, again.secret);
+        assert_eq!(
+            ceremony.distribution.commitments,
+            again.distribution.commitments
+        );
+        assert_eq!(
+            ceremony.distribution.responses,
+            again.distribution.responses
+        );
+        assert_eq!(ceremony.shares[2].response, again.shares[2].response);
+        assert_ne!(
+            fixed_ceremony(4, 3, 8).unwrap().distribution.commitments,
+            ceremony.distribution.commitments
+        );
+
+        assert!(verifier.verify_distribution_shares(&ceremony.distribution));
+        assert_eq!(
+            verifier
+                .reconstruct(&ceremony.shares[1..], &ceremony.distribution)
+                .unwrap(),
+            ceremony.secret
+        );
+    }
+
+    #[test]
+    fn test_vector() {
+        let vector = TestVector::generate(3, 2, 42).unwrap();
+
+        assert!(vector.check());
+        assert_eq!(vector, TestVector::generate(3, 2, 42).unwrap());
+        assert_eq!(vector.coefficients.len(), 2);
+        assert_eq!(vector.distribution.threshold(), 2);
+
+        let mut tampered = vector.clone();
+
+        tampered.shares[1].response += 1;
+        assert!(!tampered.check());
+
+        let mut tampered = vector.clone();
+
+        tampered.w += 1;
+        assert!(!tampered.check());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_vector_json() {
+        let vector = TestVector::generate(3, 2, 42).unwrap();
+        let parsed = TestVector::from_json(&vector.to_json()).unwrap();
+
+        assert_eq!(parsed, vector);
+        assert!(parsed.check());
+    }
+}
@@ -0,0 +1,368 @@
+use std::collections::BTreeMap;
+
+use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    arith,
+    error::VssError,
+    participant::Participant,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    util::Util,
+    vss::VSS,
+    wire::Writer,
+};
+
+// Threshold decryption
+
+// ElGamal under a key nobody holds: with the shared value K = G^x of a distribution as public
+// key, a plaintext m encrypts to c1 = G^r and c2 = m * K^r, and the holders of `threshold` shares
+// compute K^r together without reconstructing anything. Shareholders hold G^x_i rather than x_i,
+// so they cannot raise c1 to their share themselves; the encryptor does it for them by raising
+// every encrypted share to r as well, Z_i = Y_i^r = y_i^(r x_i), and proves one r throughout with
+// a Chaum-Pedersen proof over all of them. The Z_i are encrypted shares of r p, so a partial
+// decryption is the shareholder's decrypted share of them, G^(r x_i), proven with the usual DLEQ,
+// and any `threshold` of them interpolate to K^r.
+//
+// Publishing K is what makes this work, and it gives away the secret of a distribution that
+// masks one under G^x, such as the established secret of a `JointDistribution`. Use a key for
+// either, not both.
+
+/// A distribution's shared value published as an ElGamal public key, see `ThresholdKey::new`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThresholdKey {
+    pub distribution: DistributionShareBox,
+    /// K = G^x
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub key: BigInt,
+}
+
+/// A plaintext encrypted to a `ThresholdKey`, with the encrypted shares raised to the same
+/// randomness and the proof of it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThresholdCiphertext {
+    /// c1 = G^r
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub ephemeral: BigInt,
+    /// c2 = m * K^r
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub ciphertext: BigInt,
+    /// Z_i = Y_i^r by public key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_map"))]
+    pub shares: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response: BigInt,
+}
+
+// partial decryptions are bound to their ciphertext
+fn context(ciphertext: &ThresholdCiphertext) -> Vec<u8> {
+    let mut writer = Writer::new();
+
+    writer.raw(b"elgamal");
+    writer.bigint(&ciphertext.ephemeral);
+    writer.bigint(&ciphertext.ciphertext);
+    writer.into_bytes()
+}
+
+// the challenge of the proof that c1 and every Z_i share r, over the commitments `nonces` to G
+// and to each Y_i in key order
+fn challenge<D: Transcript>(
+    vss: &VSS<D>,
+    key: &ThresholdKey,
+    ciphertext: &ThresholdCiphertext,
+    nonces: &[BigInt],
+) -> BigInt {
+    let mut hasher = vss.context_hasher(b"elgamal");
+
+    vss.update_hash(&mut hasher, b"key", &key.key);
+    vss.update_hash(&mut hasher, b"ephemeral", &ciphertext.ephemeral);
+    vss.update_hash(&mut hasher, b"ciphertext", &ciphertext.ciphertext);
+
+    for (publickey, share) in &ciphertext.shares {
+        vss.update_hash(&mut hasher, b"publickey", publickey);
+        vss.update_hash(&mut hasher, b"share", share);
+    }
+
+    for nonce in nonces {
+        vss.update_hash(&mut hasher, b"nonce", nonce);
+    }
+
+    BigUint::from_bytes_be(&hasher.challenge())
+        .mod_floor(&(vss.q.to_biguint().unwrap() - BigUint::one()))
+        .to_bigint()
+        .unwrap()
+}
+
+impl ThresholdKey {
+    /// The key of `distribution`, interpolated from at least `threshold` share boxes extracted
+    /// from it, all of which must verify.
+    pub fn new<D: Transcript>(
+        vss: &VSS<D>,
+        distribution: &DistributionShareBox,
+        share_boxes: &[ShareBox],
+    ) -> Result<Self, VssError> {
+        let invalid = vss.invalid_shares(share_boxes, distribution);
+
+        if !invalid.is_empty() {
+            return Err(VssError::InvalidShares {
+                publickeys: invalid,
+            });
+        }
+
+        Ok(ThresholdKey {
+            distribution: distribution.clone(),
+            key: vss.interpolate(share_boxes, distribution)?,
+        })
+    }
+
+    /// Encrypts `plaintext`, which must lie in `0 < plaintext < q`.
+    pub fn encrypt<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        plaintext: &BigInt,
+    ) -> Result<ThresholdCiphertext, VssError> {
+        self.encrypt_with_rng(vss, plaintext, &mut rand::thread_rng())
+    }
+
+    /// Like `encrypt`, drawing the randomness and the proof nonce from `rng`.
+    pub fn encrypt_with_rng<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        plaintext: &BigInt,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<ThresholdCiphertext, VssError> {
+        if plaintext <= &BigInt::zero() || plaintext >= &vss.q {
+            return Err(VssError::InvalidPlaintext);
+        }
+
+        let order = &vss.q - BigInt::one();
+        let power =
+            |base: &BigInt, exponent: &BigInt| arith::modpow(base, &vss.blind(exponent), &vss.q);
+        let r = rng.gen_bigint_range(&BigInt::one(), &order);
+        let w = rng.gen_bigint_range(&BigInt::zero(), &order);
+        let mut ciphertext = ThresholdCiphertext {
+            ephemeral: vss.exp_G(&vss.blind(&r)),
+            ciphertext: (plaintext * power(&self.key, &r)) % &vss.q,
+            shares: self
+                .distribution
+                .shares
+                .iter()
+                .map(|(publickey, share)| (publickey.clone(), power(share, &r)))
+                .collect(),
+            ..Default::default()
+        };
+        let nonces: Vec<BigInt> = std::iter::once(vss.exp_G(&vss.blind(&w)))
+            .chain(
+                self.distribution
+                    .shares
+                    .values()
+                    .map(|share| power(share, &w)),
+            )
+            .collect();
+
+        ciphertext.challenge = challenge(vss, self, &ciphertext, &nonces);
+        ciphertext.response = (w - &ciphertext.challenge * r).mod_floor(&order);
+
+        Ok(ciphertext)
+    }
+
+    // the distribution of r p the ciphertext carries, for extracting and verifying partial
+    // decryptions; its commitments and proof are still those of p
+    fn ciphertext_distribution(&self, ciphertext: &ThresholdCiphertext) -> DistributionShareBox {
+        let mut distribution = self.distribution.clone();
+
+        distribution.shares = ciphertext.shares.clone();
+        distribution
+    }
+
+    /// Decrypts `ciphertext` from at least `threshold` partial decryptions, all of which must
+    /// verify.
+    pub fn decrypt<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        ciphertext: &ThresholdCiphertext,
+        partials: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        if !vss.verify_ciphertext(self, ciphertext) {
+            return Err(VssError::InvalidCiphertext);
+        }
+
+        let invalid: Vec<BigInt> = partials
+            .iter()
+            .filter(|partial| !vss.verify_partial_decryption(ciphertext, partial))
+            .map(|partial| partial.publickey.clone())
+            .collect();
+
+        if !invalid.is_empty() {
+            return Err(VssError::InvalidShares {
+                publickeys: invalid,
+            });
+        }
+
+        let mask = vss.interpolate(partials, &self.ciphertext_distribution(ciphertext))?;
+        let inverse = Util::mod_inverse(&mask, &vss.q).ok_or(VssError::NonInvertible)?;
+
+        Ok((&ciphertext.ciphertext * inverse) % &vss.q)
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// This shareholder's partial decryption of `ciphertext`, G^(r x_i) with a proof against its
+    /// Z_i. Ciphertexts that fail `VSS::verify_ciphertext` are refused, as their Z_i may be
+    /// anything, the shareholder's own encrypted share included.
+    pub fn partial_decrypt(
+        &self,
+        key: &ThresholdKey,
+        ciphertext: &ThresholdCiphertext,
+    ) -> Result<ShareBox, VssError> {
+        if !self.vss.verify_ciphertext(key, ciphertext) {
+            return Err(VssError::InvalidCiphertext);
+        }
+
+        self.extract_secret_share_with_context(
+            &key.ciphertext_distribution(ciphertext),
+            &self.privatekey,
+            &context(ciphertext),
+        )
+    }
+}
+
+impl<D: Transcript> VSS<D> {
+    /// Checks that `ciphertext` raises every encrypted share of `key` to the randomness of its
+    /// ephemeral key.
+    pub fn verify_ciphertext(&self, key: &ThresholdKey, ciphertext: &ThresholdCiphertext) -> bool {
+        if ciphertext.shares.keys().ne(key.distribution.shares.keys()) {
+            return false;
+        }
+
+        let power = |base: &BigInt, exponent: &BigInt| arith::modpow(base, exponent, &self.q);
+        let c = &ciphertext.challenge;
+        let s = &ciphertext.response;
+        let nonces: Vec<BigInt> =
+            std::iter::once((power(&self.G, s) * power(&ciphertext.ephemeral, c)) % &self.q)
+                .chain(
+                    key.distribution
+                        .shares
+                        .values()
+                        .zip(ciphertext.shares.values())
+                        .map(|(share, raised)| (power(share, s) * power(raised, c)) % &self.q),
+                )
+                .collect();
+
+        challenge(self, key, ciphertext, &nonces) == *c
+    }
+
+    /// Checks a partial decryption of `ciphertext` against the shareholder's Z_i.
+    pub fn verify_partial_decryption(
+        &self,
+        ciphertext: &ThresholdCiphertext,
+        partial: &ShareBox,
+    ) -> bool {
+        ciphertext
+            .shares
+            .get(&partial.publickey)
+            .is_some_and(|raised| self.verify_with_context(partial, raised, &context(ciphertext)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use rand::{prng::ChaChaRng, SeedableRng};
+
+    use crate::{error::VssError, sharebox::ShareBox, testkit::fixed_ceremony};
+
+    use super::ThresholdKey;
+
+    #[test]
+    fn test_threshold_decryption() {
+        let ceremony = fixed_ceremony(3, 2, 29).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let key = ThresholdKey::new(vss, &ceremony.distribution, &ceremony.shares[..2]).unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(29);
+        let plaintext = BigInt::from(4242);
+        let ciphertext = key.encrypt_with_rng(vss, &plaintext, &mut rng).unwrap();
+
+        assert!(vss.verify_ciphertext(&key, &ciphertext));
+
+        let partials: Vec<ShareBox> = participants
+            .iter()
+            .map(|p| p.partial_decrypt(&key, &ciphertext).unwrap())
+            .collect();
+
+        assert!(partials
+            .iter()
+            .all(|partial| vss.verify_partial_decryption(&ciphertext, partial)));
+        assert_eq!(
+            key.decrypt(vss, &ciphertext, &partials[..2]),
+            Ok(plaintext.clone())
+        );
+        assert_eq!(
+            key.decrypt(vss, &ciphertext, &partials[1..]),
+            Ok(plaintext.clone())
+        );
+        assert!(key.decrypt(vss, &ciphertext, &partials[..1]).is_err());
+
+        // partial decryptions belong to their ciphertext
+        let other = key.encrypt_with_rng(vss, &plaintext, &mut rng).unwrap();
+
+        assert!(!vss.verify_partial_decryption(&other, &partials[0]));
+        assert_eq!(
+            key.decrypt(vss, &other, &partials[..2]),
+            Err(VssError::InvalidShares {
+                publickeys: partials[..2].iter().map(|p| p.publickey.clone()).collect()
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_ciphertext() {
+        let ceremony = fixed_ceremony(3, 2, 31).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let key = ThresholdKey::new(vss, &ceremony.distribution, &ceremony.shares[1..]).unwrap();
+        let mut rng = ChaChaRng::seed_from_u64(31);
+
+        assert_eq!(
+            key.encrypt_with_rng(vss, &vss.q, &mut rng),
+            Err(VssError::InvalidPlaintext)
+        );
+
+        let ciphertext = key
+            .encrypt_with_rng(vss, &BigInt::from(7), &mut rng)
+            .unwrap();
+
+        // handing a shareholder its own encrypted share would have it decrypt its key share
+        let mut replayed = ciphertext.clone();
+        let publickey = ceremony.participants[0].publickey.clone();
+
+        replayed.shares.insert(
+            publickey.clone(),
+            ceremony.distribution.shares[&publickey].clone(),
+        );
+
+        assert!(!vss.verify_ciphertext(&key, &replayed));
+        assert_eq!(
+            ceremony.participants[0].partial_decrypt(&key, &replayed),
+            Err(VssError::InvalidCiphertext)
+        );
+
+        // nor may the plaintext part be swapped
+        let mut altered = ciphertext;
+
+        altered.ciphertext = (&altered.ciphertext * BigInt::from(2)) % &vss.q;
+
+        assert_eq!(
+            key.decrypt(vss, &altered, &[]),
+            Err(VssError::InvalidCiphertext)
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use sha2::Digest;
+
+// Fiat-Shamir transcripts
+
+// Challenges are derived from a transcript of the proof's public elements. A plain hash such as
+// SHA-256 is a transcript that ignores the labels, so its challenges are exactly the hash of the
+// concatenated elements, as they always were. A Merlin transcript (feature `merlin`) absorbs every
+// element under its label, which gives domain separation between elements and proofs.
+//
+// Every transcript is started with the protocol label, if set, under `label` (length prefixed).
+// A DLEQ statement log_g1(h1) = log_g2(h2) then appends `g1`, `h1`, `g2`, `h2`, `a1` and `a2`, the
+// generators as context. A distribution proof appends one statement per recipient, in the order
+// of the public keys, with `h1` the commitment X_i and `h2` the encrypted share, and takes a single
+// challenge; a share proof appends the statement with `h1` the public key, `g2` the decrypted
+// share and `h2` the encrypted share, followed for fresh share boxes by the nonce and issue time
+// under `freshness`. The secret mask is taken from a separate transcript of
+// `shared_value`. Elements are encoded with the parameters' `HashEncoding`.
+//
+// Under `HashEncoding::FixedWidth` every challenge is bound to the group: transcripts start with
+// `q`, `g`, `G` and the length-prefixed proof context, empty or not, and plain hashes absorb the
+// context elements too. Decimal transcripts bind these only for a non-empty context and leave the
+// generators to Merlin, as they always have.
+
+/// The hash or transcript proofs derive their challenges from.
+pub trait Transcript: Clone {
+    fn start() -> Self;
+
+    /// Absorbs an element of the proof under `label`.
+    fn append(&mut self, label: &'static [u8], bytes: &[u8]);
+
+    /// Absorbs an element that plain hashes have always left out of the challenge, such as the
+    /// generators of a DLEQ statement. Hashes ignore it so their challenges stay unchanged.
+    fn append_context(&mut self, label: &'static [u8], bytes: &[u8]);
+
+    /// `append_context`, or `append` where `bound` is set, so that plain hashes absorb the context
+    /// as well. Fixed-width parameters bind their context; decimal ones leave it out.
+    fn append_bound_context(&mut self, bound: bool, label: &'static [u8], bytes: &[u8]) {
+        if bound {
+            self.append(label, bytes);
+        } else {
+            self.append_context(label, bytes);
+        }
+    }
+
+    fn challenge(self) -> Vec<u8>;
+}
+
+impl<D: Digest + Clone> Transcript for D {
+    fn start() -> Self {
+        D::new()
+    }
+
+    fn append(&mut self, _label: &'static [u8], bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn append_context(&mut self, _label: &'static [u8], _bytes: &[u8]) {}
+
+    fn challenge(self) -> Vec<u8> {
+        self.finalize().to_vec()
+    }
+}
+
+/// A Merlin transcript as the challenge hash, e.g. `Participant::new().with_digest::<MerlinTranscript>()`.
+#[cfg(feature = "merlin")]
+#[derive(Clone)]
+pub struct MerlinTranscript(merlin::Transcript);
+
+#[cfg(feature = "merlin")]
+impl Transcript for MerlinTranscript {
+    fn start() -> Self {
+        MerlinTranscript(merlin::Transcript::new(b"rust-vss"))
+    }
+
+    fn append(&mut self, label: &'static [u8], bytes: &[u8]) {
+        self.0.append_message(label, bytes);
+    }
+
+    fn append_context(&mut self, label: &'static [u8], bytes: &[u8]) {
+        self.0.append_message(label, bytes);
+    }
+
+    // 64 bytes, so the reduction modulo the group order is close to uniform
+    fn challenge(mut self) -> Vec<u8> {
+        let mut challenge = vec![0; 64];
+
+        self.0.challenge_bytes(b"challenge", &mut challenge);
+
+        challenge
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+use sha2::Sha256;
+
+use crate::{
+    participant::Participant, schnorr, secret_key::SecretKey, transcript::Transcript, vss::VSS,
+};
+
+// Share lifetime accounting
+
+// A shareholder releasing its share box for reconstruction keeps a record of every release: which
+// share box, to whom, and when. Each release is backed by a request the requester signed with its
+// key pair (a Schnorr signature over the group), so the ledger can be audited later without
+// trusting the shareholder's word for who asked.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReleaseRequest {
+    /// id of the requested share box
+    pub share_id: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub requester: BigInt,
+    /// seconds since the unix epoch, as claimed by the requester
+    pub at: u64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub commitment: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub response: BigInt,
+}
+
+impl ReleaseRequest {
+    fn message(share_id: &str, at: u64) -> Vec<u8> {
+        let mut message = (share_id.len() as u64).to_be_bytes().to_vec();
+
+        message.extend_from_slice(share_id.as_bytes());
+        message.extend_from_slice(&at.to_be_bytes());
+
+        message
+    }
+
+    pub(crate) fn sign<D: Transcript>(
+        vss: &VSS<D>,
+        privatekey: &SecretKey,
+        share_id: &str,
+        at: u64,
+    ) -> Self {
+        let (commitment, response) =
+            schnorr::sign(vss, privatekey, &ReleaseRequest::message(share_id, at));
+
+        ReleaseRequest {
+            share_id: share_id.to_string(),
+            requester: vss.generate_public_key(privatekey),
+            at,
+            commitment,
+            response,
+        }
+    }
+
+    pub(crate) fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        schnorr::verify(
+            vss,
+            &self.requester,
+            &ReleaseRequest::message(&self.share_id, self.at),
+            &self.commitment,
+            &self.response,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageLedger<D = Sha256> {
+    vss: VSS<D>,
+    releases: BTreeMap<String, Vec<ReleaseRequest>>,
+}
+
+impl<D: Transcript> UsageLedger<D> {
+    /// A ledger checking requests against the group parameters and challenge hash of `holder`.
+    pub fn new(holder: &Participant<D>) -> Self {
+        UsageLedger {
+            vss: holder.vss.clone(),
+            releases: BTreeMap::new(),
+        }
+    }
+
+    /// Records the release of the share box named in `request`. Returns false, recording
+    /// nothing, if the request signature does not verify.
+    pub fn record_release(&mut self, request: ReleaseRequest) -> bool {
+        if !request.verify(&self.vss) {
+            return false;
+        }
+
+        self.releases
+            .entry(request.share_id.clone())
+            .or_default()
+            .push(request);
+
+        true
+    }
+
+    /// How many times `share_id` has been released.
+    pub fn release_count(&self, share_id: &str) -> usize {
+        self.releases.get(share_id).map_or(0, Vec::len)
+    }
+
+    /// Every recorded release of `share_id`, oldest first.
+    pub fn releases(&self, share_id: &str) -> &[ReleaseRequest] {
+        self.releases.get(share_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every recorded release to the holder of `requester`.
+    pub fn releases_to(&self, requester: &BigInt) -> Vec<&ReleaseRequest> {
+        self.releases
+            .values()
+            .flatten()
+            .filter(|request| &request.requester == requester)
+            .collect()
+    }
+
+    pub fn share_ids(&self) -> Vec<&str> {
+        self.releases.keys().map(String::as_str).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testkit::test_participant;
+
+    use super::UsageLedger;
+
+    #[test]
+    fn test_record_release() {
+        let holder = test_participant(7901);
+        let alice = test_participant(4801);
+        let bob = test_participant(1453);
+        let mut ledger = UsageLedger::new(&holder);
+
+        assert!(ledger.record_release(alice.sign_release_request("ceremony-1", 100)));
+        assert!(ledger.record_release(bob.sign_release_request("ceremony-1", 200)));
+        assert!(ledger.record_release(alice.sign_release_request("ceremony-2", 300)));
+
+        assert_eq!(ledger.release_count("ceremony-1"), 2);
+        assert_eq!(ledger.release_count("ceremony-3"), 0);
+        assert_eq!(ledger.releases("ceremony-1")[1].requester, bob.publickey);
+        assert_eq!(ledger.releases_to(&alice.publickey).len(), 2);
+        assert_eq!(ledger.share_ids(), vec!["ceremony-1", "ceremony-2"]);
+    }
+
+    #[test]
+    fn test_forged_request() {
+        let holder = test_participant(7901);
+        let alice = test_participant(4801);
+        let mut ledger = UsageLedger::new(&holder);
+
+        let mut request = alice.sign_release_request("ceremony-1", 100);
+
+        request.at += 1;
+        assert!(!ledger.record_release(request));
+
+        // claiming somebody else's key
+        let mut request = alice.sign_release_request("ceremony-1", 100);
+
+        request.requester = holder.publickey.clone();
+        assert!(!ledger.record_release(request));
+        assert_eq!(ledger.release_count("ceremony-1"), 0);
+    }
+}
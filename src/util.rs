@@ -1,24 +1,54 @@
-use num_bigint::{BigInt, Sign};
-use num_traits::{One, Zero};
+use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::arith::{self, Arithmetic, Backend};
 
 pub struct Util {}
 
 impl Util {
-    // implementation of the extended Euclidean algorithm
+    // implementation of the extended Euclidean algorithm, iterative so that the depth does not
+    // grow with the operands
     // https://en.wikipedia.org/wiki/Extended_Euclidean_algorithm
     pub fn extend_gcd(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
-        if a == &BigInt::zero() {
-            (b.clone(), BigInt::zero(), BigInt::one())
-        } else {
-            let (g, x, y) = Util::extend_gcd(&(b % a), a);
+        // run on (b, a), which yields the same coefficients as the recursive form did. Only the
+        // coefficient of `a` is carried, the other one follows from the gcd at the end
+        if a.is_zero() {
+            return (b.clone(), BigInt::zero(), BigInt::one());
+        }
 
-            (g, y - (b / a) * &x, x)
+        let (mut old_r, mut r) = (b.clone(), a.clone());
+        let (mut old_t, mut t) = (BigInt::zero(), BigInt::one());
+
+        while !r.is_zero() {
+            let (quotient, next_r) = old_r.div_rem(&r);
+            let next_t = &old_t - &quotient * &t;
+
+            old_r = std::mem::replace(&mut r, next_r);
+            old_t = std::mem::replace(&mut t, next_t);
         }
+
+        let y = if b.is_zero() {
+            BigInt::zero()
+        } else {
+            (&old_r - a * &old_t) / b
+        };
+
+        (old_r, old_t, y)
     }
 
     // calculates the modular multiplicative inverse
     // https://en.wikipedia.org/wiki/Modular_multiplicative_inverse
+    //
+    // The binary algorithm takes only shifts and subtractions and is about twice as fast for
+    // operands as large as an odd modulus, such as the inverse of a group element modulo q. Small
+    // operands, as the Lagrange denominators are, take few Euclidean steps, and the binary
+    // algorithm needs an odd modulus. Fermat's a^(q - 2) is far slower than either.
     pub fn mod_inverse(a: &BigInt, modular: &BigInt) -> Option<BigInt> {
+        if modular.is_odd() && modular > &BigInt::one() && 2 * a.bits() > modular.bits() {
+            return Util::mod_inverse_binary(a, modular);
+        }
+
         let (g, x, _) = Util::extend_gcd(a, modular);
 
         if g != BigInt::one() {
@@ -30,7 +60,59 @@ impl Util {
         }
     }
 
-    pub fn lagrange_coefficient(i: &i64, values: &[i64]) -> (BigInt, BigInt) {
+    // binary extended GCD, for an odd modulus above one
+    // https://en.wikipedia.org/wiki/Binary_GCD_algorithm
+    fn mod_inverse_binary(a: &BigInt, modular: &BigInt) -> Option<BigInt> {
+        let m = modular.to_biguint().unwrap();
+        let mut u = a.mod_floor(modular).to_biguint().unwrap();
+        let mut v = m.clone();
+        // x1 * a = u and x2 * a = v modulo m
+        let (mut x1, mut x2) = (BigUint::one(), BigUint::zero());
+        let halve = |x: &mut BigUint| {
+            if x.is_odd() {
+                *x += &m;
+            }
+            *x >>= 1;
+        };
+
+        while !u.is_one() && !v.is_one() {
+            // u = v, so their gcd is above one
+            if u.is_zero() {
+                return None;
+            }
+
+            while u.is_even() {
+                u >>= 1;
+                halve(&mut x1);
+            }
+
+            while v.is_even() {
+                v >>= 1;
+                halve(&mut x2);
+            }
+
+            if u >= v {
+                u -= &v;
+                x1 = if x1 >= x2 { x1 - &x2 } else { x1 + &m - &x2 };
+            } else {
+                v -= &u;
+                x2 = if x2 >= x1 { x2 - &x1 } else { x2 + &m - &x1 };
+            }
+        }
+
+        let x = if u.is_one() { x1 } else { x2 };
+
+        Some(x.to_bigint().unwrap())
+    }
+
+    pub fn lagrange_coefficient(i: &BigInt, values: &[BigInt]) -> (BigInt, BigInt) {
+        Util::lagrange_coefficient_at(i, values, &BigInt::zero())
+    }
+
+    /// The Lagrange coefficient of position `i` among `values` at `x` as (numerator, denominator),
+    /// prod (j - x) / prod (j - i) over the other positions j; `lagrange_coefficient` is the one
+    /// at zero.
+    pub fn lagrange_coefficient_at(i: &BigInt, values: &[BigInt], x: &BigInt) -> (BigInt, BigInt) {
         if !values.contains(i) {
             return (BigInt::zero(), BigInt::one());
         }
@@ -38,19 +120,124 @@ impl Util {
         let mut numerator = BigInt::one();
         let mut denominator = BigInt::one();
 
-        let vec_to = values.to_vec();
-        let max = vec_to.iter().max().unwrap();
+        // each position counts once, and only positions from 1 up take part
+        let mut others: Vec<&BigInt> = values
+            .iter()
+            .filter(|j| j.is_positive() && *j != i)
+            .collect();
 
-        for j in 1..=*max {
-            if j != *i && values.contains(&j) {
-                numerator *= j;
-                denominator *= j - *i;
-            }
+        others.sort_unstable();
+        others.dedup();
+
+        for j in others {
+            numerator *= j - x;
+            denominator *= j - i;
         }
 
         (numerator, denominator)
     }
 
+    // Straus' simultaneous exponentiation: prod bases[i]^exponents[i] mod `modulus`, sharing one
+    // chain of squarings between all bases and reading the exponents 4 bits at a time. Exponents
+    // must not be negative.
+    pub fn multi_exp(bases: &[BigInt], exponents: &[BigInt], modulus: &BigInt) -> BigInt {
+        const WINDOW: usize = 4;
+
+        if !Backend::TABLES {
+            return bases.iter().zip(exponents).fold(
+                BigInt::one() % modulus,
+                |product, (base, exponent)| {
+                    (product * arith::modpow(base, exponent, modulus)) % modulus
+                },
+            );
+        }
+
+        let tables: Vec<Vec<BigInt>> = bases
+            .iter()
+            .map(|base| {
+                let mut table = vec![BigInt::one() % modulus];
+
+                for k in 1..1 << WINDOW {
+                    table.push((&table[k - 1] * base) % modulus);
+                }
+
+                table
+            })
+            .collect();
+        let digits: Vec<Vec<u8>> = exponents
+            .iter()
+            .map(|exponent| {
+                debug_assert!(exponent.sign() != Sign::Minus);
+
+                exponent
+                    .to_bytes_le()
+                    .1
+                    .iter()
+                    .flat_map(|byte| [byte & 0x0f, byte >> WINDOW])
+                    .collect()
+            })
+            .collect();
+        let len = digits.iter().map(Vec::len).max().unwrap_or(0);
+        let mut result = BigInt::one() % modulus;
+
+        for window in (0..len).rev() {
+            if !result.is_one() {
+                for _ in 0..WINDOW {
+                    result = (&result * &result) % modulus;
+                }
+            }
+
+            for (table, digits) in tables.iter().zip(&digits) {
+                match digits.get(window) {
+                    Some(&digit) if digit != 0 => {
+                        result = (result * &table[digit as usize]) % modulus;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        result
+    }
+
+    // the Jacobi symbol (a / n) for an odd, positive `n`, by quadratic reciprocity. For a prime
+    // `n` it is the Legendre symbol: 1 for the squares, -1 for the non-squares and 0 for 0
+    pub fn jacobi(a: &BigInt, n: &BigInt) -> i8 {
+        debug_assert!(n.is_positive() && n.is_odd());
+
+        let mut a = a.mod_floor(n);
+        let mut n = n.clone();
+        let mut symbol = 1;
+
+        while !a.is_zero() {
+            while a.is_even() {
+                a >>= 1;
+
+                // (2 / n) = -1 when n = 3, 5 mod 8
+                let rest = (&n % 8u8).to_u8().unwrap();
+
+                if rest == 3 || rest == 5 {
+                    symbol = -symbol;
+                }
+            }
+
+            std::mem::swap(&mut a, &mut n);
+
+            // both are odd now, and the sign flips when both are 3 mod 4
+            if (&a % 4u8).to_u8() == Some(3) && (&n % 4u8).to_u8() == Some(3) {
+                symbol = -symbol;
+            }
+
+            a = a.mod_floor(&n);
+        }
+
+        if n.is_one() {
+            symbol
+        } else {
+            0
+        }
+    }
+
     pub fn abs(n: &BigInt) -> BigInt {
         match n.sign() {
             Sign::Minus => BigInt::new(Sign::Plus, n.to_u32_digits().1),
@@ -63,6 +250,7 @@ impl Util {
 #[cfg(test)]
 mod tests {
     use num_bigint::BigInt;
+    use num_integer::Integer;
     use num_traits::{One, Zero};
 
     use super::Util;
@@ -80,6 +268,74 @@ mod tests {
     }
 
     #[test]
+    fn test_multi_exp() {
+        let modulus = BigInt::from(179426549);
+        let bases = [
+            BigInt::from(1301081),
+            BigInt::from(15486487),
+            BigInt::from(2),
+            BigInt::zero(),
+        ];
+        let exponents = [
+            BigInt::from(179426547),
+            BigInt::from(65536),
+            BigInt::zero(),
+            BigInt::zero(),
+        ];
+        let expected = bases
+            .iter()
+            .zip(&exponents)
+            .fold(BigInt::one(), |product, (base, exponent)| {
+                (product * base.modpow(exponent, &modulus)) % &modulus
+            });
+
+        assert_eq!(Util::multi_exp(&bases, &exponents, &modulus), expected);
+        assert_eq!(
+            Util::multi_exp(&bases[..2], &exponents[..2], &modulus),
+            (bases[0].modpow(&exponents[0], &modulus) * bases[1].modpow(&exponents[1], &modulus))
+                % &modulus
+        );
+        assert_eq!(Util::multi_exp(&[], &[], &modulus), BigInt::one());
+    }
+
+    #[test]
+    fn test_jacobi() {
+        let q = BigInt::from(179426549);
+
+        // Euler's criterion: a^((q - 1) / 2) is 1 for the squares and q - 1 for the rest
+        for a in [1, 2, 3, 5, 1301081, 15486487, 179426548] {
+            let a = BigInt::from(a);
+            let euler = a.modpow(&((&q - 1) / 2), &q);
+            let expected = if euler.is_one() { 1 } else { -1 };
+
+            assert_eq!(Util::jacobi(&a, &q), expected);
+        }
+
+        assert_eq!(Util::jacobi(&BigInt::zero(), &q), 0);
+        assert_eq!(Util::jacobi(&BigInt::from(-1), &q), 1);
+        assert_eq!(Util::jacobi(&BigInt::from(3), &BigInt::from(9)), 0);
+        assert_eq!(Util::jacobi(&BigInt::from(2), &BigInt::from(15)), 1);
+    }
+
+    #[test]
+    fn test_extend_gcd_deep() {
+        // consecutive Fibonacci numbers take the most steps, here about 3000
+        let (mut a, mut b) = (BigInt::one(), BigInt::one());
+
+        for _ in 0..3000 {
+            let next = &a + &b;
+
+            a = std::mem::replace(&mut b, next);
+        }
+
+        let (g, x, y) = Util::extend_gcd(&a, &b);
+
+        assert_eq!(g, BigInt::one());
+        assert_eq!(a * x + b * y, g);
+    }
+
+    #[test]
+    #[allow(clippy::single_match)]
     fn test_mod_inverse() {
         let exist = Util::mod_inverse(&BigInt::from(3), &BigInt::from(26));
         let not_exist = Util::mod_inverse(&BigInt::from(4), &BigInt::from(32));
@@ -98,10 +354,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mod_inverse_binary() {
+        let q = BigInt::from(179426549);
+
+        for a in [1, 2, 3, 1301081, 15486487, 179426548, 358853099, -5] {
+            let a = BigInt::from(a);
+            let x = Util::mod_inverse_binary(&a, &q).unwrap();
+
+            assert_eq!((a * x).mod_floor(&q), BigInt::one());
+        }
+
+        assert_eq!(Util::mod_inverse_binary(&q, &q), None);
+
+        // 21 and 15 share the factor 3
+        assert_eq!(
+            Util::mod_inverse_binary(&BigInt::from(21), &BigInt::from(15)),
+            None
+        );
+        assert_eq!(
+            Util::mod_inverse_binary(&BigInt::from(7), &BigInt::from(15)),
+            Some(BigInt::from(13))
+        );
+    }
+
     #[test]
     fn test_lagrange_coefficient() {
-        let i_array = [9, 1, 2, 3];
-        let values = [0, 1, 2, 3, 4, 5, 6];
+        let i_array = [9, 1, 2, 3].map(BigInt::from);
+        let values = [0, 1, 2, 3, 4, 5, 6].map(BigInt::from);
         let result = Util::lagrange_coefficient(&i_array[0], &values);
 
         assert_eq!(result, (BigInt::zero(), BigInt::one()));
@@ -118,8 +398,31 @@ mod tests {
         let result = Util::lagrange_coefficient(&i_array[3], &values);
         assert_eq!(result, (BigInt::from(240), BigInt::from(12)));
 
-        let result = Util::lagrange_coefficient(&3, &[1, 3, 4]);
+        let result = Util::lagrange_coefficient(&BigInt::from(3), &[1, 3, 4].map(BigInt::from));
         assert_eq!(result, (BigInt::from(4), BigInt::from(-2)));
+
+        // 1_000_000 / (1_000_000 - 2) * 1 / (1 - 2)
+        let result =
+            Util::lagrange_coefficient(&BigInt::from(2), &[1, 2, 1_000_000, 1].map(BigInt::from));
+        assert_eq!(result, (BigInt::from(1_000_000), BigInt::from(-999_998)));
+    }
+
+    #[test]
+    fn test_lagrange_coefficient_at() {
+        let values = [1, 2, 3].map(BigInt::from);
+
+        // (2 - 4) * (3 - 4) / ((2 - 1) * (3 - 1))
+        let result = Util::lagrange_coefficient_at(&values[0], &values, &BigInt::from(4));
+        assert_eq!(result, (BigInt::from(2), BigInt::from(2)));
+
+        // (1 - 4) * (3 - 4) / ((1 - 2) * (3 - 2))
+        let result = Util::lagrange_coefficient_at(&values[1], &values, &BigInt::from(4));
+        assert_eq!(result, (BigInt::from(3), BigInt::from(-1)));
+
+        assert_eq!(
+            Util::lagrange_coefficient_at(&values[2], &values, &BigInt::zero()),
+            Util::lagrange_coefficient(&values[2], &values)
+        );
     }
 
     #[test]
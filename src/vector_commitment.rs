@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+
+use num_bigint::BigInt;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::VssError, session::SessionId, sharebox::DistributionShareBox, transcript::Transcript,
+    vss::VSS,
+};
+
+// Hash-based vector commitment to the coefficient commitments
+
+// With a threshold in the hundreds the commitments C_0..C_{t-1} dominate the size of a
+// distribution. A compact distribution replaces them with the root of a Merkle tree over them;
+// verifiers fetch only the commitments they need, each with an opening proving it belongs to the
+// root, or fetch all of them to expand back into a full distribution. Leaves and inner nodes are
+// domain separated, and the hashes are bound to the protocol label. They are SHA-256 whatever the
+// challenge hash, so a root is 32 bytes for every participant.
+
+type Hash = [u8; 32];
+
+fn leaf_hash<D: Transcript>(vss: &VSS<D>, index: usize, commitment: &BigInt) -> Hash {
+    let mut hasher = vss
+        .hasher_of::<Sha256>()
+        .chain([0])
+        .chain((index as u64).to_be_bytes());
+
+    vss.update_hash(&mut hasher, b"commitment", commitment);
+
+    hasher.finalize().into()
+}
+
+fn node_hash<D: Transcript>(vss: &VSS<D>, left: &Hash, right: &Hash) -> Hash {
+    vss.hasher_of::<Sha256>()
+        .chain([1])
+        .chain(left)
+        .chain(right)
+        .finalize()
+        .into()
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitmentTree {
+    // levels[0] are the leaves, the last level holds the root. A node without a sibling is
+    // carried up unchanged.
+    levels: Vec<Vec<Hash>>,
+    commitments: Vec<BigInt>,
+}
+
+impl CommitmentTree {
+    pub(crate) fn new<D: Transcript>(vss: &VSS<D>, commitments: &[BigInt]) -> Self {
+        let mut levels = vec![commitments
+            .iter()
+            .enumerate()
+            .map(|(i, commitment)| leaf_hash(vss, i, commitment))
+            .collect::<Vec<Hash>>()];
+
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(vss, left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+
+            levels.push(level);
+        }
+
+        CommitmentTree {
+            levels,
+            commitments: commitments.to_vec(),
+        }
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.commitments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commitments.is_empty()
+    }
+
+    pub fn open(&self, index: usize) -> Option<CommitmentOpening> {
+        let commitment = self.commitments.get(index)?.clone();
+        let mut path = Vec::new();
+        let mut position = index;
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                path.push(*sibling);
+            }
+
+            position /= 2;
+        }
+
+        Some(CommitmentOpening {
+            index,
+            commitment,
+            path,
+        })
+    }
+}
+
+/// Proof that `commitment` is the commitment at `index` under a tree root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommitmentOpening {
+    pub index: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub commitment: BigInt,
+    pub path: Vec<Hash>,
+}
+
+impl CommitmentOpening {
+    pub(crate) fn verify<D: Transcript>(&self, vss: &VSS<D>, root: &Hash, len: usize) -> bool {
+        if self.index >= len {
+            return false;
+        }
+
+        let mut hash = leaf_hash(vss, self.index, &self.commitment);
+        let mut path = self.path.iter();
+        let mut position = self.index;
+        let mut level_len = len;
+
+        while level_len > 1 {
+            let sibling = position ^ 1;
+
+            if sibling < level_len {
+                let sibling_hash = match path.next() {
+                    Some(sibling_hash) => sibling_hash,
+                    None => return false,
+                };
+
+                hash = if position.is_multiple_of(2) {
+                    node_hash(vss, &hash, sibling_hash)
+                } else {
+                    node_hash(vss, sibling_hash, &hash)
+                };
+            }
+
+            position /= 2;
+            level_len = level_len.div_ceil(2);
+        }
+
+        path.next().is_none() && &hash == root
+    }
+}
+
+/// A distribution carrying the root of the commitment tree in place of the commitments.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompactDistribution {
+    pub root: Hash,
+    pub threshold: usize,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::positions"))]
+    pub positions: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_map"))]
+    pub shares: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_vec"))]
+    pub publickeys: Vec<BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub challenge: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_map"))]
+    pub responses: BTreeMap<BigInt, BigInt>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
+    pub u: BigInt,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub secret_len: Option<usize>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub params_fingerprint: Option<[u8; 8]>,
+    /// the recorded number of recipients; when present the threshold was recorded as well
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub n: Option<usize>,
+    /// `DistributionShareBox::session_id` of the distribution
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub session_id: Option<SessionId>,
+}
+
+impl CompactDistribution {
+    pub(crate) fn new<D: Transcript>(
+        vss: &VSS<D>,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Self {
+        CompactDistribution {
+            root: CommitmentTree::new(vss, &distribution_sharebox.commitments).root(),
+            threshold: distribution_sharebox.threshold(),
+            positions: distribution_sharebox.positions.clone(),
+            shares: distribution_sharebox.shares.clone(),
+            publickeys: distribution_sharebox.publickeys.clone(),
+            challenge: distribution_sharebox.challenge.clone(),
+            responses: distribution_sharebox.responses.clone(),
+            u: distribution_sharebox.u.clone(),
+            secret_len: distribution_sharebox.secret_len,
+            params_fingerprint: distribution_sharebox.params_fingerprint,
+            n: distribution_sharebox.n,
+            session_id: distribution_sharebox.session_id,
+        }
+    }
+
+    pub(crate) fn verify_opening<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        opening: &CommitmentOpening,
+    ) -> bool {
+        opening.verify(vss, &self.root, self.threshold)
+    }
+
+    /// Restores the full distribution from the commitments, which must match the root.
+    pub(crate) fn expand<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        commitments: &[BigInt],
+    ) -> Result<DistributionShareBox, VssError> {
+        if commitments.len() != self.threshold
+            || CommitmentTree::new(vss, commitments).root() != self.root
+        {
+            return Err(VssError::InvalidProof);
+        }
+
+        let mut distribution_sharebox = DistributionShareBox::new();
+
+        distribution_sharebox.init(
+            commitments,
+            self.positions.clone(),
+            self.shares.clone(),
+            &self.publickeys,
+            &self.challenge,
+            self.responses.clone(),
+            &self.u,
+        );
+        distribution_sharebox.secret_len = self.secret_len;
+        distribution_sharebox.params_fingerprint = self.params_fingerprint;
+        distribution_sharebox.threshold = self.n.map(|_| self.threshold);
+        distribution_sharebox.n = self.n;
+        distribution_sharebox.session_id = self.session_id;
+
+        Ok(distribution_sharebox)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::vss::VSS;
+
+    use super::CommitmentTree;
+
+    #[test]
+    fn test_openings() {
+        let vss = VSS::new();
+
+        for len in 1..=9 {
+            let commitments: Vec<BigInt> = (0..len).map(|i| BigInt::from(1000 + i)).collect();
+            let tree = CommitmentTree::new(&vss, &commitments);
+
+            for index in 0..len as usize {
+                let opening = tree.open(index).unwrap();
+
+                assert!(opening.verify(&vss, &tree.root(), tree.len()));
+
+                let mut tampered = opening.clone();
+                tampered.commitment += 1;
+                assert!(!tampered.verify(&vss, &tree.root(), tree.len()));
+
+                let mut moved = opening.clone();
+                moved.index = (index + 1) % len as usize;
+                assert!(len == 1 || !moved.verify(&vss, &tree.root(), tree.len()));
+            }
+
+            assert!(tree.open(len as usize).is_none());
+        }
+    }
+
+    #[test]
+    fn test_label_binding() {
+        let mut vss = VSS::new();
+        let commitments = [BigInt::from(1), BigInt::from(2), BigInt::from(3)];
+        let root = CommitmentTree::new(&vss, &commitments).root();
+
+        vss.label = String::from("myapp-vss-v1");
+
+        assert_ne!(CommitmentTree::new(&vss, &commitments).root(), root);
+    }
+}
@@ -0,0 +1,253 @@
+use bls12_381::{
+    hash_to_curve::{ExpandMsgXmd, HashToCurve},
+    G1Projective, Scalar,
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    bls::Bls12381G1,
+    bls_sign::{lowest_positions, BlsKeyShare},
+    dleq::DLEQ,
+    error::VssError,
+    group::CyclicGroup,
+    transcript::Transcript,
+};
+
+// Threshold VRF
+
+// The DDH-based VRF on a shared BLS12-381 key x: the evaluation of an input m is gamma = H(m)^x
+// in G1, and the output is a hash of gamma. Shareholder i evaluates H(m)^p(i) with its key share
+// and proves it with DLEQ(g1, X_i, H(m), gamma_i) against its commitment X_i = g1^p(i), so a
+// partial evaluation is checked like a decrypted share. Any `threshold` of them interpolate in the
+// exponent to gamma, and the partials that went into it are the proof: anyone holding the
+// commitments, whose first is the public key, recomputes gamma from them. gamma is unique for
+// the key and the input, so the output is too, whichever shareholders took part.
+
+/// The hash-to-curve domain separation tag of VRF inputs.
+pub const VRF_DST: &[u8] = b"RUST_VSS_VRF_BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+/// A shareholder's partial evaluation H(m)^p(i) with its DLEQ proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlsVrfShare {
+    pub position: u64,
+    pub gamma: G1Projective,
+    pub challenge: Scalar,
+    pub response: Scalar,
+}
+
+/// A combined evaluation, gamma = H(m)^x, with the partial evaluations that prove it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlsVrfEvaluation {
+    pub gamma: G1Projective,
+    pub shares: Vec<BlsVrfShare>,
+}
+
+impl BlsVrfEvaluation {
+    /// The pseudorandom output, SHA-256 of the compressed gamma.
+    pub fn output(&self) -> [u8; 32] {
+        Sha256::new()
+            .chain(b"vrf output")
+            .chain(Bls12381G1.element_to_bytes(&self.gamma))
+            .finalize()
+            .into()
+    }
+}
+
+// the challenge hasher of a partial evaluation; plain hashes leave the generators out, so the
+// input is bound here
+fn challenge_hasher(input: &[u8]) -> Sha256 {
+    let mut hasher = Sha256::start();
+
+    hasher.append(b"dst", VRF_DST);
+    hasher.append(b"input", input);
+    hasher
+}
+
+impl Bls12381G1 {
+    /// H(m) in G1 under `VRF_DST`.
+    pub fn hash_to_g1(input: &[u8]) -> G1Projective {
+        <G1Projective as HashToCurve<ExpandMsgXmd<Sha256>>>::hash_to_curve(input, VRF_DST)
+    }
+
+    /// This shareholder's partial evaluation of `input`.
+    pub fn evaluate_vrf_share(share: &BlsKeyShare, input: &[u8]) -> BlsVrfShare {
+        let group = Bls12381G1;
+        let point = Bls12381G1::hash_to_g1(input);
+        let mut dleq = DLEQ::with_group(
+            group,
+            Bls12381G1::generator(),
+            Bls12381G1::generator() * share.secret,
+            point,
+            point * share.secret,
+            share.secret,
+            group.random_scalar(),
+        );
+        let mut hasher = challenge_hasher(input);
+
+        dleq.commit_hash(&mut hasher);
+        dleq.c = Some(group.scalar_from_hash(&hasher.challenge()));
+
+        BlsVrfShare {
+            position: share.position,
+            gamma: dleq.h2,
+            challenge: dleq.c.unwrap(),
+            response: dleq.get_r().unwrap(),
+        }
+    }
+
+    /// Checks a partial evaluation of `input` against the shareholder's commitment X_i,
+    /// evaluated from the commitments of the key.
+    pub fn verify_vrf_share(
+        commitments: &[G1Projective],
+        input: &[u8],
+        share: &BlsVrfShare,
+    ) -> bool {
+        if share.position == 0 {
+            return false;
+        }
+
+        let group = Bls12381G1;
+        let mut dleq = DLEQ::with_group(
+            group,
+            Bls12381G1::generator(),
+            Bls12381G1::evaluate_commitments(commitments, share.position),
+            Bls12381G1::hash_to_g1(input),
+            share.gamma,
+            Scalar::zero(),
+            Scalar::zero(),
+        );
+        let mut hasher = challenge_hasher(input);
+
+        dleq.c = Some(share.challenge);
+        dleq.r = Some(share.response);
+        dleq.update_hash(&mut hasher);
+        dleq.check(&hasher)
+    }
+
+    /// Combines the partial evaluations of `input` at the lowest `threshold` positions, the
+    /// number of commitments, into the evaluation of the key. Every partial must verify.
+    pub fn combine_vrf(
+        commitments: &[G1Projective],
+        input: &[u8],
+        shares: &[BlsVrfShare],
+    ) -> Result<BlsVrfEvaluation, VssError> {
+        if !shares
+            .iter()
+            .all(|share| Bls12381G1::verify_vrf_share(commitments, input, share))
+        {
+            return Err(VssError::InvalidProof);
+        }
+
+        let weighted = lowest_positions(
+            commitments.len(),
+            shares.iter().map(|share| (share.position, *share)),
+        )?;
+
+        Ok(BlsVrfEvaluation {
+            gamma: weighted
+                .iter()
+                .fold(G1Projective::identity(), |gamma, (lambda, share)| {
+                    gamma + share.gamma * lambda
+                }),
+            shares: weighted.into_iter().map(|(_, share)| share).collect(),
+        })
+    }
+
+    /// Checks `evaluation` of `input` under the key of `commitments`: its partials verify and
+    /// combine to its gamma.
+    pub fn verify_vrf(
+        commitments: &[G1Projective],
+        input: &[u8],
+        evaluation: &BlsVrfEvaluation,
+    ) -> bool {
+        Bls12381G1::combine_vrf(commitments, input, &evaluation.shares)
+            .is_ok_and(|combined| combined.gamma == evaluation.gamma)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bls12_381::Scalar;
+
+    use crate::{bls::Bls12381G1, error::VssError};
+
+    #[test]
+    fn test_threshold_vrf() {
+        let secret = Scalar::from(0xf00d);
+        let (commitments, shares) = Bls12381G1::deal_key(&secret, 2, 4).unwrap();
+        let input = b"round 12";
+        let partials: Vec<_> = shares
+            .iter()
+            .map(|share| Bls12381G1::evaluate_vrf_share(share, input))
+            .collect();
+
+        assert!(partials.iter().all(|partial| Bls12381G1::verify_vrf_share(
+            &commitments,
+            input,
+            partial
+        )));
+
+        let evaluation = Bls12381G1::combine_vrf(&commitments, input, &partials[2..]).unwrap();
+
+        // the output is that of the whole key, whoever evaluated
+        assert_eq!(evaluation.gamma, Bls12381G1::hash_to_g1(input) * secret);
+        assert_eq!(
+            Bls12381G1::combine_vrf(&commitments, input, &partials[..2])
+                .unwrap()
+                .output(),
+            evaluation.output()
+        );
+        assert!(Bls12381G1::verify_vrf(&commitments, input, &evaluation));
+        assert!(!Bls12381G1::verify_vrf(
+            &commitments,
+            b"round 13",
+            &evaluation
+        ));
+
+        let other = Bls12381G1::combine_vrf(&commitments, b"round 13", &[]);
+
+        assert_eq!(
+            other,
+            Err(VssError::ThresholdNotMet {
+                required: 2,
+                provided: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_vrf_share() {
+        let (commitments, shares) = Bls12381G1::deal_key(&Scalar::from(3), 2, 3).unwrap();
+        let input = b"seed";
+        let mut partial = Bls12381G1::evaluate_vrf_share(&shares[0], input);
+
+        assert!(!Bls12381G1::verify_vrf_share(
+            &commitments,
+            b"other",
+            &partial
+        ));
+
+        // a partial passed off at another position does not verify
+        partial.position = 2;
+
+        assert!(!Bls12381G1::verify_vrf_share(&commitments, input, &partial));
+        assert_eq!(
+            Bls12381G1::combine_vrf(&commitments, input, &[partial]),
+            Err(VssError::InvalidProof)
+        );
+
+        let mut evaluation = Bls12381G1::combine_vrf(
+            &commitments,
+            input,
+            &[
+                Bls12381G1::evaluate_vrf_share(&shares[1], input),
+                Bls12381G1::evaluate_vrf_share(&shares[2], input),
+            ],
+        )
+        .unwrap();
+
+        evaluation.gamma = Bls12381G1::hash_to_g1(input);
+
+        assert!(!Bls12381G1::verify_vrf(&commitments, input, &evaluation));
+    }
+}
@@ -1,20 +1,43 @@
 #![allow(non_snake_case)]
 
-use std::collections::BTreeMap;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    marker::PhantomData,
+    sync::OnceLock,
+};
 
 use num_bigint::{BigInt, BigUint, RandBigInt, ToBigInt};
 use num_integer::Integer;
 use num_primes::Generator;
-use num_traits::{One, Zero};
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use sha2::{Digest, Sha256};
+use num_traits::{One, Signed, Zero};
+use rand::{CryptoRng, RngCore};
+#[cfg(not(target_arch = "wasm32"))]
+use rayon::iter::{IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelIterator};
+use sha2::Sha256;
+use sha3::{
+    digest::{ExtendableOutput, Update, XofReader},
+    Shake256,
+};
 
+#[cfg(feature = "metrics")]
+use crate::metrics::{Check, VssMetrics};
 use crate::{
+    arith,
     dleq::DLEQ,
+    error::VssError,
+    group::{blind_exponent, HashEncoding, ModpGroup},
+    polynomial::derivative_row,
+    precompute::BaseCache,
+    secret_key::SecretKey,
+    session::SessionId,
     sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
     util::Util,
+    wire::{Reader, Writer},
 };
 
+#[allow(clippy::upper_case_acronyms)]
 /// 2048-bit MODP Group
 /// New Modular Exponential (MODP) Diffie-Hellman groups
 ///
@@ -39,28 +62,142 @@ use crate::{
 /// The generator is: 2.
 ///
 /// referenced from https://github.com/AlexiaChen/mpvss-rs
-
-#[derive(Debug, Clone, Default)]
-pub struct VSS {
+///
+/// `D` is the hash of the Fiat-Shamir challenges and of the secret mask, SHA-256 by default. All
+/// parties of a ceremony must use the same hash.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct VSS<D = Sha256> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub q: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub g: BigInt,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint"))]
     pub G: BigInt,
     pub length: u32,
+    /// application label mixed into every hash, empty by default
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub label: String,
+    #[cfg_attr(feature = "serde", serde(default = "HashEncoding::legacy"))]
+    pub encoding: HashEncoding,
+    /// add a random multiple of the group order to secret exponents against timing side
+    /// channels, at the cost of longer exponents and the fixed-base tables; off by default and
+    /// not part of the parameters
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub exponent_blinding: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    digest: PhantomData<fn() -> D>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    precomputed_g: BaseCache,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    precomputed_G: BaseCache,
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    metrics: Option<std::sync::Arc<VssMetrics>>,
+}
+
+// q and g of `VSS::new`, parsed once and shared by every instance
+fn rfc3526_group() -> &'static (BigInt, BigInt) {
+    static GROUP: OnceLock<(BigInt, BigInt)> = OnceLock::new();
+
+    GROUP.get_or_init(|| {
+        let q = BigInt::parse_bytes(b"ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff", 16).unwrap();
+        let g = (&q - BigInt::one()) / BigInt::from(2_u64);
+
+        (q, g)
+    })
 }
 
+// implemented by hand so that `D` needs none of these traits
+
+impl<D> fmt::Debug for VSS<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VSS")
+            .field("q", &self.q)
+            .field("g", &self.g)
+            .field("G", &self.G)
+            .field("length", &self.length)
+            .field("label", &self.label)
+            .field("encoding", &self.encoding)
+            .field("exponent_blinding", &self.exponent_blinding)
+            .finish()
+    }
+}
+
+impl<D> Clone for VSS<D> {
+    fn clone(&self) -> Self {
+        VSS {
+            q: self.q.clone(),
+            g: self.g.clone(),
+            G: self.G.clone(),
+            length: self.length,
+            label: self.label.clone(),
+            encoding: self.encoding,
+            exponent_blinding: self.exponent_blinding,
+            digest: PhantomData,
+            precomputed_g: self.precomputed_g.clone(),
+            precomputed_G: self.precomputed_G.clone(),
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl<D> Default for VSS<D> {
+    fn default() -> Self {
+        VSS {
+            q: BigInt::zero(),
+            g: BigInt::zero(),
+            G: BigInt::zero(),
+            length: 0,
+            label: String::new(),
+            encoding: HashEncoding::default(),
+            exponent_blinding: false,
+            digest: PhantomData,
+            precomputed_g: BaseCache::default(),
+            precomputed_G: BaseCache::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+}
+
+impl<D> PartialEq for VSS<D> {
+    fn eq(&self, other: &Self) -> bool {
+        self.q == other.q
+            && self.g == other.g
+            && self.G == other.G
+            && self.length == other.length
+            && self.label == other.label
+            && self.encoding == other.encoding
+    }
+}
+
+impl<D> Eq for VSS<D> {}
+
 impl VSS {
     /// `q` is a safe prime of length 2048 bit RFC3526 https://tools.ietf.org/html/rfc3526.
     /// `2` and the corresponding sophie germain prime are generators.
     /// sophie germain prime is p if 2*p + 1 is also prime, let 2*p + 1 = q
     pub fn new() -> Self {
-        let q = BigUint::parse_bytes(b"ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb9ed529077096966d670c354e4abc9804f1746c08ca18217c32905e462e36ce3be39e772c180e86039b2783a2ec07a28fb5c55df06f4c52c9de2bcbf6955817183995497cea956ae515d2261898fa051015728e5a8aacaa68ffffffffffffffff", 16).unwrap();
-        let g = (q.clone() - BigUint::one()) / BigUint::from(2_u64);
+        let (q, g) = rfc3526_group();
 
         VSS {
-            q: q.to_bigint().unwrap(),
-            g: g.to_bigint().unwrap(),
+            q: q.clone(),
+            g: g.clone(),
             G: BigInt::from(2_i64),
             length: 2048,
+            label: String::new(),
+            encoding: HashEncoding::default(),
+            exponent_blinding: false,
+            digest: PhantomData,
+            precomputed_g: BaseCache::default(),
+            precomputed_G: BaseCache::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
 
@@ -74,27 +211,373 @@ impl VSS {
             g: g.to_bigint().unwrap(),
             G: BigInt::from(2_i64),
             length,
+            label: String::new(),
+            encoding: HashEncoding::default(),
+            exponent_blinding: false,
+            digest: PhantomData,
+            precomputed_g: BaseCache::default(),
+            precomputed_G: BaseCache::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
+}
 
-    pub fn generate_private_key(&self) -> BigInt {
-        let mut rng = rand::thread_rng();
+impl<D: Transcript> VSS<D> {
+    /// The same parameters with challenges hashed by `E`.
+    pub fn with_digest<E: Transcript>(self) -> VSS<E> {
+        VSS {
+            q: self.q,
+            g: self.g,
+            G: self.G,
+            length: self.length,
+            label: self.label,
+            encoding: self.encoding,
+            exponent_blinding: self.exponent_blinding,
+            digest: PhantomData,
+            precomputed_g: self.precomputed_g,
+            precomputed_G: self.precomputed_G,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics,
+        }
+    }
+
+    /// The same parameters, recording every share box and distribution verification and the
+    /// progress of reconstructions into `metrics`. Clones share them.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(mut self, metrics: std::sync::Arc<VssMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Option<&VssMetrics> {
+        self.metrics.as_deref()
+    }
+
+    /// g^exponent mod q from a table of g built on first use, see `PrecomputedBase`.
+    pub fn exp_g(&self, exponent: &BigInt) -> BigInt {
+        self.precomputed_g.exp(&self.g, &self.q, exponent)
+    }
+
+    /// G^exponent mod q, as `exp_g`.
+    pub fn exp_G(&self, exponent: &BigInt) -> BigInt {
+        self.precomputed_G.exp(&self.G, &self.q, exponent)
+    }
+
+    /// SHA-256 of the group parameters `q`, `g` and `G`, each length prefixed. Versioned share
+    /// boxes carry it so they are never read against another group.
+    pub fn group_id(&self) -> [u8; 32] {
+        let mut encoded = Vec::new();
+
+        for value in [&self.q, &self.g, &self.G] {
+            let (_, bytes) = value.to_bytes_be();
+
+            encoded.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+            encoded.extend_from_slice(&bytes);
+        }
+
+        <Sha256 as sha2::Digest>::digest(&encoded).into()
+    }
+
+    /// The first 8 bytes of `group_id`, carried by distributions so that parties configured with
+    /// another group learn so instead of seeing proofs fail.
+    pub fn params_fingerprint(&self) -> [u8; 8] {
+        self.group_id()[..8].try_into().unwrap()
+    }
+
+    /// Fails if `distribution_sharebox` was dealt over other group parameters. Distributions from
+    /// before fingerprints pass.
+    pub fn check_params(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(), VssError> {
+        match distribution_sharebox.params_fingerprint {
+            Some(fingerprint) if fingerprint != self.params_fingerprint() => {
+                Err(VssError::ParamsMismatch { fingerprint })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The parameters in the binary encoding of share boxes, see `from_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new();
+
+        writer.bigint(&self.q);
+        writer.bigint(&self.g);
+        writer.bigint(&self.G);
+        writer.u32(self.length);
+        writer.byte_string(self.label.as_bytes());
+        writer.u8(self.encoding as u8);
+
+        writer.into_bytes()
+    }
+
+    /// Decodes `to_bytes`: `q`, `g` and `G` as length-prefixed magnitudes, the 4-byte length, the
+    /// length-prefixed UTF-8 label and a byte for the hash encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VssError> {
+        let mut reader = Reader::new(bytes);
+        let vss = VSS {
+            q: reader.bigint()?,
+            g: reader.bigint()?,
+            G: reader.bigint()?,
+            length: reader.u32()?,
+            label: String::from_utf8(reader.byte_string()?.to_vec())
+                .map_err(|_| VssError::MalformedEncoding)?,
+            encoding: match reader.u8()? {
+                0 => HashEncoding::Decimal,
+                1 => HashEncoding::FixedWidth,
+                _ => return Err(VssError::MalformedEncoding),
+            },
+            exponent_blinding: false,
+            digest: PhantomData,
+            precomputed_g: BaseCache::default(),
+            precomputed_G: BaseCache::default(),
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        };
+
+        reader.finish()?;
+
+        Ok(vss)
+    }
+
+    // a hash instance bound to the application label, so hashes from applications using
+    // different labels never match. The label is length prefixed to keep it unambiguous.
+    pub fn hasher(&self) -> D {
+        self.hasher_of()
+    }
+
+    // `hasher` with another hash than the challenge hash, for hashes of a fixed size
+    pub(crate) fn hasher_of<E: Transcript>(&self) -> E {
+        let mut hasher = E::start();
+
+        if !self.label.is_empty() {
+            let mut label = (self.label.len() as u64).to_be_bytes().to_vec();
+
+            label.extend_from_slice(self.label.as_bytes());
+            hasher.append(b"label", &label);
+        }
+
+        hasher
+    }
+
+    // whether challenges bind the group and the context of every statement, see `transcript`
+    pub(crate) fn binds_context(&self) -> bool {
+        self.encoding == HashEncoding::FixedWidth
+    }
+
+    // the challenge hasher of proofs bound to `context`, which also binds the group parameters and
+    // generators. Decimal parameters bind them only for a non-empty context, so that challenges
+    // without one stay as they were before contexts.
+    pub(crate) fn context_hasher(&self, context: &[u8]) -> D {
+        let mut hasher = self.hasher();
+
+        if self.binds_context() || !context.is_empty() {
+            let mut bound = (context.len() as u64).to_be_bytes().to_vec();
+
+            bound.extend_from_slice(context);
+            self.update_hash(&mut hasher, b"q", &self.q);
+            self.update_hash(&mut hasher, b"g", &self.g);
+            self.update_hash(&mut hasher, b"G", &self.G);
+            hasher.append(b"context", &bound);
+        }
+
+        hasher
+    }
+
+    // The mask XORed onto a secret of `secret_len` bytes: that many bytes of SHAKE-256 over the
+    // label and the shared value G^p(0). Distributions without a length predate it and are
+    // masked with a single hash reduced modulo q, which only covers secrets up to the hash size.
+    pub(crate) fn secret_mask(&self, shared_value: &BigInt, secret_len: Option<usize>) -> BigUint {
+        let secret_len = match secret_len {
+            Some(secret_len) => secret_len,
+            None => {
+                let mut mask_hasher = self.hasher();
+
+                self.update_hash(&mut mask_hasher, b"shared_value", shared_value);
+
+                return BigUint::from_bytes_be(&mask_hasher.challenge())
+                    .mod_floor(&self.q.to_biguint().unwrap());
+            }
+        };
+        let mut xof = Shake256::default();
+        let mut mask = vec![0; secret_len];
+
+        xof.update((self.label.len() as u64).to_be_bytes());
+        xof.update(self.label.as_bytes());
+        xof.update(
+            self.encoding
+                .to_bytes(shared_value, self.q.bits().div_ceil(8)),
+        );
+        xof.finalize_xof().read(&mut mask);
+
+        BigUint::from_bytes_be(&mask)
+    }
+
+    // the mask of the secret at `index` of a multi-secret distribution, as `secret_mask` with the
+    // index ahead of the shared value, so that every secret gets its own
+    pub(crate) fn secret_mask_at(
+        &self,
+        shared_value: &BigInt,
+        index: usize,
+        secret_len: usize,
+    ) -> BigUint {
+        let mut xof = Shake256::default();
+        let mut mask = vec![0; secret_len];
+
+        xof.update((self.label.len() as u64).to_be_bytes());
+        xof.update(self.label.as_bytes());
+        xof.update(b"index");
+        xof.update((index as u64).to_be_bytes());
+        xof.update(
+            self.encoding
+                .to_bytes(shared_value, self.q.bits().div_ceil(8)),
+        );
+        xof.finalize_xof().read(&mut mask);
+
+        BigUint::from_bytes_be(&mask)
+    }
+
+    /// The group the proofs are computed in.
+    pub fn group(&self) -> ModpGroup {
+        ModpGroup {
+            q: self.q.clone(),
+            encoding: self.encoding,
+            exponent_blinding: self.exponent_blinding,
+        }
+    }
+
+    /// Feeds `value` into `hasher` under `label`, with the encoding of these parameters.
+    pub fn update_hash<E: Transcript>(&self, hasher: &mut E, label: &'static [u8], value: &BigInt) {
+        hasher.append(
+            label,
+            &self.encoding.to_bytes(value, self.q.bits().div_ceil(8)),
+        );
+    }
+
+    pub fn generate_private_key(&self) -> SecretKey {
+        self.generate_private_key_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Like `generate_private_key`, drawing from `rng`.
+    pub fn generate_private_key_with_rng(&self, rng: &mut (impl CryptoRng + RngCore)) -> SecretKey {
         let mut private_key = rng.gen_biguint_below(&self.q.to_biguint().unwrap());
 
         while private_key.gcd(&(self.q.to_biguint().unwrap() - BigUint::one())) != BigUint::one() {
             private_key = rng.gen_biguint_below(&self.q.to_biguint().unwrap());
         }
 
-        private_key.to_bigint().unwrap()
+        SecretKey::new(private_key.to_bigint().unwrap())
+    }
+
+    pub fn generate_public_key(&self, private_key: &SecretKey) -> BigInt {
+        self.exp_G(&self.blind(private_key.expose()))
+    }
+
+    // an element of the group modulo q, as every received public key, share and commitment must be
+    pub(crate) fn in_group(&self, element: &BigInt) -> bool {
+        element.is_positive() && element < &self.q
+    }
+
+    // a reduced exponent in [0, q - 1), as every received challenge and response must be
+    pub(crate) fn in_exponent_range(&self, exponent: &BigInt) -> bool {
+        !exponent.is_negative() && exponent < &(&self.q - BigInt::one())
     }
 
-    pub fn generate_public_key(&self, private_key: &BigInt) -> BigInt {
-        self.G.modpow(private_key, &self.q)
+    /// `exponent` blinded when `exponent_blinding` is set, for an exponentiation of any element.
+    pub(crate) fn blind(&self, exponent: &BigInt) -> BigInt {
+        if self.exponent_blinding {
+            blind_exponent(exponent, &(&self.q - BigInt::one()))
+        } else {
+            exponent.clone()
+        }
     }
 
+    // the order of the exponents of elements generated by `G`: (q - 1) / 2, a prime for a safe
+    // prime q, if G is a square, and q - 1 otherwise
+    pub(crate) fn subgroup_order(&self) -> BigInt {
+        let order = &self.q - BigInt::one();
+        let half = &order >> 1;
+
+        if arith::modpow(&self.G, &half, &self.q).is_one() {
+            half
+        } else {
+            order
+        }
+    }
+
+    /// Checks that `publickey` lies in `1 < publickey < q - 1`, which rules out the elements of
+    /// order 1 and 2, and in the subgroup generated by `G`: its order divides `(q - 1) / 2` if
+    /// that of `G` does, and `q - 1` otherwise.
+    pub fn validate_public_key(&self, publickey: &BigInt) -> Result<(), VssError> {
+        let invalid = || VssError::InvalidPublicKey {
+            publickey: publickey.clone(),
+        };
+        let order = &self.q - BigInt::one();
+
+        if publickey <= &BigInt::one() || publickey >= &order {
+            return Err(invalid());
+        }
+
+        if !arith::modpow(publickey, &self.subgroup_order(), &self.q).is_one() {
+            return Err(invalid());
+        }
+
+        Ok(())
+    }
+
+    /// Checks a share box against its encrypted share outside of sessions. A share box of a
+    /// session only verifies with `verify_in_session`, or with `verify_share`, which takes the
+    /// session from the distribution.
     pub fn verify(&self, sharebox: &ShareBox, encrypted_share: &BigInt) -> bool {
+        self.verify_with_context(sharebox, encrypted_share, &[])
+    }
+
+    /// Like `verify` for a share box extracted within `session`.
+    pub fn verify_in_session(
+        &self,
+        sharebox: &ShareBox,
+        encrypted_share: &BigInt,
+        session: &SessionId,
+    ) -> bool {
+        self.verify_with_context(sharebox, encrypted_share, &session.bind(&[]))
+    }
+
+    pub fn verify_with_context(
+        &self,
+        sharebox: &ShareBox,
+        encrypted_share: &BigInt,
+        context: &[u8],
+    ) -> bool {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            return metrics.observe(Check::Share, || {
+                self.verify_share_proof(sharebox, encrypted_share, context)
+            });
+        }
+
+        self.verify_share_proof(sharebox, encrypted_share, context)
+    }
+
+    fn verify_share_proof(
+        &self,
+        sharebox: &ShareBox,
+        encrypted_share: &BigInt,
+        context: &[u8],
+    ) -> bool {
+        if !self.in_group(&sharebox.publickey)
+            || !self.in_group(&sharebox.share)
+            || !self.in_group(encrypted_share)
+            || !self.in_exponent_range(&sharebox.challenge)
+            || !self.in_exponent_range(&sharebox.response)
+        {
+            return false;
+        }
+
         let mut dleq = DLEQ::new();
-        let mut challenge_hasher = Sha256::new();
+        let mut challenge_hasher = self.context_hasher(context);
 
         dleq.g1 = self.G.clone();
         dleq.h1 = sharebox.publickey.clone();
@@ -102,8 +585,13 @@ impl VSS {
         dleq.h2 = encrypted_share.clone();
         dleq.r = Some(sharebox.response.clone());
         dleq.c = Some(sharebox.challenge.clone());
-        dleq.q = self.q.clone();
+        dleq.group = self.group();
         dleq.update_hash(&mut challenge_hasher);
+
+        if let Some(freshness) = &sharebox.freshness {
+            freshness.append_to(&mut challenge_hasher);
+        }
+
         dleq.check(&challenge_hasher)
     }
 
@@ -112,6 +600,16 @@ impl VSS {
         sharebox: &ShareBox,
         distribution_sharebox: &DistributionShareBox,
         publickey: &BigInt,
+    ) -> bool {
+        self.verify_share_with_context(sharebox, distribution_sharebox, publickey, &[])
+    }
+
+    pub fn verify_share_with_context(
+        &self,
+        sharebox: &ShareBox,
+        distribution_sharebox: &DistributionShareBox,
+        publickey: &BigInt,
+        context: &[u8],
     ) -> bool {
         let encrypted_share = distribution_sharebox.shares.get(publickey);
 
@@ -119,131 +617,609 @@ impl VSS {
             return false;
         }
 
-        self.verify(sharebox, encrypted_share.unwrap())
+        self.verify_with_context(
+            sharebox,
+            encrypted_share.unwrap(),
+            &distribution_sharebox.proof_context(context),
+        )
     }
 
-    pub fn verify_distribution_shares(&self, distribution_sharebox: &DistributionShareBox) -> bool {
-        let mut dleq = DLEQ::new();
-        let mut challenge_hasher = Sha256::new();
+    /// Verifies many share boxes against the distribution at once, returning one flag per share
+    /// box, in input order, each true if that share box verified.
+    ///
+    /// The proofs are first checked together: each one's challenge against the commitments it
+    /// carries, then all equations a1 = G^r y^c and a2 = S^r Y^c raised to random 128-bit
+    /// exponents and multiplied into one, which costs about two multi-exponentiations for the
+    /// whole batch. The Legendre symbols of both sides are compared per proof, as the random
+    /// exponents only catch errors of large order, which holds in safe-prime groups. If the batch
+    /// fails, or a share box carries no commitments, every proof is checked on its own to find
+    /// the cheaters.
+    pub fn verify_shares(
+        &self,
+        shareboxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Vec<bool> {
+        if self.verify_share_batch(shareboxes, distribution_sharebox) {
+            return vec![true; shareboxes.len()];
+        }
 
-        for publickey in &distribution_sharebox.publickeys {
-            let position = distribution_sharebox.positions.get(publickey);
-            let response = distribution_sharebox.responses.get(publickey);
-            let encrypted_share = distribution_sharebox.shares.get(publickey);
+        #[cfg(not(target_arch = "wasm32"))]
+        let shareboxes = shareboxes.par_iter();
+        #[cfg(target_arch = "wasm32")]
+        let shareboxes = shareboxes.iter();
+
+        shareboxes
+            .map(|sharebox| self.verify_share(sharebox, distribution_sharebox, &sharebox.publickey))
+            .collect()
+    }
+
+    // the public keys of the share boxes that fail `verify_shares`, in input order
+    pub(crate) fn invalid_shares(
+        &self,
+        shareboxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Vec<BigInt> {
+        shareboxes
+            .iter()
+            .zip(self.verify_shares(shareboxes, distribution_sharebox))
+            .filter(|(_, valid)| !valid)
+            .map(|(sharebox, _)| sharebox.publickey.clone())
+            .collect()
+    }
+
+    fn verify_share_batch(
+        &self,
+        shareboxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> bool {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            return metrics.observe(Check::ShareBatch, || {
+                self.verify_share_batch_proofs(shareboxes, distribution_sharebox)
+            });
+        }
+
+        self.verify_share_batch_proofs(shareboxes, distribution_sharebox)
+    }
+
+    fn verify_share_batch_proofs(
+        &self,
+        shareboxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> bool {
+        let context = distribution_sharebox.proof_context(&[]);
+        let order = &self.q - BigInt::one();
+        let mut rng = rand::thread_rng();
+        // (b / q)^e, the Legendre symbol of b^e
+        let symbol = |base: &BigInt, exponent: &BigInt| {
+            if exponent.is_odd() {
+                Util::jacobi(base, &self.q)
+            } else {
+                1
+            }
+        };
+        let mut g_exponent = BigInt::zero();
+        let mut bases = vec![self.G.clone()];
+        let mut exponents = vec![BigInt::zero()];
+        let mut commitments = Vec::with_capacity(2 * shareboxes.len());
+        let mut weights = Vec::with_capacity(2 * shareboxes.len());
 
-            if position.is_none() || response.is_none() || encrypted_share.is_none() {
+        for sharebox in shareboxes {
+            let (Some(encrypted_share), Some(hint)) = (
+                distribution_sharebox.shares.get(&sharebox.publickey),
+                &sharebox.commitments,
+            ) else {
+                return false;
+            };
+
+            if !self.in_group(&sharebox.publickey)
+                || !self.in_group(&sharebox.share)
+                || !self.in_group(encrypted_share)
+                || !self.in_group(&hint.a1)
+                || !self.in_group(&hint.a2)
+                || !self.in_exponent_range(&sharebox.challenge)
+                || !self.in_exponent_range(&sharebox.response)
+            {
                 return false;
             }
 
-            let mut x = BigInt::one();
-            let mut exponent = BigInt::one();
+            let (c, r) = (&sharebox.challenge, &sharebox.response);
+            let mut dleq = DLEQ::new();
+            let mut challenge_hasher = self.context_hasher(&context);
 
-            for j in 0..distribution_sharebox.commitments.len() {
-                x = (x * distribution_sharebox.commitments[j].modpow(&exponent, &self.q)) % &self.q;
-                exponent = (exponent * BigInt::from(*position.unwrap() as i64))
-                    % &(self.q.clone() - BigInt::one());
+            dleq.g1 = self.G.clone();
+            dleq.h1 = sharebox.publickey.clone();
+            dleq.g2 = sharebox.share.clone();
+            dleq.h2 = encrypted_share.clone();
+            dleq.c = Some(c.clone());
+            dleq.group = self.group();
+            dleq.append_recomputed(&[hint.a1.clone(), hint.a2.clone()], &mut challenge_hasher);
+
+            if let Some(freshness) = &sharebox.freshness {
+                freshness.append_to(&mut challenge_hasher);
             }
 
+            if !dleq.check(&challenge_hasher)
+                || Util::jacobi(&hint.a1, &self.q)
+                    != symbol(&self.G, r) * symbol(&sharebox.publickey, c)
+                || Util::jacobi(&hint.a2, &self.q)
+                    != symbol(&sharebox.share, r) * symbol(encrypted_share, c)
+            {
+                return false;
+            }
+
+            let delta = rng.gen_biguint(128).to_bigint().unwrap();
+            let epsilon = rng.gen_biguint(128).to_bigint().unwrap();
+
+            g_exponent += &delta * r;
+            bases.extend([
+                sharebox.publickey.clone(),
+                sharebox.share.clone(),
+                encrypted_share.clone(),
+            ]);
+            exponents.extend([
+                (&delta * c).mod_floor(&order),
+                (&epsilon * r).mod_floor(&order),
+                (&epsilon * c).mod_floor(&order),
+            ]);
+            commitments.extend([hint.a1.clone(), hint.a2.clone()]);
+            weights.extend([delta, epsilon]);
+        }
+
+        exponents[0] = g_exponent.mod_floor(&order);
+
+        Util::multi_exp(&bases, &exponents, &self.q)
+            == Util::multi_exp(&commitments, &weights, &self.q)
+    }
+
+    // X_i = prod_j C_j^(i^j), the commitment to the share at `position`, as one
+    // multi-exponentiation
+    pub fn evaluate_commitments(&self, commitments: &[BigInt], position: &BigInt) -> BigInt {
+        let order = &self.q - BigInt::one();
+        let position = position.mod_floor(&order);
+        let mut exponents = Vec::with_capacity(commitments.len());
+        let mut exponent = BigInt::one();
+
+        for _ in commitments {
+            exponents.push(exponent.clone());
+            exponent = (exponent * &position).mod_floor(&order);
+        }
+
+        Util::multi_exp(commitments, &exponents, &self.q)
+    }
+
+    // X_i for a share p^(order)(i) of a hierarchical distribution, the product of the
+    // commitments raised to the factors of the derivative
+    pub(crate) fn evaluate_derivative_commitments(
+        &self,
+        commitments: &[BigInt],
+        position: &BigInt,
+        order: u32,
+    ) -> BigInt {
+        if order == 0 {
+            return self.evaluate_commitments(commitments, position);
+        }
+
+        let order_q = &self.q - BigInt::one();
+        let exponents: Vec<BigInt> = derivative_row(commitments.len(), position, order)
+            .iter()
+            .map(|factor| factor.mod_floor(&order_q))
+            .collect();
+
+        Util::multi_exp(commitments, &exponents, &self.q)
+    }
+
+    pub fn verify_distribution_shares(&self, distribution_sharebox: &DistributionShareBox) -> bool {
+        self.verify_distribution_shares_with_context(distribution_sharebox, &[])
+    }
+
+    pub fn verify_distribution_shares_with_context(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        context: &[u8],
+    ) -> bool {
+        self.verify_distribution_with_orders(distribution_sharebox, context, &BTreeMap::new())
+    }
+
+    // like `verify_distribution_shares_with_context`, for shares that are derivatives of the
+    // polynomial of the order given by recipient, 0 for those not listed
+    pub(crate) fn verify_distribution_with_orders(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        context: &[u8],
+        orders: &BTreeMap<BigInt, u32>,
+    ) -> bool {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            return metrics.observe(Check::Distribution, || {
+                self.verify_distribution_proof(distribution_sharebox, context, orders)
+            });
+        }
+
+        self.verify_distribution_proof(distribution_sharebox, context, orders)
+    }
+
+    fn verify_distribution_proof(
+        &self,
+        distribution_sharebox: &DistributionShareBox,
+        context: &[u8],
+        orders: &BTreeMap<BigInt, u32>,
+    ) -> bool {
+        if self.check_params(distribution_sharebox).is_err()
+            || distribution_sharebox.validate().is_err()
+        {
+            return false;
+        }
+
+        // cheap checks first, so that a malformed distribution fails before any exponentiation
+        let mut dleqs = Vec::with_capacity(distribution_sharebox.publickeys.len());
+
+        if !distribution_sharebox
+            .commitments
+            .iter()
+            .all(|commitment| self.in_group(commitment))
+            || !self.in_exponent_range(&distribution_sharebox.challenge)
+        {
+            return false;
+        }
+
+        for publickey in &distribution_sharebox.publickeys {
+            let (position, response, encrypted_share) = match (
+                distribution_sharebox.positions.get(publickey),
+                distribution_sharebox.responses.get(publickey),
+                distribution_sharebox.shares.get(publickey),
+            ) {
+                (Some(position), Some(response), Some(encrypted_share)) => {
+                    (position, response, encrypted_share)
+                }
+                _ => return false,
+            };
+
+            if !self.in_group(publickey)
+                || !self.in_group(encrypted_share)
+                || !self.in_exponent_range(response)
+            {
+                return false;
+            }
+
+            let mut dleq = DLEQ::new();
+
             dleq.g1 = self.g.clone();
-            dleq.h1 = x;
             dleq.g2 = publickey.clone();
-            dleq.h2 = encrypted_share.unwrap().clone();
-            dleq.r = Some(response.unwrap().clone());
+            dleq.h2 = encrypted_share.clone();
+            dleq.r = Some(response.clone());
             dleq.c = Some(distribution_sharebox.challenge.clone());
-            dleq.q = self.q.clone();
-            dleq.update_hash(&mut challenge_hasher);
+            dleq.group = self.group();
+            dleqs.push((position, orders.get(publickey).copied().unwrap_or(0), dleq));
         }
 
-        dleq.check(&challenge_hasher)
+        // X_i and a1, a2 of every entry are independent; only the hashing is ordered
+        #[cfg(not(target_arch = "wasm32"))]
+        let entries = dleqs.par_iter_mut();
+        #[cfg(target_arch = "wasm32")]
+        let entries = dleqs.iter_mut();
+        let commitments: Vec<[BigInt; 2]> = entries
+            .map(|(position, order, dleq)| {
+                dleq.h1 = self.evaluate_derivative_commitments(
+                    &distribution_sharebox.commitments,
+                    position,
+                    *order,
+                );
+                dleq.recompute()
+            })
+            .collect();
+        let mut challenge_hasher =
+            self.context_hasher(&distribution_sharebox.proof_context(context));
+
+        for ((_, _, dleq), commitments) in dleqs.iter().zip(&commitments) {
+            dleq.append_recomputed(commitments, &mut challenge_hasher);
+        }
+
+        match dleqs.last() {
+            Some((_, _, dleq)) => dleq.check(&challenge_hasher),
+            None => false,
+        }
     }
 
-    fn compute_factor(&self, position: i64, share: &BigInt, values: &[i64]) -> BigInt {
-        let mut exponent = BigInt::one();
-        let lagrangeCoefficient = Util::lagrange_coefficient(&position, values);
+    pub(crate) fn compute_factor(
+        &self,
+        position: &BigInt,
+        share: &BigInt,
+        values: &[BigInt],
+    ) -> Result<BigInt, VssError> {
+        self.compute_factor_at(position, share, values, &BigInt::zero())
+    }
 
-        if &lagrangeCoefficient.0 % &lagrangeCoefficient.1 == BigInt::zero() {
+    // share^lambda for the Lagrange coefficient of `position` at `x`, interpolating towards the
+    // value at `x` rather than at zero
+    pub(crate) fn compute_factor_at(
+        &self,
+        position: &BigInt,
+        share: &BigInt,
+        values: &[BigInt],
+        x: &BigInt,
+    ) -> Result<BigInt, VssError> {
+        self.raise_to_coefficient(
+            position,
+            share,
+            Util::lagrange_coefficient_at(position, values, x),
+        )
+    }
+
+    // share^(numerator / denominator) for an interpolation coefficient given as a fraction, the
+    // denominator inverted modulo the order of the subgroup of G. In a safe-prime group that
+    // order is prime, so every set of distinct positions below it interpolates
+    pub(crate) fn raise_to_coefficient(
+        &self,
+        position: &BigInt,
+        share: &BigInt,
+        lagrangeCoefficient: (BigInt, BigInt),
+    ) -> Result<BigInt, VssError> {
+        let exponent = if &lagrangeCoefficient.0 % &lagrangeCoefficient.1 == BigInt::zero() {
             // lagrange coefficient is an integer
-            exponent = &lagrangeCoefficient.0 / Util::abs(&lagrangeCoefficient.1);
+            Util::abs(&lagrangeCoefficient.0) / Util::abs(&lagrangeCoefficient.1)
         } else {
             // lagrange coefficient is a proper faction, cancel fraction if possible
-            let mut numerator = lagrangeCoefficient.0.to_biguint().unwrap();
+            let mut numerator = Util::abs(&lagrangeCoefficient.0).to_biguint().unwrap();
             let mut denominator = Util::abs(&lagrangeCoefficient.1).to_biguint().unwrap();
             let gcd = numerator.gcd(&denominator);
 
             numerator /= &gcd;
             denominator /= &gcd;
 
-            let q1 = &self.q - BigInt::one();
-            let inverseDenominator =
-                Util::mod_inverse(&denominator.to_bigint().unwrap(), &q1.to_bigint().unwrap());
+            let order = self.subgroup_order();
+            let inverseDenominator = Util::mod_inverse(&denominator.to_bigint().unwrap(), &order)
+                .ok_or_else(|| VssError::NonInvertibleCoefficient {
+                position: position.clone(),
+            })?;
 
-            if let Some(inverseDenom) = inverseDenominator {
-                exponent =
-                    (numerator.to_bigint().unwrap() * inverseDenom) % q1.to_bigint().unwrap();
-            } else {
-                eprintln!("Error: Denominator of Lagrange coefficient fraction does not have an inverse. Share cannot be processed")
-            }
-        }
+            (numerator.to_bigint().unwrap() * inverseDenominator) % order
+        };
 
-        let mut factor = share
-            .to_bigint()
-            .unwrap()
-            .modpow(&exponent, &self.q.to_bigint().unwrap());
+        let mut factor = arith::modpow(share, &exponent, &self.q);
 
         if lagrangeCoefficient.0 * lagrangeCoefficient.1 < BigInt::zero() {
-            let inverseFactor = Util::mod_inverse(&factor, &self.q.to_bigint().unwrap());
-
-            if let Some(inverseFactor) = inverseFactor {
-                factor = inverseFactor;
-            } else {
-                eprintln!("Error: Lagrange coefficient was negative and does not have an inverse. Share cannot be processed");
-            }
+            factor = Util::mod_inverse(&factor, &self.q.to_bigint().unwrap())
+                .ok_or(VssError::NonInvertible)?;
         }
 
-        factor
+        Ok(factor)
     }
 
     pub fn reconstruct(
         &self,
         share_boxes: &[ShareBox],
         distribution_sharebox: &DistributionShareBox,
-    ) -> Option<BigInt> {
-        if share_boxes.len() < distribution_sharebox.commitments.len() {
-            return None;
+    ) -> Result<BigInt, VssError> {
+        let shared_value = self.interpolate(share_boxes, distribution_sharebox)?;
+
+        self.unmask(&shared_value, distribution_sharebox)
+    }
+
+    // G^p(0), interpolated in the exponent from the decrypted shares
+    pub(crate) fn interpolate(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<BigInt, VssError> {
+        self.interpolate_with(share_boxes, distribution_sharebox, |publickey| {
+            distribution_sharebox.positions.get(publickey)
+        })
+    }
+
+    // like `interpolate`, looking the share boxes' positions up with `position_of`, for holders
+    // the distribution itself does not list
+    pub(crate) fn interpolate_with<'a>(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+        position_of: impl Fn(&BigInt) -> Option<&'a BigInt>,
+    ) -> Result<BigInt, VssError> {
+        self.interpolate_at_with(
+            share_boxes,
+            distribution_sharebox,
+            position_of,
+            &BigInt::zero(),
+        )
+    }
+
+    // G^p(x) rather than G^p(0), for distributions with values at other points
+    pub(crate) fn interpolate_at(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+        x: &BigInt,
+    ) -> Result<BigInt, VssError> {
+        self.interpolate_at_with(
+            share_boxes,
+            distribution_sharebox,
+            |publickey| distribution_sharebox.positions.get(publickey),
+            x,
+        )
+    }
+
+    fn interpolate_at_with<'a>(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+        position_of: impl Fn(&BigInt) -> Option<&'a BigInt>,
+        x: &BigInt,
+    ) -> Result<BigInt, VssError> {
+        self.check_params(distribution_sharebox)?;
+        distribution_sharebox.validate()?;
+
+        if share_boxes.len() < distribution_sharebox.threshold() {
+            return Err(VssError::ThresholdNotMet {
+                required: distribution_sharebox.threshold(),
+                provided: share_boxes.len(),
+            });
         }
 
         let mut shares = BTreeMap::new();
 
         for share_box in share_boxes.iter() {
-            let position = distribution_sharebox.positions.get(&share_box.publickey);
+            let position =
+                position_of(&share_box.publickey).ok_or_else(|| VssError::UnknownShareholder {
+                    publickey: share_box.publickey.clone(),
+                })?;
+
+            if !share_box.share.is_positive() || share_box.share >= self.q {
+                return Err(VssError::InvalidShare {
+                    publickey: share_box.publickey.clone(),
+                });
+            }
 
-            position?;
+            shares.insert(position.clone(), share_box.share.clone());
+        }
 
-            shares.insert(*position.unwrap(), share_box.share.clone());
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.set_reconstruction_progress(shares.len(), distribution_sharebox.threshold());
+        }
+
+        // a shareholder submitting twice counts once
+        if shares.len() < distribution_sharebox.threshold() {
+            return Err(VssError::ThresholdNotMet {
+                required: distribution_sharebox.threshold(),
+                provided: shares.len(),
+            });
         }
 
         let mut secret = BigInt::one();
-        let values: Vec<i64> = shares.keys().copied().collect();
-        let shares_vec: Vec<(i64, BigInt)> = shares
-            .into_iter()
-            .map(|(position, share)| (position, share))
-            .collect();
+        let values: Vec<BigInt> = shares.keys().cloned().collect();
+        let shares_vec: Vec<(BigInt, BigInt)> = shares.into_iter().collect();
         let shares_slice = shares_vec.as_slice();
-        let factors: Vec<BigInt> = shares_slice
-            .par_iter()
-            .map(|(position, share)| self.compute_factor(*position, share, values.as_slice()))
-            .collect();
+        // wasm32 has no threads for rayon
+        #[cfg(not(target_arch = "wasm32"))]
+        let shares_iter = shares_slice.par_iter();
+        #[cfg(target_arch = "wasm32")]
+        let shares_iter = shares_slice.iter();
+        let factors: Vec<BigInt> = shares_iter
+            .map(|(position, share)| self.compute_factor_at(position, share, values.as_slice(), x))
+            .collect::<Result<_, _>>()?;
 
         secret = factors
             .into_iter()
             .fold(secret, |acc, factor| (acc * factor) % &self.q);
 
-        let secret_hash = Sha256::digest(secret.to_biguint().unwrap().to_str_radix(10).as_bytes());
-        let hash_big_uint =
-            BigUint::from_bytes_be(&secret_hash[..]).mod_floor(&self.q.to_biguint().unwrap());
-        let decrypted_secret = hash_big_uint ^ distribution_sharebox.u.to_biguint().unwrap();
+        Ok(secret)
+    }
+
+    // the secret under the mask `u`, given the shared value G^p(0)
+    pub(crate) fn unmask(
+        &self,
+        shared_value: &BigInt,
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<BigInt, VssError> {
+        let u = distribution_sharebox
+            .u
+            .to_biguint()
+            .ok_or(VssError::NegativeValue)?;
+        let decrypted_secret = self.secret_mask(shared_value, distribution_sharebox.secret_len) ^ u;
 
-        Some(decrypted_secret.to_bigint().unwrap())
+        Ok(decrypted_secret.to_bigint().unwrap())
+    }
+
+    /// Like `reconstruct`, checking every share box's proof first. Share boxes that fail are left
+    /// out and their public keys returned next to the secret, in input order. If too few valid
+    /// share boxes remain, the error names the cheaters.
+    pub fn reconstruct_verified(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(BigInt, Vec<BigInt>), VssError> {
+        let cheaters = self.invalid_shares(share_boxes, distribution_sharebox);
+        let valid: Vec<ShareBox> = share_boxes
+            .iter()
+            .filter(|share_box| !cheaters.contains(&share_box.publickey))
+            .cloned()
+            .collect();
+
+        if !cheaters.is_empty() && valid.len() < distribution_sharebox.threshold() {
+            return Err(VssError::InvalidShares {
+                publickeys: cheaters,
+            });
+        }
+
+        let secret = self.reconstruct(&valid, distribution_sharebox)?;
+
+        Ok((secret, cheaters))
+    }
+
+    /// Like `reconstruct_verified`, reconstructing even when fewer than the threshold of proofs
+    /// verify. Every threshold-sized subset of the submitted share boxes is then interpolated, and
+    /// a secret is accepted if the share boxes agreeing on it are more than the threshold, a
+    /// majority of those submitted, and no subset of them disagrees. The share boxes outside that
+    /// majority are returned, in input order. The search is over all t-combinations, so it suits
+    /// small committees only.
+    pub fn reconstruct_robust(
+        &self,
+        share_boxes: &[ShareBox],
+        distribution_sharebox: &DistributionShareBox,
+    ) -> Result<(BigInt, Vec<BigInt>), VssError> {
+        let cheaters = match self.reconstruct_verified(share_boxes, distribution_sharebox) {
+            Err(VssError::InvalidShares { publickeys }) => publickeys,
+            result => return result,
+        };
+        let by_publickey: BTreeMap<&BigInt, &ShareBox> = share_boxes
+            .iter()
+            .map(|share_box| (&share_box.publickey, share_box))
+            .collect();
+        let submitted: Vec<BigInt> = by_publickey.keys().map(|&key| key.clone()).collect();
+        let results: Vec<(Vec<BigInt>, BigInt)> = distribution_sharebox
+            .minimal_authorized_subsets(Some(&submitted))
+            .filter_map(|subset| {
+                let subset_boxes: Vec<ShareBox> = subset
+                    .iter()
+                    .map(|publickey| by_publickey[publickey].clone())
+                    .collect();
+
+                self.reconstruct(&subset_boxes, distribution_sharebox)
+                    .ok()
+                    .map(|secret| (subset, secret))
+            })
+            .collect();
+        let mut votes: BTreeMap<&BigInt, usize> = BTreeMap::new();
+
+        for (_, secret) in results.iter() {
+            *votes.entry(secret).or_default() += 1;
+        }
+
+        let mut candidates: Vec<(&BigInt, usize)> = votes.into_iter().collect();
+
+        candidates.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+
+        // a wrong share can land on the same value from several subsets, so a candidate also
+        // needs every subset drawn from its supporters alone to agree with it
+        let consensus = candidates.into_iter().find_map(|(candidate, _)| {
+            let publickeys: BTreeSet<&BigInt> = results
+                .iter()
+                .filter(|(_, secret)| secret == candidate)
+                .flat_map(|(subset, _)| subset)
+                .collect();
+            let consistent = results.iter().all(|(subset, secret)| {
+                secret == candidate || !subset.iter().all(|key| publickeys.contains(key))
+            });
+
+            (consistent
+                && publickeys.len() > distribution_sharebox.threshold()
+                && 2 * publickeys.len() > submitted.len())
+            .then(|| (candidate.clone(), publickeys))
+        });
+
+        match consensus {
+            Some((secret, publickeys)) => Ok((
+                secret,
+                share_boxes
+                    .iter()
+                    .filter(|share_box| !publickeys.contains(&share_box.publickey))
+                    .map(|share_box| share_box.publickey.clone())
+                    .collect(),
+            )),
+            None => Err(VssError::InvalidShares {
+                publickeys: cheaters,
+            }),
+        }
     }
 }
 
@@ -253,6 +1229,16 @@ mod tests {
     use num_integer::Integer;
     use num_primes::Verification;
     use num_traits::One;
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        error::VssError,
+        group::HashEncoding,
+        participant::Participant,
+        secret_key::SecretKey,
+        sharebox::{DistributionShareBox, ShareBox},
+        testkit::{fixed_ceremony, test_params},
+    };
 
     use super::VSS;
 
@@ -283,6 +1269,401 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_hasher_label() {
+        let mut vss = VSS::new();
+        let plain = Sha256::digest(b"message");
+
+        assert_eq!(vss.hasher().chain(b"message").finalize(), plain);
+
+        vss.label = String::from("myapp-vss-v1");
+
+        let labelled = vss.hasher().chain(b"message").finalize();
+
+        assert_ne!(labelled, plain);
+
+        vss.label = String::from("otherapp-vss-v1");
+
+        assert_ne!(vss.hasher().chain(b"message").finalize(), labelled);
+    }
+
+    #[test]
+    fn test_context_hasher() {
+        let mut vss = test_params();
+
+        // decimal parameters bind nothing without a context
+        assert_eq!(vss.context_hasher(&[]).finalize(), vss.hasher().finalize());
+
+        vss.encoding = HashEncoding::FixedWidth;
+
+        let bound = vss.context_hasher(&[]).finalize();
+
+        assert_ne!(bound, vss.hasher().finalize());
+        assert_ne!(bound, vss.context_hasher(b"session").finalize());
+
+        let mut other = vss.clone();
+
+        other.G = BigInt::from(2);
+
+        assert_ne!(other.context_hasher(&[]).finalize(), bound);
+
+        // provers and verifiers agree on the bound transcripts
+        let participants: Vec<_> = [7901, 4801, 1453]
+            .iter()
+            .map(|key| {
+                Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let publickeys: Vec<_> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let distribution = Participant::with_params(vss.clone())
+            .distribute_secret(&BigInt::from(1234567), &publickeys, 2)
+            .unwrap();
+        let shareboxes: Vec<_> = participants
+            .iter()
+            .map(|p| {
+                p.extract_secret_share(&distribution, &p.privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(vss.verify_distribution_shares(&distribution));
+        assert_eq!(vss.verify_shares(&shareboxes, &distribution), vec![true; 3]);
+        assert_eq!(
+            vss.reconstruct(&shareboxes, &distribution),
+            Ok(BigInt::from(1234567))
+        );
+    }
+
+    #[test]
+    fn test_params_fingerprint() {
+        let ceremony = fixed_ceremony(3, 2, 3).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut other = vss.clone();
+
+        other.G = BigInt::from(2);
+
+        assert_eq!(
+            ceremony.distribution.params_fingerprint,
+            Some(vss.params_fingerprint())
+        );
+        assert_eq!(vss.check_params(&ceremony.distribution), Ok(()));
+        assert_eq!(
+            other.check_params(&ceremony.distribution),
+            Err(VssError::ParamsMismatch {
+                fingerprint: vss.params_fingerprint()
+            })
+        );
+        assert!(!other.verify_distribution_shares(&ceremony.distribution));
+        assert_eq!(
+            other.reconstruct(&ceremony.shares, &ceremony.distribution),
+            Err(VssError::ParamsMismatch {
+                fingerprint: vss.params_fingerprint()
+            })
+        );
+
+        other.label = String::from("myapp-vss-v1");
+
+        assert_eq!(VSS::from_bytes(&other.to_bytes()), Ok(other));
+        assert_eq!(
+            VSS::<Sha256>::from_bytes(&vss.to_bytes()[1..]),
+            Err(VssError::MalformedEncoding)
+        );
+    }
+
+    #[test]
+    fn test_verify_distribution_shares() {
+        let ceremony = fixed_ceremony(12, 5, 3).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let publickey = ceremony.distribution.publickeys[7].clone();
+
+        assert!(vss.verify_distribution_shares(&ceremony.distribution));
+
+        let mut tampered = ceremony.distribution.clone();
+
+        tampered.responses.insert(publickey.clone(), BigInt::one());
+
+        assert!(!vss.verify_distribution_shares(&tampered));
+
+        // out of the group, rejected before any exponentiation
+        let mut tampered = ceremony.distribution.clone();
+
+        tampered.shares.insert(publickey, vss.q.clone());
+
+        assert!(!vss.verify_distribution_shares(&tampered));
+
+        let mut tampered = ceremony.distribution.clone();
+
+        tampered.commitments[0] = BigInt::from(0);
+
+        assert!(!vss.verify_distribution_shares(&tampered));
+        assert!(!vss.verify_distribution_shares(&Default::default()));
+    }
+
+    #[test]
+    fn test_verify_shares() {
+        let ceremony = fixed_ceremony(5, 3, 5).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut shareboxes = ceremony.shares.clone();
+
+        assert_eq!(
+            vss.verify_shares(&shareboxes, &ceremony.distribution),
+            vec![true; 5]
+        );
+
+        shareboxes[1].response += 1;
+        shareboxes[3].share += 1;
+
+        assert_eq!(
+            vss.verify_shares(&shareboxes, &ceremony.distribution),
+            vec![true, false, true, false, true]
+        );
+        assert_eq!(
+            vss.invalid_shares(&shareboxes, &ceremony.distribution),
+            vec![
+                shareboxes[1].publickey.clone(),
+                shareboxes[3].publickey.clone()
+            ]
+        );
+
+        shareboxes[0].challenge += 1;
+        shareboxes[4].publickey = BigInt::from(2);
+
+        assert_eq!(
+            vss.verify_shares(&shareboxes, &ceremony.distribution),
+            vec![false, false, true, false, false]
+        );
+        assert!(vss.verify_shares(&[], &ceremony.distribution).is_empty());
+    }
+
+    #[test]
+    fn test_verify_share_batch() {
+        let ceremony = fixed_ceremony(5, 3, 5).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let distribution = &ceremony.distribution;
+        let mut shareboxes = ceremony.shares.clone();
+
+        assert!(shareboxes
+            .iter()
+            .all(|sharebox| sharebox.commitments.is_some()));
+        assert!(vss.verify_share_batch(&shareboxes, distribution));
+        assert!(vss.verify_share_batch(&[], distribution));
+
+        // a response that no longer matches its commitments fails the combined equation
+        shareboxes[2].response += 1;
+
+        assert!(!vss.verify_share_batch(&shareboxes, distribution));
+        assert_eq!(
+            vss.verify_shares(&shareboxes, distribution),
+            vec![true, true, false, true, true]
+        );
+
+        // wrong or missing commitments fail the batch, but not the share boxes themselves
+        let mut shareboxes = ceremony.shares.clone();
+
+        shareboxes[0].commitments.as_mut().unwrap().a1 += 1;
+        shareboxes[1].commitments = None;
+
+        assert!(!vss.verify_share_batch(&shareboxes, distribution));
+        assert_eq!(vss.verify_shares(&shareboxes, distribution), vec![true; 5]);
+    }
+
+    #[test]
+    fn test_negative_values() {
+        let ceremony = fixed_ceremony(5, 3, 5).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let distribution = &ceremony.distribution;
+        let publickey = &distribution.publickeys[0];
+
+        // each of these would panic in an exponentiation or in the hash encoding
+        let share_faults: [fn(&mut ShareBox); 4] = [
+            |sharebox| sharebox.challenge = -&sharebox.challenge,
+            |sharebox| sharebox.response = -&sharebox.response - 1,
+            |sharebox| sharebox.share = -&sharebox.share,
+            |sharebox| sharebox.publickey = -&sharebox.publickey,
+        ];
+
+        for fault in share_faults {
+            let mut sharebox = ceremony.shares[0].clone();
+
+            fault(&mut sharebox);
+            assert!(!vss.verify(&sharebox, &distribution.shares[publickey]));
+        }
+
+        let distribution_faults: [fn(&mut DistributionShareBox); 3] = [
+            |distribution| distribution.challenge = -&distribution.challenge,
+            |distribution| {
+                for response in distribution.responses.values_mut() {
+                    *response = -&*response - 1;
+                }
+            },
+            |distribution| distribution.commitments[1] = -&distribution.commitments[1],
+        ];
+
+        for fault in distribution_faults {
+            let mut tampered = distribution.clone();
+
+            fault(&mut tampered);
+            assert!(!vss.verify_distribution_shares(&tampered));
+        }
+
+        // a challenge or response must also be reduced
+        let mut sharebox = ceremony.shares[0].clone();
+
+        sharebox.response += &vss.q - BigInt::one();
+        assert!(!vss.verify(&sharebox, &distribution.shares[publickey]));
+
+        let mut tampered = distribution.clone();
+
+        tampered.u = -&tampered.u - 1;
+        assert_eq!(
+            vss.reconstruct(&ceremony.shares[..3], &tampered),
+            Err(VssError::NegativeValue)
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_verified() {
+        let ceremony = fixed_ceremony(5, 3, 5).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut shareboxes = ceremony.shares.clone();
+
+        assert_eq!(
+            vss.reconstruct_verified(&shareboxes, &ceremony.distribution),
+            Ok((ceremony.secret.clone(), vec![]))
+        );
+
+        shareboxes[4].share += 1;
+
+        assert_ne!(
+            vss.reconstruct(&shareboxes, &ceremony.distribution),
+            Ok(ceremony.secret.clone())
+        );
+        assert_eq!(
+            vss.reconstruct_verified(&shareboxes, &ceremony.distribution),
+            Ok((
+                ceremony.secret.clone(),
+                vec![shareboxes[4].publickey.clone()]
+            ))
+        );
+
+        shareboxes[2].response += 1;
+
+        assert_eq!(
+            vss.reconstruct_verified(&shareboxes[1..], &ceremony.distribution),
+            Err(VssError::InvalidShares {
+                publickeys: vec![
+                    shareboxes[2].publickey.clone(),
+                    shareboxes[4].publickey.clone()
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_reconstruct_odd_positions() {
+        // G = 2 is a square modulo this safe prime, so its order (q - 1) / 2 is prime
+        let vss = VSS::builder()
+            .q(BigInt::from(179426879))
+            .g(BigInt::from(89713439))
+            .G(BigInt::from(2))
+            .min_bits(16)
+            .length(64)
+            .build()
+            .unwrap();
+        let participants: Vec<_> = [7901, 4801, 1453, 2179, 3001]
+            .iter()
+            .map(|key| {
+                Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(*key)))
+            })
+            .collect();
+        let publickeys: Vec<_> = participants.iter().map(|p| p.publickey.clone()).collect();
+        let secret = BigInt::from(1234567);
+        let distribution = Participant::with_params(vss.clone())
+            .distribute_secret(&secret, &publickeys, 3)
+            .unwrap();
+        // positions 1, 3 and 5, whose coefficients 15/8, -5/4 and 3/8 have even denominators
+        let shareboxes: Vec<_> = [0, 2, 4]
+            .iter()
+            .map(|&i| {
+                participants[i]
+                    .extract_secret_share(&distribution, &participants[i].privatekey)
+                    .unwrap()
+            })
+            .collect();
+
+        assert_eq!(vss.subgroup_order(), BigInt::from(89713439));
+        assert_eq!(vss.reconstruct(&shareboxes, &distribution), Ok(secret));
+    }
+
+    #[test]
+    fn test_reconstruct_robust() {
+        let ceremony = fixed_ceremony(5, 3, 5).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let mut shareboxes = ceremony.shares.clone();
+
+        // three proofs fail, but only one share is wrong
+        shareboxes[0].response += 1;
+        shareboxes[1].response += 1;
+        shareboxes[4].share += 1;
+
+        assert!(vss
+            .reconstruct_verified(&shareboxes, &ceremony.distribution)
+            .is_err());
+        assert_eq!(
+            vss.reconstruct_robust(&shareboxes, &ceremony.distribution),
+            Ok((
+                ceremony.secret.clone(),
+                vec![shareboxes[4].publickey.clone()]
+            ))
+        );
+
+        // with two wrong shares out of five no four agree
+        shareboxes[3].share += 1;
+
+        assert_eq!(
+            vss.reconstruct_robust(&shareboxes, &ceremony.distribution),
+            Err(VssError::InvalidShares {
+                publickeys: vec![
+                    shareboxes[0].publickey.clone(),
+                    shareboxes[1].publickey.clone(),
+                    shareboxes[3].publickey.clone(),
+                    shareboxes[4].publickey.clone()
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_public_key() {
+        let vss = VSS::builder()
+            .q(BigInt::from(179426879))
+            .g(BigInt::from(89713439))
+            .G(BigInt::from(4))
+            .min_bits(16)
+            .build()
+            .unwrap();
+        let publickey = vss.generate_public_key(&SecretKey::new(BigInt::from(7901)));
+        let invalid = |publickey: i64| {
+            Err(VssError::InvalidPublicKey {
+                publickey: BigInt::from(publickey),
+            })
+        };
+
+        assert_eq!(vss.validate_public_key(&publickey), Ok(()));
+        assert_eq!(vss.validate_public_key(&BigInt::from(0)), invalid(0));
+        assert_eq!(vss.validate_public_key(&BigInt::from(1)), invalid(1));
+        assert_eq!(
+            vss.validate_public_key(&BigInt::from(179426878)),
+            invalid(179426878)
+        );
+        assert_eq!(
+            vss.validate_public_key(&BigInt::from(179426879)),
+            invalid(179426879)
+        );
+        // 4 is a square and generates the squares, of order p, while 7 is not one
+        assert_eq!(vss.validate_public_key(&BigInt::from(7)), invalid(7));
+    }
+
     #[test]
     fn test_generate_private_key() {
         let mut vss = VSS::new();
@@ -293,7 +1674,7 @@ mod tests {
         let private_key = vss.generate_private_key();
 
         assert_eq!(
-            private_key.gcd(&(vss.q.clone() - BigInt::one())),
+            private_key.expose().gcd(&(vss.q.clone() - BigInt::one())),
             BigInt::one()
         );
     }
@@ -309,7 +1690,7 @@ mod tests {
         vss.g = g;
         vss.G = G;
 
-        let private_key = BigInt::from(105929);
+        let private_key = SecretKey::new(BigInt::from(105929));
         let public_key = vss.generate_public_key(&private_key);
 
         assert_eq!(public_key, BigInt::from(148446388));
@@ -0,0 +1,185 @@
+use num_bigint::BigInt;
+use rand::{prng::ChaChaRng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    participant::Participant,
+    secret_to_bytes,
+    sharebox::{DistributionShareBox, ShareBox},
+};
+
+// JavaScript bindings
+
+// Participants, distributions and share boxes cross the boundary as the JSON of the `serde`
+// feature, with big integers as decimal strings. Randomness comes from a ChaCha generator seeded
+// by `getrandom`, which uses `crypto.getRandomValues` in the browser, since the operating system
+// generator of `rand` is not available there. Reconstruction runs without rayon on wasm32.
+
+fn rng() -> Result<ChaChaRng, String> {
+    let mut seed = [0; 32];
+
+    getrandom::getrandom(&mut seed).map_err(|e| e.to_string())?;
+
+    Ok(ChaChaRng::from_seed(seed))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, String> {
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| e.to_string())
+}
+
+fn keygen_json() -> Result<String, String> {
+    let mut participant = Participant::new();
+
+    participant.privatekey = participant.vss.generate_private_key_with_rng(&mut rng()?);
+    participant.publickey = participant.vss.generate_public_key(&participant.privatekey);
+
+    to_json(&participant)
+}
+
+fn distribute_json(
+    dealer: &str,
+    secret: &[u8],
+    publickeys: &str,
+    threshold: u32,
+) -> Result<String, String> {
+    let mut dealer: Participant = from_json(dealer)?;
+    let publickeys: Vec<String> = from_json(publickeys)?;
+    let publickeys = publickeys
+        .iter()
+        .map(|publickey| {
+            publickey
+                .parse::<BigInt>()
+                .map_err(|_| format!("not a decimal public key: {}", publickey))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let distribution = dealer
+        .distribute_secret_bytes_with_rng(secret, &publickeys, threshold, &mut rng()?)
+        .map_err(|e| e.to_string())?;
+
+    to_json(&distribution)
+}
+
+fn verify_distribution_json(distribution: &str) -> Result<bool, String> {
+    let distribution: DistributionShareBox = from_json(distribution)?;
+
+    Ok(Participant::new().verify_distribution_shares(&distribution))
+}
+
+fn extract_json(participant: &str, distribution: &str) -> Result<String, String> {
+    let participant: Participant = from_json(participant)?;
+    let distribution: DistributionShareBox = from_json(distribution)?;
+    let sharebox = participant
+        .extract_secret_share_with_rng(&distribution, &participant.privatekey, &mut rng()?)
+        .map_err(|e| e.to_string())?;
+
+    to_json(&sharebox)
+}
+
+fn verify_share_json(distribution: &str, sharebox: &str) -> Result<bool, String> {
+    let distribution: DistributionShareBox = from_json(distribution)?;
+    let sharebox: ShareBox = from_json(sharebox)?;
+
+    Ok(Participant::new().verify_share(&sharebox, &distribution, &sharebox.publickey))
+}
+
+fn reconstruct_json(distribution: &str, shareboxes: &str) -> Result<Vec<u8>, String> {
+    let distribution: DistributionShareBox = from_json(distribution)?;
+    let shareboxes: Vec<ShareBox> = from_json(shareboxes)?;
+    let secret = Participant::new()
+        .reconstruct(&shareboxes, &distribution)
+        .map_err(|e| e.to_string())?;
+    let len = distribution
+        .secret_len
+        .unwrap_or_else(|| secret.to_bytes_be().1.len());
+
+    secret_to_bytes(&secret, len).map_err(|e| e.to_string())
+}
+
+/// A new participant, as JSON holding its private key.
+#[wasm_bindgen]
+pub fn keygen() -> Result<String, JsError> {
+    keygen_json().map_err(|e| JsError::new(&e))
+}
+
+/// Deals `secret` to the JSON array of decimal `publickeys`, returning the distribution as JSON.
+#[wasm_bindgen]
+pub fn distribute(
+    dealer: &str,
+    secret: &[u8],
+    publickeys: &str,
+    threshold: u32,
+) -> Result<String, JsError> {
+    distribute_json(dealer, secret, publickeys, threshold).map_err(|e| JsError::new(&e))
+}
+
+#[wasm_bindgen(js_name = verifyDistribution)]
+pub fn verify_distribution(distribution: &str) -> Result<bool, JsError> {
+    verify_distribution_json(distribution).map_err(|e| JsError::new(&e))
+}
+
+/// The share box of `participant` in `distribution`, as JSON.
+#[wasm_bindgen]
+pub fn extract(participant: &str, distribution: &str) -> Result<String, JsError> {
+    extract_json(participant, distribution).map_err(|e| JsError::new(&e))
+}
+
+#[wasm_bindgen(js_name = verifyShare)]
+pub fn verify_share(distribution: &str, sharebox: &str) -> Result<bool, JsError> {
+    verify_share_json(distribution, sharebox).map_err(|e| JsError::new(&e))
+}
+
+/// The secret bytes from a JSON array of share boxes.
+#[wasm_bindgen]
+pub fn reconstruct(distribution: &str, shareboxes: &str) -> Result<Vec<u8>, JsError> {
+    reconstruct_json(distribution, shareboxes).map_err(|e| JsError::new(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::participant::Participant;
+
+    use super::{
+        distribute_json, extract_json, keygen_json, reconstruct_json, verify_distribution_json,
+        verify_share_json,
+    };
+
+    #[test]
+    fn test_bindings() {
+        let keys: Vec<String> = (0..3).map(|_| keygen_json().unwrap()).collect();
+        let publickeys: Vec<String> = keys
+            .iter()
+            .map(|key| {
+                serde_json::from_str::<Participant>(key)
+                    .unwrap()
+                    .publickey
+                    .to_string()
+            })
+            .collect();
+        let secret = [0, 1, 2, 3];
+        let distribution = distribute_json(
+            &keygen_json().unwrap(),
+            &secret,
+            &serde_json::to_string(&publickeys).unwrap(),
+            2,
+        )
+        .unwrap();
+
+        assert!(verify_distribution_json(&distribution).unwrap());
+
+        let shareboxes: Vec<String> = keys[..2]
+            .iter()
+            .map(|key| extract_json(key, &distribution).unwrap())
+            .collect();
+
+        assert!(verify_share_json(&distribution, &shareboxes[0]).unwrap());
+        assert_eq!(
+            reconstruct_json(&distribution, &format!("[{}]", shareboxes.join(","))).unwrap(),
+            secret
+        );
+        assert!(distribute_json(&keys[0], &secret, "[\"0x12\"]", 1).is_err());
+    }
+}
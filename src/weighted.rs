@@ -0,0 +1,283 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::One;
+use rand::{CryptoRng, RngCore};
+
+use crate::{
+    arith,
+    error::VssError,
+    participant::{sequential_positions, Participant},
+    secret_key::SecretKey,
+    sharebox::{DistributionShareBox, ShareBox},
+    transcript::Transcript,
+    vss::VSS,
+};
+
+// Weighted shares
+
+// A participant of weight w holds w shares, at w positions, under one key pair. A distribution
+// lists every key once, so each share goes to a slot key of its own, y^e_s for the s-th exponent
+// e_s coprime to q - 1, starting from e_0 = 1. Anyone derives the slot keys from the participant's
+// key, and the participant its slot private keys x e_s, so the slots are ordinary entries of an
+// ordinary distribution: they are proven, decrypted, verified and interpolated like any other, and
+// the threshold counts slots, that is weight.
+
+/// A distribution in which participants hold as many slots as their weight.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WeightedDistribution {
+    /// over the slot keys
+    pub distribution: DistributionShareBox,
+    /// the participant's key by slot key
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_utils::bigint_map"))]
+    pub owners: BTreeMap<BigInt, BigInt>,
+}
+
+impl<D: Transcript> VSS<D> {
+    // e_0, e_1, ...: the first `weight` exponents coprime to q - 1
+    fn slot_exponents(&self, weight: usize) -> Vec<BigInt> {
+        let order = &self.q - BigInt::one();
+
+        std::iter::successors(Some(BigInt::one()), |e| Some(e + BigInt::one()))
+            .filter(|e| e.gcd(&order).is_one())
+            .take(weight)
+            .collect()
+    }
+
+    /// The keys of the `weight` slots of `publickey`, in slot order.
+    pub fn slot_keys(&self, publickey: &BigInt, weight: usize) -> Vec<BigInt> {
+        self.slot_exponents(weight)
+            .iter()
+            .map(|e| arith::modpow(publickey, e, &self.q))
+            .collect()
+    }
+}
+
+impl<D: Transcript> Participant<D> {
+    /// Distributes `secret` to `recipients`, each a key with its weight, so that shareholders
+    /// holding `threshold` weight between them reconstruct.
+    pub fn distribute_weighted(
+        &mut self,
+        secret: &BigInt,
+        recipients: &[(BigInt, u32)],
+        threshold: u32,
+    ) -> Result<WeightedDistribution, VssError> {
+        self.distribute_weighted_with_rng(secret, recipients, threshold, &mut rand::thread_rng())
+    }
+
+    /// Like `distribute_weighted`, drawing the polynomial and the proof nonce from `rng`.
+    pub fn distribute_weighted_with_rng(
+        &mut self,
+        secret: &BigInt,
+        recipients: &[(BigInt, u32)],
+        threshold: u32,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> Result<WeightedDistribution, VssError> {
+        let mut owners = BTreeMap::new();
+        let mut slot_keys = Vec::new();
+
+        for (publickey, weight) in recipients {
+            if *weight == 0 {
+                return Err(VssError::InvalidWeight {
+                    publickey: publickey.clone(),
+                });
+            }
+
+            for slot_key in self.vss.slot_keys(publickey, *weight as usize) {
+                if owners.insert(slot_key.clone(), publickey.clone()).is_some() {
+                    return Err(VssError::DuplicatePublicKey {
+                        publickey: publickey.clone(),
+                    });
+                }
+
+                slot_keys.push(slot_key);
+            }
+        }
+
+        let (distribution, _) = self.deal_polynomial(
+            secret,
+            &slot_keys,
+            &sequential_positions(slot_keys.len()),
+            threshold,
+            &[],
+            None,
+            rng,
+        )?;
+
+        Ok(WeightedDistribution {
+            distribution,
+            owners,
+        })
+    }
+
+    /// This participant's share boxes, one per slot, each proven under its slot key.
+    pub fn extract_weighted_shares(
+        &self,
+        weighted: &WeightedDistribution,
+    ) -> Result<Vec<ShareBox>, VssError> {
+        let order = &self.vss.q - BigInt::one();
+        let weight = weighted.weight(&self.publickey);
+
+        if weight == 0 {
+            return Err(VssError::MissingShare {
+                publickey: self.publickey.clone(),
+            });
+        }
+
+        self.vss
+            .slot_exponents(weight)
+            .iter()
+            .map(|e| {
+                let slot_key = SecretKey::new((self.privatekey.expose() * e).mod_floor(&order));
+
+                self.extract_secret_share(&weighted.distribution, &slot_key)
+            })
+            .collect()
+    }
+}
+
+impl WeightedDistribution {
+    /// The weight of `publickey`, 0 for keys without slots.
+    pub fn weight(&self, publickey: &BigInt) -> usize {
+        self.owners
+            .values()
+            .filter(|owner| *owner == publickey)
+            .count()
+    }
+
+    /// The total weight of the owners of `share_boxes`, each slot counted once.
+    pub fn held_weight(&self, share_boxes: &[ShareBox]) -> usize {
+        share_boxes
+            .iter()
+            .filter(|share_box| self.owners.contains_key(&share_box.publickey))
+            .map(|share_box| &share_box.publickey)
+            .collect::<BTreeSet<_>>()
+            .len()
+    }
+
+    /// Verifies the distribution and that every owner's slots are its slot keys, in order.
+    pub fn verify<D: Transcript>(&self, vss: &VSS<D>) -> bool {
+        let mut slots: BTreeMap<&BigInt, Vec<&BigInt>> = BTreeMap::new();
+
+        for slot_key in &self.distribution.publickeys {
+            match self.owners.get(slot_key) {
+                Some(owner) => slots.entry(owner).or_default().push(slot_key),
+                None => return false,
+            }
+        }
+
+        self.owners.len() == self.distribution.publickeys.len()
+            && slots.iter().all(|(owner, keys)| {
+                vss.slot_keys(owner, keys.len())
+                    .iter()
+                    .eq(keys.iter().copied())
+            })
+            && vss.verify_distribution_shares(&self.distribution)
+    }
+
+    /// Reconstructs the secret from slot share boxes worth at least `threshold`.
+    pub fn reconstruct<D: Transcript>(
+        &self,
+        vss: &VSS<D>,
+        share_boxes: &[ShareBox],
+    ) -> Result<BigInt, VssError> {
+        vss.reconstruct(share_boxes, &self.distribution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigInt;
+
+    use crate::{error::VssError, testkit::fixed_ceremony};
+
+    #[test]
+    fn test_weighted_distribution() {
+        let ceremony = fixed_ceremony(3, 2, 53).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let recipients: Vec<(BigInt, u32)> = participants
+            .iter()
+            .zip([2, 1, 1])
+            .map(|(p, weight)| (p.publickey.clone(), weight))
+            .collect();
+        let secret = BigInt::from(424242);
+        let weighted = ceremony
+            .dealer
+            .clone()
+            .distribute_weighted(&secret, &recipients, 3)
+            .unwrap();
+
+        assert_eq!(weighted.distribution.n(), 4);
+        assert_eq!(weighted.weight(&participants[0].publickey), 2);
+        assert!(weighted.verify(vss));
+
+        let heavy = participants[0].extract_weighted_shares(&weighted).unwrap();
+        let light = participants[1].extract_weighted_shares(&weighted).unwrap();
+
+        assert_eq!(heavy.len(), 2);
+        assert!(heavy.iter().all(|share| vss.verify_share(
+            share,
+            &weighted.distribution,
+            &share.publickey
+        )));
+
+        // the heavy participant and one other reach the threshold, the two light ones do not
+        let shares = [heavy.clone(), light.clone()].concat();
+
+        assert_eq!(weighted.held_weight(&shares), 3);
+        assert_eq!(weighted.reconstruct(vss, &shares), Ok(secret));
+
+        let light_shares = [
+            light,
+            participants[2].extract_weighted_shares(&weighted).unwrap(),
+        ]
+        .concat();
+
+        assert_eq!(
+            weighted.reconstruct(vss, &light_shares),
+            Err(VssError::ThresholdNotMet {
+                required: 3,
+                provided: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_weighted_distribution() {
+        let ceremony = fixed_ceremony(3, 2, 59).unwrap();
+        let vss = &ceremony.dealer.vss;
+        let participants = &ceremony.participants;
+        let recipients: Vec<(BigInt, u32)> = participants
+            .iter()
+            .map(|p| (p.publickey.clone(), 1))
+            .collect();
+        let mut weighted = ceremony
+            .dealer
+            .clone()
+            .distribute_weighted(&BigInt::from(7), &recipients, 2)
+            .unwrap();
+
+        assert_eq!(
+            ceremony.dealer.clone().distribute_weighted(
+                &BigInt::from(7),
+                &[(participants[0].publickey.clone(), 0)],
+                1
+            ),
+            Err(VssError::InvalidWeight {
+                publickey: participants[0].publickey.clone()
+            })
+        );
+
+        // a slot claimed for another owner
+        let slot = participants[1].publickey.clone();
+
+        weighted
+            .owners
+            .insert(slot, participants[0].publickey.clone());
+
+        assert!(!weighted.verify(vss));
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+
+use num_bigint::{BigInt, BigUint, ToBigInt};
+
+use crate::error::VssError;
+
+// Compact binary encoding
+
+// Every integer is its big-endian magnitude behind a 4-byte big-endian length, with no leading
+// zero bytes and zero as the empty magnitude. Sequences and maps are a 4-byte count followed by
+// their entries, maps in ascending key order. Decoding rejects anything that another encoder
+// would not have produced byte for byte: leading zeros, unordered or repeated keys and trailing
+// bytes, so equal values always have equal encodings.
+
+// Versioned encodings start with `MAGIC`, the format version, a byte for the kind of value and
+// the `group_id` of the parameters, followed by the plain encoding. Encodings without the header
+// predate versioning and are read as version 0 with no group check. A bare encoding starts with a
+// 4-byte length, and read as a length the magic announces more than a gigabyte.
+
+pub(crate) const MAGIC: [u8; 4] = *b"VSS\0";
+
+/// the format version written by this release
+pub const FORMAT_VERSION: u16 = 1;
+
+pub(crate) const KIND_SHAREBOX: u8 = 1;
+pub(crate) const KIND_DISTRIBUTION: u8 = 2;
+
+pub(crate) fn seal(kind: u8, group_id: &[u8; 32], body: Vec<u8>) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+
+    bytes.extend_from_slice(&FORMAT_VERSION.to_be_bytes());
+    bytes.push(kind);
+    bytes.extend_from_slice(group_id);
+    bytes.extend(body);
+
+    bytes
+}
+
+/// The plain encoding inside `bytes`, after checking its header against `kind` and `group_id`.
+pub(crate) fn open<'a>(
+    bytes: &'a [u8],
+    kind: u8,
+    group_id: &[u8; 32],
+) -> Result<&'a [u8], VssError> {
+    if !bytes.starts_with(&MAGIC) {
+        return Ok(bytes);
+    }
+
+    let mut reader = Reader::new(&bytes[MAGIC.len()..]);
+    let version = u16::from_be_bytes(reader.take(2)?.try_into().unwrap());
+
+    match version {
+        1 => {
+            if reader.u8()? != kind {
+                return Err(VssError::MalformedEncoding);
+            }
+
+            if reader.take(32)? != group_id {
+                return Err(VssError::GroupMismatch);
+            }
+
+            Ok(reader.bytes)
+        }
+        version => Err(VssError::UnsupportedVersion { version }),
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Writer {
+    bytes: Vec<u8>,
+}
+
+impl Writer {
+    pub(crate) fn new() -> Self {
+        Writer { bytes: Vec::new() }
+    }
+
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn u32(&mut self, value: u32) {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub(crate) fn u64(&mut self, value: u64) {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+    }
+
+    pub(crate) fn i64(&mut self, value: i64) {
+        self.bytes.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u32(u32::try_from(len).expect("more than 2^32 entries"));
+    }
+
+    pub(crate) fn raw(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn byte_string(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    /// Writes the magnitude of `value`, which is always a non-negative residue here.
+    pub(crate) fn bigint(&mut self, value: &BigInt) {
+        let (_, bytes) = value.to_bytes_be();
+        let bytes: &[u8] = if bytes == [0] { &[] } else { &bytes };
+
+        self.len(bytes.len());
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn bigint_vec(&mut self, values: &[BigInt]) {
+        self.len(values.len());
+
+        for value in values {
+            self.bigint(value);
+        }
+    }
+
+    pub(crate) fn map<V>(
+        &mut self,
+        map: &BTreeMap<BigInt, V>,
+        mut value: impl FnMut(&mut Self, &V),
+    ) {
+        self.len(map.len());
+
+        for (key, entry) in map {
+            self.bigint(key);
+            value(self, entry);
+        }
+    }
+
+    pub(crate) fn bigint_map(&mut self, map: &BTreeMap<BigInt, BigInt>) {
+        self.map(map, Writer::bigint);
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Reader { bytes }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], VssError> {
+        if self.bytes.len() < len {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        let (head, tail) = self.bytes.split_at(len);
+
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, VssError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, VssError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, VssError> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn i64(&mut self) -> Result<i64, VssError> {
+        Ok(i64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn raw(&mut self, len: usize) -> Result<&'a [u8], VssError> {
+        self.take(len)
+    }
+
+    pub(crate) fn byte_string(&mut self) -> Result<&'a [u8], VssError> {
+        let len = self.u32()? as usize;
+
+        self.take(len)
+    }
+
+    /// Whether every byte has been read.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub(crate) fn bigint(&mut self) -> Result<BigInt, VssError> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+
+        if bytes.first() == Some(&0) {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        Ok(BigUint::from_bytes_be(bytes).to_bigint().unwrap())
+    }
+
+    pub(crate) fn bigint_vec(&mut self) -> Result<Vec<BigInt>, VssError> {
+        let count = self.u32()?;
+
+        // every entry takes at least its 4-byte length, so a count beyond that is truncated input
+        if count as usize > self.bytes.len() / 4 {
+            return Err(VssError::MalformedEncoding);
+        }
+
+        (0..count).map(|_| self.bigint()).collect()
+    }
+
+    pub(crate) fn map<V>(
+        &mut self,
+        mut value: impl FnMut(&mut Self) -> Result<V, VssError>,
+    ) -> Result<BTreeMap<BigInt, V>, VssError> {
+        let mut map = BTreeMap::new();
+
+        for _ in 0..self.u32()? {
+            let key = self.bigint()?;
+
+            if map.last_key_value().is_some_and(|(last, _)| last >= &key) {
+                return Err(VssError::MalformedEncoding);
+            }
+
+            let entry = value(self)?;
+
+            map.insert(key, entry);
+        }
+
+        Ok(map)
+    }
+
+    pub(crate) fn bigint_map(&mut self) -> Result<BTreeMap<BigInt, BigInt>, VssError> {
+        self.map(Reader::bigint)
+    }
+
+    /// Fails unless every byte has been read.
+    pub(crate) fn finish(self) -> Result<(), VssError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(VssError::MalformedEncoding)
+        }
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "serde")]
+
+use rust_vss::compat::load_corpus;
+
+// transcripts recorded by earlier releases must keep verifying and reconstructing
+#[test]
+fn test_compatibility_corpus() {
+    let corpus = load_corpus(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures")).unwrap();
+
+    assert!(!corpus.is_empty());
+
+    for (path, entry) in corpus {
+        assert_eq!(entry.verify(), Ok(()), "{}", path.display());
+    }
+}
@@ -1,6 +1,11 @@
-use rust_vss::{string_from_secret, string_to_secret, Participant};
+use num_bigint::BigInt;
+use rust_vss::{
+    bytes_to_secret, secret_to_bytes, string_from_secret, string_to_secret, Participant, SecretKey,
+    VssError, VSS,
+};
 
 #[test]
+#[allow(clippy::bool_assert_comparison, clippy::useless_vec)]
 fn test_vss_whole_process() {
     let secret_message = String::from("Test");
     let mut dealer = Participant::new();
@@ -16,15 +21,17 @@ fn test_vss_whole_process() {
     participant3.initialize();
 
     // distribute process
-    let distribute_share_boxes = dealer.distribute_secret(
-        &string_to_secret(&secret_message),
-        &vec![
-            participant1.publickey.clone(),
-            participant2.publickey.clone(),
-            participant3.publickey.clone(),
-        ],
-        3,
-    );
+    let distribute_share_boxes = dealer
+        .distribute_secret(
+            &string_to_secret(&secret_message),
+            &vec![
+                participant1.publickey.clone(),
+                participant2.publickey.clone(),
+                participant3.publickey.clone(),
+            ],
+            3,
+        )
+        .unwrap();
 
     assert_eq!(
         participant1.verify_distribution_shares(&distribute_share_boxes),
@@ -85,3 +92,93 @@ fn test_vss_whole_process() {
     let reconstruct3_str = string_from_secret(&reconstruct3);
     assert_eq!(reconstruct3_str, secret_message.clone());
 }
+
+#[test]
+fn test_vss_bytes_secret() {
+    // an Ed25519 seed with leading zero bytes
+    let mut seed = [0xa5u8; 32];
+
+    seed[..3].copy_from_slice(&[0, 0, 7]);
+
+    let mut dealer = Participant::new();
+    let mut participant1 = Participant::new();
+    let mut participant2 = Participant::new();
+
+    dealer.initialize();
+    participant1.initialize();
+    participant2.initialize();
+
+    let publickeys = [
+        participant1.publickey.clone(),
+        participant2.publickey.clone(),
+    ];
+    let distribution = dealer
+        .distribute_secret(&bytes_to_secret(&seed), &publickeys, 2)
+        .unwrap();
+    let share_boxes = [
+        participant1
+            .extract_secret_share(&distribution, &participant1.privatekey)
+            .unwrap(),
+        participant2
+            .extract_secret_share(&distribution, &participant2.privatekey)
+            .unwrap(),
+    ];
+    let secret = participant1
+        .reconstruct(&share_boxes, &distribution)
+        .unwrap();
+
+    assert_eq!(secret_to_bytes(&secret, 32).unwrap(), seed);
+    assert_eq!(
+        secret_to_bytes(&secret, 28).unwrap_err(),
+        VssError::SecretTooLarge { length: 28 }
+    );
+    assert_eq!(
+        secret_to_bytes(&bytes_to_secret(&[0; 4]), 4).unwrap(),
+        [0; 4]
+    );
+}
+
+#[test]
+fn test_vss_custom_params() {
+    let mut vss = VSS::new();
+
+    vss.q = BigInt::from(179426549);
+    vss.g = BigInt::from(1301081);
+    vss.G = BigInt::from(15486487);
+    vss.length = 64;
+
+    let mut dealer = Participant::with_params(vss.clone());
+
+    dealer.initialize();
+
+    let participants: Vec<Participant> = [7901, 4801, 1453]
+        .map(|privatekey| {
+            Participant::from_private_key(vss.clone(), SecretKey::new(BigInt::from(privatekey)))
+        })
+        .into_iter()
+        .collect();
+    let publickeys: Vec<BigInt> = participants.iter().map(|p| p.publickey.clone()).collect();
+
+    assert_eq!(
+        publickeys[0],
+        vss.generate_public_key(&SecretKey::new(BigInt::from(7901)))
+    );
+
+    let secret = BigInt::from(1234567);
+    let distribution = dealer.distribute_secret(&secret, &publickeys, 2).unwrap();
+
+    assert!(participants[0].verify_distribution_shares(&distribution));
+
+    let share_boxes: Vec<_> = participants[1..]
+        .iter()
+        .map(|p| {
+            p.extract_secret_share(&distribution, &p.privatekey)
+                .unwrap()
+        })
+        .collect();
+
+    assert_eq!(
+        participants[0].reconstruct(&share_boxes, &distribution),
+        Ok(secret)
+    );
+}